@@ -0,0 +1,86 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use std::sync::mpsc::{SyncSender, TrySendError};
+use std::time::{Duration, Instant};
+
+use base::cross_process_instant::CrossProcessInstant;
+use base::id::WebViewId;
+use image::RgbaImage;
+use log::debug;
+
+/// The number of captured frames that may wait to be received before new frames are dropped.
+/// Consumers that fall behind, for instance because they encode frames more slowly than they
+/// are painted, get the most recent frames once they catch up instead of using more and more
+/// memory.
+pub(crate) const MAX_QUEUED_CAPTURED_FRAMES: usize = 4;
+
+/// A frame produced by a capture stream. See [`crate::Paint::start_painter_capture_stream`].
+#[derive(Clone, Debug)]
+pub struct CapturedFrame {
+    /// The contents of the captured area.
+    pub image: RgbaImage,
+    /// The time at which the frame was painted.
+    pub time: CrossProcessInstant,
+}
+
+/// A continuous capture of the frames painted by a `Painter`, or of the area of a single one
+/// of its `WebView`s, delivered over a channel at a limited rate. The channel holds at most
+/// [`MAX_QUEUED_CAPTURED_FRAMES`] frames and frames that do not fit are dropped.
+pub(crate) struct CaptureStream {
+    /// The `WebView` whose area is captured, or `None` to capture the entire
+    /// `RenderingContext`.
+    pub webview_id: Option<WebViewId>,
+    /// The minimum time between two captured frames.
+    min_interval: Duration,
+    /// When the last frame was captured.
+    last_frame_time: Option<Instant>,
+    /// The number of frames dropped because the consumer did not receive them in time.
+    dropped_frames: usize,
+    sender: SyncSender<CapturedFrame>,
+}
+
+impl CaptureStream {
+    pub(crate) fn new(
+        webview_id: Option<WebViewId>,
+        frames_per_second: f32,
+        sender: SyncSender<CapturedFrame>,
+    ) -> Self {
+        Self {
+            webview_id,
+            min_interval: Duration::from_secs_f32(1.0 / frames_per_second.max(0.001)),
+            last_frame_time: None,
+            dropped_frames: 0,
+            sender,
+        }
+    }
+
+    /// Whether enough time has passed since the last captured frame to capture another one.
+    pub(crate) fn wants_frame(&self, now: Instant) -> bool {
+        self.last_frame_time
+            .is_none_or(|last_frame_time| now - last_frame_time >= self.min_interval)
+    }
+
+    /// Send a captured frame to the consumer of this stream, or drop it if the consumer has
+    /// not received the frames queued before it yet. Returns `false` if the consumer has gone
+    /// away, in which case the stream should be stopped.
+    pub(crate) fn send_frame(&mut self, now: Instant, image: RgbaImage) -> bool {
+        self.last_frame_time = Some(now);
+        match self.sender.try_send(CapturedFrame {
+            image,
+            time: CrossProcessInstant::now(),
+        }) {
+            Ok(()) => true,
+            Err(TrySendError::Full(_)) => {
+                self.dropped_frames += 1;
+                debug!(
+                    "Dropped captured frame, {} so far, because the consumer is behind",
+                    self.dropped_frames
+                );
+                true
+            },
+            Err(TrySendError::Disconnected(_)) => false,
+        }
+    }
+}
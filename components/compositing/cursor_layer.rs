@@ -0,0 +1,69 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use embedder_traits::Cursor;
+use webrender_api::units::{LayoutPoint, LayoutRect, LayoutSize};
+use webrender_api::{ColorF, CommonItemProperties, DisplayListBuilder, SpaceAndClipInfo};
+
+/// The rectangles making up the arrow drawn for most cursors, relative to the hotspot. The
+/// arrow is approximated by rows that get wider towards the bottom, followed by a stem.
+const ARROW: &[(f32, f32, f32, f32)] = &[
+    (0., 0., 1., 1.),
+    (0., 1., 2., 1.),
+    (0., 2., 3., 1.),
+    (0., 3., 4., 1.),
+    (0., 4., 5., 1.),
+    (0., 5., 6., 1.),
+    (0., 6., 7., 1.),
+    (0., 7., 8., 1.),
+    (0., 8., 9., 1.),
+    (0., 9., 10., 1.),
+    (0., 10., 6., 1.),
+    (0., 11., 3., 1.),
+    (0., 12., 2., 1.),
+    (4., 11., 2., 5.),
+];
+
+/// The rectangles making up the I-beam drawn for text cursors, relative to the hotspot.
+const I_BEAM: &[(f32, f32, f32, f32)] =
+    &[(0., -8., 1., 16.), (-2., -8., 5., 1.), (-2., 7., 5., 1.)];
+
+/// The rectangles making up the cross drawn for crosshair and move cursors, relative to the
+/// hotspot.
+const CROSS: &[(f32, f32, f32, f32)] = &[(-7., 0., 15., 1.), (0., -7., 1., 15.)];
+
+/// Draw the given [`Cursor`] with its hotspot at `position`. This is used by `Painter`s whose
+/// `RenderingContext` has no OS cursor, such as offscreen contexts used for headless or remote
+/// sessions, so that captured frames still show pointer feedback. Only a few distinct shapes
+/// are drawn: an I-beam for text cursors, a cross for crosshair and move cursors and an arrow
+/// for all others. Each is drawn in black with a white outline, so that it is visible on any
+/// content.
+pub(crate) fn push_cursor(
+    builder: &mut DisplayListBuilder,
+    space_and_clip: SpaceAndClipInfo,
+    position: LayoutPoint,
+    cursor: Cursor,
+) {
+    let shape = match cursor {
+        Cursor::None => return,
+        Cursor::Text | Cursor::VerticalText => I_BEAM,
+        Cursor::Crosshair | Cursor::Cell | Cursor::Move | Cursor::AllScroll => CROSS,
+        _ => ARROW,
+    };
+
+    for (outline, color) in [(1.0, ColorF::WHITE), (0.0, ColorF::BLACK)] {
+        for (x, y, width, height) in shape {
+            let rect = LayoutRect::from_origin_and_size(
+                position + LayoutSize::new(*x, *y).to_vector(),
+                LayoutSize::new(*width, *height),
+            )
+            .inflate(outline, outline);
+            builder.push_rect(
+                &CommonItemProperties::new(rect, space_and_clip),
+                rect,
+                color,
+            );
+        }
+    }
+}
@@ -0,0 +1,123 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use std::path::PathBuf;
+use std::thread::{self, JoinHandle};
+use std::time::Instant;
+
+use compositing_traits::rendering_context::RenderingContext;
+use crossbeam_channel::{Sender, TrySendError};
+use image::RgbaImage;
+use log::warn;
+use webrender_api::units::DeviceIntRect;
+
+/// The number of recorded frames that may wait to be written before new frames are dropped.
+/// Each frame is a full-size uncompressed image, so this bounds the memory used by a recording
+/// when painting is faster than encoding.
+const MAX_QUEUED_FRAMES: usize = 8;
+
+/// Records every frame painted by a `Painter` to a directory as a sequence of numbered PNG
+/// images. The name of each image also contains the time at which it was painted, in
+/// milliseconds since the recording started, for example `frame-000042-001375ms.png`.
+///
+/// Images are encoded and written on a background thread, so that recording slows down
+/// painting as little as possible. If that thread falls behind by more than
+/// [`MAX_QUEUED_FRAMES`], new frames are dropped, which leaves gaps in the frame numbers.
+/// Dropping the [`FrameRecorder`] waits for all queued frames to be written.
+pub(crate) struct FrameRecorder {
+    sender: Option<Sender<(PathBuf, RgbaImage)>>,
+    join_handle: Option<JoinHandle<()>>,
+    directory: PathBuf,
+    start_time: Instant,
+    next_frame_number: usize,
+    /// The number of frames dropped because too many were waiting to be written.
+    dropped_frames: usize,
+}
+
+impl FrameRecorder {
+    pub(crate) fn new(directory: PathBuf) -> Self {
+        let (sender, receiver) =
+            crossbeam_channel::bounded::<(PathBuf, RgbaImage)>(MAX_QUEUED_FRAMES);
+        let thread_directory = directory.clone();
+        let join_handle = thread::Builder::new()
+            .name(String::from("FrameRecorder"))
+            .spawn(move || {
+                if let Err(error) = std::fs::create_dir_all(&thread_directory) {
+                    warn!(
+                        "Could not create frame recording directory {thread_directory:?}: {error}"
+                    );
+                    return;
+                }
+                for (path, image) in receiver {
+                    if let Err(error) = image.save_with_format(&path, image::ImageFormat::Png) {
+                        warn!("Could not save recorded frame to {path:?}: {error}");
+                    }
+                }
+            })
+            .expect("Could not create FrameRecorder thread.");
+
+        Self {
+            sender: Some(sender),
+            join_handle: Some(join_handle),
+            directory,
+            start_time: Instant::now(),
+            next_frame_number: 0,
+            dropped_frames: 0,
+        }
+    }
+
+    /// Read back the contents of the given [`RenderingContext`], which must have just been
+    /// painted, and queue them to be written as the next frame of the recording. The frame is
+    /// dropped if [`MAX_QUEUED_FRAMES`] frames are already waiting to be written.
+    pub(crate) fn record_frame(&mut self, rendering_context: &dyn RenderingContext) {
+        let Some(sender) = &self.sender else {
+            return;
+        };
+        // Skip reading back frames that would be dropped anyway.
+        if sender.is_full() {
+            self.drop_frame();
+            return;
+        }
+
+        let size = rendering_context.size2d().to_i32();
+        let Some(image) = rendering_context.read_to_image(DeviceIntRect::from_size(size)) else {
+            warn!("Could not read back frame for recording");
+            return;
+        };
+
+        let path = self.directory.join(format!(
+            "frame-{:06}-{:06}ms.png",
+            self.next_frame_number,
+            self.start_time.elapsed().as_millis()
+        ));
+        self.next_frame_number += 1;
+        if let Err(TrySendError::Full(_)) = sender.try_send((path, image)) {
+            self.drop_frame();
+        }
+    }
+
+    fn drop_frame(&mut self) {
+        if self.dropped_frames == 0 {
+            warn!("Dropping recorded frames, because they cannot be written fast enough");
+        }
+        self.dropped_frames += 1;
+        self.next_frame_number += 1;
+    }
+}
+
+impl Drop for FrameRecorder {
+    fn drop(&mut self) {
+        // Closing the channel makes the thread exit once it has written all queued frames.
+        self.sender = None;
+        if let Some(join_handle) = self.join_handle.take() {
+            let _ = join_handle.join();
+        }
+        if self.dropped_frames > 0 {
+            warn!(
+                "Dropped {} of {} recorded frames",
+                self.dropped_frames, self.next_frame_number
+            );
+        }
+    }
+}
@@ -0,0 +1,170 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use base::cross_process_instant::CrossProcessInstant;
+use base::id::WebViewId;
+use embedder_traits::InputEventId;
+use profile_traits::time::{ProfilerCategory, ProfilerChan, send_profile_data};
+use rustc_hash::FxHashMap;
+
+/// The number of most recent input events that an [`InputLatencyHistogram`] is built from.
+const MAX_LATENCY_SAMPLES: usize = 256;
+
+/// The maximum number of input events of a single `WebView` that are tracked while waiting
+/// for the frame that reflects them. Input events beyond this are not measured.
+const MAX_PENDING_INPUT_EVENTS: usize = 64;
+
+/// The upper bounds of the buckets of an [`InputLatencyHistogram`]. The last bucket of the
+/// histogram holds all latencies above the last bound.
+pub const INPUT_LATENCY_BUCKET_BOUNDS: [Duration; 6] = [
+    Duration::from_millis(8),
+    Duration::from_millis(16),
+    Duration::from_millis(33),
+    Duration::from_millis(66),
+    Duration::from_millis(100),
+    Duration::from_millis(250),
+];
+
+/// A histogram of the time between input events for a `WebView` reaching the renderer and
+/// the frame that reflects them being presented, built from the most recent input events.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct InputLatencyHistogram {
+    /// The number of input events in each bucket. The bucket at index `i` holds latencies
+    /// up to `INPUT_LATENCY_BUCKET_BOUNDS[i]` and above the previous bound, while the last
+    /// bucket holds all latencies above the last bound.
+    pub bucket_counts: [usize; INPUT_LATENCY_BUCKET_BOUNDS.len() + 1],
+    /// The mean latency of the input events in the histogram.
+    pub mean: Duration,
+    /// The highest latency of the input events in the histogram.
+    pub max: Duration,
+}
+
+impl InputLatencyHistogram {
+    fn new(samples: &VecDeque<Duration>) -> Self {
+        let mut histogram = Self::default();
+        if samples.is_empty() {
+            return histogram;
+        }
+        for sample in samples {
+            let bucket = INPUT_LATENCY_BUCKET_BOUNDS
+                .iter()
+                .position(|bound| sample <= bound)
+                .unwrap_or(INPUT_LATENCY_BUCKET_BOUNDS.len());
+            histogram.bucket_counts[bucket] += 1;
+            histogram.max = histogram.max.max(*sample);
+        }
+        histogram.mean = samples.iter().sum::<Duration>() / samples.len() as u32;
+        histogram
+    }
+
+    /// The total number of input events in the histogram.
+    pub fn sample_count(&self) -> usize {
+        self.bucket_counts.iter().sum()
+    }
+}
+
+/// An input event that has reached the renderer, but whose effects have not been presented
+/// yet.
+struct PendingInputEvent {
+    id: InputEventId,
+    /// When the input event reached the renderer.
+    received: CrossProcessInstant,
+    /// The first frame that can reflect the handling of this input event, once it has been
+    /// handled by the page. See `Painter::latest_requested_frame`.
+    frame: Option<u64>,
+}
+
+/// Measures the latency between input events reaching a `Painter` and the presentation of
+/// the frame that reflects them, per `WebView`.
+#[derive(Default)]
+pub(crate) struct InputLatencyTracker {
+    pending_input_events: FxHashMap<WebViewId, Vec<PendingInputEvent>>,
+    samples: FxHashMap<WebViewId, VecDeque<Duration>>,
+}
+
+impl InputLatencyTracker {
+    pub(crate) fn input_event_received(&mut self, webview_id: WebViewId, id: InputEventId) {
+        let pending_input_events = self.pending_input_events.entry(webview_id).or_default();
+        if pending_input_events.len() >= MAX_PENDING_INPUT_EVENTS {
+            return;
+        }
+        pending_input_events.push(PendingInputEvent {
+            id,
+            received: CrossProcessInstant::now(),
+            frame: None,
+        });
+    }
+
+    /// Note that the page has handled the given input event, so that any changes that it
+    /// caused will be part of `frame` or a later one.
+    pub(crate) fn input_event_handled(
+        &mut self,
+        webview_id: WebViewId,
+        id: InputEventId,
+        frame: u64,
+    ) {
+        let Some(pending_input_event) =
+            self.pending_input_events
+                .get_mut(&webview_id)
+                .and_then(|pending_input_events| {
+                    pending_input_events
+                        .iter_mut()
+                        .find(|pending_input_event| pending_input_event.id == id)
+                })
+        else {
+            return;
+        };
+        pending_input_event.frame = Some(frame);
+    }
+
+    /// Record the latency of every handled input event whose frame is now presented and report
+    /// it to the time profiler.
+    pub(crate) fn frame_presented(
+        &mut self,
+        frame_is_ready: impl Fn(u64) -> bool,
+        time_profiler_channel: &ProfilerChan,
+    ) {
+        let presented = CrossProcessInstant::now();
+        for (webview_id, pending_input_events) in self.pending_input_events.iter_mut() {
+            pending_input_events.retain(|pending_input_event| {
+                if !pending_input_event.frame.is_some_and(&frame_is_ready) {
+                    return true;
+                }
+
+                send_profile_data(
+                    ProfilerCategory::InputToPresentLatency,
+                    None,
+                    time_profiler_channel,
+                    pending_input_event.received,
+                    presented,
+                );
+                let samples = self.samples.entry(*webview_id).or_default();
+                if samples.len() >= MAX_LATENCY_SAMPLES {
+                    samples.pop_front();
+                }
+                samples.push_back(
+                    (presented - pending_input_event.received)
+                        .try_into()
+                        .unwrap_or_default(),
+                );
+                false
+            });
+        }
+    }
+
+    pub(crate) fn histogram(&self, webview_id: WebViewId) -> InputLatencyHistogram {
+        self.samples
+            .get(&webview_id)
+            .map(InputLatencyHistogram::new)
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn remove_webview(&mut self, webview_id: WebViewId) {
+        self.pending_input_events.remove(&webview_id);
+        self.samples.remove(&webview_id);
+    }
+}
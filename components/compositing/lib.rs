@@ -16,21 +16,41 @@ use profile_traits::{mem, time};
 #[cfg(feature = "webxr")]
 use webxr::WebXrRegistry;
 
-pub use crate::paint::{Paint, WebRenderDebugOption};
+pub use crate::capture_stream::CapturedFrame;
+pub use crate::input_latency::{INPUT_LATENCY_BUCKET_BOUNDS, InputLatencyHistogram};
+pub use crate::paint::{
+    DisplayListStatistics, OcclusionNotifier, Paint, PaintBackground, PaintError,
+    PainterDebugState, PainterOptions, RenderingPriority, ShaderPrecachePolicy, TextAntialiasing,
+    WebRenderDebugOption, WebViewBorder, WebViewClip, WebViewDecorations, WebViewLifecycleEvent,
+    WebViewPaintTiming, WebViewShadow,
+};
+pub use crate::recording::{PaintRecording, RecordedPaintEvent, RecordedPaintEventKind};
+pub use crate::screenshot::SharedMemoryCapture;
+pub use crate::tracing::PaintMessageTraceEntry;
 
 #[macro_use]
 mod tracing;
 
+mod capture_stream;
+mod cursor_layer;
+mod frame_recorder;
+mod input_latency;
 mod largest_contentful_paint_calculator;
+mod overlay_scrollbars;
+mod page_zoom;
 mod paint;
 mod painter;
 mod pinch_zoom;
 mod pipeline_details;
+mod recording;
 mod refresh_driver;
 mod render_notifier;
 mod screenshot;
+mod smooth_scroll;
+mod synchronized_resize;
 mod touch;
 mod webrender_external_images;
+mod webrender_instance;
 mod webview_renderer;
 
 /// Data used to initialize the `Paint` subsystem.
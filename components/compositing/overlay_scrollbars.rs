@@ -0,0 +1,145 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use std::time::{Duration, Instant};
+
+use compositing_traits::display_list::ScrollableNodeInfo;
+use servo_config::pref;
+use webrender_api::units::{LayoutPoint, LayoutRect, LayoutSize, LayoutVector2D};
+use webrender_api::{ColorF, CommonItemProperties, DisplayListBuilder, SpaceAndClipInfo};
+
+use crate::painter::Painter;
+use crate::refresh_driver::RefreshDriverObserver;
+
+/// How long overlay scrollbars stay fully visible after the last scroll.
+const VISIBLE_DURATION: Duration = Duration::from_millis(500);
+
+/// How long overlay scrollbars take to fade out once they are no longer fully visible.
+const FADE_OUT_DURATION: Duration = Duration::from_millis(300);
+
+/// The minimum length of a scrollbar thumb, in device pixels, so that it stays visible on
+/// very long pages.
+const MINIMUM_THUMB_LENGTH: f32 = 20.0;
+
+/// The distance between a scrollbar thumb and the edge of the scroll frame, in device pixels.
+const THUMB_MARGIN: f32 = 2.0;
+
+/// The opacity of the overlay scrollbars of a scroll frame that was last scrolled at
+/// `last_scrolled`, or `None` if they have completely faded out.
+pub(crate) fn opacity_at(last_scrolled: Instant, now: Instant) -> Option<f32> {
+    let elapsed = now.saturating_duration_since(last_scrolled);
+    if elapsed <= VISIBLE_DURATION {
+        return Some(1.0);
+    }
+    let fade_elapsed = elapsed - VISIBLE_DURATION;
+    if fade_elapsed >= FADE_OUT_DURATION {
+        return None;
+    }
+    Some(1.0 - fade_elapsed.as_secs_f32() / FADE_OUT_DURATION.as_secs_f32())
+}
+
+/// Draw the thumbs of the vertical and horizontal overlay scrollbars of the given scroll
+/// frame, for each direction in which its content overflows. `origin` is the position of
+/// the scroll frame in the space of `space_and_clip`, and `scale` is the number of device
+/// pixels per unit of that space, so that the thumbs keep the width set by the
+/// `overlay_scrollbars_width` preference at any zoom level.
+pub(crate) fn push_overlay_scrollbars(
+    builder: &mut DisplayListBuilder,
+    space_and_clip: SpaceAndClipInfo,
+    scroll_info: &ScrollableNodeInfo,
+    origin: LayoutVector2D,
+    scale: f32,
+    opacity: f32,
+) {
+    let [red, green, blue, alpha] = pref!(overlay_scrollbars_color_rgba);
+    let color = ColorF::new(
+        red as f32,
+        green as f32,
+        blue as f32,
+        alpha as f32 * opacity,
+    );
+    let width = pref!(overlay_scrollbars_width) as f32 / scale;
+    let margin = THUMB_MARGIN / scale;
+    let minimum_length = MINIMUM_THUMB_LENGTH / scale;
+
+    let frame = scroll_info.clip_rect.translate(origin);
+    let content_size = scroll_info.content_rect.size();
+    let frame_size = frame.size();
+
+    if content_size.height > frame_size.height {
+        let track_length = frame_size.height - 2.0 * margin;
+        let (thumb_start, thumb_length) = thumb_extent(
+            track_length,
+            frame_size.height,
+            content_size.height,
+            scroll_info.offset.y,
+            minimum_length,
+        );
+        let rect = LayoutRect::from_origin_and_size(
+            LayoutPoint::new(
+                frame.max.x - margin - width,
+                frame.min.y + margin + thumb_start,
+            ),
+            LayoutSize::new(width, thumb_length),
+        );
+        builder.push_rect(
+            &CommonItemProperties::new(rect, space_and_clip),
+            rect,
+            color,
+        );
+    }
+
+    if content_size.width > frame_size.width {
+        let track_length = frame_size.width - 2.0 * margin;
+        let (thumb_start, thumb_length) = thumb_extent(
+            track_length,
+            frame_size.width,
+            content_size.width,
+            scroll_info.offset.x,
+            minimum_length,
+        );
+        let rect = LayoutRect::from_origin_and_size(
+            LayoutPoint::new(
+                frame.min.x + margin + thumb_start,
+                frame.max.y - margin - width,
+            ),
+            LayoutSize::new(thumb_length, width),
+        );
+        builder.push_rect(
+            &CommonItemProperties::new(rect, space_and_clip),
+            rect,
+            color,
+        );
+    }
+}
+
+/// The start and length of a scrollbar thumb along a track of `track_length`, for a scroll
+/// frame of `frame_length` showing content of `content_length` scrolled by `offset`.
+fn thumb_extent(
+    track_length: f32,
+    frame_length: f32,
+    content_length: f32,
+    offset: f32,
+    minimum_length: f32,
+) -> (f32, f32) {
+    let thumb_length = (track_length * frame_length / content_length)
+        .max(minimum_length)
+        .min(track_length);
+    let scroll_range = content_length - frame_length;
+    let progress = if scroll_range > 0.0 {
+        (offset / scroll_range).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    ((track_length - thumb_length) * progress, thumb_length)
+}
+
+/// Redraws the overlay scrollbars of a [`Painter`] on every frame while they fade out.
+pub(crate) struct OverlayScrollbarsRefreshDriverObserver;
+
+impl RefreshDriverObserver for OverlayScrollbarsRefreshDriverObserver {
+    fn frame_started(&self, painter: &mut Painter) -> bool {
+        painter.update_overlay_scrollbars_at_new_frame_start()
+    }
+}
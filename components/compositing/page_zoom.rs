@@ -0,0 +1,28 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use std::time::Duration;
+
+use base::id::WebViewId;
+
+use crate::painter::Painter;
+use crate::refresh_driver::RefreshDriverObserver;
+
+/// How long the page zoom of a `WebView` needs to stay unchanged before the page is laid out
+/// again at the new zoom level. Until then, the contents laid out at the previous zoom level
+/// are scaled to the new one, so that zooming quickly, for instance with the mouse wheel,
+/// stays responsive and only leads to a single reflow at the final zoom level.
+pub(crate) const PAGE_ZOOM_REFLOW_DELAY: Duration = Duration::from_millis(150);
+
+/// Waits for the page zoom of a `WebView` to settle, in order to lay out its page at the
+/// final zoom level.
+pub(crate) struct PageZoomRefreshDriverObserver {
+    pub webview_id: WebViewId,
+}
+
+impl RefreshDriverObserver for PageZoomRefreshDriverObserver {
+    fn frame_started(&self, painter: &mut Painter) -> bool {
+        painter.update_page_zoom_at_new_frame_start(self.webview_id)
+    }
+}
@@ -2,30 +2,36 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
 
-use std::cell::{Cell, Ref, RefCell, RefMut};
+use std::cell::{Cell, OnceCell, Ref, RefCell, RefMut};
+use std::cmp::Reverse;
 use std::collections::HashMap;
 use std::env;
 use std::fs::create_dir_all;
+use std::path::PathBuf;
 use std::rc::Rc;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::sync::Arc;
+use std::sync::mpsc::Receiver;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
+use base::cross_process_instant::CrossProcessInstant;
 use base::generic_channel::{GenericSender, RoutedReceiver};
 use base::id::{PainterId, PipelineId, WebViewId};
 use bitflags::bitflags;
 use canvas_traits::webgl::{WebGLContextId, WebGLThreads};
 use compositing_traits::rendering_context::RenderingContext;
 use compositing_traits::{
-    PaintMessage, PaintProxy, PainterSurfmanDetails, PainterSurfmanDetailsMap,
+    PaintMessage, PaintProxy, PainterSurfmanDetails, PainterSurfmanDetailsMap, RendererStatistics,
     WebRenderExternalImageIdManager, WebViewTrait,
 };
 use constellation_traits::EmbedderToConstellationMessage;
 use crossbeam_channel::Sender;
 use dpi::PhysicalSize;
 use embedder_traits::{
-    EventLoopWaker, InputEventAndId, InputEventId, InputEventResult, ScreenshotCaptureError,
-    Scroll, ShutdownState, ViewportDetails, WebViewPoint, WebViewRect,
+    Cursor, EventLoopWaker, InputEvent, InputEventAndId, InputEventId, InputEventResult,
+    PaintHitTestResult, ScreenshotCaptureError, Scroll, ShutdownState, ViewportDetails,
+    WebViewPoint, WebViewRect,
 };
-use euclid::{Scale, Size2D};
+use euclid::{Scale, Size2D, Transform2D};
 use image::RgbaImage;
 use ipc_channel::ipc::{self};
 use log::{debug, warn};
@@ -43,20 +49,282 @@ use webgl::WebGLComm;
 use webgl::webgl_thread::WebGLContextBusyMap;
 #[cfg(feature = "webgpu")]
 use webgpu::canvas_context::WebGpuExternalImageMap;
-use webrender::{CaptureBits, MemoryReport};
-use webrender_api::units::{DevicePixel, DevicePoint};
-use webrender_api::{FontInstanceKey, FontKey, ImageKey};
+use webrender::{CaptureBits, RendererError};
+use webrender_api::units::{DevicePixel, DevicePoint, DeviceRect, DeviceVector2D, LayoutVector2D};
+use webrender_api::{BorderRadius, ColorF, DocumentId, FontInstanceKey, FontKey, ImageKey};
 
 use crate::InitialPaintState;
+use crate::capture_stream::CapturedFrame;
+use crate::input_latency::InputLatencyHistogram;
 use crate::painter::Painter;
-use crate::webview_renderer::UnknownWebView;
+use crate::recording::{PaintRecorder, PaintRecording, RecordedPaintEventKind};
+use crate::screenshot::SharedMemoryCapture;
+use crate::tracing::{LogTarget, MessageTarget, PaintMessageTrace, PaintMessageTraceEntry};
+use crate::webview_renderer::{UnknownWebView, WebViewRenderer};
 
 /// An option to control what kind of WebRender debugging is enabled while Servo is running.
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub enum WebRenderDebugOption {
     Profiler,
     TextureCacheDebug,
     RenderTargetDebug,
+    /// Measure the GPU time of each frame, without showing the profiler overlay.
+    GpuTimeQueries,
+}
+
+impl WebRenderDebugOption {
+    pub(crate) const ALL: [Self; 4] = [
+        Self::Profiler,
+        Self::TextureCacheDebug,
+        Self::RenderTargetDebug,
+        Self::GpuTimeQueries,
+    ];
+}
+
+/// An error that happens when [`Paint`] is asked to operate on a [`Painter`] or `WebView`
+/// that it does not know about. This can happen when a `WebView` races with the teardown
+/// of the [`Painter`] for its [`RenderingContext`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PaintError {
+    /// There is no [`Painter`] with the given [`PainterId`]. This can happen if the
+    /// [`RenderingContext`] for the [`Painter`] has been unregistered.
+    UnknownPainter(PainterId),
+    /// The [`Painter`] for the `WebView` with the given [`WebViewId`] does not know about it.
+    UnknownWebView(WebViewId),
+    /// An operation involving two `WebView`s was requested, but they are painted by
+    /// different [`Painter`]s.
+    DifferentPainters(WebViewId, WebViewId),
+    /// A `WebView` cannot be mirrored into the [`Painter`] that already paints it.
+    MirrorIntoOwnPainter(WebViewId),
+    /// The `WebView` with the given [`WebViewId`] was already added to its [`Painter`].
+    WebViewAlreadyAdded(WebViewId),
+}
+
+/// A change in the state of a `WebView` in [`Paint`]. These are queued as they happen and
+/// can be retrieved with [`Paint::take_webview_lifecycle_events`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WebViewLifecycleEvent {
+    /// The `WebView` was added to the [`Painter`] for its [`RenderingContext`].
+    Added,
+    /// The `WebView` was removed, either because it was closed or because its
+    /// [`RenderingContext`] was unregistered.
+    Removed,
+    /// The `WebView` was shown.
+    Shown,
+    /// The `WebView` was hidden.
+    Hidden,
+    /// The `WebView` moved within the painting order of its [`RenderingContext`], for
+    /// instance because it was raised above another `WebView`.
+    PaintingOrderChanged,
+    /// The `WebView` was suspended to stay within the limit set with
+    /// [`Paint::set_webview_limit`]. It is throttled until it is resumed.
+    Suspended,
+    /// The `WebView` was resumed after being suspended, because it was shown again or the
+    /// limit was lifted.
+    Resumed,
+}
+
+/// Options used when creating the WebRender instance of a [`Painter`]. Every option that is
+/// `None` uses Servo's default value.
+#[derive(Clone, Debug, Default)]
+pub struct PainterOptions {
+    /// Whether to use optimized shaders. Defaults to `true`, because unoptimized shaders do
+    /// not work on some Android emulators.
+    pub use_optimized_shaders: Option<bool>,
+    /// Whether to panic when WebRender encounters an OpenGL error. Defaults to `false`.
+    pub panic_on_gl_error: Option<bool>,
+    /// When to compile the shaders of the WebRender instance. Defaults to
+    /// [`ShaderPrecachePolicy::Full`] if the `gfx_precache_shaders` preference is enabled and
+    /// to [`ShaderPrecachePolicy::Lazy`] otherwise.
+    pub shader_precache_policy: Option<ShaderPrecachePolicy>,
+    /// Whether to upload textures immediately rather than through pixel buffer objects.
+    /// Defaults to `true` only when running on ANGLE.
+    pub immediate_texture_upload: Option<bool>,
+}
+
+/// A clip applied to a `WebView` when compositing it into its [`RenderingContext`]. Only the
+/// parts of the `WebView` inside of `rect` are painted and receive input events. See
+/// [`Paint::set_webview_clip`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct WebViewClip {
+    /// The clip rectangle in device pixels, relative to the origin of the `WebView`'s
+    /// rectangle.
+    pub rect: DeviceRect,
+    /// The radii of the corners of the clip rectangle in device pixels.
+    pub radii: BorderRadius,
+}
+
+/// A solid border painted around the outside of a `WebView`. See [`WebViewDecorations`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct WebViewBorder {
+    /// The width of the border in device pixels.
+    pub width: f32,
+    /// The color of the border.
+    pub color: ColorF,
+}
+
+/// A drop shadow painted below a `WebView`. See [`WebViewDecorations`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct WebViewShadow {
+    /// The offset of the shadow from the `WebView` in device pixels.
+    pub offset: DeviceVector2D,
+    /// The blur radius of the shadow in device pixels.
+    pub blur_radius: f32,
+    /// How far the shadow extends beyond the `WebView` in every direction in device pixels,
+    /// before blurring.
+    pub spread_radius: f32,
+    /// The color of the shadow.
+    pub color: ColorF,
+}
+
+/// Decorations painted by [`Paint`] around a `WebView`, outside of its content. They surround
+/// the visible part of the `WebView`, which is its clip if it has one, and follow its
+/// compositing transformation and opacity. See [`Paint::set_webview_decorations`].
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct WebViewDecorations {
+    /// A border painted directly around the `WebView`.
+    pub border: Option<WebViewBorder>,
+    /// A border painted around the outside of `border`, meant to indicate focus.
+    pub focus_ring: Option<WebViewBorder>,
+    /// A drop shadow painted below the `WebView`.
+    pub shadow: Option<WebViewShadow>,
+}
+
+/// The priority of a [`Painter`] when generating frames. See [`Paint::set_painter_priority`].
+#[derive(Clone, Copy, Debug, Default, Eq, Ord, PartialEq, PartialOrd)]
+pub enum RenderingPriority {
+    /// For background windows, whose frames may be delayed.
+    Low,
+    /// The default priority.
+    #[default]
+    Normal,
+    /// For the focused window.
+    High,
+}
+
+/// The times at which the page currently displayed in a `WebView` was first painted by its
+/// [`Painter`]. See [`Paint::webview_paint_timing`].
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct WebViewPaintTiming {
+    /// The time at which the first display list of the page was painted.
+    pub first_paint: Option<CrossProcessInstant>,
+    /// The time at which the first display list of the page with contentful items, such as
+    /// text or images, was painted.
+    pub first_contentful_paint: Option<CrossProcessInstant>,
+}
+
+/// The size of the display lists of a `WebView`, which determines how much work WebRender
+/// has to do to build its scene. See [`Paint::display_list_statistics`].
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct DisplayListStatistics {
+    /// The total size in bytes of the serialized display lists currently installed for all
+    /// of the pipelines of the `WebView`.
+    pub size_in_bytes: usize,
+    /// The total number of display items in those display lists.
+    pub item_count: usize,
+    /// The size in bytes of the display lists received for the `WebView` since the frame
+    /// before its most recently painted frame.
+    pub last_frame_size_in_bytes: usize,
+    /// The number of display items in the display lists received for the `WebView` since
+    /// the frame before its most recently painted frame.
+    pub last_frame_item_count: usize,
+}
+
+/// When a [`Painter`] compiles the shaders of its WebRender instance. See
+/// [`PainterOptions::shader_precache_policy`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ShaderPrecachePolicy {
+    /// Compile shaders when they are first used. This makes creating a [`Painter`] fast, but
+    /// its first frames may stutter.
+    Lazy,
+    /// Start compiling all shaders when the [`Painter`] is created, without waiting for the
+    /// OpenGL driver to finish. Drivers that compile shaders in parallel do so in the
+    /// background. Use [`Paint::warm_up_painter`] to wait for the compilation to finish.
+    Async,
+    /// Compile all shaders and wait for them to be ready when the [`Painter`] is created.
+    Full,
+}
+
+/// How a [`Painter`] antialiases text. See [`Paint::set_painter_text_antialiasing`].
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum TextAntialiasing {
+    /// Subpixel antialiasing, if enabled by the `gfx_subpixel_text_antialiasing_enabled`
+    /// preference, and grayscale antialiasing otherwise.
+    #[default]
+    Subpixel,
+    /// Grayscale antialiasing. This should be used when painting to transparent or rotated
+    /// surfaces, where subpixel antialiasing produces color fringes.
+    Grayscale,
+    /// No antialiasing.
+    None,
+}
+
+/// Content painted below all of the `WebView`s of a [`Painter`], filling its entire
+/// [`RenderingContext`]. This is visible before the first `WebView` is ready and wherever
+/// `WebView`s do not cover the [`RenderingContext`]. See [`Paint::set_painter_background`].
+#[derive(Clone, Debug, Default)]
+pub enum PaintBackground {
+    /// Nothing is painted, so only the clear color is visible.
+    #[default]
+    None,
+    /// A solid color.
+    Color(ColorF),
+    /// A linear gradient from `start` to `end`, which are in device pixels relative to the
+    /// top-left corner of the [`RenderingContext`]. Each stop is a pair of an offset between
+    /// `0.0` and `1.0` and the color at that offset.
+    LinearGradient {
+        start: DevicePoint,
+        end: DevicePoint,
+        stops: Vec<(f32, ColorF)>,
+    },
+    /// An image with premultiplied alpha, stretched to fill the [`RenderingContext`].
+    Image(Arc<RgbaImage>),
+}
+
+/// A snapshot of the state of a single [`Painter`], returned by [`Paint::debug_state`]. This
+/// is meant for diagnostics and its contents may change at any time.
+#[derive(Clone, Debug)]
+pub struct PainterDebugState {
+    /// The [`PainterId`] of the [`Painter`].
+    pub painter_id: PainterId,
+    /// The WebRender document that the [`Painter`] renders.
+    pub webrender_document: DocumentId,
+    /// The `WebView`s of this [`Painter`] in painting order, from bottom to top.
+    pub painting_order: Vec<WebViewId>,
+    /// The `WebView`s of this [`Painter`] that are currently hidden.
+    pub hidden_webviews: Vec<WebViewId>,
+    /// Whether or not frame generation is currently throttled for this [`Painter`].
+    pub throttled: bool,
+    /// The [`RenderingPriority`] used when scheduling the frames of this [`Painter`].
+    pub priority: RenderingPriority,
+    /// The WebRender debugging options enabled for this [`Painter`].
+    pub webrender_debug_options: Vec<WebRenderDebugOption>,
+}
+
+/// Reports the occlusion of the `RenderingContext` of a single [`Painter`] to [`Paint`]. Unlike
+/// [`Paint`] itself, this can be sent to and used from any thread, so that embedders can
+/// forward occlusion and minimization events from the operating system as soon as they
+/// receive them. See [`Paint::occlusion_notifier`].
+#[derive(Clone)]
+pub struct OcclusionNotifier {
+    paint_proxy: PaintProxy,
+    painter_id: PainterId,
+}
+
+impl OcclusionNotifier {
+    /// Set whether or not the `RenderingContext` is occluded. While it is, its [`Painter`]
+    /// generates and presents no new frames and animations of its `WebView`s do not tick.
+    /// Other [`Painter`]s are not affected.
+    pub fn set_occluded(&self, occluded: bool) {
+        self.paint_proxy
+            .send(PaintMessage::SetOccluded(self.painter_id, occluded));
+    }
+}
+
+impl From<UnknownWebView> for PaintError {
+    fn from(unknown_webview: UnknownWebView) -> Self {
+        Self::UnknownWebView(unknown_webview.0)
+    }
 }
 
 /// [`Paint`] is Servo's rendering subsystem. It has a few responsibilities:
@@ -85,6 +353,9 @@ pub struct Paint {
     /// a single [`RenderingContext`].
     painters: Vec<Rc<RefCell<Painter>>>,
 
+    /// The [`Painter`] of the focused window, if any. See [`Paint::set_focused_painter`].
+    focused_painter: Cell<Option<PainterId>>,
+
     /// A [`PaintProxy`] which can be used to allow other parts of Servo to communicate
     /// with this [`Paint`].
     pub(crate) paint_proxy: PaintProxy,
@@ -135,6 +406,23 @@ pub struct Paint {
     /// An map of external images shared between all `WebGpuExternalImages`.
     #[cfg(feature = "webgpu")]
     webgpu_image_map: std::cell::OnceCell<WebGpuExternalImageMap>,
+
+    /// The worker thread pool shared by the WebRender instances of all [`Painter`]s. WebRender
+    /// uses these threads to rasterize glyphs and blob images, so sharing them avoids spawning
+    /// a new set of threads for every `RenderingContext`.
+    webrender_workers: OnceCell<Arc<rayon::ThreadPool>>,
+
+    /// [`WebViewLifecycleEvent`]s that have happened since the last call to
+    /// [`Paint::take_webview_lifecycle_events`].
+    webview_lifecycle_events: RefCell<Vec<(WebViewId, WebViewLifecycleEvent)>>,
+
+    /// The most recent messages handled by this [`Paint`], recorded while message tracing is
+    /// enabled. See [`Paint::set_message_tracing_enabled`].
+    message_trace: PaintMessageTrace,
+
+    /// Records messages, input events and new frames while recording is enabled. See
+    /// [`Paint::start_recording`].
+    recorder: RefCell<Option<PaintRecorder>>,
 }
 
 /// Why we need to be repainted. This is used for debugging.
@@ -153,6 +441,8 @@ bitflags! {
         const Resize = 1 << 3;
         /// A fling has started and a repaint needs to happen to process the animation.
         const StartedFlinging = 1 << 4;
+        /// A smooth scroll has started and a repaint needs to happen to process the animation.
+        const StartedSmoothScrolling = 1 << 5;
     }
 }
 
@@ -196,6 +486,7 @@ impl Paint {
 
         Rc::new(RefCell::new(Paint {
             painters: Default::default(),
+            focused_painter: Default::default(),
             paint_proxy: state.paint_proxy,
             event_loop_waker: state.event_loop_waker,
             shutdown_state: state.shutdown_state,
@@ -212,6 +503,10 @@ impl Paint {
             webxr_main_thread: RefCell::new(webxr_main_thread),
             #[cfg(feature = "webgpu")]
             webgpu_image_map: Default::default(),
+            webrender_workers: Default::default(),
+            webview_lifecycle_events: Default::default(),
+            message_trace: Default::default(),
+            recorder: Default::default(),
         }))
     }
 
@@ -219,18 +514,36 @@ impl Paint {
         &mut self,
         rendering_context: Rc<dyn RenderingContext>,
     ) -> PainterId {
-        if let Some(painter_id) = self.painters.iter().find_map(|painter| {
-            let painter = painter.borrow();
-            if Rc::ptr_eq(&painter.rendering_context, &rendering_context) {
-                Some(painter.painter_id)
-            } else {
-                None
-            }
-        }) {
+        self.register_rendering_context_with_options(rendering_context, &Default::default())
+    }
+
+    /// Like [`Paint::register_rendering_context`], but create the WebRender instance of a new
+    /// [`Painter`] using the given [`PainterOptions`]. The options are ignored if there is
+    /// already a [`Painter`] for `rendering_context`.
+    pub fn register_rendering_context_with_options(
+        &mut self,
+        rendering_context: Rc<dyn RenderingContext>,
+        options: &PainterOptions,
+    ) -> PainterId {
+        if let Some(painter_id) = self.painter_id_for_rendering_context(&rendering_context) {
             return painter_id;
         }
 
-        let painter = Painter::new(rendering_context.clone(), self);
+        let painter = Painter::new(rendering_context.clone(), self, options);
+        self.insert_painter_surfman_details(painter.painter_id, &rendering_context);
+
+        let painter_id = painter.painter_id;
+        self.painters.push(Rc::new(RefCell::new(painter)));
+        painter_id
+    }
+
+    /// Remember the surfman connection and adapter of the [`RenderingContext`] of the
+    /// [`Painter`] with the given [`PainterId`], so that WebGL can create compatible contexts.
+    fn insert_painter_surfman_details(
+        &self,
+        painter_id: PainterId,
+        rendering_context: &Rc<dyn RenderingContext>,
+    ) {
         let connection = rendering_context
             .connection()
             .expect("Failed to get connection");
@@ -243,17 +556,102 @@ impl Paint {
             adapter,
         };
         self.painter_surfman_details_map
-            .insert(painter.painter_id, painter_surfman_details);
+            .insert(painter_id, painter_surfman_details);
+    }
 
-        let painter_id = painter.painter_id;
-        self.painters.push(Rc::new(RefCell::new(painter)));
-        painter_id
+    /// Make the [`Painter`] with the given [`PainterId`] paint to `rendering_context` from now
+    /// on. When the new `RenderingContext` uses the same OpenGL context as the old one, the
+    /// WebRender instance of the [`Painter`] is kept. Otherwise it is recreated for the new
+    /// `RenderingContext` and everything that the [`Painter`] painted is sent to it again.
+    pub fn replace_rendering_context(
+        &self,
+        painter_id: PainterId,
+        rendering_context: Rc<dyn RenderingContext>,
+    ) -> Result<(), PaintError> {
+        self.painter_mut(painter_id)?
+            .replace_rendering_context(rendering_context.clone());
+        self.insert_painter_surfman_details(painter_id, &rendering_context);
+        Ok(())
+    }
+
+    /// Remove the [`Painter`] for the given [`PainterId`]. Every `WebView` painted by this
+    /// [`Painter`] is closed and all of its WebRender resources, including the WebRender
+    /// document and renderer, are released. After this the [`Painter`] no longer holds a
+    /// reference to its [`RenderingContext`].
+    pub fn unregister_rendering_context(&mut self, painter_id: PainterId) {
+        {
+            let Some(painter) = self.maybe_painter(painter_id) else {
+                warn!("Tried unregistering unknown RenderingContext: {painter_id:?}");
+                return;
+            };
+            for webview_id in painter.painting_order() {
+                painter.send_to_constellation(EmbedderToConstellationMessage::CloseWebView(
+                    *webview_id,
+                ));
+                self.queue_webview_lifecycle_event(*webview_id, WebViewLifecycleEvent::Removed);
+            }
+        }
+
+        self.remove_painter(painter_id);
+    }
+
+    /// Set whether or not the `RenderingContext` of the [`Painter`] with the given
+    /// [`PainterId`] is occluded, for instance because its window is minimized. No new
+    /// frames are generated or presented for occluded `RenderingContext`s and animations of
+    /// their `WebView`s do not tick. See also [`Paint::occlusion_notifier`].
+    pub fn set_rendering_context_occluded(
+        &self,
+        painter_id: PainterId,
+        occluded: bool,
+    ) -> Result<(), PaintError> {
+        self.painter_mut(painter_id)?.set_occluded(occluded);
+        Ok(())
+    }
+
+    /// Get an [`OcclusionNotifier`] for the [`Painter`] with the given [`PainterId`], which
+    /// can report the occlusion of its `RenderingContext` from any thread.
+    pub fn occlusion_notifier(&self, painter_id: PainterId) -> OcclusionNotifier {
+        OcclusionNotifier {
+            paint_proxy: self.paint_proxy.clone(),
+            painter_id,
+        }
+    }
+
+    /// Pause or resume rendering for the [`Painter`] with the given [`PainterId`]. While
+    /// paused, no transactions are sent to its WebRender instance and nothing is rendered.
+    /// Transactions produced in the meantime are sent when rendering resumes.
+    pub fn set_rendering_paused(
+        &self,
+        painter_id: PainterId,
+        paused: bool,
+    ) -> Result<(), PaintError> {
+        self.painter_mut(painter_id)?.set_paused(paused);
+        Ok(())
+    }
+
+    /// Get the [`PainterId`] of the [`Painter`] that paints to the given [`RenderingContext`],
+    /// if there is one.
+    pub fn painter_id_for_rendering_context(
+        &self,
+        rendering_context: &Rc<dyn RenderingContext>,
+    ) -> Option<PainterId> {
+        self.painters.iter().find_map(|painter| {
+            let painter = painter.borrow();
+            if Rc::ptr_eq(&painter.rendering_context, rendering_context) {
+                Some(painter.painter_id)
+            } else {
+                None
+            }
+        })
     }
 
     fn remove_painter(&mut self, painter_id: PainterId) {
         self.painters
             .retain(|painter| painter.borrow().painter_id != painter_id);
         self.painter_surfman_details_map.remove(painter_id);
+        if self.focused_painter.get() == Some(painter_id) {
+            self.focused_painter.set(None);
+        }
     }
 
     pub(crate) fn maybe_painter<'a>(&'a self, painter_id: PainterId) -> Option<Ref<'a, Painter>> {
@@ -263,9 +661,12 @@ impl Paint {
             .find(|painter| painter.painter_id == painter_id)
     }
 
-    pub(crate) fn painter<'a>(&'a self, painter_id: PainterId) -> Ref<'a, Painter> {
+    pub(crate) fn painter<'a>(
+        &'a self,
+        painter_id: PainterId,
+    ) -> Result<Ref<'a, Painter>, PaintError> {
         self.maybe_painter(painter_id)
-            .expect("painter_id not found")
+            .ok_or(PaintError::UnknownPainter(painter_id))
     }
 
     pub(crate) fn maybe_painter_mut<'a>(
@@ -278,17 +679,106 @@ impl Paint {
             .find(|painter| painter.painter_id == painter_id)
     }
 
-    pub(crate) fn painter_mut<'a>(&'a self, painter_id: PainterId) -> RefMut<'a, Painter> {
+    pub(crate) fn painter_mut<'a>(
+        &'a self,
+        painter_id: PainterId,
+    ) -> Result<RefMut<'a, Painter>, PaintError> {
         self.maybe_painter_mut(painter_id)
-            .expect("painter_id not found")
+            .ok_or(PaintError::UnknownPainter(painter_id))
+    }
+
+    /// Get the [`Painter`] that a message from the `Constellation` or a content process is
+    /// addressed to. These messages can arrive after the [`Painter`] has been removed, for
+    /// instance because its last `WebView` was closed, in which case they are dropped.
+    fn painter_for_message<'a>(
+        &'a self,
+        painter_id: PainterId,
+        log_target: &'static str,
+    ) -> Option<RefMut<'a, Painter>> {
+        let painter = self.maybe_painter_mut(painter_id);
+        if painter.is_none() {
+            debug!("Dropping {log_target} message for unknown painter {painter_id:?}");
+            self.message_trace.note_last_message_dropped();
+        }
+        painter
     }
 
-    pub fn painter_id(&self) -> PainterId {
-        self.painters[0].borrow().painter_id
+    /// The `WebView`s painted by the [`Painter`] with the given [`PainterId`], in painting
+    /// order from bottom to top.
+    pub fn webviews_in_painter(&self, painter_id: PainterId) -> Result<Vec<WebViewId>, PaintError> {
+        Ok(self.painter(painter_id)?.painting_order().to_vec())
     }
 
-    pub fn rendering_context_size(&self, painter_id: PainterId) -> Size2D<u32, DevicePixel> {
-        self.painter(painter_id).rendering_context.size2d()
+    /// The [`PainterId`] of every [`Painter`], along with the [`RenderingContext`] it paints
+    /// to, in the order that the [`Painter`]s were created.
+    pub fn rendering_contexts(&self) -> Vec<(PainterId, Rc<dyn RenderingContext>)> {
+        self.painters
+            .iter()
+            .map(|painter| {
+                let painter = painter.borrow();
+                (painter.painter_id, painter.rendering_context.clone())
+            })
+            .collect()
+    }
+
+    /// Every `WebView` of every [`Painter`], along with the `RenderingContext` it paints to.
+    /// [`Painter`]s are visited in the order they were created and the `WebView`s of each
+    /// [`Painter`] in painting order from bottom to top, so the order is deterministic.
+    pub fn painting_order_all(&self) -> Vec<(Rc<dyn RenderingContext>, WebViewId)> {
+        self.painters
+            .iter()
+            .flat_map(|painter| {
+                let painter = painter.borrow();
+                painter
+                    .painting_order()
+                    .iter()
+                    .map(|webview_id| (painter.rendering_context.clone(), *webview_id))
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    /// The [`PainterId`] of the [`Painter`] that paints the given `WebView`, or `None` if that
+    /// [`Painter`] does not exist or does not know about the `WebView`.
+    pub fn painter_id_for_webview(&self, webview_id: WebViewId) -> Option<PainterId> {
+        let painter = self.maybe_painter(webview_id.into())?;
+        painter
+            .webview_renderer(webview_id)
+            .map(|_| painter.painter_id)
+    }
+
+    /// A snapshot of the state of every [`Painter`], for diagnostic purposes. [`Painter`]s are
+    /// listed in the order in which their frames are scheduled.
+    pub fn debug_state(&self) -> Vec<PainterDebugState> {
+        self.painters_in_frame_order()
+            .iter()
+            .map(|painter| {
+                let painter = painter.borrow();
+                PainterDebugState {
+                    painter_id: painter.painter_id,
+                    webrender_document: painter.webrender_document,
+                    painting_order: painter.painting_order().to_vec(),
+                    hidden_webviews: painter
+                        .webview_renderers()
+                        .filter(|webview_renderer| webview_renderer.hidden())
+                        .map(|webview_renderer| webview_renderer.id)
+                        .collect(),
+                    throttled: painter.throttled(),
+                    priority: self.frame_priority(&painter),
+                    webrender_debug_options: WebRenderDebugOption::ALL
+                        .into_iter()
+                        .filter(|option| painter.webrender_debug_enabled(*option))
+                        .collect(),
+                }
+            })
+            .collect()
+    }
+
+    pub fn rendering_context_size(
+        &self,
+        painter_id: PainterId,
+    ) -> Result<Size2D<u32, DevicePixel>, PaintError> {
+        Ok(self.painter(painter_id)?.rendering_context.size2d())
     }
 
     pub fn webgl_threads(&self) -> WebGLThreads {
@@ -320,6 +810,24 @@ impl Paint {
         self.webgpu_image_map.get_or_init(Default::default).clone()
     }
 
+    pub(crate) fn webrender_workers(&self) -> Arc<rayon::ThreadPool> {
+        self.webrender_workers
+            .get_or_init(|| {
+                let worker_threads = std::thread::available_parallelism()
+                    .map(|i| i.get())
+                    .unwrap_or(pref!(threadpools_fallback_worker_num) as usize)
+                    .min(pref!(threadpools_webrender_workers_max).max(1) as usize);
+                Arc::new(
+                    rayon::ThreadPoolBuilder::new()
+                        .num_threads(worker_threads)
+                        .thread_name(|idx| format!("WRWorker#{}", idx))
+                        .build()
+                        .expect("Unable to initialize WebRender worker pool."),
+                )
+            })
+            .clone()
+    }
+
     pub fn webviews_needing_repaint(&self) -> Vec<WebViewId> {
         self.painters
             .iter()
@@ -352,6 +860,35 @@ impl Paint {
 
     fn handle_browser_message(&self, msg: PaintMessage) {
         trace_msg_from_constellation!(msg, "{msg:?}");
+        let log_target = msg.log_target();
+        let message_name: &'static str = (&msg).into();
+        let (painter_id, webview_id) = msg.painter_and_webview_ids();
+        let document_id = painter_id
+            .and_then(|painter_id| self.maybe_painter(painter_id))
+            .map(|painter| painter.webrender_document);
+        let _span = profile_traits::trace_span!(
+            "PaintMessage",
+            message = message_name,
+            painter_id = ?painter_id,
+            document_id = ?document_id,
+            webview_id = ?webview_id,
+        )
+        .entered();
+        if self.message_trace.enabled() {
+            self.message_trace.record(PaintMessageTraceEntry {
+                time: CrossProcessInstant::now(),
+                message: message_name,
+                painter_id,
+                document_id,
+                webview_id,
+                dropped: false,
+            });
+        }
+        self.record(
+            painter_id,
+            webview_id,
+            RecordedPaintEventKind::Message(message_name.to_owned()),
+        );
 
         match self.shutdown_state() {
             ShutdownState::NotShuttingDown => {},
@@ -369,12 +906,15 @@ impl Paint {
             PaintMessage::CollectMemoryReport(sender) => {
                 self.collect_memory_report(sender);
             },
+            PaintMessage::CollectRendererStatistics(painter_id, result_sender) => {
+                let _ = result_sender.send(self.renderer_statistics(painter_id).ok().flatten());
+            },
             PaintMessage::ChangeRunningAnimationsState(
                 webview_id,
                 pipeline_id,
                 animation_state,
             ) => {
-                if let Some(mut painter) = self.maybe_painter_mut(webview_id.into()) {
+                if let Some(mut painter) = self.painter_for_message(webview_id.into(), log_target) {
                     painter.change_running_animations_state(
                         webview_id,
                         pipeline_id,
@@ -383,25 +923,30 @@ impl Paint {
                 }
             },
             PaintMessage::SetFrameTreeForWebView(webview_id, frame_tree) => {
-                if let Some(mut painter) = self.maybe_painter_mut(webview_id.into()) {
+                if let Some(mut painter) = self.painter_for_message(webview_id.into(), log_target) {
                     painter.set_frame_tree_for_webview(&frame_tree);
                 }
             },
             PaintMessage::SetThrottled(webview_id, pipeline_id, throttled) => {
-                if let Some(mut painter) = self.maybe_painter_mut(webview_id.into()) {
+                if let Some(mut painter) = self.painter_for_message(webview_id.into(), log_target) {
                     painter.set_throttled(webview_id, pipeline_id, throttled);
                 }
             },
             PaintMessage::PipelineExited(webview_id, pipeline_id, pipeline_exit_source) => {
-                if let Some(mut painter) = self.maybe_painter_mut(webview_id.into()) {
+                if let Some(mut painter) = self.painter_for_message(webview_id.into(), log_target) {
                     painter.notify_pipeline_exited(webview_id, pipeline_id, pipeline_exit_source);
                 }
             },
             PaintMessage::NewWebRenderFrameReady(..) => {
                 unreachable!("New WebRender frames should be handled in the caller.");
             },
+            PaintMessage::WebRenderWakeUp(painter_id, composite_needed) => {
+                if let Some(mut painter) = self.painter_for_message(painter_id, log_target) {
+                    painter.handle_webrender_wake_up(composite_needed);
+                }
+            },
             PaintMessage::SendInitialTransaction(webview_id, pipeline_id) => {
-                if let Some(mut painter) = self.maybe_painter_mut(webview_id.into()) {
+                if let Some(mut painter) = self.painter_for_message(webview_id.into(), log_target) {
                     painter.send_initial_pipeline_transaction(webview_id, pipeline_id);
                 }
             },
@@ -411,7 +956,7 @@ impl Paint {
                 offset,
                 external_scroll_id,
             ) => {
-                if let Some(mut painter) = self.maybe_painter_mut(webview_id.into()) {
+                if let Some(mut painter) = self.painter_for_message(webview_id.into(), log_target) {
                     painter.scroll_node_by_delta(
                         webview_id,
                         pipeline_id,
@@ -421,7 +966,7 @@ impl Paint {
                 }
             },
             PaintMessage::ScrollViewportByDelta(webview_id, delta) => {
-                if let Some(mut painter) = self.maybe_painter_mut(webview_id.into()) {
+                if let Some(mut painter) = self.painter_for_message(webview_id.into(), log_target) {
                     painter.scroll_viewport_by_delta(webview_id, delta);
                 }
             },
@@ -430,7 +975,7 @@ impl Paint {
                 pipeline_id,
                 epoch,
             } => {
-                if let Some(mut painter) = self.maybe_painter_mut(webview_id.into()) {
+                if let Some(mut painter) = self.painter_for_message(webview_id.into(), log_target) {
                     painter.update_epoch(webview_id, pipeline_id, epoch);
                 }
             },
@@ -439,7 +984,7 @@ impl Paint {
                 display_list_descriptor,
                 display_list_receiver,
             } => {
-                if let Some(mut painter) = self.maybe_painter_mut(webview_id.into()) {
+                if let Some(mut painter) = self.painter_for_message(webview_id.into(), log_target) {
                     painter.handle_new_display_list(
                         webview_id,
                         display_list_descriptor,
@@ -449,7 +994,7 @@ impl Paint {
             },
             PaintMessage::GenerateFrame(painter_ids) => {
                 for painter_id in painter_ids {
-                    if let Some(mut painter) = self.maybe_painter_mut(painter_id) {
+                    if let Some(mut painter) = self.painter_for_message(painter_id, log_target) {
                         painter.generate_frame_for_script();
                     }
                 }
@@ -461,7 +1006,7 @@ impl Paint {
                 self.handle_generate_image_keys_for_pipeline(webview_id, pipeline_id);
             },
             PaintMessage::UpdateImages(painter_id, updates) => {
-                if let Some(mut painter) = self.maybe_painter_mut(painter_id) {
+                if let Some(mut painter) = self.painter_for_message(painter_id, log_target) {
                     painter.update_images(updates);
                 }
             },
@@ -471,21 +1016,21 @@ impl Paint {
                 canvas_epoch,
                 image_keys,
             ) => {
-                if let Some(mut painter) = self.maybe_painter_mut(webview_id.into()) {
+                if let Some(mut painter) = self.painter_for_message(webview_id.into(), log_target) {
                     painter.delay_new_frames_for_canvas(pipeline_id, canvas_epoch, image_keys);
                 }
             },
             PaintMessage::AddFont(painter_id, font_key, data, index) => {
                 debug_assert!(painter_id == font_key.into());
 
-                if let Some(mut painter) = self.maybe_painter_mut(painter_id) {
+                if let Some(mut painter) = self.painter_for_message(painter_id, log_target) {
                     painter.add_font(font_key, data, index);
                 }
             },
             PaintMessage::AddSystemFont(painter_id, font_key, native_handle) => {
                 debug_assert!(painter_id == font_key.into());
 
-                if let Some(mut painter) = self.maybe_painter_mut(painter_id) {
+                if let Some(mut painter) = self.painter_for_message(painter_id, log_target) {
                     painter.add_system_font(font_key, native_handle);
                 }
             },
@@ -500,12 +1045,12 @@ impl Paint {
                 debug_assert!(painter_id == font_key.into());
                 debug_assert!(painter_id == font_instance_key.into());
 
-                if let Some(mut painter) = self.maybe_painter_mut(painter_id) {
+                if let Some(mut painter) = self.painter_for_message(painter_id, log_target) {
                     painter.add_font_instance(font_instance_key, font_key, size, flags, variations);
                 }
             },
             PaintMessage::RemoveFonts(painter_id, keys, instance_keys) => {
-                if let Some(mut painter) = self.maybe_painter_mut(painter_id) {
+                if let Some(mut painter) = self.painter_for_message(painter_id, log_target) {
                     painter.remove_fonts(keys, instance_keys);
                 }
             },
@@ -523,60 +1068,123 @@ impl Paint {
                 );
             },
             PaintMessage::Viewport(webview_id, viewport_description) => {
-                if let Some(mut painter) = self.maybe_painter_mut(webview_id.into()) {
+                if let Some(mut painter) = self.painter_for_message(webview_id.into(), log_target) {
                     painter.set_viewport_description(webview_id, viewport_description);
                 }
             },
             PaintMessage::ScreenshotReadinessReponse(webview_id, pipelines_and_epochs) => {
-                if let Some(painter) = self.maybe_painter(webview_id.into()) {
+                if let Some(painter) = self.painter_for_message(webview_id.into(), log_target) {
                     painter.handle_screenshot_readiness_reply(webview_id, pipelines_and_epochs);
                 }
             },
             PaintMessage::SendLCPCandidate(lcp_candidate, webview_id, pipeline_id, epoch) => {
-                if let Some(mut painter) = self.maybe_painter_mut(webview_id.into()) {
+                if let Some(mut painter) = self.painter_for_message(webview_id.into(), log_target) {
                     painter.append_lcp_candidate(lcp_candidate, webview_id, pipeline_id, epoch);
                 }
             },
+            PaintMessage::SetOccluded(painter_id, occluded) => {
+                if let Some(mut painter) = self.painter_for_message(painter_id, log_target) {
+                    painter.set_occluded(occluded);
+                }
+            },
         }
     }
 
-    pub fn remove_webview(&mut self, webview_id: WebViewId) {
+    /// Remove the given `WebView` from its [`Painter`], releasing all of the state kept for
+    /// it, such as pending screenshot requests, which fail. This returns the [`PainterId`] of
+    /// the [`Painter`] that the `WebView` was removed from or `None` if it was unknown. A
+    /// [`WebViewLifecycleEvent::Removed`] event is queued for the `WebView` when it was
+    /// removed.
+    pub fn remove_webview(&mut self, webview_id: WebViewId) -> Option<PainterId> {
         let painter_id = webview_id.into();
 
-        {
-            let mut painter = self.painter_mut(painter_id);
-            painter.remove_webview(webview_id);
-            if !painter.is_empty() {
-                return;
+        let removed = {
+            // The `Painter` may have already been removed if its `RenderingContext` was
+            // unregistered before all of its `WebView`s were dropped.
+            let mut painter = self.maybe_painter_mut(painter_id)?;
+            let removed = painter.remove_webview(webview_id);
+            if removed {
+                self.queue_webview_lifecycle_event(webview_id, WebViewLifecycleEvent::Removed);
             }
+            removed
+        };
+
+        self.remove_mirrors(webview_id);
+
+        if self
+            .maybe_painter(painter_id)
+            .is_some_and(|painter| painter.is_empty())
+        {
+            self.remove_painter(painter_id);
         }
 
-        self.remove_painter(painter_id);
+        removed.then_some(painter_id)
     }
 
-    fn collect_memory_report(&self, sender: profile_traits::mem::ReportsChan) {
-        let mut memory_report = MemoryReport::default();
+    /// Stop mirroring the given `WebView` into any [`Painter`], because it was removed.
+    fn remove_mirrors(&self, webview_id: WebViewId) {
         for painter in &self.painters {
-            memory_report += painter.borrow().report_memory();
+            let mut painter = painter.borrow_mut();
+            if painter.mirrors_webview(webview_id) {
+                painter.set_mirror(webview_id, None);
+            }
         }
+    }
 
-        let mut reports = vec![
-            Report {
-                path: path!["webrender", "fonts"],
-                kind: ReportKind::ExplicitJemallocHeapSize,
-                size: memory_report.fonts,
-            },
-            Report {
-                path: path!["webrender", "images"],
-                kind: ReportKind::ExplicitJemallocHeapSize,
-                size: memory_report.images,
-            },
-            Report {
-                path: path!["webrender", "display-list"],
-                kind: ReportKind::ExplicitJemallocHeapSize,
-                size: memory_report.display_list,
-            },
-        ];
+    fn collect_memory_report(&self, sender: profile_traits::mem::ReportsChan) {
+        // Each `Painter` has its own WebRender instance, so report its memory usage separately.
+        let mut reports = Vec::new();
+        for painter in &self.painters {
+            let painter = painter.borrow();
+            let painter_path = format!("{:?}", painter.painter_id);
+            let memory_report = painter.report_memory();
+            reports.extend([
+                Report {
+                    path: path!["webrender", painter_path, "fonts"],
+                    kind: ReportKind::ExplicitJemallocHeapSize,
+                    size: memory_report.fonts,
+                },
+                Report {
+                    path: path!["webrender", painter_path, "images"],
+                    kind: ReportKind::ExplicitJemallocHeapSize,
+                    size: memory_report.images,
+                },
+                Report {
+                    path: path!["webrender", painter_path, "display-list"],
+                    kind: ReportKind::ExplicitJemallocHeapSize,
+                    size: memory_report.display_list,
+                },
+                Report {
+                    path: path!["webrender", painter_path, "gpu", "texture-cache"],
+                    kind: ReportKind::NonExplicitSize,
+                    size: memory_report.atlas_textures +
+                        memory_report.standalone_textures +
+                        memory_report.texture_upload_pbos,
+                },
+                Report {
+                    path: path!["webrender", painter_path, "gpu", "render-targets"],
+                    kind: ReportKind::NonExplicitSize,
+                    size: memory_report.render_target_textures +
+                        memory_report.picture_tile_textures +
+                        memory_report.depth_target_textures,
+                },
+                Report {
+                    path: path!["webrender", painter_path, "gpu", "gpu-cache"],
+                    kind: ReportKind::NonExplicitSize,
+                    size: memory_report.gpu_cache_textures + memory_report.vertex_data_textures,
+                },
+            ]);
+            reports.extend(painter.webview_renderers().map(|webview_renderer| Report {
+                path: path![
+                    "paint",
+                    painter_path,
+                    format!("{:?}", webview_renderer.id),
+                    "display-lists"
+                ],
+                kind: ReportKind::NonExplicitSize,
+                size: webview_renderer.display_list_statistics().size_in_bytes,
+            }));
+        }
 
         perform_memory_report(|ops| {
             let scroll_trees_memory_usage = self
@@ -616,6 +1224,9 @@ impl Paint {
             PaintMessage::GenerateImageKeysForPipeline(webview_id, pipeline_id) => {
                 self.handle_generate_image_keys_for_pipeline(webview_id, pipeline_id);
             },
+            PaintMessage::CollectRendererStatistics(_, result_sender) => {
+                let _ = result_sender.send(None);
+            },
             PaintMessage::GenerateFontKeys(
                 number_of_font_keys,
                 number_of_font_instance_keys,
@@ -635,57 +1246,676 @@ impl Paint {
         }
     }
 
-    pub fn add_webview(&self, webview: Box<dyn WebViewTrait>, viewport_details: ViewportDetails) {
-        self.painter_mut(webview.id().into())
-            .add_webview(webview, viewport_details);
+    /// Add the given `WebView` to the [`Painter`] identified by the [`PainterId`] of its
+    /// [`WebViewId`]. This fails if that [`Painter`] does not exist, for instance because its
+    /// `RenderingContext` was never registered or was already unregistered, or if the
+    /// `WebView` was added before.
+    pub fn add_webview(
+        &self,
+        webview: Box<dyn WebViewTrait>,
+        viewport_details: ViewportDetails,
+    ) -> Result<(), PaintError> {
+        let webview_id = webview.id();
+        let mut painter = self.painter_mut(webview_id.into())?;
+        if !painter.add_webview(webview, viewport_details) {
+            return Err(PaintError::WebViewAlreadyAdded(webview_id));
+        }
+        self.queue_webview_lifecycle_event(webview_id, WebViewLifecycleEvent::Added);
+        self.update_webview_suspension(&mut painter);
+        Ok(())
+    }
+
+    pub fn show_webview(&self, webview_id: WebViewId) -> Result<(), PaintError> {
+        let mut painter = self.painter_mut(webview_id.into())?;
+        if painter.set_webview_hidden(webview_id, false)? {
+            self.queue_webview_lifecycle_event(webview_id, WebViewLifecycleEvent::Shown);
+        }
+        self.update_webview_suspension(&mut painter);
+        Ok(())
+    }
+
+    /// Throttle or unthrottle the given `WebView` at the request of the embedder. A throttled
+    /// `WebView` stops running animations and producing new display lists. `WebView`s that
+    /// are suspended to stay within the limit set with [`Paint::set_webview_limit`] are also
+    /// throttled, but resuming them does not undo throttling requested with this method.
+    pub fn set_webview_throttled(
+        &self,
+        webview_id: WebViewId,
+        throttled: bool,
+    ) -> Result<(), PaintError> {
+        self.painter_mut(webview_id.into())?
+            .set_webview_throttled(webview_id, throttled);
+        Ok(())
+    }
+
+    pub fn hide_webview(&self, webview_id: WebViewId) -> Result<(), PaintError> {
+        let mut painter = self.painter_mut(webview_id.into())?;
+        if painter.set_webview_hidden(webview_id, true)? {
+            self.queue_webview_lifecycle_event(webview_id, WebViewLifecycleEvent::Hidden);
+        }
+        self.update_webview_suspension(&mut painter);
+        Ok(())
+    }
+
+    /// Limit the number of `WebView`s of the [`Painter`] with the given [`PainterId`] that are
+    /// not suspended, or pass `None` to remove the limit. When the limit is exceeded, hidden
+    /// `WebView`s are suspended in order from least to most recently shown, which throttles
+    /// them. They are resumed when shown again. This helps to bound the resources used by
+    /// embedders with many tabs.
+    pub fn set_webview_limit(
+        &self,
+        painter_id: PainterId,
+        webview_limit: Option<usize>,
+    ) -> Result<(), PaintError> {
+        let mut painter = self.painter_mut(painter_id)?;
+        painter.set_webview_limit(webview_limit);
+        self.update_webview_suspension(&mut painter);
+        Ok(())
+    }
+
+    fn update_webview_suspension(&self, painter: &mut Painter) {
+        for (webview_id, event) in painter.update_webview_suspension() {
+            self.queue_webview_lifecycle_event(webview_id, event);
+        }
+    }
+
+    /// Hide every visible `WebView` painted by the [`Painter`] with the given [`PainterId`],
+    /// for instance when its window is minimized. The painting order is not affected.
+    pub fn hide_all_webviews(&self, painter_id: PainterId) -> Result<(), PaintError> {
+        let mut painter = self.painter_mut(painter_id)?;
+        for webview_id in painter.hide_all_webviews() {
+            self.queue_webview_lifecycle_event(webview_id, WebViewLifecycleEvent::Hidden);
+        }
+        self.update_webview_suspension(&mut painter);
+        Ok(())
+    }
+
+    /// Show every `WebView` that was hidden by the last call to [`Paint::hide_all_webviews`]
+    /// for the [`Painter`] with the given [`PainterId`], restoring its previous state.
+    pub fn show_all_webviews(&self, painter_id: PainterId) -> Result<(), PaintError> {
+        let mut painter = self.painter_mut(painter_id)?;
+        for webview_id in painter.show_all_webviews() {
+            self.queue_webview_lifecycle_event(webview_id, WebViewLifecycleEvent::Shown);
+        }
+        self.update_webview_suspension(&mut painter);
+        Ok(())
+    }
+
+    fn queue_webview_lifecycle_event(&self, webview_id: WebViewId, event: WebViewLifecycleEvent) {
+        self.webview_lifecycle_events
+            .borrow_mut()
+            .push((webview_id, event));
+    }
+
+    /// Take the errors returned by the WebRender renderers of all [`Painter`]s since the last
+    /// time this method was called, along with the [`PainterId`] of the [`Painter`] whose
+    /// renderer returned them. Each [`Painter`] recovers from these errors on its own by
+    /// recreating its WebRender instance, without affecting other [`Painter`]s, until too
+    /// many frames in a row have failed. See [`Paint::take_failed_painters`].
+    pub fn take_renderer_errors(&self) -> Vec<(PainterId, RendererError)> {
+        self.painters
+            .iter()
+            .flat_map(|painter| {
+                let mut painter = painter.borrow_mut();
+                let painter_id = painter.painter_id;
+                painter
+                    .take_renderer_errors()
+                    .into_iter()
+                    .map(move |error| (painter_id, error))
+            })
+            .collect()
+    }
+
+    /// Make the next `frames` frames of the [`Painter`] with the given [`PainterId`] fail to
+    /// render, as if its WebRender renderer had run out of memory. This allows embedders to
+    /// test how they handle the errors returned by [`Paint::take_renderer_errors`] and
+    /// [`Paint::take_failed_painters`].
+    pub fn simulate_renderer_failure(
+        &self,
+        painter_id: PainterId,
+        frames: u32,
+    ) -> Result<(), PaintError> {
+        self.painter_mut(painter_id)?
+            .simulate_renderer_failure(frames);
+        Ok(())
+    }
+
+    /// Take the [`PainterId`]s of the [`Painter`]s that have stopped rendering since the last
+    /// time this method was called, because too many frames in a row failed to render. These
+    /// [`Painter`]s do not render anything anymore, so embedders should close their
+    /// `RenderingContext`s and recreate them.
+    pub fn take_failed_painters(&self) -> Vec<PainterId> {
+        self.painters
+            .iter()
+            .filter_map(|painter| {
+                let mut painter = painter.borrow_mut();
+                painter
+                    .take_rendering_failure()
+                    .then_some(painter.painter_id)
+            })
+            .collect()
+    }
+
+    /// Take all of the [`WebViewLifecycleEvent`]s that have happened since the last time this
+    /// method was called, in the order that they happened.
+    pub fn take_webview_lifecycle_events(&self) -> Vec<(WebViewId, WebViewLifecycleEvent)> {
+        std::mem::take(&mut *self.webview_lifecycle_events.borrow_mut())
+    }
+
+    /// Whether the given `WebView` is hidden. See [`Paint::hide_webview`].
+    pub fn webview_hidden(&self, webview_id: WebViewId) -> Result<bool, PaintError> {
+        self.painter(webview_id.into())?
+            .webview_renderer(webview_id)
+            .map(WebViewRenderer::hidden)
+            .ok_or(PaintError::UnknownWebView(webview_id))
+    }
+
+    /// The rectangle explicitly set for the given `WebView` with [`Paint::set_webview_rect`]
+    /// or `None` if it fills its entire `RenderingContext`.
+    pub fn webview_explicit_rect(
+        &self,
+        webview_id: WebViewId,
+    ) -> Result<Option<DeviceRect>, PaintError> {
+        self.painter(webview_id.into())?
+            .webview_renderer(webview_id)
+            .map(WebViewRenderer::explicit_rect)
+            .ok_or(PaintError::UnknownWebView(webview_id))
+    }
+
+    /// Position the given `WebView` at `rect` within its `RenderingContext`, or make it fill
+    /// the entire `RenderingContext` when `rect` is `None`.
+    pub fn set_webview_rect(
+        &self,
+        webview_id: WebViewId,
+        rect: Option<DeviceRect>,
+    ) -> Result<(), PaintError> {
+        if self.shutdown_state() != ShutdownState::NotShuttingDown {
+            return Ok(());
+        }
+        Ok(self
+            .painter_mut(webview_id.into())?
+            .set_webview_rect(webview_id, rect)?)
+    }
+
+    /// Call `callback` once the next display list of the given `WebView` has been painted
+    /// into its `RenderingContext`. See [`Painter::request_frame_presented_notification`].
+    pub fn request_frame_presented_notification(
+        &self,
+        webview_id: WebViewId,
+        callback: Box<dyn FnOnce()>,
+    ) -> Result<(), PaintError> {
+        Ok(self
+            .painter_mut(webview_id.into())?
+            .request_frame_presented_notification(webview_id, callback)?)
     }
 
-    pub fn show_webview(&self, webview_id: WebViewId) -> Result<(), UnknownWebView> {
-        self.painter_mut(webview_id.into())
-            .set_webview_hidden(webview_id, false)
+    /// Set the opacity used when compositing the given `WebView` into its `RenderingContext`.
+    /// Values are clamped to the range `0.0..=1.0`, where `0.0` is fully transparent.
+    pub fn set_webview_opacity(
+        &self,
+        webview_id: WebViewId,
+        opacity: f32,
+    ) -> Result<(), PaintError> {
+        Ok(self
+            .painter_mut(webview_id.into())?
+            .set_webview_opacity(webview_id, opacity)?)
     }
 
-    pub fn hide_webview(&self, webview_id: WebViewId) -> Result<(), UnknownWebView> {
-        self.painter_mut(webview_id.into())
-            .set_webview_hidden(webview_id, true)
+    /// Cap the rate at which the given `WebView` produces new frames to `max_frame_rate` frames
+    /// per second, for instance 30 for a dashboard in the background, or remove the cap when
+    /// `None`. The animations of the `WebView` tick at most at this rate, so it builds and
+    /// sends fewer display lists, while other `WebView`s of its `RenderingContext` keep
+    /// running at the full rate.
+    pub fn set_webview_max_frame_rate(
+        &self,
+        webview_id: WebViewId,
+        max_frame_rate: Option<f32>,
+    ) -> Result<(), PaintError> {
+        Ok(self
+            .painter_mut(webview_id.into())?
+            .set_webview_max_frame_rate(webview_id, max_frame_rate)?)
+    }
+
+    /// Declare whether or not the contents of the given `WebView` are fully opaque. `WebView`s
+    /// that are completely covered by an opaque `WebView` above them in the painting order are
+    /// not composited at all.
+    pub fn set_webview_opaque(
+        &self,
+        webview_id: WebViewId,
+        opaque: bool,
+    ) -> Result<(), PaintError> {
+        Ok(self
+            .painter_mut(webview_id.into())?
+            .set_webview_opaque(webview_id, opaque)?)
+    }
+
+    /// Make the given `WebView` float above all non-floating `WebView`s of its
+    /// `RenderingContext`, regardless of the painting order, for instance for a
+    /// picture-in-picture video. Floating `WebView`s are composited in painting order
+    /// relative to each other.
+    pub fn set_webview_floating(
+        &self,
+        webview_id: WebViewId,
+        floating: bool,
+    ) -> Result<(), PaintError> {
+        Ok(self
+            .painter_mut(webview_id.into())?
+            .set_webview_floating(webview_id, floating)?)
+    }
+
+    /// Set the offset, in device pixels, applied to the given `WebView` when compositing it
+    /// while it is floating. This allows moving a floating `WebView` while the user drags it,
+    /// without changing its rectangle and causing a relayout.
+    pub fn set_webview_floating_offset(
+        &self,
+        webview_id: WebViewId,
+        offset: DeviceVector2D,
+    ) -> Result<(), PaintError> {
+        Ok(self
+            .painter_mut(webview_id.into())?
+            .set_webview_floating_offset(webview_id, offset)?)
+    }
+
+    /// Clip the given `WebView` to a rectangle with optionally rounded corners when compositing
+    /// it, or pass `None` to remove the clip.
+    pub fn set_webview_clip(
+        &self,
+        webview_id: WebViewId,
+        clip: Option<WebViewClip>,
+    ) -> Result<(), PaintError> {
+        Ok(self
+            .painter_mut(webview_id.into())?
+            .set_webview_clip(webview_id, clip)?)
+    }
+
+    /// Set the decorations, such as a border or a drop shadow, that are painted around the given
+    /// `WebView` when compositing it. Unlike styling the page, this does not touch its content.
+    pub fn set_webview_decorations(
+        &self,
+        webview_id: WebViewId,
+        decorations: WebViewDecorations,
+    ) -> Result<(), PaintError> {
+        Ok(self
+            .painter_mut(webview_id.into())?
+            .set_webview_decorations(webview_id, decorations)?)
+    }
+
+    /// Set a 2D transformation, such as a scale, rotation, or translation, that is applied to the
+    /// given `WebView` when compositing it into its `RenderingContext`. The transformation is
+    /// relative to the origin of the `WebView`'s rectangle.
+    pub fn set_webview_transform(
+        &self,
+        webview_id: WebViewId,
+        transform: Transform2D<f32, DevicePixel, DevicePixel>,
+    ) -> Result<(), PaintError> {
+        Ok(self
+            .painter_mut(webview_id.into())?
+            .set_webview_transform(webview_id, transform)?)
+    }
+
+    /// Move the given `WebView` to `index` in the painting order of its `RenderingContext`.
+    pub fn move_webview_to_painting_index(
+        &self,
+        webview_id: WebViewId,
+        index: usize,
+    ) -> Result<(), PaintError> {
+        self.painter_mut(webview_id.into())?
+            .move_webview_to_painting_index(webview_id, index)?;
+        self.queue_webview_lifecycle_event(webview_id, WebViewLifecycleEvent::PaintingOrderChanged);
+        Ok(())
+    }
+
+    /// Move the given `WebView` so that it is painted directly above another `WebView`.
+    /// Both `WebView`s must share the same `RenderingContext`.
+    pub fn move_webview_above(
+        &self,
+        webview_id: WebViewId,
+        other_webview_id: WebViewId,
+    ) -> Result<(), PaintError> {
+        let painter_id = Self::shared_painter_id(webview_id, other_webview_id)?;
+        self.painter_mut(painter_id)?
+            .move_webview_above(webview_id, other_webview_id)?;
+        self.queue_webview_lifecycle_event(webview_id, WebViewLifecycleEvent::PaintingOrderChanged);
+        Ok(())
+    }
+
+    /// Move the given `WebView` so that it is painted directly below another `WebView`.
+    /// Both `WebView`s must share the same `RenderingContext`.
+    pub fn move_webview_below(
+        &self,
+        webview_id: WebViewId,
+        other_webview_id: WebViewId,
+    ) -> Result<(), PaintError> {
+        let painter_id = Self::shared_painter_id(webview_id, other_webview_id)?;
+        self.painter_mut(painter_id)?
+            .move_webview_below(webview_id, other_webview_id)?;
+        self.queue_webview_lifecycle_event(webview_id, WebViewLifecycleEvent::PaintingOrderChanged);
+        Ok(())
+    }
+
+    fn shared_painter_id(
+        webview_id: WebViewId,
+        other_webview_id: WebViewId,
+    ) -> Result<PainterId, PaintError> {
+        let painter_id = PainterId::from(webview_id);
+        if painter_id != PainterId::from(other_webview_id) {
+            return Err(PaintError::DifferentPainters(webview_id, other_webview_id));
+        }
+        Ok(painter_id)
     }
 
     pub fn set_hidpi_scale_factor(
         &self,
         webview_id: WebViewId,
         new_scale_factor: Scale<f32, DeviceIndependentPixel, DevicePixel>,
-    ) {
+    ) -> Result<(), PaintError> {
         if self.shutdown_state() != ShutdownState::NotShuttingDown {
-            return;
+            return Ok(());
         }
-        self.painter_mut(webview_id.into())
+        self.painter_mut(webview_id.into())?
             .set_hidpi_scale_factor(webview_id, new_scale_factor);
+        Ok(())
     }
 
-    pub fn resize_rendering_context(&self, webview_id: WebViewId, new_size: PhysicalSize<u32>) {
+    pub fn resize_rendering_context(
+        &self,
+        webview_id: WebViewId,
+        new_size: PhysicalSize<u32>,
+    ) -> Result<(), PaintError> {
+        self.resize_painter_rendering_context(webview_id.into(), new_size)
+    }
+
+    /// Resize the `RenderingContext` of the [`Painter`] with the given [`PainterId`]. This
+    /// updates the view of its WebRender document and resizes every `WebView` that fills the
+    /// `RenderingContext`, which causes them to relayout.
+    pub fn resize_painter_rendering_context(
+        &self,
+        painter_id: PainterId,
+        new_size: PhysicalSize<u32>,
+    ) -> Result<(), PaintError> {
         if self.shutdown_state() != ShutdownState::NotShuttingDown {
-            return;
+            return Ok(());
         }
-        self.painter_mut(webview_id.into())
+        self.painter_mut(painter_id)?
             .resize_rendering_context(new_size);
+        Ok(())
     }
 
-    pub fn set_page_zoom(&self, webview_id: WebViewId, new_zoom: f32) {
+    /// Like [`Paint::resize_painter_rendering_context`], but call `callback` right after the
+    /// first frame at the new size, with every visible `WebView` that fills the
+    /// `RenderingContext` laid out at that size, has been presented. This allows embedders
+    /// that present the `RenderingContext` in a child surface of their own compositor to
+    /// commit the new size of both surfaces together. A pending resize is superseded by the
+    /// next one and its callback is never called.
+    pub fn resize_painter_rendering_context_synchronized(
+        &self,
+        painter_id: PainterId,
+        new_size: PhysicalSize<u32>,
+        callback: Box<dyn FnOnce()>,
+    ) -> Result<(), PaintError> {
         if self.shutdown_state() != ShutdownState::NotShuttingDown {
-            return;
+            return Ok(());
         }
-        self.painter_mut(webview_id.into())
+        self.painter_mut(painter_id)?
+            .resize_rendering_context_synchronized(new_size, callback);
+        Ok(())
+    }
+
+    /// Hold back the frames of the [`Painter`] with the given [`PainterId`] for up to `timeout`
+    /// after each resize of its `RenderingContext`, until every visible `WebView` that fills
+    /// the `RenderingContext` has been laid out at the new size, or stop doing so with `None`.
+    /// While frames are held back, rendering and presenting the [`Painter`] do nothing, so that
+    /// content laid out at the old size is never shown stretched over the new surface.
+    pub fn set_painter_resize_present_timeout(
+        &self,
+        painter_id: PainterId,
+        timeout: Option<Duration>,
+    ) -> Result<(), PaintError> {
+        self.painter_mut(painter_id)?
+            .set_resize_present_timeout(timeout);
+        Ok(())
+    }
+
+    pub fn set_page_zoom(&self, webview_id: WebViewId, new_zoom: f32) -> Result<(), PaintError> {
+        if self.shutdown_state() != ShutdownState::NotShuttingDown {
+            return Ok(());
+        }
+        self.painter_mut(webview_id.into())?
             .set_page_zoom(webview_id, new_zoom);
+        Ok(())
+    }
+
+    /// Set the color used to clear the `RenderingContext` of the given `WebView` before
+    /// painting. This affects all `WebView`s that share the same `RenderingContext`.
+    pub fn set_clear_color(
+        &self,
+        webview_id: WebViewId,
+        clear_color: ColorF,
+    ) -> Result<(), PaintError> {
+        self.painter_mut(webview_id.into())?
+            .set_clear_color(clear_color);
+        Ok(())
+    }
+
+    /// The [`WebViewPaintTiming`] of the page currently displayed in the given `WebView`.
+    /// These are only recorded once the page is actually painted to the `RenderingContext`
+    /// of the `WebView`'s [`Painter`], so a page loaded in a hidden `WebView` has no paint
+    /// timing until the `WebView` is shown.
+    pub fn webview_paint_timing(
+        &self,
+        webview_id: WebViewId,
+    ) -> Result<WebViewPaintTiming, PaintError> {
+        Ok(self.painter(webview_id.into())?.paint_timing(webview_id)?)
+    }
+
+    /// The [`InputLatencyHistogram`] of the given `WebView`, describing the time between its
+    /// most recent input events reaching its [`Painter`] and the presentation of the frames
+    /// that reflect them. Each measurement is also reported to the time profiler under
+    /// `ProfilerCategory::InputToPresentLatency`.
+    pub fn input_latency_histogram(
+        &self,
+        webview_id: WebViewId,
+    ) -> Result<InputLatencyHistogram, PaintError> {
+        Ok(self
+            .painter(webview_id.into())?
+            .input_latency_histogram(webview_id)?)
+    }
+
+    /// The [`DisplayListStatistics`] of the given `WebView`, which can be polled to find out
+    /// which `WebView` is responsible for spikes in WebRender scene building. The same sizes
+    /// are included in memory reports.
+    pub fn display_list_statistics(
+        &self,
+        webview_id: WebViewId,
+    ) -> Result<DisplayListStatistics, PaintError> {
+        Ok(self
+            .painter(webview_id.into())?
+            .display_list_statistics(webview_id)?)
     }
 
-    pub fn page_zoom(&self, webview_id: WebViewId) -> f32 {
-        self.painter(webview_id.into()).page_zoom(webview_id)
+    pub fn page_zoom(&self, webview_id: WebViewId) -> Result<f32, PaintError> {
+        Ok(self.painter(webview_id.into())?.page_zoom(webview_id))
     }
 
     /// Render the WebRender scene to the active `RenderingContext`.
-    pub fn render(&self, webview_id: WebViewId) {
-        self.painter_mut(webview_id.into())
-            .render(&self.time_profiler_chan);
+    pub fn render(&self, webview_id: WebViewId) -> Result<(), PaintError> {
+        {
+            let mut painter = self.painter_mut(webview_id.into())?;
+            painter.render(&self.time_profiler_chan);
+            // The embedder presents the `RenderingContext` itself right after this.
+            painter.maybe_finish_synchronized_resize();
+        }
+        self.update_mirrors(webview_id.into());
+        Ok(())
+    }
+
+    /// Mirror the given `WebView` into `rect` of the `RenderingContext` of the [`Painter`] with
+    /// the given [`PainterId`], or stop mirroring it there when `rect` is `None`. The mirror
+    /// shows a copy of what was last painted for the `WebView`, which is updated every time
+    /// its own `RenderingContext` is painted, so content is not loaded or laid out twice.
+    /// Mirrors are painted above all `WebView`s of the target [`Painter`] and do not receive
+    /// input events.
+    pub fn mirror_webview(
+        &self,
+        webview_id: WebViewId,
+        target_painter_id: PainterId,
+        rect: Option<DeviceRect>,
+    ) -> Result<(), PaintError> {
+        let source_painter_id = webview_id.into();
+        if source_painter_id == target_painter_id {
+            return Err(PaintError::MirrorIntoOwnPainter(webview_id));
+        }
+        if self
+            .painter(source_painter_id)?
+            .webview_renderer(webview_id)
+            .is_none()
+        {
+            return Err(PaintError::UnknownWebView(webview_id));
+        }
+
+        self.painter_mut(target_painter_id)?
+            .set_mirror(webview_id, rect);
+        let mirrored = self
+            .painters
+            .iter()
+            .any(|painter| painter.borrow().mirrors_webview(webview_id));
+        self.painter_mut(source_painter_id)?
+            .set_webview_mirrored(webview_id, mirrored);
+        Ok(())
+    }
+
+    /// Send the snapshots of mirrored `WebView`s taken during the last paint of the
+    /// [`Painter`] with the given [`PainterId`] to every [`Painter`] that mirrors them.
+    fn update_mirrors(&self, painter_id: PainterId) {
+        let Some(snapshots) = self
+            .maybe_painter_mut(painter_id)
+            .map(|mut painter| painter.take_mirror_snapshots())
+        else {
+            return;
+        };
+        for (webview_id, image) in snapshots {
+            let mut mirrored = false;
+            for painter in &self.painters {
+                let mut painter = painter.borrow_mut();
+                if painter.mirrors_webview(webview_id) {
+                    painter.update_mirror_image(webview_id, &image);
+                    mirrored = true;
+                }
+            }
+            // The `Painter`s mirroring this `WebView` may have been removed in the meantime.
+            if !mirrored {
+                if let Some(mut painter) = self.maybe_painter_mut(painter_id) {
+                    painter.set_webview_mirrored(webview_id, false);
+                }
+            }
+        }
+    }
+
+    /// Set the [`PaintBackground`] painted below all of the `WebView`s of the [`Painter`] with
+    /// the given [`PainterId`].
+    pub fn set_painter_background(
+        &self,
+        painter_id: PainterId,
+        background: PaintBackground,
+    ) -> Result<(), PaintError> {
+        self.painter_mut(painter_id)?.set_background(background);
+        Ok(())
+    }
+
+    /// Set whether the [`Painter`] with the given [`PainterId`] only generates new frames when
+    /// the embedder reports a vsync tick with [`Paint::notify_vsync`]. This allows painting
+    /// each `RenderingContext` in time with the refresh rate of the display showing it.
+    pub fn set_vsync_driven(
+        &self,
+        painter_id: PainterId,
+        vsync_driven: bool,
+    ) -> Result<(), PaintError> {
+        self.painter_mut(painter_id)?.set_vsync_driven(vsync_driven);
+        Ok(())
+    }
+
+    /// Notify the [`Painter`] with the given [`PainterId`] of a vsync tick of the display
+    /// that shows its `RenderingContext`. This has no effect unless the [`Painter`] was made
+    /// vsync driven with [`Paint::set_vsync_driven`].
+    pub fn notify_vsync(&self, painter_id: PainterId) -> Result<(), PaintError> {
+        self.painter_mut(painter_id)?.notify_vsync();
+        Ok(())
+    }
+
+    /// Immediately send all WebRender transactions queued for the [`Painter`] with the given
+    /// [`PainterId`]. Transactions are otherwise sent once per batch of messages handled by
+    /// [`Paint::handle_messages`], once per call to [`Paint::perform_updates`], and before
+    /// rendering.
+    pub fn flush_pending_transactions(&self, painter_id: PainterId) -> Result<(), PaintError> {
+        self.painter_mut(painter_id)?.flush_pending_transactions();
+        Ok(())
+    }
+
+    /// Render and present the [`Painter`] with the given [`PainterId`], but only if it has
+    /// received a new WebRender frame or otherwise needs to be repainted. This allows
+    /// embedders with multiple windows to only swap the buffers of the windows whose
+    /// contents have actually changed. Returns `true` if a frame was presented.
+    pub fn present_painter(&self, painter_id: PainterId) -> Result<bool, PaintError> {
+        let presented = self
+            .painter_mut(painter_id)?
+            .present(&self.time_profiler_chan);
+        self.update_mirrors(painter_id);
+        Ok(presented)
+    }
+
+    /// Render and present every [`Painter`] that needs to be repainted, like
+    /// [`Paint::present_painter`]. The [`Painter`] of the focused window is presented first,
+    /// followed by the others from highest to lowest [`RenderingPriority`]. The first one is
+    /// always presented, but once presenting has taken longer than `frame_budget`, the
+    /// remaining [`Painter`]s are deferred to the
+    /// next turn of the embedder event loop, which is woken up for that purpose. Returns
+    /// `true` if any [`Painter`] was deferred.
+    pub fn present_painters(&self, frame_budget: Duration) -> bool {
+        let start = Instant::now();
+        let mut deferred = false;
+        for (index, painter) in self.painters_in_frame_order().into_iter().enumerate() {
+            let painter_id = painter.borrow().painter_id;
+            if index > 0 && start.elapsed() >= frame_budget {
+                deferred |= painter.borrow().needs_repaint();
+                continue;
+            }
+            painter.borrow_mut().present(&self.time_profiler_chan);
+            self.update_mirrors(painter_id);
+        }
+
+        if deferred {
+            self.event_loop_waker.wake();
+        }
+        deferred
+    }
+
+    /// Set the [`Painter`] of the focused window, or `None` if no window of the embedder is
+    /// focused. Frames of the focused [`Painter`] are built and presented before those of
+    /// others, as if it had [`RenderingPriority::High`].
+    pub fn set_focused_painter(&self, painter_id: Option<PainterId>) {
+        self.focused_painter.set(painter_id);
+    }
+
+    /// The [`Painter`] of the focused window, if any. See [`Paint::set_focused_painter`].
+    pub fn focused_painter(&self) -> Option<PainterId> {
+        self.focused_painter.get()
+    }
+
+    /// The [`RenderingPriority`] used when scheduling the frames of the given [`Painter`],
+    /// which is the highest one for the [`Painter`] of the focused window.
+    fn frame_priority(&self, painter: &Painter) -> RenderingPriority {
+        if self.focused_painter.get() == Some(painter.painter_id) {
+            return RenderingPriority::High;
+        }
+        painter.priority()
+    }
+
+    /// All [`Painter`]s in the order in which their frames are scheduled, from highest to
+    /// lowest [`Paint::frame_priority`].
+    fn painters_in_frame_order(&self) -> Vec<Rc<RefCell<Painter>>> {
+        let mut painters = self.painters.clone();
+        painters.sort_by_key(|painter| Reverse(self.frame_priority(&painter.borrow())));
+        painters
     }
 
     /// Get the message receiver for this [`Paint`].
@@ -701,12 +1931,20 @@ impl Paint {
         // when calling `handle_new_webrender_frame_ready`.
         let mut saw_webrender_frame_ready_for_painter = HashMap::new();
         messages.retain(|message| match message {
-            PaintMessage::NewWebRenderFrameReady(painter_id, _document_id, need_repaint) => {
+            PaintMessage::NewWebRenderFrameReady(painter_id, document_id) => {
                 if let Some(painter) = self.maybe_painter(*painter_id) {
-                    painter.decrement_pending_frames();
+                    // A single message stands for all of the frames of this document that
+                    // became ready since the last one was handled.
+                    let ready_frames = painter.take_ready_frames(*document_id);
+                    // Only the `Painter` that owns the WebRender document of this frame needs
+                    // to present it. Other `RenderingContext`s are left untouched.
+                    if painter.webrender_document != *document_id {
+                        debug!("Ignoring new frame for unknown document {document_id:?}");
+                        return false;
+                    }
                     *saw_webrender_frame_ready_for_painter
                         .entry(*painter_id)
-                        .or_insert(*need_repaint) |= *need_repaint;
+                        .or_insert(ready_frames.repaint_needed) |= ready_frames.repaint_needed;
                 }
 
                 false
@@ -722,9 +1960,37 @@ impl Paint {
         }
 
         for (painter_id, repaint_needed) in saw_webrender_frame_ready_for_painter.iter() {
-            if let Some(painter) = self.maybe_painter(*painter_id) {
+            // Throttled painters do not paint new frames. A new frame is generated once they
+            // are no longer throttled.
+            let Some(mut painter) = self.maybe_painter_mut(*painter_id) else {
+                continue;
+            };
+            if !painter.throttled() {
                 painter.handle_new_webrender_frame_ready(*repaint_needed);
             }
+            self.record(
+                Some(*painter_id),
+                None,
+                RecordedPaintEventKind::FrameReady {
+                    repaint_needed: *repaint_needed,
+                },
+            );
+        }
+
+        // Send everything produced while handling this batch of messages to WebRender in one
+        // go, so that each `Painter` builds at most one new frame per batch. `Painter`s are
+        // flushed from highest to lowest priority, so that lower priority `Painter`s can defer
+        // their frames while a higher priority one is waiting for WebRender.
+        let mut busy_priority = None;
+        for painter in self.painters_in_frame_order() {
+            let mut painter = painter.borrow_mut();
+            let priority = self.frame_priority(&painter);
+            painter.flush_pending_transactions_for_priority(
+                busy_priority.is_some_and(|busy_priority| busy_priority > priority),
+            );
+            if busy_priority.is_none() && painter.has_frames_in_flight() {
+                busy_priority = Some(priority);
+            }
         }
     }
 
@@ -745,13 +2011,264 @@ impl Paint {
         self.shutdown_state() != ShutdownState::FinishedShuttingDown
     }
 
+    /// Handle a memory pressure signal from the system by releasing the GPU caches of every
+    /// [`Painter`] whose `WebView`s are all hidden, occluded or paused. Visible [`Painter`]s
+    /// keep their caches, so that they can continue to paint without stalling.
+    pub fn notify_memory_pressure(&self) {
+        for painter in &self.painters {
+            painter.borrow_mut().release_memory_if_hidden();
+        }
+    }
+
     pub fn toggle_webrender_debug(&self, option: WebRenderDebugOption) {
         for painter in &self.painters {
             painter.borrow_mut().toggle_webrender_debug(option);
         }
     }
 
-    pub fn capture_webrender(&self, webview_id: WebViewId) {
+    /// Toggle a WebRender debugging option, such as the profiler overlay, only for the
+    /// [`Painter`] with the given [`PainterId`].
+    pub fn toggle_painter_webrender_debug(
+        &self,
+        painter_id: PainterId,
+        option: WebRenderDebugOption,
+    ) -> Result<(), PaintError> {
+        self.painter_mut(painter_id)?.toggle_webrender_debug(option);
+        Ok(())
+    }
+
+    /// Whether a WebRender debugging option is enabled for the [`Painter`] with the given
+    /// [`PainterId`].
+    pub fn painter_webrender_debug_enabled(
+        &self,
+        painter_id: PainterId,
+        option: WebRenderDebugOption,
+    ) -> Result<bool, PaintError> {
+        Ok(self.painter(painter_id)?.webrender_debug_enabled(option))
+    }
+
+    /// Enable or disable a WebRender debugging option only for the [`Painter`] with the given
+    /// [`PainterId`]. Unlike [`Paint::toggle_painter_webrender_debug`], this does not depend
+    /// on the current state of the option.
+    pub fn set_painter_webrender_debug(
+        &self,
+        painter_id: PainterId,
+        option: WebRenderDebugOption,
+        enabled: bool,
+    ) -> Result<(), PaintError> {
+        self.painter_mut(painter_id)?
+            .set_webrender_debug(option, enabled);
+        Ok(())
+    }
+
+    /// The [`RendererStatistics`] of the most recent frame rendered by the [`Painter`] with the
+    /// given [`PainterId`], or `None` if it has not rendered anything yet. This can be polled
+    /// to attribute rendering cost to a specific group of `WebView`s.
+    pub fn renderer_statistics(
+        &self,
+        painter_id: PainterId,
+    ) -> Result<Option<RendererStatistics>, PaintError> {
+        Ok(self.painter(painter_id)?.renderer_statistics())
+    }
+
+    /// Enable or disable recording the messages handled by [`Paint`] into a ring buffer of
+    /// the most recent ones, along with the renderer, WebRender document and `WebView` that
+    /// each was addressed to. The recorded messages can be retrieved with
+    /// [`Paint::message_trace`] and are logged if [`Paint`] is dropped during a panic.
+    /// Disabling tracing clears the recorded messages.
+    pub fn set_message_tracing_enabled(&self, enabled: bool) {
+        self.message_trace.set_enabled(enabled);
+    }
+
+    /// The most recent messages handled by [`Paint`] while message tracing was enabled, from
+    /// oldest to newest. See [`Paint::set_message_tracing_enabled`].
+    pub fn message_trace(&self) -> Vec<PaintMessageTraceEntry> {
+        self.message_trace.entries()
+    }
+
+    /// Start recording the messages handled by [`Paint`], the input events sent to `WebView`s
+    /// and the new frames built by WebRender, along with the time at which each happened.
+    /// This replaces any ongoing recording. See [`Paint::stop_recording`].
+    pub fn start_recording(&self) {
+        *self.recorder.borrow_mut() = Some(PaintRecorder::new());
+    }
+
+    /// Stop recording and return what was recorded since [`Paint::start_recording`], or
+    /// `None` if nothing was being recorded.
+    pub fn stop_recording(&self) -> Option<PaintRecording> {
+        self.recorder.borrow_mut().take().map(PaintRecorder::finish)
+    }
+
+    /// Record that the given input event was sent to the given `WebView`, if recording is
+    /// enabled.
+    pub fn record_input_event(&self, webview_id: WebViewId, event: &InputEvent) {
+        if self.recorder.borrow().is_none() {
+            return;
+        }
+        self.record(
+            Some(webview_id.into()),
+            Some(webview_id),
+            RecordedPaintEventKind::InputEvent(event.clone()),
+        );
+    }
+
+    fn record(
+        &self,
+        painter_id: Option<PainterId>,
+        webview_id: Option<WebViewId>,
+        kind: RecordedPaintEventKind,
+    ) {
+        if let Some(recorder) = self.recorder.borrow_mut().as_mut() {
+            recorder.record(painter_id, webview_id, kind);
+        }
+    }
+
+    /// Find the `WebView` of the [`Painter`] with the given [`PainterId`] that shows what is
+    /// painted at `point` in the coordinate space of its `RenderingContext`, along with the
+    /// hit test results for that `WebView` at that point. When `WebView`s overlap, this is the
+    /// topmost visible one whose painted area contains the point, so embedders can use this
+    /// to decide which `WebView` should receive an input event. Returns `None` if no
+    /// `WebView` is painted at the point.
+    pub fn hit_test(
+        &self,
+        painter_id: PainterId,
+        point: DevicePoint,
+    ) -> Result<Option<(WebViewId, Vec<PaintHitTestResult>)>, PaintError> {
+        Ok(self.painter(painter_id)?.hit_test(point))
+    }
+
+    /// Set the [`RenderingPriority`] of the [`Painter`] with the given [`PainterId`]. While a
+    /// [`Painter`] is waiting for WebRender to build a frame, new frames of [`Painter`]s with a
+    /// lower priority are deferred, so that busy background windows cannot delay the frames of
+    /// the focused window. A frame is never deferred twice in a row.
+    pub fn set_painter_priority(
+        &self,
+        painter_id: PainterId,
+        priority: RenderingPriority,
+    ) -> Result<(), PaintError> {
+        self.painter_mut(painter_id)?.set_priority(priority);
+        Ok(())
+    }
+
+    /// Set whether or not the [`Painter`] with the given [`PainterId`] draws the cursor on top
+    /// of its `WebView`s, at the last position that the mouse moved over and with the shape
+    /// most recently requested by the page under it. This is meant for `RenderingContext`s
+    /// without an OS cursor, such as offscreen ones used for headless or remote sessions, so
+    /// that captured frames show pointer feedback.
+    pub fn set_painter_draws_cursor(
+        &self,
+        painter_id: PainterId,
+        draws_cursor: bool,
+    ) -> Result<(), PaintError> {
+        self.painter_mut(painter_id)?.set_draws_cursor(draws_cursor);
+        Ok(())
+    }
+
+    /// Note that the page under the mouse in the given `WebView` requested the given
+    /// [`Cursor`], so that [`Painter`]s that draw the cursor use its shape.
+    pub fn set_webview_cursor(
+        &self,
+        webview_id: WebViewId,
+        cursor: Cursor,
+    ) -> Result<(), PaintError> {
+        self.painter_mut(webview_id.into())?.set_cursor(cursor);
+        Ok(())
+    }
+
+    /// Start a continuous capture of the frames painted by the [`Painter`] with the given
+    /// [`PainterId`], sending at most `frames_per_second` [`CapturedFrame`]s per second over
+    /// the returned [`Receiver`]. Frames are only captured when the [`Painter`] paints, so
+    /// nothing is sent while its contents do not change. The capture stops once the
+    /// [`Receiver`] is dropped. Only a few frames are queued, so frames are dropped while the
+    /// receiver falls behind. This can back screen sharing and casting of windows.
+    pub fn start_painter_capture_stream(
+        &self,
+        painter_id: PainterId,
+        frames_per_second: f32,
+    ) -> Result<Receiver<CapturedFrame>, PaintError> {
+        Ok(self
+            .painter_mut(painter_id)?
+            .start_capture_stream(None, frames_per_second)?)
+    }
+
+    /// Like [`Paint::start_painter_capture_stream`], but only capture the area of the given
+    /// `WebView`. No frames are sent while the `WebView` is hidden and the capture stops
+    /// when the `WebView` is removed.
+    pub fn start_webview_capture_stream(
+        &self,
+        webview_id: WebViewId,
+        frames_per_second: f32,
+    ) -> Result<Receiver<CapturedFrame>, PaintError> {
+        Ok(self
+            .painter_mut(webview_id.into())?
+            .start_capture_stream(Some(webview_id), frames_per_second)?)
+    }
+
+    /// Start writing every frame painted by the [`Painter`] with the given [`PainterId`] to
+    /// `directory` as a sequence of numbered PNG images, whose names also contain the time at
+    /// which each frame was painted. This is meant for producing reproducible artifacts when
+    /// debugging rendering issues, as reading back every frame is slow.
+    pub fn start_painter_frame_recording(
+        &self,
+        painter_id: PainterId,
+        directory: PathBuf,
+    ) -> Result<(), PaintError> {
+        self.painter_mut(painter_id)?
+            .start_frame_recording(directory);
+        Ok(())
+    }
+
+    /// Stop recording the frames of the [`Painter`] with the given [`PainterId`]. This waits
+    /// for all frames recorded so far to be written.
+    pub fn stop_painter_frame_recording(&self, painter_id: PainterId) -> Result<(), PaintError> {
+        self.painter_mut(painter_id)?.stop_frame_recording();
+        Ok(())
+    }
+
+    /// Set the callback that the [`Painter`] with the given [`PainterId`] calls to let the
+    /// embedder draw on top of every frame, or remove it with `None`. The callback is called
+    /// after WebRender has rendered a frame and before it is presented, with the
+    /// [`RenderingContext`] of the [`Painter`] current. It must not call back into [`Paint`].
+    pub fn set_painter_overlay_drawing_callback(
+        &self,
+        painter_id: PainterId,
+        callback: Option<Box<dyn FnMut(&dyn RenderingContext)>>,
+    ) -> Result<(), PaintError> {
+        self.painter_mut(painter_id)?
+            .set_overlay_drawing_callback(callback);
+        Ok(())
+    }
+
+    /// Wait for the OpenGL driver of the [`Painter`] with the given [`PainterId`] to finish
+    /// all pending work, including the shaders it compiles in the background when using
+    /// [`ShaderPrecachePolicy::Async`]. Calling this right before the [`RenderingContext`] of
+    /// a [`Painter`] is first shown moves the cost of compiling shaders out of its first
+    /// frames. Returns whether the shaders of the [`Painter`] are ready.
+    pub fn warm_up_painter(&self, painter_id: PainterId) -> Result<bool, PaintError> {
+        Ok(self.painter_mut(painter_id)?.warm_up())
+    }
+
+    /// Change how the [`Painter`] with the given [`PainterId`] antialiases text. This takes
+    /// effect for all of its existing text on the next frame.
+    pub fn set_painter_text_antialiasing(
+        &self,
+        painter_id: PainterId,
+        text_antialiasing: TextAntialiasing,
+    ) -> Result<(), PaintError> {
+        self.painter_mut(painter_id)?
+            .set_text_antialiasing(text_antialiasing);
+        Ok(())
+    }
+
+    pub fn capture_webrender(&self, webview_id: WebViewId) -> Result<(), PaintError> {
+        self.capture_painter_webrender(webview_id.into())
+    }
+
+    /// Save a WebRender capture of the WebRender instance of the [`Painter`] with the given
+    /// [`PainterId`]. Each [`Painter`] is captured into its own directory, so that captures of
+    /// different `RenderingContext`s can be told apart.
+    pub fn capture_painter_webrender(&self, painter_id: PainterId) -> Result<(), PaintError> {
+        let painter = self.painter(painter_id)?;
         let capture_id = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .unwrap_or_default()
@@ -761,66 +2278,162 @@ impl Paint {
             .iter()
             .filter_map(|val| {
                 val.as_ref()
-                    .map(|dir| dir.join("webrender-captures").join(&capture_id))
+                    .map(|dir| {
+                        dir.join("webrender-captures")
+                            .join(&capture_id)
+                            .join(format!("{painter_id:?}"))
+                    })
                     .ok()
             })
             .find(|val| create_dir_all(val).is_ok());
 
         let Some(capture_path) = available_path else {
             log::error!("Couldn't create a path for WebRender captures.");
-            return;
+            return Ok(());
         };
 
         log::info!("Saving WebRender capture to {capture_path:?}");
-        self.painter(webview_id.into())
+        painter
             .webrender_api
             .save_capture(capture_path.clone(), CaptureBits::all());
+        Ok(())
     }
 
-    pub fn notify_input_event(&self, webview_id: WebViewId, event: InputEventAndId) {
+    pub fn notify_input_event(
+        &self,
+        webview_id: WebViewId,
+        event: InputEventAndId,
+    ) -> Result<(), PaintError> {
         if self.shutdown_state() != ShutdownState::NotShuttingDown {
-            return;
+            return Ok(());
         }
-        self.painter_mut(webview_id.into())
+        self.painter_mut(webview_id.into())?
             .notify_input_event(webview_id, event);
+        Ok(())
     }
 
-    pub fn notify_scroll_event(&self, webview_id: WebViewId, scroll: Scroll, point: WebViewPoint) {
+    pub fn notify_scroll_event(
+        &self,
+        webview_id: WebViewId,
+        scroll: Scroll,
+        point: WebViewPoint,
+    ) -> Result<(), PaintError> {
         if self.shutdown_state() != ShutdownState::NotShuttingDown {
-            return;
+            return Ok(());
         }
-        self.painter_mut(webview_id.into())
+        self.painter_mut(webview_id.into())?
             .notify_scroll_event(webview_id, scroll, point);
+        Ok(())
+    }
+
+    /// Smoothly scroll the viewport of the given `WebView` to `target_offset`, in CSS pixels,
+    /// over `duration`. The animation runs in the renderer on every frame of the `WebView`'s
+    /// [`Painter`], so it stays smooth even while script or layout are busy. A scroll event
+    /// for the `WebView` stops the animation.
+    pub fn smooth_scroll_webview_to(
+        &self,
+        webview_id: WebViewId,
+        target_offset: LayoutVector2D,
+        duration: Duration,
+    ) -> Result<(), PaintError> {
+        Ok(self
+            .painter_mut(webview_id.into())?
+            .smooth_scroll_viewport_to(webview_id, target_offset, duration)?)
     }
 
-    pub fn pinch_zoom(&self, webview_id: WebViewId, pinch_zoom_delta: f32, center: DevicePoint) {
+    /// Adjust the pinch zoom of the given `WebView`. Pinch zoom state is kept by the
+    /// [`WebViewRenderer`] of each `WebView` and applied in the root display list of its
+    /// [`Painter`], so this never affects other `WebView`s or WebRender instances.
+    pub fn pinch_zoom(
+        &self,
+        webview_id: WebViewId,
+        pinch_zoom_delta: f32,
+        center: DevicePoint,
+    ) -> Result<(), PaintError> {
         if self.shutdown_state() != ShutdownState::NotShuttingDown {
-            return;
+            return Ok(());
         }
-        self.painter_mut(webview_id.into())
+        self.painter_mut(webview_id.into())?
             .pinch_zoom(webview_id, pinch_zoom_delta, center);
+        Ok(())
     }
 
     pub fn device_pixels_per_page_pixel(
         &self,
         webview_id: WebViewId,
-    ) -> Scale<f32, CSSPixel, DevicePixel> {
-        self.painter_mut(webview_id.into())
-            .device_pixels_per_page_pixel(webview_id)
+    ) -> Result<Scale<f32, CSSPixel, DevicePixel>, PaintError> {
+        Ok(self
+            .painter(webview_id.into())?
+            .device_pixels_per_page_pixel(webview_id))
     }
 
     pub(crate) fn shutdown_state(&self) -> ShutdownState {
         self.shutdown_state.get()
     }
 
+    /// Read back the current contents of the entire `RenderingContext` used by the given
+    /// `WebView`. This returns `Ok(None)` if the pixels could not be read.
+    pub fn read_pixels(&self, webview_id: WebViewId) -> Result<Option<RgbaImage>, PaintError> {
+        Ok(self.painter(webview_id.into())?.read_pixels())
+    }
+
+    /// Capture the entire contents of the `RenderingContext` associated with the given
+    /// [`PainterId`]. The capture is taken after the next paint once the WebRender instance
+    /// of that `RenderingContext` has no more pending frames.
+    pub fn capture_rendering_context(
+        &self,
+        painter_id: PainterId,
+        callback: Box<dyn FnOnce(Result<RgbaImage, ScreenshotCaptureError>) + 'static>,
+    ) {
+        let Some(painter) = self.maybe_painter(painter_id) else {
+            callback(Err(ScreenshotCaptureError::RenderingContextDoesNotExist));
+            return;
+        };
+        painter.request_rendering_context_capture(callback);
+    }
+
+    /// Capture the entire `RenderingContext` of the given [`Painter`] like
+    /// [`Paint::capture_rendering_context`], but read its pixels straight into shared memory,
+    /// so that they can be handed to another thread or process without another copy.
+    pub fn capture_rendering_context_to_shared_memory(
+        &self,
+        painter_id: PainterId,
+        callback: Box<dyn FnOnce(Result<SharedMemoryCapture, ScreenshotCaptureError>) + 'static>,
+    ) {
+        let Some(painter) = self.maybe_painter(painter_id) else {
+            callback(Err(ScreenshotCaptureError::RenderingContextDoesNotExist));
+            return;
+        };
+        painter.request_rendering_context_capture_to_shared_memory(callback);
+    }
+
     pub fn request_screenshot(
         &self,
         webview_id: WebViewId,
         rect: Option<WebViewRect>,
         callback: Box<dyn FnOnce(Result<RgbaImage, ScreenshotCaptureError>) + 'static>,
     ) {
-        self.painter(webview_id.into())
-            .request_screenshot(webview_id, rect, callback);
+        let Some(painter) = self.maybe_painter(webview_id.into()) else {
+            callback(Err(ScreenshotCaptureError::WebViewDoesNotExist));
+            return;
+        };
+        painter.request_screenshot(webview_id, rect, callback);
+    }
+
+    /// Capture the given `WebView` on its own, without the other content of its
+    /// `RenderingContext` and even if it is hidden or covered by other `WebView`s. `rect` is
+    /// relative to the origin of the `WebView` and `None` captures the entire `WebView`.
+    pub fn capture_webview(
+        &self,
+        webview_id: WebViewId,
+        rect: Option<DeviceRect>,
+        callback: Box<dyn FnOnce(Result<RgbaImage, ScreenshotCaptureError>) + 'static>,
+    ) {
+        let Some(mut painter) = self.maybe_painter_mut(webview_id.into()) else {
+            callback(Err(ScreenshotCaptureError::WebViewDoesNotExist));
+            return;
+        };
+        painter.request_webview_capture(webview_id, rect, callback);
     }
 
     pub fn notify_input_event_handled(
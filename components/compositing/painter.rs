@@ -4,65 +4,143 @@
 
 use std::cell::Cell;
 use std::collections::hash_map::Entry;
+use std::path::PathBuf;
 use std::rc::Rc;
 use std::sync::Arc;
+use std::sync::mpsc::Receiver;
+use std::time::{Duration, Instant};
 
 use base::Epoch;
 use base::cross_process_instant::CrossProcessInstant;
 use base::generic_channel::GenericSharedMemory;
 use base::id::{PainterId, PipelineId, WebViewId};
-use compositing_traits::display_list::{PaintDisplayListInfo, ScrollType};
+use compositing_traits::display_list::{PaintDisplayListInfo, ScrollType, SpatialTreeNodeInfo};
 use compositing_traits::largest_contentful_paint_candidate::LCPCandidate;
-use compositing_traits::rendering_context::RenderingContext;
+use compositing_traits::rendering_context::{Framebuffer, RenderingContext};
 use compositing_traits::viewport_description::ViewportDescription;
 use compositing_traits::{
-    ImageUpdate, PipelineExitSource, SendableFrameTree, WebRenderExternalImageHandlers,
-    WebRenderImageHandlerType, WebViewTrait,
+    ImageUpdate, PipelineExitSource, RendererStatistics, SendableFrameTree, WebViewTrait,
 };
 use constellation_traits::{EmbedderToConstellationMessage, PaintMetricEvent};
 use crossbeam_channel::Sender;
 use dpi::PhysicalSize;
 use embedder_traits::{
-    InputEvent, InputEventAndId, InputEventId, InputEventResult, PaintHitTestResult,
+    Cursor, InputEvent, InputEventAndId, InputEventId, InputEventResult, PaintHitTestResult,
     ScreenshotCaptureError, Scroll, ViewportDetails, WebViewPoint, WebViewRect,
 };
-use euclid::{Point2D, Rect, Scale, Size2D};
-use gleam::gl::RENDERER;
+use euclid::{Point2D, Rect, Scale, Size2D, Transform2D};
 use image::RgbaImage;
 use ipc_channel::ipc::IpcBytesReceiver;
 use log::{debug, error, info, warn};
-use media::WindowGLContext;
 use profile_traits::time::{ProfilerCategory, ProfilerChan};
 use profile_traits::time_profile;
 use rustc_hash::{FxHashMap, FxHashSet};
-use servo_config::{opts, pref};
+use servo_config::pref;
 use servo_geometry::DeviceIndependentPixel;
 use smallvec::SmallVec;
 use style_traits::CSSPixel;
-use webrender::{
-    MemoryReport, ONE_TIME_USAGE_HINT, RenderApi, ShaderPrecacheFlags, Transaction, UploadMethod,
-};
+use webrender::{MemoryReport, RenderApi, RendererError, Transaction};
 use webrender_api::units::{
-    DevicePixel, DevicePoint, LayoutPoint, LayoutRect, LayoutSize, LayoutTransform, LayoutVector2D,
+    DeviceIntRect, DeviceIntSize, DevicePixel, DevicePoint, DeviceRect, DeviceVector2D,
+    LayoutPoint, LayoutRect, LayoutSideOffsets, LayoutSize, LayoutTransform, LayoutVector2D,
     WorldPoint,
 };
 use webrender_api::{
-    self, BuiltDisplayList, BuiltDisplayListDescriptor, ColorF, DirtyRect, DisplayListPayload,
-    DocumentId, Epoch as WebRenderEpoch, ExternalScrollId, FontInstanceFlags, FontInstanceKey,
-    FontInstanceOptions, FontKey, FontVariation, ImageKey, NativeFontHandle,
-    PipelineId as WebRenderPipelineId, PropertyBinding, ReferenceFrameKind, RenderReasons,
-    SampledScrollOffset, SpaceAndClipInfo, SpatialId, TransformStyle,
+    self, AlphaType, BorderDetails, BorderRadius, BorderSide, BorderStyle, BoxShadowClipMode,
+    BuiltDisplayList, BuiltDisplayListDescriptor, ClipChainId, ClipMode, ColorF,
+    CommonItemProperties, ComplexClipRegion, DirtyRect, DisplayListBuilder, DocumentId,
+    Epoch as WebRenderEpoch, ExtendMode, ExternalScrollId, FilterOp, FontInstanceFlags,
+    FontInstanceKey, FontInstanceOptions, FontKey, FontRenderMode, FontVariation, GradientStop,
+    ImageData, ImageDescriptor, ImageDescriptorFlags, ImageFormat, ImageKey, ImageRendering,
+    MixBlendMode, NativeFontHandle, PipelineId as WebRenderPipelineId, PrimitiveFlags,
+    PropertyBinding, RasterSpace, ReferenceFrameKind, RenderReasons, SampledScrollOffset,
+    SpaceAndClipInfo, SpatialId, StackingContextFlags, TransformStyle,
 };
 use wr_malloc_size_of::MallocSizeOfOps;
 
-use crate::Paint;
+use crate::capture_stream::{CaptureStream, CapturedFrame, MAX_QUEUED_CAPTURED_FRAMES};
+use crate::frame_recorder::FrameRecorder;
+use crate::input_latency::{InputLatencyHistogram, InputLatencyTracker};
 use crate::largest_contentful_paint_calculator::LargestContentfulPaintCalculator;
-use crate::paint::{RepaintReason, WebRenderDebugOption};
+use crate::overlay_scrollbars::OverlayScrollbarsRefreshDriverObserver;
+use crate::paint::{
+    DisplayListStatistics, PaintBackground, PainterOptions, RenderingPriority, RepaintReason,
+    TextAntialiasing, WebRenderDebugOption, WebViewClip, WebViewDecorations, WebViewLifecycleEvent,
+    WebViewPaintTiming,
+};
+use crate::pipeline_details::DisplayListData;
 use crate::refresh_driver::{AnimationRefreshDriverObserver, BaseRefreshDriver};
-use crate::render_notifier::RenderNotifier;
-use crate::screenshot::ScreenshotTaker;
-use crate::webrender_external_images::WebGLExternalImages;
+use crate::render_notifier::{ReadyFrames, SharedReadyFrames};
+use crate::screenshot::{ScreenshotTaker, SharedMemoryCapture, SharedMemoryCaptureCallback};
+use crate::synchronized_resize::SynchronizedResizeRefreshDriverObserver;
+use crate::webrender_instance::WebRenderInstanceFactory;
 use crate::webview_renderer::{PinchZoomResult, ScrollResult, UnknownWebView, WebViewRenderer};
+use crate::{Paint, cursor_layer, overlay_scrollbars};
+
+/// The number of frames in a row that may fail to render before a [`Painter`] stops trying to
+/// recover and stops rendering altogether. See [`Painter::handle_renderer_errors`].
+const MAX_CONSECUTIVE_FAILED_FRAMES: u32 = 3;
+
+/// Whether the WebRender renderer of a [`Painter`] renders its frames successfully.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum RendererHealth {
+    /// The given number of frames in a row have failed to render, which is zero as long as
+    /// the renderer works.
+    Recovering(u32),
+    /// Too many frames in a row failed to render and rendering was stopped. `reported` is
+    /// whether this has been taken by [`Paint::take_failed_painters`] yet.
+    Failed { reported: bool },
+}
+
+/// A callback waiting for a display list of a `WebView` to be painted. Until the next display
+/// list for the `WebView` arrives, `display_list` is `None`.
+struct FramePresentedCallback {
+    webview_id: WebViewId,
+    display_list: Option<(WebRenderPipelineId, WebRenderEpoch)>,
+    callback: Box<dyn FnOnce()>,
+}
+
+/// A resize of the [`RenderingContext`] of a [`Painter`] that waits for a frame at the new
+/// size to be presented. See [`Painter::resize_rendering_context_synchronized`] and
+/// [`Painter::set_resize_present_timeout`].
+struct SynchronizedResize {
+    size: PhysicalSize<u32>,
+    /// The `WebView`s that are resized along with the [`RenderingContext`] and, once they have
+    /// produced one, the first display list for their new viewport.
+    display_lists: FxHashMap<WebViewId, Option<(WebRenderPipelineId, WebRenderEpoch)>>,
+    /// The callback to call once a frame at the new size has been presented, if any.
+    callback: Option<Box<dyn FnOnce()>>,
+    /// Until when frames are held back while the content is not at the new size yet, or
+    /// `None` if they are not held back.
+    hold_frames_until: Option<Instant>,
+}
+
+/// A `WebView` of another [`Painter`] that is mirrored into this one. See
+/// [`Paint::mirror_webview`].
+struct WebViewMirror {
+    webview_id: WebViewId,
+    /// The area of the [`RenderingContext`] of this [`Painter`] that the mirrored `WebView` is
+    /// painted to.
+    rect: DeviceRect,
+    /// The WebRender image with the latest snapshot of the mirrored `WebView` and its size, once
+    /// one is available.
+    image: Option<(ImageKey, DeviceIntSize)>,
+}
+
+/// The parameters of a font instance added to WebRender, which are kept so that the
+/// instance can be recreated when the [`TextAntialiasing`] of a [`Painter`] changes.
+struct FontInstanceDetails {
+    font_key: FontKey,
+    size: f32,
+    flags: FontInstanceFlags,
+    variations: Vec<FontVariation>,
+}
+
+/// The data of a font that has been added to WebRender.
+enum FontData {
+    Raw(Arc<GenericSharedMemory>, u32),
+    Native(NativeFontHandle),
+}
 
 /// A [`Painter`] is responsible for all of the painting to a particular [`RenderingContext`].
 /// This holds all of the WebRender specific data structures and state necessary for painting
@@ -81,7 +159,31 @@ pub(crate) struct Painter {
     pub(crate) painter_id: PainterId,
 
     /// Our [`WebViewRenderer`]s, one for every `WebView`.
-    pub(crate) webview_renderers: FxHashMap<WebViewId, WebViewRenderer>,
+    /// Use [`Painter::webview_renderers`] to iterate over these in a deterministic order.
+    webview_renderers: FxHashMap<WebViewId, WebViewRenderer>,
+
+    /// The order in which `WebView`s are painted, from bottom to top. `WebView`s are
+    /// appended to the top when they are added.
+    painting_order: Vec<WebViewId>,
+
+    /// The `WebView`s of this [`Painter`] ordered by when they were last shown, from least to
+    /// most recently shown.
+    recently_shown: Vec<WebViewId>,
+
+    /// The maximum number of `WebView`s of this [`Painter`] that are not suspended, if any.
+    /// See [`Painter::update_webview_suspension`].
+    webview_limit: Option<usize>,
+
+    /// The `WebView`s of this [`Painter`] that are currently suspended.
+    suspended_webviews: FxHashSet<WebViewId>,
+
+    /// The `WebView`s of this [`Painter`] that the embedder throttled. These stay throttled
+    /// when they are resumed after being suspended. See [`Painter::set_webview_throttled`].
+    embedder_throttled_webviews: FxHashSet<WebViewId>,
+
+    /// The `WebView`s that were visible when [`Painter::hide_all_webviews`] was last called,
+    /// so that [`Painter::show_all_webviews`] can restore them.
+    webviews_hidden_by_hide_all: Vec<WebViewId>,
 
     /// Tracks whether or not the view needs to be repainted.
     pub(crate) needs_repaint: Cell<RepaintReason>,
@@ -89,6 +191,65 @@ pub(crate) struct Painter {
     /// The number of frames pending to receive from WebRender.
     pub(crate) pending_frames: Cell<usize>,
 
+    /// The number of frames requested from WebRender since this [`Painter`] was created.
+    /// Together with `frames_ready`, this identifies the frame that contains a given change,
+    /// so that screenshots can wait for that frame rather than for all pending frames.
+    frames_requested: Cell<u64>,
+
+    /// The number of frames that WebRender has finished building since this [`Painter`] was
+    /// created.
+    frames_ready: Cell<u64>,
+
+    /// Whether or not frame generation is throttled for this [`Painter`]. This happens when
+    /// none of its `WebView`s are visible, when the embedder has reported that the
+    /// [`RenderingContext`] is occluded, or when rendering is paused. While throttled,
+    /// WebRender transactions are still sent, but no new frames are generated for them.
+    throttled: Cell<bool>,
+
+    /// Whether or not the embedder has reported that the [`RenderingContext`] of this
+    /// [`Painter`] is occluded, for instance because its window is minimized.
+    occluded: bool,
+
+    /// Whether or not the embedder has paused rendering for this [`Painter`]. While paused,
+    /// transactions are queued in `paused_transactions` instead of being sent to WebRender
+    /// and nothing is rendered.
+    paused: bool,
+
+    /// The priority of this [`Painter`] when generating frames. See
+    /// [`Painter::flush_pending_transactions_for_priority`].
+    priority: RenderingPriority,
+
+    /// Whether the last call to [`Painter::flush_pending_transactions_for_priority`] deferred
+    /// a frame in favor of a higher priority [`Painter`].
+    frame_deferred_for_priority: bool,
+
+    /// WebRender transactions waiting to be sent by [`Painter::flush_pending_transactions`].
+    pending_transactions: Vec<Transaction>,
+
+    /// The reasons for the new frame requested since the last call to
+    /// [`Painter::flush_pending_transactions`], if any.
+    pending_frame_reasons: Cell<Option<RenderReasons>>,
+
+    /// Whether the root pipeline display list needs to be rebuilt during the next call to
+    /// [`Painter::flush_pending_transactions`].
+    root_pipeline_display_list_dirty: bool,
+
+    /// The WebRender frames that are ready, but have not been handled yet. This is shared with
+    /// the [`crate::render_notifier::RenderNotifier`] of this [`Painter`].
+    ready_frames: SharedReadyFrames,
+
+    /// Whether or not new frames are only generated when the embedder reports a vsync tick
+    /// for the [`RenderingContext`] of this [`Painter`] with [`Painter::notify_vsync`].
+    vsync_driven: bool,
+
+    /// Whether a vsync tick has arrived that allows generating a frame during the current
+    /// call to [`Painter::flush_pending_transactions`].
+    vsync_tick: bool,
+
+    /// WebRender transactions that were queued while this [`Painter`] was paused. These are
+    /// sent, in order, once rendering resumes.
+    paused_transactions: Vec<Transaction>,
+
     /// The [`BaseRefreshDriver`] which manages the painting of `WebView`s during animations.
     refresh_driver: Rc<BaseRefreshDriver>,
 
@@ -104,16 +265,137 @@ pub(crate) struct Painter {
     /// The webrender renderer.
     pub(crate) webrender_renderer: Option<webrender::Renderer>,
 
+    /// The areas of the [`RenderingContext`] that changed during the last call to
+    /// [`Painter::render`], as reported by WebRender. This is empty if the entire surface
+    /// may have changed.
+    last_frame_damage: Vec<DeviceIntRect>,
+
+    /// The [`RendererStatistics`] reported by WebRender for the last call to
+    /// [`Painter::render`] or `None` if nothing has been rendered yet.
+    last_renderer_statistics: Option<RendererStatistics>,
+
     /// The GL bindings for webrender
     webrender_gl: Rc<dyn gleam::gl::Gl>,
 
+    /// Creates a new WebRender instance when the current one has to be replaced. See
+    /// [`Painter::recreate_webrender_instance`].
+    webrender_instance_factory: WebRenderInstanceFactory,
+
+    /// The color used to clear the [`RenderingContext`] before painting. This defaults
+    /// to the value of the `shell_background_color_rgba` preference, but can be changed
+    /// by the embedder.
+    clear_color: ColorF,
+
+    /// The [`PaintBackground`] painted below all `WebView`s.
+    background: PaintBackground,
+
+    /// The WebRender image used for a [`PaintBackground::Image`] background, if any.
+    background_image_key: Option<ImageKey>,
+
+    /// The `WebView`s of this [`Painter`] that are mirrored into other [`Painter`]s. Their
+    /// contents are read back after every paint.
+    mirrored_webviews: FxHashSet<WebViewId>,
+
+    /// The snapshots of mirrored `WebView`s taken during the last paint, which have not yet
+    /// been taken by [`Painter::take_mirror_snapshots`].
+    mirror_snapshots: Vec<(WebViewId, RgbaImage)>,
+
+    /// The `WebView`s of other [`Painter`]s that are mirrored into this one, painted above all
+    /// of the `WebView`s of this [`Painter`].
+    mirrors: Vec<WebViewMirror>,
+
+    /// The `WebView` that is being captured on its own, if any. While this is set, the root
+    /// display list only contains this `WebView` and frames are not painted to the
+    /// [`RenderingContext`]. See [`Painter::request_webview_capture`].
+    isolated_webview: Option<WebViewId>,
+
+    /// Whether a frame has been rendered outside of the [`RenderingContext`] since the last
+    /// call to [`Painter::render`], or the last frame failed to render, which means that
+    /// WebRender's damage tracking cannot be trusted for the next frame.
+    rendered_offscreen: bool,
+
+    /// The errors returned by the WebRender renderer of this [`Painter`] that have not been
+    /// taken by [`Paint::take_renderer_errors`] yet.
+    renderer_errors: Vec<RendererError>,
+
+    /// Whether the WebRender renderer of this [`Painter`] is still rendering frames, or has
+    /// been given up on after failing too often. See [`Painter::handle_renderer_errors`].
+    renderer_health: RendererHealth,
+
+    /// The number of upcoming frames that fail to render on purpose. See
+    /// [`Painter::simulate_renderer_failure`].
+    simulated_failed_frames: u32,
+
+    /// How text is antialiased. See [`Painter::set_text_antialiasing`].
+    text_antialiasing: TextAntialiasing,
+
+    /// The font instances that have been added to WebRender by this [`Painter`].
+    font_instances: FxHashMap<FontInstanceKey, FontInstanceDetails>,
+
+    /// The fonts that have been added to WebRender by this [`Painter`], which are kept so that
+    /// they can be added to a new WebRender instance. See
+    /// [`Painter::recreate_webrender_instance`].
+    fonts: FxHashMap<FontKey, FontData>,
+
+    /// The images that have been added to WebRender for the `WebView`s of this [`Painter`],
+    /// which are kept for the same reason as `fonts`.
+    images: FxHashMap<ImageKey, (ImageDescriptor, ImageData)>,
+
     /// The last position in the rendered view that the mouse moved over. This becomes `None`
     /// when the mouse leaves the rendered view.
     pub(crate) last_mouse_move_position: Option<DevicePoint>,
 
+    /// Whether or not this [`Painter`] draws the cursor at `last_mouse_move_position` on top
+    /// of its `WebView`s. See [`Painter::set_draws_cursor`].
+    draws_cursor: bool,
+
+    /// The most recent [`Cursor`] requested by the page under the mouse in any of the
+    /// `WebView`s of this [`Painter`].
+    cursor: Cursor,
+
     /// A [`ScreenshotTaker`] responsible for handling all screenshot requests.
     pub(crate) screenshot_taker: ScreenshotTaker,
 
+    /// Measures the time between input events reaching this [`Painter`] and the presentation
+    /// of the frames that reflect them.
+    input_latency_tracker: InputLatencyTracker,
+
+    /// When set, every frame painted by this [`Painter`] is written to disk. See
+    /// [`Painter::start_frame_recording`].
+    frame_recorder: Option<FrameRecorder>,
+
+    /// Continuous captures of the frames painted by this [`Painter`]. See
+    /// [`Painter::start_capture_stream`].
+    capture_streams: Vec<CaptureStream>,
+
+    /// A callback of the embedder that draws on top of every frame rendered by WebRender,
+    /// before it is presented. See [`Painter::set_overlay_drawing_callback`].
+    overlay_drawing_callback: Option<Box<dyn FnMut(&dyn RenderingContext)>>,
+
+    /// Callbacks waiting for the next display list of a `WebView` to be painted. See
+    /// [`Painter::request_frame_presented_notification`].
+    frame_presented_callbacks: Vec<FramePresentedCallback>,
+
+    /// A resize waiting for a frame at the new size to be presented. See
+    /// [`Painter::resize_rendering_context_synchronized`].
+    synchronized_resize: Option<SynchronizedResize>,
+
+    /// How long frames are held back after a resize, waiting for the content to be laid out
+    /// at the new size. See [`Painter::set_resize_present_timeout`].
+    resize_present_timeout: Option<Duration>,
+
+    /// Whether or not a [`SynchronizedResizeRefreshDriverObserver`] is currently registered
+    /// for this [`Painter`].
+    observing_frames_for_synchronized_resize: bool,
+
+    /// When the viewport of each `WebView` whose overlay scrollbars are visible was last
+    /// scrolled. See [`Painter::show_overlay_scrollbars`].
+    overlay_scrollbars: FxHashMap<WebViewId, Instant>,
+
+    /// Whether or not an [`OverlayScrollbarsRefreshDriverObserver`] is currently registered
+    /// for this [`Painter`].
+    observing_frames_for_overlay_scrollbars: bool,
+
     /// A [`FrameRequestDelayer`] which is used to wait for canvas image updates to
     /// arrive before requesting a new frame, as these happen asynchronously with
     /// `ScriptThread` display list construction.
@@ -128,49 +410,16 @@ pub(crate) struct Painter {
 
 impl Drop for Painter {
     fn drop(&mut self) {
-        if let Err(error) = self.rendering_context.make_current() {
-            error!("Failed to make the rendering context current: {error:?}");
-        }
-
-        self.webrender_api.stop_render_backend();
-        self.webrender_api.shut_down(true);
-
-        if let Some(renderer) = self.webrender_renderer.take() {
-            renderer.deinit();
-        }
+        self.shut_down_webrender_instance();
     }
 }
 
 impl Painter {
-    pub(crate) fn new(rendering_context: Rc<dyn RenderingContext>, paint: &Paint) -> Self {
-        let webrender_gl = rendering_context.gleam_gl_api();
-
-        // Make sure the gl context is made current.
-        if let Err(err) = rendering_context.make_current() {
-            warn!("Failed to make the rendering context current: {:?}", err);
-        }
-        debug_assert_eq!(webrender_gl.get_error(), gleam::gl::NO_ERROR,);
-
-        let id_manager = paint.webrender_external_image_id_manager();
-        let mut external_image_handlers = Box::new(WebRenderExternalImageHandlers::new(id_manager));
-
-        // Set WebRender external image handler for WebGL textures.
-        let image_handler = Box::new(WebGLExternalImages::new(
-            paint.webgl_threads(),
-            rendering_context.clone(),
-            paint.swap_chains.clone(),
-            paint.busy_webgl_contexts_map.clone(),
-        ));
-        external_image_handlers.set_handler(image_handler, WebRenderImageHandlerType::WebGl);
-
-        #[cfg(feature = "webgpu")]
-        external_image_handlers.set_handler(
-            Box::new(webgpu::WebGpuExternalImages::new(paint.webgpu_image_map())),
-            WebRenderImageHandlerType::WebGpu,
-        );
-
-        WindowGLContext::initialize_image_handler(&mut external_image_handlers);
-
+    pub(crate) fn new(
+        rendering_context: Rc<dyn RenderingContext>,
+        paint: &Paint,
+        options: &PainterOptions,
+    ) -> Self {
         let embedder_to_constellation_sender = paint.embedder_to_constellation_sender.clone();
         let refresh_driver = Rc::new(BaseRefreshDriver::new(
             paint.event_loop_waker.clone_box(),
@@ -180,92 +429,83 @@ impl Painter {
             embedder_to_constellation_sender.clone(),
         ));
 
-        rendering_context.prepare_for_rendering();
-        let clear_color = servo_config::pref!(shell_background_color_rgba);
-        let clear_color = ColorF::new(
-            clear_color[0] as f32,
-            clear_color[1] as f32,
-            clear_color[2] as f32,
-            clear_color[3] as f32,
-        );
-
-        // Use same texture upload method as Gecko with ANGLE:
-        // https://searchfox.org/mozilla-central/source/gfx/webrender_bindings/src/bindings.rs#1215-1219
-        let upload_method = if webrender_gl.get_string(RENDERER).starts_with("ANGLE") {
-            UploadMethod::Immediate
-        } else {
-            UploadMethod::PixelBuffer(ONE_TIME_USAGE_HINT)
-        };
-        let worker_threads = std::thread::available_parallelism()
-            .map(|i| i.get())
-            .unwrap_or(pref!(threadpools_fallback_worker_num) as usize)
-            .min(pref!(threadpools_webrender_workers_max).max(1) as usize);
-        let workers = Some(Arc::new(
-            rayon::ThreadPoolBuilder::new()
-                .num_threads(worker_threads)
-                .thread_name(|idx| format!("WRWorker#{}", idx))
-                .build()
-                .expect("Unable to initialize WebRender worker pool."),
-        ));
-
+        let clear_color = default_clear_color();
         let painter_id = PainterId::next();
-        let (mut webrender_renderer, webrender_api_sender) = webrender::create_webrender_instance(
-            webrender_gl.clone(),
-            Box::new(RenderNotifier::new(painter_id, paint.paint_proxy.clone())),
-            webrender::WebRenderOptions {
-                // We force the use of optimized shaders here because rendering is broken
-                // on Android emulators with unoptimized shaders. This is due to a known
-                // issue in the emulator's OpenGL emulation layer.
-                // See: https://github.com/servo/servo/issues/31726
-                use_optimized_shaders: true,
-                resource_override_path: opts::get().shaders_path.clone(),
-                debug_flags: webrender::DebugFlags::empty(),
-                precache_flags: if pref!(gfx_precache_shaders) {
-                    ShaderPrecacheFlags::FULL_COMPILE
-                } else {
-                    ShaderPrecacheFlags::empty()
-                },
-                enable_aa: pref!(gfx_text_antialiasing_enabled),
-                enable_subpixel_aa: pref!(gfx_subpixel_text_antialiasing_enabled),
-                allow_texture_swizzling: pref!(gfx_texture_swizzling_enabled),
-                clear_color,
-                upload_method,
-                workers,
-                size_of_op: Some(servo_allocator::usable_size),
-                // This ensures that we can use the `PainterId` as the `IdNamespace`, which allows mapping
-                // from `FontKey`, `FontInstanceKey`, and `ImageKey` back to `PainterId`.
-                namespace_alloc_by_client: true,
-                shared_font_namespace: Some(painter_id.into()),
-                ..Default::default()
-            },
+        let ready_frames = SharedReadyFrames::default();
+        let webrender_instance_factory = WebRenderInstanceFactory::new(paint, options);
+        let webrender_instance = webrender_instance_factory.create_instance(
+            painter_id,
+            &rendering_context,
+            ready_frames.clone(),
+            clear_color,
             None,
-        )
-        .expect("Unable to initialize WebRender.");
-
-        webrender_renderer.set_external_image_handler(external_image_handlers);
-
-        let webrender_api = webrender_api_sender.create_api_by_client(painter_id.into());
-        let webrender_document = webrender_api.add_document(rendering_context.size2d().to_i32());
-
-        let gl_renderer = webrender_gl.get_string(gleam::gl::RENDERER);
-        let gl_version = webrender_gl.get_string(gleam::gl::VERSION);
-        info!("Running on {gl_renderer} with OpenGL version {gl_version}");
+        );
 
         let painter = Painter {
             painter_id,
             embedder_to_constellation_sender,
             webview_renderers: Default::default(),
+            painting_order: Default::default(),
+            recently_shown: Default::default(),
+            webview_limit: None,
+            suspended_webviews: Default::default(),
+            embedder_throttled_webviews: Default::default(),
+            webviews_hidden_by_hide_all: Default::default(),
             rendering_context,
             needs_repaint: Cell::default(),
             pending_frames: Default::default(),
+            frames_requested: Default::default(),
+            frames_ready: Default::default(),
+            priority: Default::default(),
+            frame_deferred_for_priority: false,
+            throttled: Default::default(),
+            occluded: false,
+            paused: false,
+            paused_transactions: Default::default(),
+            pending_transactions: Default::default(),
+            pending_frame_reasons: Default::default(),
+            root_pipeline_display_list_dirty: false,
+            vsync_driven: false,
+            vsync_tick: false,
+            ready_frames,
             screenshot_taker: Default::default(),
+            input_latency_tracker: Default::default(),
+            frame_recorder: None,
+            overlay_drawing_callback: None,
+            capture_streams: Vec::new(),
+            frame_presented_callbacks: Default::default(),
+            synchronized_resize: None,
+            resize_present_timeout: None,
+            observing_frames_for_synchronized_resize: false,
+            overlay_scrollbars: Default::default(),
+            observing_frames_for_overlay_scrollbars: false,
             refresh_driver,
             animation_refresh_driver_observer,
-            webrender_renderer: Some(webrender_renderer),
-            webrender_api,
-            webrender_document,
-            webrender_gl,
+            webrender_renderer: Some(webrender_instance.renderer),
+            webrender_api: webrender_instance.api,
+            webrender_document: webrender_instance.document,
+            webrender_gl: webrender_instance.gl,
+            webrender_instance_factory,
+            last_frame_damage: Vec::new(),
+            last_renderer_statistics: None,
+            clear_color,
+            background: PaintBackground::None,
+            background_image_key: None,
+            mirrored_webviews: Default::default(),
+            mirror_snapshots: Default::default(),
+            mirrors: Default::default(),
+            isolated_webview: None,
+            rendered_offscreen: false,
+            renderer_errors: Vec::new(),
+            renderer_health: RendererHealth::Recovering(0),
+            simulated_failed_frames: 0,
+            text_antialiasing: Default::default(),
+            font_instances: Default::default(),
+            fonts: Default::default(),
+            images: Default::default(),
             last_mouse_move_position: None,
+            draws_cursor: false,
+            cursor: Cursor::Default,
             frame_delayer: Default::default(),
             lcp_calculator: LargestContentfulPaintCalculator::new(),
         };
@@ -281,18 +521,19 @@ impl Painter {
         }
 
         let mut need_zoom = false;
-        let scroll_offset_updates: Vec<_> = self
-            .webview_renderers
-            .values_mut()
-            .filter_map(|webview_renderer| {
-                let (zoom, scroll_result) = webview_renderer
-                    .process_pending_scroll_and_pinch_zoom_events(&self.webrender_api);
-                need_zoom = need_zoom || (zoom == PinchZoomResult::DidPinchZoom);
-                scroll_result
-            })
-            .collect();
+        let mut scroll_offset_updates = Vec::new();
+        for webview_id in self.painting_order.iter() {
+            let Some(webview_renderer) = self.webview_renderers.get_mut(webview_id) else {
+                continue;
+            };
+            let (zoom, scroll_result) =
+                webview_renderer.process_pending_scroll_and_pinch_zoom_events(&self.webrender_api);
+            need_zoom = need_zoom || (zoom == PinchZoomResult::DidPinchZoom);
+            scroll_offset_updates.extend(scroll_result);
+        }
 
         self.send_zoom_and_scroll_offset_updates(need_zoom, scroll_offset_updates);
+        self.flush_pending_transactions();
     }
 
     #[track_caller]
@@ -330,6 +571,30 @@ impl Painter {
         self.pending_frames.get() != 0 || self.frame_delayer.pending_frame
     }
 
+    /// An identifier for the most recently requested frame, which is the first frame that
+    /// contains all of the changes sent to WebRender so far. Returns `None` if the
+    /// [`FrameDelayer`] is still delaying a frame, because the identifier of that frame is
+    /// not known yet.
+    pub(crate) fn latest_requested_frame(&self) -> Option<u64> {
+        if self.frame_delayer.pending_frame {
+            return None;
+        }
+        Some(self.frames_requested.get())
+    }
+
+    /// Whether the frame with the given identifier, as returned by
+    /// [`Painter::latest_requested_frame`], or a later one is ready to be painted. A frame
+    /// that was dropped because this [`Painter`] is throttled counts as ready.
+    pub(crate) fn frame_is_ready(&self, frame: u64) -> bool {
+        self.frames_ready.get() >= frame.min(self.frames_requested.get())
+    }
+
+    /// Whether WebRender is building a frame for this [`Painter`] that is not ready yet.
+    pub(crate) fn has_frames_in_flight(&self) -> bool {
+        let requested_frames = usize::from(self.pending_frame_reasons.get().is_some());
+        self.pending_frames.get() > requested_frames
+    }
+
     pub(crate) fn set_needs_repaint(&self, reason: RepaintReason) {
         let mut needs_repaint = self.needs_repaint.get();
         needs_repaint.insert(reason);
@@ -337,6 +602,10 @@ impl Painter {
     }
 
     pub(crate) fn needs_repaint(&self) -> bool {
+        if self.paused {
+            return false;
+        }
+
         let repaint_reason = self.needs_repaint.get();
         if repaint_reason.is_empty() {
             return false;
@@ -347,14 +616,12 @@ impl Painter {
 
     /// Returns true if any animation callbacks (ie `requestAnimationFrame`) are waiting for a response.
     pub(crate) fn animation_callbacks_running(&self) -> bool {
-        self.webview_renderers
-            .values()
+        self.webview_renderers()
             .any(WebViewRenderer::animation_callbacks_running)
     }
 
     pub(crate) fn animating_webviews(&self) -> Vec<WebViewId> {
-        self.webview_renderers
-            .values()
+        self.webview_renderers()
             .filter_map(|webview_renderer| {
                 if webview_renderer.animating() {
                     Some(webview_renderer.id)
@@ -365,6 +632,20 @@ impl Painter {
             .collect()
     }
 
+    /// The animating `WebView`s of this [`Painter`] whose animations are due for a tick at
+    /// `now`. `WebView`s with a capped frame rate skip ticks until their next frame is due.
+    pub(crate) fn take_animating_webviews_due_for_tick(&mut self, now: Instant) -> Vec<WebViewId> {
+        let animating_webviews = self.animating_webviews();
+        animating_webviews
+            .into_iter()
+            .filter(|webview_id| {
+                self.webview_renderers
+                    .get_mut(webview_id)
+                    .is_some_and(|webview_renderer| webview_renderer.take_animation_tick(now))
+            })
+            .collect()
+    }
+
     pub(crate) fn send_to_constellation(&self, message: EmbedderToConstellationMessage) {
         if let Err(error) = self.embedder_to_constellation_sender.send(message) {
             warn!("Could not send message to constellation ({error:?})");
@@ -373,6 +654,16 @@ impl Painter {
 
     #[servo_tracing::instrument(skip_all)]
     pub(crate) fn render(&mut self, time_profiler_channel: &ProfilerChan) {
+        if self.paused ||
+            self.isolated_webview.is_some() ||
+            self.holding_frames_for_resize() ||
+            self.rendering_failed()
+        {
+            return;
+        }
+
+        self.flush_pending_transactions();
+
         let refresh_driver = self.refresh_driver.clone();
         refresh_driver.notify_will_paint(self);
 
@@ -383,6 +674,7 @@ impl Painter {
 
         self.rendering_context.prepare_for_rendering();
 
+        let mut render_errors = Vec::new();
         time_profile!(
             ProfilerCategory::Painting,
             None,
@@ -393,104 +685,726 @@ impl Painter {
                 }
 
                 // Paint the scene.
-                // TODO(gw): Take notice of any errors the renderer returns!
-                self.clear_background();
+                // When the back buffer still holds an earlier frame, WebRender only repaints
+                // the damaged areas, so the rest of the buffer must not be cleared. The
+                // embedder overlay may have drawn anywhere in that frame, so everything is
+                // repainted while there is one.
+                let buffer_age = if std::mem::take(&mut self.rendered_offscreen) ||
+                    self.overlay_drawing_callback.is_some()
+                {
+                    0
+                } else {
+                    self.rendering_context.buffer_age().unwrap_or(0)
+                };
+                if buffer_age == 0 {
+                    self.clear_background();
+                }
                 if let Some(renderer) = self.webrender_renderer.as_mut() {
                     let size = self.rendering_context.size2d().to_i32();
-                    renderer.render(size, 0 /* buffer_age */).ok();
+                    let result = if self.simulated_failed_frames > 0 {
+                        self.simulated_failed_frames -= 1;
+                        Err(vec![RendererError::OutOfMemory])
+                    } else {
+                        renderer.render(size, buffer_age)
+                    };
+                    match result {
+                        Ok(results) => {
+                            self.last_renderer_statistics = Some(RendererStatistics {
+                                draw_calls: results.stats.total_draw_calls,
+                                alpha_target_count: results.stats.alpha_target_count,
+                                color_target_count: results.stats.color_target_count,
+                                texture_upload_mb: results.stats.texture_upload_mb,
+                                resource_upload_time: results.stats.resource_upload_time,
+                                gpu_cache_upload_time: results.stats.gpu_cache_upload_time,
+                            });
+                            self.last_frame_damage = results.dirty_rects;
+                            self.renderer_health = RendererHealth::Recovering(0);
+                        },
+                        Err(errors) => {
+                            self.last_frame_damage = Vec::new();
+                            render_errors = errors;
+                        },
+                    }
                 }
             }
         );
+        if !render_errors.is_empty() {
+            self.handle_renderer_errors(render_errors);
+        }
+        self.draw_embedder_overlay();
 
         // We've painted the default target, which means that from the embedder's perspective,
         // the scene no longer needs to be repainted.
         self.needs_repaint.set(RepaintReason::empty());
 
+        if let Some(frame_recorder) = self.frame_recorder.as_mut() {
+            frame_recorder.record_frame(&*self.rendering_context);
+        }
+        self.snapshot_mirrored_webviews();
+        self.send_capture_stream_frames();
+        for webview_renderer in self.webview_renderers.values_mut() {
+            webview_renderer.note_painted();
+        }
+
         self.screenshot_taker.maybe_take_screenshots(self);
         self.send_pending_paint_metrics_messages_after_composite();
+        self.run_frame_presented_callbacks();
+
+        // Only frames that have actually been requested can reflect an input event, so a
+        // repaint of an older frame does not count.
+        let frames_requested = self.frames_requested.get();
+        let frames_ready = self.frames_ready.get();
+        self.input_latency_tracker.frame_presented(
+            |frame| frame <= frames_requested && frame <= frames_ready,
+            time_profiler_channel,
+        );
     }
 
-    fn clear_background(&self) {
-        self.assert_gl_framebuffer_complete();
+    /// The [`DisplayListStatistics`] of the given `WebView`.
+    pub(crate) fn display_list_statistics(
+        &self,
+        webview_id: WebViewId,
+    ) -> Result<DisplayListStatistics, UnknownWebView> {
+        Ok(self
+            .webview_renderers
+            .get(&webview_id)
+            .ok_or(UnknownWebView(webview_id))?
+            .display_list_statistics())
+    }
 
-        // Always clear the entire RenderingContext, regardless of how many WebViews there are
-        // or where they are positioned. This is so WebView actually clears even before the
-        // first WebView is ready.
-        let color = servo_config::pref!(shell_background_color_rgba);
-        self.webrender_gl.clear_color(
-            color[0] as f32,
-            color[1] as f32,
-            color[2] as f32,
-            color[3] as f32,
-        );
-        self.webrender_gl.clear(gleam::gl::COLOR_BUFFER_BIT);
+    /// Start writing every frame painted by this [`Painter`] to the given directory as a
+    /// sequence of numbered PNG images, replacing any ongoing recording.
+    pub(crate) fn start_frame_recording(&mut self, directory: PathBuf) {
+        self.frame_recorder = Some(FrameRecorder::new(directory));
+        self.set_needs_repaint(RepaintReason::NewWebRenderFrame);
     }
 
-    /// Send all pending paint metrics messages after a composite operation, which may advance
-    /// the epoch for pipelines in the WebRender scene.
-    ///
-    /// If there are pending paint metrics, we check if any of the painted epochs is one
-    /// of the ones that the paint metrics recorder is expecting. In that case, we get the
-    /// current time, inform the constellation about it and remove the pending metric from
-    /// the list.
-    fn send_pending_paint_metrics_messages_after_composite(&mut self) {
-        let paint_time = CrossProcessInstant::now();
-        for webview_renderer in self.webview_renderers.values() {
-            for (pipeline_id, pipeline) in webview_renderer.pipelines.iter() {
-                let Some(current_epoch) = self
-                    .webrender_renderer
-                    .as_ref()
-                    .and_then(|wr| wr.current_epoch(self.webrender_document, pipeline_id.into()))
-                else {
-                    continue;
-                };
+    /// Stop the ongoing frame recording of this [`Painter`], if any, waiting for all of its
+    /// frames to be written.
+    pub(crate) fn stop_frame_recording(&mut self) {
+        self.frame_recorder = None;
+    }
 
-                match pipeline.first_paint_metric.get() {
-                    // We need to check whether the current epoch is later, because
-                    // CrossProcessPaintMessage::SendInitialTransaction sends an
-                    // empty display list to WebRender which can happen before we receive
-                    // the first "real" display list.
-                    PaintMetricState::Seen(epoch, first_reflow) if epoch <= current_epoch => {
-                        assert!(epoch <= current_epoch);
-                        #[cfg(feature = "tracing")]
-                        tracing::info!(
-                            name: "FirstPaint",
-                            servo_profiling = true,
-                            epoch = ?epoch,
-                            paint_time = ?paint_time,
-                            pipeline_id = ?pipeline_id,
-                        );
+    /// Set the callback that draws the overlay of the embedder on top of every frame, or
+    /// remove it with `None`. The callback is called after WebRender has rendered a frame
+    /// and before it is presented, with the [`RenderingContext`] of this [`Painter`] current,
+    /// and must restore any OpenGL state it changes.
+    pub(crate) fn set_overlay_drawing_callback(
+        &mut self,
+        callback: Option<Box<dyn FnMut(&dyn RenderingContext)>>,
+    ) {
+        self.overlay_drawing_callback = callback;
+        self.set_needs_repaint(RepaintReason::NewWebRenderFrame);
+    }
 
-                        self.send_to_constellation(EmbedderToConstellationMessage::PaintMetric(
-                            *pipeline_id,
-                            PaintMetricEvent::FirstPaint(paint_time, first_reflow),
-                        ));
+    /// Let the embedder draw its overlay on top of the frame that WebRender just rendered.
+    /// Since the overlay can change anywhere, the whole surface is presented as damaged.
+    fn draw_embedder_overlay(&mut self) {
+        let Some(callback) = self.overlay_drawing_callback.as_mut() else {
+            return;
+        };
+        callback(&*self.rendering_context);
+        self.last_frame_damage = Vec::new();
+        self.assert_no_gl_error();
+    }
 
-                        pipeline.first_paint_metric.set(PaintMetricState::Sent);
-                    },
-                    _ => {},
-                }
+    /// The [`InputLatencyHistogram`] of the most recent input events of the given `WebView`.
+    pub(crate) fn input_latency_histogram(
+        &self,
+        webview_id: WebViewId,
+    ) -> Result<InputLatencyHistogram, UnknownWebView> {
+        if !self.webview_renderers.contains_key(&webview_id) {
+            return Err(UnknownWebView(webview_id));
+        }
+        Ok(self.input_latency_tracker.histogram(webview_id))
+    }
 
-                match pipeline.first_contentful_paint_metric.get() {
-                    PaintMetricState::Seen(epoch, first_reflow) if epoch <= current_epoch => {
-                        #[cfg(feature = "tracing")]
-                        tracing::info!(
-                            name: "FirstContentfulPaint",
-                            servo_profiling = true,
-                            epoch = ?epoch,
-                            paint_time = ?paint_time,
-                            pipeline_id = ?pipeline_id,
-                        );
-                        self.send_to_constellation(EmbedderToConstellationMessage::PaintMetric(
-                            *pipeline_id,
-                            PaintMetricEvent::FirstContentfulPaint(paint_time, first_reflow),
-                        ));
-                        pipeline
-                            .first_contentful_paint_metric
-                            .set(PaintMetricState::Sent);
+    /// The [`WebViewPaintTiming`] of the root pipeline of the given `WebView`.
+    pub(crate) fn paint_timing(
+        &self,
+        webview_id: WebViewId,
+    ) -> Result<WebViewPaintTiming, UnknownWebView> {
+        let webview_renderer = self
+            .webview_renderers
+            .get(&webview_id)
+            .ok_or(UnknownWebView(webview_id))?;
+        Ok(webview_renderer
+            .root_pipeline_id
+            .and_then(|pipeline_id| webview_renderer.pipelines.get(&pipeline_id))
+            .map(|pipeline| pipeline.paint_timing.get())
+            .unwrap_or_default())
+    }
+
+    pub(crate) fn renderer_statistics(&self) -> Option<RendererStatistics> {
+        self.last_renderer_statistics
+    }
+
+    /// Read back the contents of every visible `WebView` of this [`Painter`] that is mirrored
+    /// into another [`Painter`]. This must happen after rendering and before presenting, while
+    /// the back buffer holds the new frame.
+    fn snapshot_mirrored_webviews(&mut self) {
+        if self.mirrored_webviews.is_empty() {
+            return;
+        }
+
+        let mut snapshots = Vec::new();
+        for webview_renderer in self.webview_renderers() {
+            if webview_renderer.hidden() || !self.mirrored_webviews.contains(&webview_renderer.id) {
+                continue;
+            }
+            let rect = self.framebuffer_rect(webview_renderer.painted_rect());
+            if let Some(image) = self.rendering_context.read_to_image(rect) {
+                snapshots.push((webview_renderer.id, image));
+            }
+        }
+        self.mirror_snapshots = snapshots;
+    }
+
+    /// Convert a rectangle in the coordinate space of the [`RenderingContext`] to one in the
+    /// coordinate space of its framebuffer, which has its origin at the bottom left, so that
+    /// its pixels can be read back.
+    fn framebuffer_rect(&self, rect: DeviceRect) -> DeviceIntRect {
+        let rendering_context_height = self.rendering_context.size2d().height as f32;
+        let rect = rect.round_out();
+        DeviceIntRect::from_origin_and_size(
+            Point2D::new(
+                rect.min.x as i32,
+                0.max((rendering_context_height - rect.max.y) as i32),
+            ),
+            rect.size().to_i32(),
+        )
+    }
+
+    /// Start a continuous capture of the frames painted by this [`Painter`], limited to the
+    /// area of the given `WebView`, if any, and to the given number of frames per second.
+    /// Frames are only captured when this [`Painter`] paints, so nothing is sent while its
+    /// contents do not change. The capture stops once the returned [`Receiver`] is dropped.
+    pub(crate) fn start_capture_stream(
+        &mut self,
+        webview_id: Option<WebViewId>,
+        frames_per_second: f32,
+    ) -> Result<Receiver<CapturedFrame>, UnknownWebView> {
+        if let Some(webview_id) = webview_id {
+            if !self.webview_renderers.contains_key(&webview_id) {
+                return Err(UnknownWebView(webview_id));
+            }
+        }
+        let (sender, receiver) = std::sync::mpsc::sync_channel(MAX_QUEUED_CAPTURED_FRAMES);
+        self.capture_streams
+            .push(CaptureStream::new(webview_id, frames_per_second, sender));
+        self.set_needs_repaint(RepaintReason::NewWebRenderFrame);
+        Ok(receiver)
+    }
+
+    /// Read back the contents of the areas captured by the capture streams of this
+    /// [`Painter`] that are due for a new frame, right after painting.
+    fn send_capture_stream_frames(&mut self) {
+        if self.capture_streams.is_empty() {
+            return;
+        }
+
+        let now = Instant::now();
+        let mut capture_streams = std::mem::take(&mut self.capture_streams);
+        capture_streams.retain_mut(|capture_stream| {
+            if !capture_stream.wants_frame(now) {
+                return true;
+            }
+            let rect = match capture_stream.webview_id {
+                Some(webview_id) => match self.webview_renderers.get(&webview_id) {
+                    Some(webview_renderer) if webview_renderer.hidden() => return true,
+                    Some(webview_renderer) => {
+                        self.framebuffer_rect(webview_renderer.painted_rect())
                     },
-                    _ => {},
-                }
+                    None => return false,
+                },
+                None => DeviceIntRect::from_size(self.rendering_context.size2d().to_i32()),
+            };
+            let Some(image) = self.rendering_context.read_to_image(rect) else {
+                return true;
+            };
+            capture_stream.send_frame(now, image)
+        });
+        self.capture_streams = capture_streams;
+    }
+
+    /// Set whether the given `WebView` of this [`Painter`] is mirrored into another
+    /// [`Painter`], so that its contents are read back after every paint.
+    pub(crate) fn set_webview_mirrored(&mut self, webview_id: WebViewId, mirrored: bool) {
+        if mirrored {
+            self.mirrored_webviews.insert(webview_id);
+            self.set_needs_repaint(RepaintReason::NewWebRenderFrame);
+        } else {
+            self.mirrored_webviews.remove(&webview_id);
+        }
+    }
+
+    /// Take the snapshots of mirrored `WebView`s taken during the last paint.
+    pub(crate) fn take_mirror_snapshots(&mut self) -> Vec<(WebViewId, RgbaImage)> {
+        std::mem::take(&mut self.mirror_snapshots)
+    }
+
+    /// Whether the given `WebView` of another [`Painter`] is mirrored into this one.
+    pub(crate) fn mirrors_webview(&self, webview_id: WebViewId) -> bool {
+        self.mirrors
+            .iter()
+            .any(|mirror| mirror.webview_id == webview_id)
+    }
+
+    /// Mirror the given `WebView` of another [`Painter`] into `rect` of this one, or stop
+    /// mirroring it when `rect` is `None`.
+    pub(crate) fn set_mirror(&mut self, webview_id: WebViewId, rect: Option<DeviceRect>) {
+        let index = self
+            .mirrors
+            .iter()
+            .position(|mirror| mirror.webview_id == webview_id);
+        match (index, rect) {
+            (Some(index), Some(rect)) => self.mirrors[index].rect = rect,
+            (None, Some(rect)) => self.mirrors.push(WebViewMirror {
+                webview_id,
+                rect,
+                image: None,
+            }),
+            (Some(index), None) => {
+                let mirror = self.mirrors.remove(index);
+                if let Some((image_key, _)) = mirror.image {
+                    let mut transaction = Transaction::new();
+                    transaction.delete_image(image_key);
+                    self.send_transaction(transaction);
+                }
+            },
+            (None, None) => return,
+        }
+        self.send_root_pipeline_display_list();
+    }
+
+    /// Replace the image shown for the given mirrored `WebView` with a new snapshot.
+    pub(crate) fn update_mirror_image(&mut self, webview_id: WebViewId, image: &RgbaImage) {
+        let Some(mirror) = self
+            .mirrors
+            .iter_mut()
+            .find(|mirror| mirror.webview_id == webview_id)
+        else {
+            return;
+        };
+
+        let size = DeviceIntSize::new(image.width() as i32, image.height() as i32);
+        let descriptor = ImageDescriptor::new(
+            size.width,
+            size.height,
+            ImageFormat::RGBA8,
+            ImageDescriptorFlags::empty(),
+        );
+        let data = ImageData::new(image.as_raw().clone());
+        let mut transaction = Transaction::new();
+        match mirror.image {
+            Some((image_key, old_size)) if old_size == size => {
+                transaction.update_image(image_key, descriptor, data, &DirtyRect::All);
+                self.send_transaction(transaction);
+                self.generate_frame(RenderReasons::SCENE);
+            },
+            _ => {
+                if let Some((image_key, _)) = mirror.image.take() {
+                    transaction.delete_image(image_key);
+                }
+                let image_key = self.webrender_api.generate_image_key();
+                transaction.add_image(image_key, descriptor, data, None);
+                mirror.image = Some((image_key, size));
+                self.send_transaction(transaction);
+                self.send_root_pipeline_display_list();
+            },
+        }
+    }
+
+    /// Call `callback` once the next display list received for the `WebView` with the given
+    /// [`WebViewId`] has been painted to the [`RenderingContext`]. If the `WebView` is
+    /// removed before that happens, the callback is dropped without being called.
+    pub(crate) fn request_frame_presented_notification(
+        &mut self,
+        webview_id: WebViewId,
+        callback: Box<dyn FnOnce()>,
+    ) -> Result<(), UnknownWebView> {
+        if !self.webview_renderers.contains_key(&webview_id) {
+            return Err(UnknownWebView(webview_id));
+        }
+        self.frame_presented_callbacks.push(FramePresentedCallback {
+            webview_id,
+            display_list: None,
+            callback,
+        });
+        Ok(())
+    }
+
+    /// Run all callbacks whose display list is now part of the WebRender scene that was
+    /// just painted.
+    fn run_frame_presented_callbacks(&mut self) {
+        if self.frame_presented_callbacks.is_empty() {
+            return;
+        }
+
+        let Some(renderer) = self.webrender_renderer.as_ref() else {
+            return;
+        };
+        let document = self.webrender_document;
+        let (ready, waiting): (Vec<_>, Vec<_>) =
+            std::mem::take(&mut self.frame_presented_callbacks)
+                .into_iter()
+                .partition(|request| {
+                    request.display_list.is_some_and(|(pipeline_id, epoch)| {
+                        renderer
+                            .current_epoch(document, pipeline_id)
+                            .is_some_and(|current_epoch| current_epoch >= epoch)
+                    })
+                });
+        self.frame_presented_callbacks = waiting;
+
+        for request in ready {
+            (request.callback)();
+        }
+    }
+
+    /// Recover from errors returned by the WebRender renderer while rendering a frame, for
+    /// instance because of a transient OpenGL error, instead of bringing down every
+    /// [`RenderingContext`]. The WebRender instance of this [`Painter`] is recreated and the
+    /// next frame is rendered from scratch, from the fonts, images and display lists that
+    /// this [`Painter`] sends to the new instance. Other [`Painter`]s are not affected. The
+    /// errors are reported to the embedder. See [`Paint::take_renderer_errors`].
+    ///
+    /// Once [`MAX_CONSECUTIVE_FAILED_FRAMES`] frames in a row have failed to render, the
+    /// errors are not considered transient anymore. Recovering again would only recreate
+    /// WebRender for every frame, so this [`Painter`] stops rendering instead, which is
+    /// reported to the embedder as well. See [`Paint::take_failed_painters`].
+    fn handle_renderer_errors(&mut self, errors: Vec<RendererError>) {
+        for error in errors {
+            error!(
+                "WebRender failed to render {:?}: {error:?}",
+                self.painter_id
+            );
+            self.renderer_errors.push(error);
+        }
+
+        let RendererHealth::Recovering(failed_frames) = self.renderer_health else {
+            return;
+        };
+        let failed_frames = failed_frames + 1;
+        if failed_frames >= MAX_CONSECUTIVE_FAILED_FRAMES {
+            error!(
+                "Stopped rendering {:?} after {failed_frames} frames in a row failed to render",
+                self.painter_id
+            );
+            self.renderer_health = RendererHealth::Failed { reported: false };
+            return;
+        }
+        self.renderer_health = RendererHealth::Recovering(failed_frames);
+        self.recreate_webrender_instance(self.rendering_context.clone());
+    }
+
+    /// Make the next `frames` frames of this [`Painter`] fail to render, as if the WebRender
+    /// renderer had run out of memory. See [`Paint::simulate_renderer_failure`].
+    pub(crate) fn simulate_renderer_failure(&mut self, frames: u32) {
+        self.simulated_failed_frames = frames;
+        self.set_needs_repaint(RepaintReason::NewWebRenderFrame);
+    }
+
+    /// Take the errors returned by the WebRender renderer of this [`Painter`] since the last
+    /// call.
+    pub(crate) fn take_renderer_errors(&mut self) -> Vec<RendererError> {
+        std::mem::take(&mut self.renderer_errors)
+    }
+
+    /// Whether this [`Painter`] stopped rendering because too many frames in a row failed to
+    /// render. See [`Painter::handle_renderer_errors`].
+    pub(crate) fn rendering_failed(&self) -> bool {
+        matches!(self.renderer_health, RendererHealth::Failed { .. })
+    }
+
+    /// Returns `true` exactly once after this [`Painter`] stopped rendering because too many
+    /// frames in a row failed to render.
+    pub(crate) fn take_rendering_failure(&mut self) -> bool {
+        if self.renderer_health != (RendererHealth::Failed { reported: false }) {
+            return false;
+        }
+        self.renderer_health = RendererHealth::Failed { reported: true };
+        true
+    }
+
+    /// Render and present a new frame, if this [`Painter`] needs to be repainted. Returns
+    /// `true` if a frame was presented. Nothing is presented while the [`RenderingContext`] is
+    /// occluded, since it could not be seen anyway.
+    pub(crate) fn present(&mut self, time_profiler_channel: &ProfilerChan) -> bool {
+        if !self.needs_repaint() ||
+            self.isolated_webview.is_some() ||
+            self.occluded ||
+            self.holding_frames_for_resize()
+        {
+            return false;
+        }
+
+        self.render(time_profiler_channel);
+        self.rendering_context
+            .present_with_damage(&self.last_frame_damage);
+        self.maybe_finish_synchronized_resize();
+        true
+    }
+
+    /// Resize the [`RenderingContext`] of this [`Painter`] and call `callback` right after the
+    /// first frame in which every visible `WebView` that fills the [`RenderingContext`] has been
+    /// laid out at the new size is presented. This is for embedders whose own compositor owns
+    /// the window and that present the [`RenderingContext`] in a child surface, such as a
+    /// Wayland subsurface, a child `HWND` or a `CALayer`. They can then commit the new size of
+    /// the parent and child surfaces together, without showing stretched or cropped frames.
+    /// A pending resize is superseded by the next one and its callback is never called.
+    pub(crate) fn resize_rendering_context_synchronized(
+        &mut self,
+        new_size: PhysicalSize<u32>,
+        callback: Box<dyn FnOnce()>,
+    ) {
+        self.resize_rendering_context(new_size);
+        self.start_synchronized_resize(new_size, Some(callback));
+    }
+
+    /// Hold back the frames of this [`Painter`] for up to `timeout` after each resize of its
+    /// [`RenderingContext`], until the content of every visible `WebView` that fills it has
+    /// been laid out at the new size, or stop doing so with `None`. This avoids showing
+    /// content laid out for the old size stretched or cropped to the new one while the window
+    /// is interactively resized. While frames are held back, [`Painter::render`] and
+    /// [`Painter::present`] do nothing.
+    pub(crate) fn set_resize_present_timeout(&mut self, timeout: Option<Duration>) {
+        self.resize_present_timeout = timeout;
+        if timeout.is_none() {
+            if let Some(synchronized_resize) = self.synchronized_resize.as_mut() {
+                synchronized_resize.hold_frames_until = None;
+            }
+        }
+    }
+
+    /// Start tracking the display lists of the `WebView`s resized along with the
+    /// [`RenderingContext`], replacing any pending synchronized resize.
+    fn start_synchronized_resize(
+        &mut self,
+        new_size: PhysicalSize<u32>,
+        callback: Option<Box<dyn FnOnce()>>,
+    ) {
+        let display_lists = self
+            .webview_renderers()
+            .filter(|webview_renderer| {
+                !webview_renderer.hidden() && !webview_renderer.has_explicit_rect()
+            })
+            .map(|webview_renderer| (webview_renderer.id, None))
+            .collect();
+        let hold_frames_until = self
+            .resize_present_timeout
+            .map(|timeout| Instant::now() + timeout);
+        self.synchronized_resize = Some(SynchronizedResize {
+            size: new_size,
+            display_lists,
+            callback,
+            hold_frames_until,
+        });
+        self.set_needs_repaint(RepaintReason::Resize);
+
+        if hold_frames_until.is_some() && !self.observing_frames_for_synchronized_resize {
+            self.refresh_driver
+                .add_observer(Rc::new(SynchronizedResizeRefreshDriverObserver));
+            self.observing_frames_for_synchronized_resize = true;
+            self.generate_frame(RenderReasons::RESIZE);
+        }
+    }
+
+    /// Whether the last frame rendered by WebRender is at the size of the pending
+    /// synchronized resize and contains the new display list of every resized `WebView`.
+    fn synchronized_resize_frame_ready(&self) -> bool {
+        let Some(synchronized_resize) = self.synchronized_resize.as_ref() else {
+            return true;
+        };
+        if self.rendering_context.size() != synchronized_resize.size {
+            return false;
+        }
+        let Some(renderer) = self.webrender_renderer.as_ref() else {
+            return true;
+        };
+        let document = self.webrender_document;
+        synchronized_resize
+            .display_lists
+            .values()
+            .all(|display_list| {
+                display_list.is_some_and(|(pipeline_id, epoch)| {
+                    renderer
+                        .current_epoch(document, pipeline_id)
+                        .is_some_and(|current_epoch| current_epoch >= epoch)
+                })
+            })
+    }
+
+    /// Whether frames are held back because the content of the resized `WebView`s has not
+    /// caught up with the new size of the [`RenderingContext`] yet. This takes the latest
+    /// frame built by WebRender into account and stops holding back frames once the timeout
+    /// set with [`Painter::set_resize_present_timeout`] has passed.
+    fn holding_frames_for_resize(&mut self) -> bool {
+        let Some(hold_frames_until) = self
+            .synchronized_resize
+            .as_ref()
+            .and_then(|synchronized_resize| synchronized_resize.hold_frames_until)
+        else {
+            return false;
+        };
+        if Instant::now() >= hold_frames_until {
+            self.stop_holding_frames_for_resize();
+            return false;
+        }
+
+        // Find out about the latest frame built by WebRender, which is what would be painted.
+        if let Err(error) = self.rendering_context.make_current() {
+            error!("Failed to make the rendering context current: {error:?}");
+        }
+        if let Some(renderer) = self.webrender_renderer.as_mut() {
+            renderer.update();
+        }
+        if self.synchronized_resize_frame_ready() {
+            self.stop_holding_frames_for_resize();
+            return false;
+        }
+        true
+    }
+
+    fn stop_holding_frames_for_resize(&mut self) {
+        if let Some(synchronized_resize) = self.synchronized_resize.as_mut() {
+            synchronized_resize.hold_frames_until = None;
+        }
+    }
+
+    /// Check whether frames held back after a resize can be shown again during a
+    /// `RefreshDriver`-based frame tick. Returns `true` if they are still held back.
+    pub(crate) fn update_synchronized_resize_at_new_frame_start(&mut self) -> bool {
+        let keep_observing = self.holding_frames_for_resize();
+        // Request another frame, so that this is checked again once it starts or, once
+        // frames are not held back anymore, so that the embedder paints a new one.
+        self.generate_frame(RenderReasons::RESIZE);
+        self.observing_frames_for_synchronized_resize = keep_observing;
+        keep_observing
+    }
+
+    /// Call the callback of the pending synchronized resize, if the frame that was just
+    /// painted is at the new size and contains the new display list of every resized
+    /// `WebView`.
+    pub(crate) fn maybe_finish_synchronized_resize(&mut self) {
+        if self.synchronized_resize.is_none() || !self.synchronized_resize_frame_ready() {
+            return;
+        }
+        if let Some(callback) = self
+            .synchronized_resize
+            .take()
+            .and_then(|synchronized_resize| synchronized_resize.callback)
+        {
+            callback();
+        }
+    }
+
+    fn clear_background(&self) {
+        self.assert_gl_framebuffer_complete();
+
+        // Always clear the entire RenderingContext, regardless of how many WebViews there are
+        // or where they are positioned. This is so WebView actually clears even before the
+        // first WebView is ready.
+        let color = self.clear_color;
+        self.webrender_gl
+            .clear_color(color.r, color.g, color.b, color.a);
+        self.webrender_gl.clear(gleam::gl::COLOR_BUFFER_BIT);
+    }
+
+    /// Set the color used to clear the [`RenderingContext`] of this [`Painter`], both
+    /// before WebRender renders and by WebRender itself. This triggers a new frame so
+    /// that the change is reflected in the next paint.
+    pub(crate) fn set_clear_color(&mut self, clear_color: ColorF) {
+        if self.clear_color == clear_color {
+            return;
+        }
+
+        self.clear_color = clear_color;
+        if let Some(renderer) = self.webrender_renderer.as_mut() {
+            renderer.set_clear_color(clear_color);
+        }
+
+        self.generate_frame(RenderReasons::CONFIG_CHANGE);
+    }
+
+    /// Send all pending paint metrics messages after a composite operation, which may advance
+    /// the epoch for pipelines in the WebRender scene.
+    ///
+    /// If there are pending paint metrics, we check if any of the painted epochs is one
+    /// of the ones that the paint metrics recorder is expecting. In that case, we get the
+    /// current time, inform the constellation about it and remove the pending metric from
+    /// the list.
+    fn send_pending_paint_metrics_messages_after_composite(&mut self) {
+        let paint_time = CrossProcessInstant::now();
+        for webview_renderer in self.webview_renderers() {
+            // The pipelines of hidden `WebView`s are not part of the frame that was just
+            // painted, even if WebRender knows about their display lists.
+            if webview_renderer.hidden() {
+                continue;
+            }
+            for (pipeline_id, pipeline) in webview_renderer.pipelines.iter() {
+                let Some(current_epoch) = self
+                    .webrender_renderer
+                    .as_ref()
+                    .and_then(|wr| wr.current_epoch(self.webrender_document, pipeline_id.into()))
+                else {
+                    continue;
+                };
+
+                match pipeline.first_paint_metric.get() {
+                    // We need to check whether the current epoch is later, because
+                    // CrossProcessPaintMessage::SendInitialTransaction sends an
+                    // empty display list to WebRender which can happen before we receive
+                    // the first "real" display list.
+                    PaintMetricState::Seen(epoch, first_reflow) if epoch <= current_epoch => {
+                        assert!(epoch <= current_epoch);
+                        #[cfg(feature = "tracing")]
+                        tracing::info!(
+                            name: "FirstPaint",
+                            servo_profiling = true,
+                            epoch = ?epoch,
+                            paint_time = ?paint_time,
+                            pipeline_id = ?pipeline_id,
+                        );
+
+                        self.send_to_constellation(EmbedderToConstellationMessage::PaintMetric(
+                            *pipeline_id,
+                            PaintMetricEvent::FirstPaint(paint_time, first_reflow),
+                        ));
+
+                        pipeline.first_paint_metric.set(PaintMetricState::Sent);
+                        pipeline.paint_timing.set(WebViewPaintTiming {
+                            first_paint: Some(paint_time),
+                            ..pipeline.paint_timing.get()
+                        });
+                    },
+                    _ => {},
+                }
+
+                match pipeline.first_contentful_paint_metric.get() {
+                    PaintMetricState::Seen(epoch, first_reflow) if epoch <= current_epoch => {
+                        #[cfg(feature = "tracing")]
+                        tracing::info!(
+                            name: "FirstContentfulPaint",
+                            servo_profiling = true,
+                            epoch = ?epoch,
+                            paint_time = ?paint_time,
+                            pipeline_id = ?pipeline_id,
+                        );
+                        self.send_to_constellation(EmbedderToConstellationMessage::PaintMetric(
+                            *pipeline_id,
+                            PaintMetricEvent::FirstContentfulPaint(paint_time, first_reflow),
+                        ));
+                        pipeline
+                            .first_contentful_paint_metric
+                            .set(PaintMetricState::Sent);
+                        pipeline.paint_timing.set(WebViewPaintTiming {
+                            first_contentful_paint: Some(paint_time),
+                            ..pipeline.paint_timing.get()
+                        });
+                    },
+                    _ => {},
+                }
 
                 match pipeline.largest_contentful_paint_metric.get() {
                     PaintMetricState::Seen(epoch, _) if epoch <= current_epoch => {
@@ -528,10 +1442,21 @@ impl Painter {
         }
     }
 
-    /// Queue a new frame in the transaction and increase the pending frames count.
-    pub(crate) fn generate_frame(&self, transaction: &mut Transaction, reason: RenderReasons) {
-        transaction.generate_frame(0, true /* present */, false /* tracked */, reason);
-        self.pending_frames.set(self.pending_frames.get() + 1);
+    /// Request that WebRender generates a new frame. Requests are coalesced, so that only a
+    /// single frame is generated for all of the requests made before the next call to
+    /// [`Painter::flush_pending_transactions`].
+    pub(crate) fn generate_frame(&self, reason: RenderReasons) {
+        if self.throttled.get() {
+            return;
+        }
+        match self.pending_frame_reasons.get() {
+            Some(reasons) => self.pending_frame_reasons.set(Some(reasons | reason)),
+            None => {
+                self.pending_frame_reasons.set(Some(reason));
+                self.pending_frames.set(self.pending_frames.get() + 1);
+                self.frames_requested.set(self.frames_requested.get() + 1);
+            },
+        }
     }
 
     pub(crate) fn hit_test_at_point_with_api_and_document(
@@ -558,55 +1483,378 @@ impl Painter {
             .collect()
     }
 
+    /// Queue a transaction to be sent to WebRender during the next call to
+    /// [`Painter::flush_pending_transactions`].
     pub(crate) fn send_transaction(&mut self, transaction: Transaction) {
-        let _ = self.rendering_context.make_current();
-        self.webrender_api
-            .send_transaction(self.webrender_document, transaction);
+        self.pending_transactions.push(transaction);
     }
 
-    /// Set the root pipeline for our WebRender scene to a display list that consists of an iframe
-    /// for each visible top-level browsing context, applying a transformation on the root for
-    /// pinch zoom, page zoom, and HiDPI scaling.
-    fn send_root_pipeline_display_list_in_transaction(&self, transaction: &mut Transaction) {
-        // Every display list needs a pipeline, but we'd like to choose one that is unlikely
-        // to conflict with our content pipelines, which start at (1, 1). (0, 0) is WebRender's
-        // dummy pipeline, so we choose (0, 1).
-        let root_pipeline = WebRenderPipelineId(0, 1);
-        transaction.set_root_pipeline(root_pipeline);
+    /// Send all queued transactions to WebRender. The root pipeline display list is rebuilt
+    /// at most once and at most a single new frame is generated, no matter how many times
+    /// either was requested since the last flush.
+    pub(crate) fn flush_pending_transactions(&mut self) {
+        if std::mem::take(&mut self.root_pipeline_display_list_dirty) {
+            let mut transaction = Transaction::new();
+            self.send_root_pipeline_display_list_in_transaction(&mut transaction);
+            self.pending_transactions.push(transaction);
+        }
 
-        let mut builder = webrender_api::DisplayListBuilder::new(root_pipeline);
-        builder.begin();
+        // When driven by vsync, a requested frame waits for the next tick, while the
+        // transactions that it will present are sent right away.
+        let vsync_tick = std::mem::take(&mut self.vsync_tick);
+        let frame_reasons = if self.vsync_driven && !vsync_tick && !self.throttled.get() {
+            None
+        } else {
+            self.pending_frame_reasons.take()
+        };
+        if frame_reasons.is_some() && self.throttled.get() {
+            self.pending_frames.set(self.pending_frames.get() - 1);
+            self.frames_requested.set(self.frames_requested.get() - 1);
+        } else if let Some(reasons) = frame_reasons {
+            let mut transaction = Transaction::new();
+            let (present, tracked) = (true, false);
+            transaction.generate_frame(0, present, tracked, reasons);
+            self.pending_transactions.push(transaction);
+        }
 
-        let root_reference_frame = SpatialId::root_reference_frame(root_pipeline);
+        if self.pending_transactions.is_empty() {
+            return;
+        }
 
-        let viewport_size = self.rendering_context.size2d().to_f32().to_untyped();
-        let viewport_rect = LayoutRect::from_origin_and_size(
-            LayoutPoint::zero(),
-            LayoutSize::from_untyped(viewport_size),
-        );
+        if self.paused {
+            self.paused_transactions
+                .append(&mut self.pending_transactions);
+            return;
+        }
 
-        let root_clip_id = builder.define_clip_rect(root_reference_frame, viewport_rect);
-        let clip_chain_id = builder.define_clip_chain(None, [root_clip_id]);
-        for webview_renderer in self.webview_renderers.values() {
-            if webview_renderer.hidden() {
-                continue;
-            }
-            let Some(pipeline_id) = webview_renderer.root_pipeline_id else {
-                continue;
-            };
+        let _ = self.rendering_context.make_current();
+        for transaction in std::mem::take(&mut self.pending_transactions) {
+            self.webrender_api
+                .send_transaction(self.webrender_document, transaction);
+        }
+    }
 
-            let pinch_zoom_transform = webview_renderer.pinch_zoom().transform().to_untyped();
-            let device_pixels_per_page_pixel_not_including_pinch_zoom = webview_renderer
-                .device_pixels_per_page_pixel_not_including_pinch_zoom()
-                .get();
+    pub(crate) fn priority(&self) -> RenderingPriority {
+        self.priority
+    }
 
-            let transform = LayoutTransform::scale(
-                device_pixels_per_page_pixel_not_including_pinch_zoom,
-                device_pixels_per_page_pixel_not_including_pinch_zoom,
-                1.0,
-            )
-            .then(&LayoutTransform::from_untyped(
-                &pinch_zoom_transform.to_3d(),
+    pub(crate) fn set_priority(&mut self, priority: RenderingPriority) {
+        self.priority = priority;
+    }
+
+    /// Block until the OpenGL driver has finished all pending work for the
+    /// [`RenderingContext`] of this [`Painter`], including shaders that are still being
+    /// compiled in the background. Returns `true` if the shaders are ready, or `false` if the
+    /// [`RenderingContext`] could not be made current.
+    pub(crate) fn warm_up(&mut self) -> bool {
+        if let Err(error) = self.rendering_context.make_current() {
+            error!("Failed to make the rendering context current: {error:?}");
+            return false;
+        }
+        let Some(renderer) = self.webrender_renderer.as_mut() else {
+            return false;
+        };
+        renderer.update();
+        self.webrender_gl.finish();
+        true
+    }
+
+    /// Like [`Painter::flush_pending_transactions`], but when a [`Painter`] with a higher
+    /// priority is busy, a requested frame is deferred until the next call. Transactions are
+    /// still sent right away. A frame is never deferred twice in a row, so that this
+    /// [`Painter`] keeps making progress.
+    pub(crate) fn flush_pending_transactions_for_priority(
+        &mut self,
+        higher_priority_painter_busy: bool,
+    ) {
+        let defer_frame = higher_priority_painter_busy &&
+            !self.frame_deferred_for_priority &&
+            !self.throttled.get() &&
+            self.pending_frame_reasons.get().is_some();
+        self.frame_deferred_for_priority = defer_frame;
+        if !defer_frame {
+            self.flush_pending_transactions();
+            return;
+        }
+
+        let frame_reasons = self.pending_frame_reasons.take();
+        self.flush_pending_transactions();
+        self.pending_frame_reasons.set(frame_reasons);
+    }
+
+    /// Set whether new frames are only generated when the embedder reports a vsync tick with
+    /// [`Painter::notify_vsync`]. When disabled, any frame waiting for a tick is generated
+    /// right away.
+    pub(crate) fn set_vsync_driven(&mut self, vsync_driven: bool) {
+        if std::mem::replace(&mut self.vsync_driven, vsync_driven) == vsync_driven {
+            return;
+        }
+        if !vsync_driven {
+            self.flush_pending_transactions();
+        }
+    }
+
+    /// Handle a vsync tick of the display showing the [`RenderingContext`] of this
+    /// [`Painter`], generating a frame if one was requested since the last tick.
+    pub(crate) fn notify_vsync(&mut self) {
+        if !self.vsync_driven {
+            return;
+        }
+        self.vsync_tick = true;
+        self.flush_pending_transactions();
+    }
+
+    /// Add the [`PaintBackground`] of this [`Painter`] to the root display list, filling the
+    /// entire viewport, below all of the `WebView`s.
+    fn push_background(
+        &self,
+        builder: &mut DisplayListBuilder,
+        spatial_id: SpatialId,
+        clip_chain_id: ClipChainId,
+        viewport_rect: LayoutRect,
+    ) {
+        let properties = CommonItemProperties::new(
+            viewport_rect,
+            SpaceAndClipInfo {
+                spatial_id,
+                clip_chain_id,
+            },
+        );
+        match &self.background {
+            PaintBackground::None => {},
+            PaintBackground::Color(color) => builder.push_rect(&properties, viewport_rect, *color),
+            PaintBackground::LinearGradient { start, end, stops } => {
+                let stops = stops
+                    .iter()
+                    .map(|(offset, color)| GradientStop {
+                        offset: *offset,
+                        color: *color,
+                    })
+                    .collect();
+                let gradient = builder.create_gradient(
+                    LayoutPoint::from_untyped(start.to_untyped()),
+                    LayoutPoint::from_untyped(end.to_untyped()),
+                    stops,
+                    ExtendMode::Clamp,
+                );
+                builder.push_gradient(
+                    &properties,
+                    viewport_rect,
+                    gradient,
+                    viewport_rect.size(),
+                    LayoutSize::zero(),
+                );
+            },
+            PaintBackground::Image(_) => {
+                let Some(image_key) = self.background_image_key else {
+                    return;
+                };
+                builder.push_image(
+                    &properties,
+                    viewport_rect,
+                    ImageRendering::Auto,
+                    AlphaType::PremultipliedAlpha,
+                    image_key,
+                    ColorF::WHITE,
+                );
+            },
+        }
+    }
+
+    /// Set the [`PaintBackground`] painted below all `WebView`s of this [`Painter`].
+    pub(crate) fn set_background(&mut self, background: PaintBackground) {
+        let mut transaction = Transaction::new();
+        if let Some(image_key) = self.background_image_key.take() {
+            transaction.delete_image(image_key);
+        }
+        if let PaintBackground::Image(image) = &background {
+            let image_key = self.webrender_api.generate_image_key();
+            let descriptor = ImageDescriptor::new(
+                image.width() as i32,
+                image.height() as i32,
+                ImageFormat::RGBA8,
+                ImageDescriptorFlags::empty(),
+            );
+            transaction.add_image(
+                image_key,
+                descriptor,
+                ImageData::new(image.as_raw().clone()),
+                None,
+            );
+            self.background_image_key = Some(image_key);
+        }
+        self.send_transaction(transaction);
+
+        self.background = background;
+        self.send_root_pipeline_display_list();
+    }
+
+    /// Find all `WebView`s that are completely covered by an opaque `WebView` painted above
+    /// them, including their decorations. These do not need to be included in the WebRender
+    /// scene at all.
+    fn occluded_webviews(&self) -> FxHashSet<WebViewId> {
+        let mut occluded_webviews = FxHashSet::default();
+        let mut opaque_rects: Vec<DeviceRect> = Vec::new();
+        for webview_renderer in self.webview_renderers().rev() {
+            if webview_renderer.hidden() {
+                continue;
+            }
+
+            let decorated_rect = webview_renderer.decorated_rect();
+            if opaque_rects
+                .iter()
+                .any(|opaque_rect| opaque_rect.contains_box(&decorated_rect))
+            {
+                occluded_webviews.insert(webview_renderer.id);
+                continue;
+            }
+            opaque_rects.extend(webview_renderer.opaque_rect());
+        }
+        occluded_webviews
+    }
+
+    /// Push the [`WebViewDecorations`] of a `WebView` whose visible part is `rect` with corners
+    /// rounded by `radii`. All values are in the coordinate space of the reference frame of
+    /// the `WebView`, except for the decorations, which are divided by `scale` to get there.
+    fn push_webview_decorations(
+        builder: &mut DisplayListBuilder,
+        decorations: &WebViewDecorations,
+        space_and_clip: SpaceAndClipInfo,
+        rect: LayoutRect,
+        radii: BorderRadius,
+        scale: f32,
+    ) {
+        if let Some(shadow) = decorations.shadow {
+            let offset = LayoutVector2D::from_untyped(shadow.offset.to_untyped()) / scale;
+            let blur_radius = shadow.blur_radius / scale;
+            let spread_radius = shadow.spread_radius / scale;
+            // WebRender blurs up to three times the blur radius beyond the shadow's box.
+            let extent = spread_radius + 3.0 * blur_radius;
+            builder.push_box_shadow(
+                &CommonItemProperties::new(
+                    rect.translate(offset).inflate(extent, extent),
+                    space_and_clip,
+                ),
+                rect,
+                offset,
+                shadow.color,
+                blur_radius,
+                spread_radius,
+                radii,
+                BoxShadowClipMode::Outset,
+            );
+        }
+
+        // Each border surrounds everything painted before it, rounding its outer corners
+        // like CSS outlines do.
+        let outset_radius = |radius: LayoutSize, width: f32| {
+            if radius.width > 0.0 && radius.height > 0.0 {
+                radius + LayoutSize::new(width, width)
+            } else {
+                radius
+            }
+        };
+        let mut border_rect = rect;
+        let mut border_radii = radii;
+        for border in [decorations.border, decorations.focus_ring]
+            .into_iter()
+            .flatten()
+        {
+            let width = border.width / scale;
+            border_rect = border_rect.inflate(width, width);
+            border_radii = BorderRadius {
+                top_left: outset_radius(border_radii.top_left, width),
+                top_right: outset_radius(border_radii.top_right, width),
+                bottom_left: outset_radius(border_radii.bottom_left, width),
+                bottom_right: outset_radius(border_radii.bottom_right, width),
+            };
+            let side = BorderSide {
+                color: border.color,
+                style: BorderStyle::Solid,
+            };
+            builder.push_border(
+                &CommonItemProperties::new(border_rect, space_and_clip),
+                border_rect,
+                LayoutSideOffsets::new_all_same(width),
+                BorderDetails::Normal(NormalBorder {
+                    left: side,
+                    right: side,
+                    top: side,
+                    bottom: side,
+                    radius: border_radii,
+                    do_aa: true,
+                }),
+            );
+        }
+    }
+
+    /// Set the root pipeline for our WebRender scene to a display list that consists of an iframe
+    /// for each visible top-level browsing context, applying a transformation on the root for
+    /// pinch zoom, page zoom, and HiDPI scaling.
+    fn send_root_pipeline_display_list_in_transaction(&self, transaction: &mut Transaction) {
+        // Every display list needs a pipeline, but we'd like to choose one that is unlikely
+        // to conflict with our content pipelines, which start at (1, 1). (0, 0) is WebRender's
+        // dummy pipeline, so we choose (0, 1).
+        let root_pipeline = WebRenderPipelineId(0, 1);
+        transaction.set_root_pipeline(root_pipeline);
+
+        let mut builder = DisplayListBuilder::new(root_pipeline);
+        builder.begin();
+
+        let root_reference_frame = SpatialId::root_reference_frame(root_pipeline);
+
+        let viewport_size = self.rendering_context.size2d().to_f32().to_untyped();
+        let viewport_rect = LayoutRect::from_origin_and_size(
+            LayoutPoint::zero(),
+            LayoutSize::from_untyped(viewport_size),
+        );
+
+        let root_clip_id = builder.define_clip_rect(root_reference_frame, viewport_rect);
+        let clip_chain_id = builder.define_clip_chain(None, [root_clip_id]);
+        if self.isolated_webview.is_none() {
+            self.push_background(
+                &mut builder,
+                root_reference_frame,
+                clip_chain_id,
+                viewport_rect,
+            );
+        }
+
+        let occluded_webviews = self.occluded_webviews();
+        let now = Instant::now();
+        for webview_renderer in self.webview_renderers() {
+            // A `WebView` that is captured on its own is painted even when it is hidden.
+            let skip = match self.isolated_webview {
+                Some(isolated_webview) => webview_renderer.id != isolated_webview,
+                None => {
+                    webview_renderer.hidden() || occluded_webviews.contains(&webview_renderer.id)
+                },
+            };
+            if skip {
+                continue;
+            }
+            let Some(pipeline_id) = webview_renderer.root_pipeline_id else {
+                continue;
+            };
+
+            let pinch_zoom_transform = webview_renderer.pinch_zoom().transform().to_untyped();
+            let compositing_transform = webview_renderer.compositing_transform();
+            let is_2d_scale_translation =
+                compositing_transform.m12 == 0.0 && compositing_transform.m21 == 0.0;
+            let device_pixels_per_page_pixel_not_including_pinch_zoom = webview_renderer
+                .device_pixels_per_page_pixel_not_including_pinch_zoom()
+                .get();
+
+            let transform = LayoutTransform::scale(
+                device_pixels_per_page_pixel_not_including_pinch_zoom,
+                device_pixels_per_page_pixel_not_including_pinch_zoom,
+                1.0,
+            )
+            .then(&LayoutTransform::from_untyped(
+                &pinch_zoom_transform.to_3d(),
+            ))
+            .then(&LayoutTransform::from_untyped(
+                &compositing_transform.to_untyped().to_3d(),
             ));
 
             let webview_reference_frame = builder.push_reference_frame(
@@ -615,25 +1863,174 @@ impl Painter {
                 TransformStyle::Flat,
                 PropertyBinding::Value(transform),
                 ReferenceFrameKind::Transform {
-                    is_2d_scale_translation: true,
-                    should_snap: true,
+                    is_2d_scale_translation,
+                    should_snap: is_2d_scale_translation,
                     paired_with_perspective: false,
                 },
                 webview_renderer.id.into(),
             );
 
+            // Translucent `WebView`s are wrapped in a stacking context with an opacity filter,
+            // so that their contents are composited as a group.
+            let opacity = webview_renderer.opacity();
+            let needs_opacity_stacking_context = opacity < 1.0;
+            if needs_opacity_stacking_context {
+                builder.push_stacking_context(
+                    LayoutPoint::zero(),
+                    webview_reference_frame,
+                    PrimitiveFlags::default(),
+                    Some(clip_chain_id),
+                    TransformStyle::Flat,
+                    MixBlendMode::Normal,
+                    &[FilterOp::Opacity(opacity.into(), opacity)],
+                    &[],
+                    &[],
+                    RasterSpace::Screen,
+                    StackingContextFlags::empty(),
+                    None,
+                );
+            }
+
             let scaled_webview_rect = webview_renderer.rect /
                 webview_renderer.device_pixels_per_page_pixel_not_including_pinch_zoom();
+
+            // The clip of a `WebView` is defined in its reference frame, so that it follows the
+            // compositing transformation of the `WebView`.
+            let scale = device_pixels_per_page_pixel_not_including_pinch_zoom;
+            let (visible_rect, visible_radii, webview_clip_chain_id) = match webview_renderer.clip()
+            {
+                Some(clip) => {
+                    let clip_rect = clip.rect.translate(webview_renderer.rect.min.to_vector()) /
+                        webview_renderer.device_pixels_per_page_pixel_not_including_pinch_zoom();
+                    let clip_rect = LayoutRect::from_untyped(&clip_rect.to_untyped());
+                    let radii = BorderRadius {
+                        top_left: clip.radii.top_left / scale,
+                        top_right: clip.radii.top_right / scale,
+                        bottom_left: clip.radii.bottom_left / scale,
+                        bottom_right: clip.radii.bottom_right / scale,
+                    };
+                    let webview_clip_id = builder.define_clip_rounded_rect(
+                        webview_reference_frame,
+                        ComplexClipRegion::new(clip_rect, radii, ClipMode::Clip),
+                    );
+                    (
+                        clip_rect,
+                        radii,
+                        builder.define_clip_chain(Some(clip_chain_id), [webview_clip_id]),
+                    )
+                },
+                None => (
+                    LayoutRect::from_untyped(&scaled_webview_rect.to_untyped()),
+                    BorderRadius::zero(),
+                    clip_chain_id,
+                ),
+            };
+
+            Self::push_webview_decorations(
+                &mut builder,
+                webview_renderer.decorations(),
+                SpaceAndClipInfo {
+                    spatial_id: webview_reference_frame,
+                    clip_chain_id,
+                },
+                visible_rect,
+                visible_radii,
+                scale,
+            );
+
             builder.push_iframe(
                 LayoutRect::from_untyped(&scaled_webview_rect.to_untyped()),
                 LayoutRect::from_untyped(&scaled_webview_rect.to_untyped()),
                 &SpaceAndClipInfo {
                     spatial_id: webview_reference_frame,
-                    clip_chain_id,
+                    clip_chain_id: webview_clip_chain_id,
                 },
                 pipeline_id.into(),
                 true,
             );
+
+            if let Some(opacity) = self
+                .overlay_scrollbars
+                .get(&webview_renderer.id)
+                .and_then(|last_scrolled| overlay_scrollbars::opacity_at(*last_scrolled, now))
+            {
+                let root_scroll_info =
+                    webview_renderer
+                        .pipelines
+                        .get(&pipeline_id)
+                        .and_then(|details| {
+                            details
+                                .scroll_tree
+                                .nodes
+                                .iter()
+                                .find_map(|node| match node.info {
+                                    SpatialTreeNodeInfo::Scroll(ref info)
+                                        if info.external_id == pipeline_id.root_scroll_id() =>
+                                    {
+                                        Some(info)
+                                    },
+                                    _ => None,
+                                })
+                        });
+                if let Some(root_scroll_info) = root_scroll_info {
+                    overlay_scrollbars::push_overlay_scrollbars(
+                        &mut builder,
+                        SpaceAndClipInfo {
+                            spatial_id: webview_reference_frame,
+                            clip_chain_id: webview_clip_chain_id,
+                        },
+                        root_scroll_info,
+                        LayoutVector2D::from_untyped(
+                            scaled_webview_rect.min.to_vector().to_untyped(),
+                        ),
+                        scale,
+                        opacity,
+                    );
+                }
+            }
+
+            if needs_opacity_stacking_context {
+                builder.pop_stacking_context();
+            }
+        }
+
+        let mirrors = match self.isolated_webview {
+            Some(_) => &[][..],
+            None => &self.mirrors[..],
+        };
+        for mirror in mirrors {
+            let Some((image_key, _)) = mirror.image else {
+                continue;
+            };
+            let rect = LayoutRect::from_untyped(&mirror.rect.to_untyped());
+            builder.push_image(
+                &CommonItemProperties::new(
+                    rect,
+                    SpaceAndClipInfo {
+                        spatial_id: root_reference_frame,
+                        clip_chain_id,
+                    },
+                ),
+                rect,
+                ImageRendering::Auto,
+                AlphaType::PremultipliedAlpha,
+                image_key,
+                ColorF::WHITE,
+            );
+        }
+
+        if self.draws_cursor && self.isolated_webview.is_none() {
+            if let Some(position) = self.last_mouse_move_position {
+                cursor_layer::push_cursor(
+                    &mut builder,
+                    SpaceAndClipInfo {
+                        spatial_id: root_reference_frame,
+                        clip_chain_id,
+                    },
+                    LayoutPoint::from_untyped(position.to_untyped()),
+                    self.cursor,
+                );
+            }
         }
 
         let built_display_list = builder.end();
@@ -648,11 +2045,12 @@ impl Painter {
     /// Set the root pipeline for our WebRender scene to a display list that consists of an iframe
     /// for each visible top-level browsing context, applying a transformation on the root for
     /// pinch zoom, page zoom, and HiDPI scaling.
+    ///
+    /// The display list is only built once the pending transactions are flushed, so that
+    /// many changes to `WebView`s in a row only lead to a single new display list.
     fn send_root_pipeline_display_list(&mut self) {
-        let mut transaction = Transaction::new();
-        self.send_root_pipeline_display_list_in_transaction(&mut transaction);
-        self.generate_frame(&mut transaction, RenderReasons::SCENE);
-        self.send_transaction(transaction);
+        self.root_pipeline_display_list_dirty = true;
+        self.generate_frame(RenderReasons::SCENE);
     }
 
     /// Update the given transaction with the scroll offsets of all active scroll nodes in
@@ -664,7 +2062,7 @@ impl Painter {
     /// TODO(mrobinson): Could we only send offsets for the branch being modified
     /// and not the entire scene?
     fn update_transaction_with_all_scroll_offsets(&self, transaction: &mut Transaction) {
-        for webview_renderer in self.webview_renderers.values() {
+        for webview_renderer in self.webview_renderers() {
             for details in webview_renderer.pipelines.values() {
                 for node in details.scroll_tree.nodes.iter() {
                     let (Some(offset), Some(external_id)) = (node.offset(), node.external_id())
@@ -696,8 +2094,10 @@ impl Painter {
             return;
         }
 
+        let showed_overlay_scrollbars = self.show_overlay_scrollbars(&scroll_offset_updates);
+
         let mut transaction = Transaction::new();
-        if need_zoom {
+        if need_zoom || showed_overlay_scrollbars {
             self.send_root_pipeline_display_list_in_transaction(&mut transaction);
         }
         for update in scroll_offset_updates {
@@ -710,16 +2110,64 @@ impl Painter {
             );
         }
 
-        self.generate_frame(&mut transaction, RenderReasons::APZ);
+        self.generate_frame(RenderReasons::APZ);
         self.send_transaction(transaction);
     }
 
-    pub(crate) fn toggle_webrender_debug(&mut self, option: WebRenderDebugOption) {
-        let Some(renderer) = self.webrender_renderer.as_mut() else {
-            return;
-        };
-        let mut flags = renderer.get_debug_flags();
-        let flag = match option {
+    /// Show the overlay scrollbars of every `WebView` whose viewport was scrolled by one of
+    /// the given [`ScrollResult`]s, if they are enabled, and start observing frames to fade
+    /// them out. Returns `true` if any overlay scrollbars were shown, in which case the root
+    /// display list needs to be rebuilt.
+    fn show_overlay_scrollbars(&mut self, scroll_results: &[ScrollResult]) -> bool {
+        if !pref!(overlay_scrollbars_enabled) {
+            return false;
+        }
+
+        let now = Instant::now();
+        let mut showed_overlay_scrollbars = false;
+        for webview_renderer in self.webview_renderers.values() {
+            let Some(root_pipeline_id) = webview_renderer.root_pipeline_id else {
+                continue;
+            };
+            let root_scroll_id = root_pipeline_id.root_scroll_id();
+            if scroll_results
+                .iter()
+                .any(|scroll_result| scroll_result.external_scroll_id == root_scroll_id)
+            {
+                self.overlay_scrollbars.insert(webview_renderer.id, now);
+                showed_overlay_scrollbars = true;
+            }
+        }
+
+        if showed_overlay_scrollbars && !self.observing_frames_for_overlay_scrollbars {
+            self.refresh_driver
+                .add_observer(Rc::new(OverlayScrollbarsRefreshDriverObserver));
+            self.observing_frames_for_overlay_scrollbars = true;
+        }
+        showed_overlay_scrollbars
+    }
+
+    /// Redraw the overlay scrollbars that are fading out during a `RefreshDriver`-based frame
+    /// tick, forgetting about those that have completely faded out. Returns `true` if any
+    /// overlay scrollbars are still visible.
+    pub(crate) fn update_overlay_scrollbars_at_new_frame_start(&mut self) -> bool {
+        let now = Instant::now();
+        self.overlay_scrollbars.retain(|_, last_scrolled| {
+            overlay_scrollbars::opacity_at(*last_scrolled, now).is_some()
+        });
+
+        let mut transaction = Transaction::new();
+        self.send_root_pipeline_display_list_in_transaction(&mut transaction);
+        self.generate_frame(RenderReasons::ANIMATED_PROPERTY);
+        self.send_transaction(transaction);
+
+        let keep_observing = !self.overlay_scrollbars.is_empty();
+        self.observing_frames_for_overlay_scrollbars = keep_observing;
+        keep_observing
+    }
+
+    fn webrender_debug_flags(option: WebRenderDebugOption) -> webrender::DebugFlags {
+        match option {
             WebRenderDebugOption::Profiler => {
                 webrender::DebugFlags::PROFILER_DBG |
                     webrender::DebugFlags::GPU_TIME_QUERIES |
@@ -727,22 +2175,61 @@ impl Painter {
             },
             WebRenderDebugOption::TextureCacheDebug => webrender::DebugFlags::TEXTURE_CACHE_DBG,
             WebRenderDebugOption::RenderTargetDebug => webrender::DebugFlags::RENDER_TARGET_DBG,
+            WebRenderDebugOption::GpuTimeQueries => webrender::DebugFlags::GPU_TIME_QUERIES,
+        }
+    }
+
+    pub(crate) fn webrender_debug_enabled(&self, option: WebRenderDebugOption) -> bool {
+        self.webrender_renderer.as_ref().is_some_and(|renderer| {
+            renderer
+                .get_debug_flags()
+                .contains(Self::webrender_debug_flags(option))
+        })
+    }
+
+    pub(crate) fn set_webrender_debug(&mut self, option: WebRenderDebugOption, enabled: bool) {
+        let Some(renderer) = self.webrender_renderer.as_mut() else {
+            return;
         };
-        flags.toggle(flag);
+        let mut flags = renderer.get_debug_flags();
+        flags.set(Self::webrender_debug_flags(option), enabled);
         renderer.set_debug_flags(flags);
 
-        let mut txn = Transaction::new();
-        self.generate_frame(&mut txn, RenderReasons::TESTING);
-        self.send_transaction(txn);
+        self.generate_frame(RenderReasons::TESTING);
     }
 
-    pub(crate) fn decrement_pending_frames(&self) {
-        self.pending_frames.set(self.pending_frames.get() - 1);
+    pub(crate) fn toggle_webrender_debug(&mut self, option: WebRenderDebugOption) {
+        let enabled = !self.webrender_debug_enabled(option);
+        self.set_webrender_debug(option, enabled);
+    }
+
+    /// Take the frames of the given WebRender document that have become ready since this was
+    /// last called, no longer counting them as pending.
+    pub(crate) fn take_ready_frames(&self, document_id: DocumentId) -> ReadyFrames {
+        let ready_frames = self
+            .ready_frames
+            .lock()
+            .remove(&document_id)
+            .unwrap_or_default();
+        self.pending_frames
+            .set(self.pending_frames.get().saturating_sub(ready_frames.count));
+        self.frames_ready.set(
+            (self.frames_ready.get() + ready_frames.count as u64).min(self.frames_requested.get()),
+        );
+        ready_frames
     }
 
+    /// Report the memory usage of this [`Painter`]'s WebRender instance. This includes both
+    /// the memory used by WebRender on the CPU and the size of the textures it has allocated
+    /// on the GPU.
     pub(crate) fn report_memory(&self) -> MemoryReport {
-        self.webrender_api
-            .report_memory(MallocSizeOfOps::new(servo_allocator::usable_size, None))
+        let mut memory_report = self
+            .webrender_api
+            .report_memory(MallocSizeOfOps::new(servo_allocator::usable_size, None));
+        if let Some(renderer) = self.webrender_renderer.as_ref() {
+            memory_report += renderer.report_memory(std::ptr::null_mut() /* swgl */);
+        }
+        memory_report
     }
 
     pub(crate) fn change_running_animations_state(
@@ -758,6 +2245,11 @@ impl Painter {
         {
             return;
         }
+        // Animations do not tick while throttled. They start ticking once this `Painter` is
+        // no longer throttled. See `Painter::update_throttling`.
+        if self.throttled.get() {
+            return;
+        }
         if !self
             .animation_refresh_driver_observer
             .notify_animation_state_changed(webview_renderer)
@@ -797,9 +2289,9 @@ impl Painter {
             return;
         }
 
-        if self
-            .animation_refresh_driver_observer
-            .notify_animation_state_changed(webview_renderer)
+        if !self.throttled.get() &&
+            self.animation_refresh_driver_observer
+                .notify_animation_state_changed(webview_renderer)
         {
             self.refresh_driver
                 .add_observer(self.animation_refresh_driver_observer.clone());
@@ -836,7 +2328,7 @@ impl Painter {
         let mut txn = Transaction::new();
         txn.set_display_list(starting_epoch.into(), (pipeline_id, Default::default()));
 
-        self.generate_frame(&mut txn, RenderReasons::SCENE);
+        self.generate_frame(RenderReasons::SCENE);
         self.send_transaction(txn);
     }
 
@@ -879,7 +2371,7 @@ impl Painter {
             }],
         );
 
-        self.generate_frame(&mut transaction, RenderReasons::APZ);
+        self.generate_frame(RenderReasons::APZ);
         self.send_transaction(transaction);
     }
 
@@ -898,6 +2390,37 @@ impl Painter {
         );
     }
 
+    /// Start smoothly scrolling the viewport of the given `WebView` to `target_offset`. See
+    /// [`WebViewRenderer::smooth_scroll_viewport_to`].
+    pub(crate) fn smooth_scroll_viewport_to(
+        &mut self,
+        webview_id: WebViewId,
+        target_offset: LayoutVector2D,
+        duration: Duration,
+    ) -> Result<(), UnknownWebView> {
+        self.webview_renderers
+            .get_mut(&webview_id)
+            .ok_or(UnknownWebView(webview_id))?
+            .smooth_scroll_viewport_to(target_offset, duration, &self.needs_repaint);
+        Ok(())
+    }
+
+    /// Advance the smooth scroll of the given `WebView` during a `RefreshDriver`-based frame
+    /// tick and send the new scroll offset to WebRender. Returns `true` if the smooth scroll
+    /// is still ongoing.
+    pub(crate) fn update_smooth_scroll_at_new_frame_start(
+        &mut self,
+        webview_id: WebViewId,
+    ) -> bool {
+        let Some(webview_renderer) = self.webview_renderers.get_mut(&webview_id) else {
+            return false;
+        };
+        let (keep_observing, scroll_result) =
+            webview_renderer.update_smooth_scroll_at_new_frame_start();
+        self.send_zoom_and_scroll_offset_updates(false, scroll_result.into_iter().collect());
+        keep_observing
+    }
+
     pub(crate) fn update_epoch(
         &mut self,
         webview_id: WebViewId,
@@ -950,25 +2473,35 @@ impl Painter {
                 return warn!("Could not receive WebRender display list spatial tree: {error}.");
             },
         };
-        let built_display_list = BuiltDisplayList::from_data(
-            DisplayListPayload {
-                items_data,
-                cache_data,
-                spatial_tree,
-            },
-            display_list_descriptor,
-        );
-        let _span = profile_traits::trace_span!("PaintMessage::SendDisplayList",).entered();
+        let size_in_bytes = items_data.len() + cache_data.len() + spatial_tree.len();
+        let display_list_data = DisplayListData {
+            epoch: display_list_info.epoch.into(),
+            descriptor: display_list_descriptor,
+            items_data,
+            cache_data,
+            spatial_tree,
+        };
+        let built_display_list = display_list_data.to_built_display_list();
+        let item_count = display_item_count(&built_display_list);
+        let _span = profile_traits::trace_span!(
+            "PaintMessage::SendDisplayList",
+            webview_id = ?webview_id,
+            size_in_bytes,
+            item_count,
+        )
+        .entered();
         let Some(webview_renderer) = self.webview_renderers.get_mut(&webview_id) else {
             return warn!("Could not find WebView for incoming display list");
         };
 
         let old_scale = webview_renderer.device_pixels_per_page_pixel();
         let pipeline_id = display_list_info.pipeline_id;
+        webview_renderer.note_display_list_received(pipeline_id.into(), size_in_bytes, item_count);
         let details = webview_renderer.ensure_pipeline_details(pipeline_id.into());
 
         details.install_new_scroll_tree(display_list_info.scroll_tree);
         details.viewport_scale = Some(display_list_info.viewport_details.hidpi_scale_factor);
+        details.display_list = Some(display_list_data);
 
         let epoch = display_list_info.epoch.into();
         let first_reflow = display_list_info.first_reflow;
@@ -988,10 +2521,27 @@ impl Painter {
 
         let mut transaction = Transaction::new();
         let is_root_pipeline = Some(pipeline_id.into()) == webview_renderer.root_pipeline_id;
+        let matches_viewport =
+            display_list_info.viewport_details == webview_renderer.viewport_details();
         if is_root_pipeline && old_scale != webview_renderer.device_pixels_per_page_pixel() {
             self.send_root_pipeline_display_list_in_transaction(&mut transaction);
         }
 
+        for request in self.frame_presented_callbacks.iter_mut() {
+            if request.webview_id == webview_id && request.display_list.is_none() {
+                request.display_list = Some((pipeline_id, epoch));
+            }
+        }
+        if is_root_pipeline && matches_viewport {
+            if let Some(display_list) = self
+                .synchronized_resize
+                .as_mut()
+                .and_then(|resize| resize.display_lists.get_mut(&webview_id))
+            {
+                *display_list = Some((pipeline_id, epoch));
+            }
+        }
+
         transaction.set_display_list(epoch, (pipeline_id, built_display_list));
 
         self.update_transaction_with_all_scroll_offsets(&mut transaction);
@@ -1005,9 +2555,7 @@ impl Painter {
             return;
         }
 
-        let mut transaction = Transaction::new();
-        self.generate_frame(&mut transaction, RenderReasons::SCENE);
-        self.send_transaction(transaction);
+        self.generate_frame(RenderReasons::SCENE);
 
         let waiting_pipelines = self.frame_delayer.take_waiting_pipelines();
 
@@ -1027,9 +2575,12 @@ impl Painter {
         for update in updates {
             match update {
                 ImageUpdate::AddImage(key, desc, data) => {
-                    txn.add_image(key, desc, data.into(), None)
+                    let data: ImageData = data.into();
+                    self.images.insert(key, (desc, data.clone()));
+                    txn.add_image(key, desc, data, None)
                 },
                 ImageUpdate::DeleteImage(key) => {
+                    self.images.remove(&key);
                     txn.delete_image(key);
                     self.frame_delayer.delete_image(key);
                 },
@@ -1037,14 +2588,16 @@ impl Painter {
                     if let Some(epoch) = epoch {
                         self.frame_delayer.update_image(key, epoch);
                     }
-                    txn.update_image(key, desc, data.into(), &DirtyRect::All)
+                    let data: ImageData = data.into();
+                    self.images.insert(key, (desc, data.clone()));
+                    txn.update_image(key, desc, data, &DirtyRect::All)
                 },
             }
         }
 
         if self.frame_delayer.needs_new_frame() {
             self.frame_delayer.set_pending_frame(false);
-            self.generate_frame(&mut txn, RenderReasons::SCENE);
+            self.generate_frame(RenderReasons::SCENE);
             let waiting_pipelines = self.frame_delayer.take_waiting_pipelines();
 
             self.send_to_constellation(
@@ -1078,12 +2631,14 @@ impl Painter {
     ) {
         let mut transaction = Transaction::new();
         transaction.add_raw_font(font_key, (**data).into(), index);
+        self.fonts.insert(font_key, FontData::Raw(data, index));
         self.send_transaction(transaction);
     }
 
     pub(crate) fn add_system_font(&mut self, font_key: FontKey, native_handle: NativeFontHandle) {
         let mut transaction = Transaction::new();
-        transaction.add_native_font(font_key, native_handle);
+        transaction.add_native_font(font_key, native_handle.clone());
+        self.fonts.insert(font_key, FontData::Native(native_handle));
         self.send_transaction(transaction);
     }
 
@@ -1101,156 +2656,892 @@ impl Painter {
             vec![]
         };
 
+        let font_instance_details = FontInstanceDetails {
+            font_key,
+            size,
+            flags,
+            variations,
+        };
+
         let mut transaction = Transaction::new();
+        self.add_font_instance_to_transaction(
+            &mut transaction,
+            instance_key,
+            &font_instance_details,
+        );
+        self.font_instances
+            .insert(instance_key, font_instance_details);
 
+        self.send_transaction(transaction);
+    }
+
+    fn add_font_instance_to_transaction(
+        &self,
+        transaction: &mut Transaction,
+        instance_key: FontInstanceKey,
+        font_instance_details: &FontInstanceDetails,
+    ) {
+        let render_mode = match self.text_antialiasing {
+            TextAntialiasing::Subpixel => FontRenderMode::Subpixel,
+            TextAntialiasing::Grayscale => FontRenderMode::Alpha,
+            TextAntialiasing::None => FontRenderMode::Mono,
+        };
         let font_instance_options = FontInstanceOptions {
-            flags,
+            flags: font_instance_details.flags,
+            render_mode,
             ..Default::default()
         };
         transaction.add_font_instance(
             instance_key,
-            font_key,
-            size,
+            font_instance_details.font_key,
+            font_instance_details.size,
             Some(font_instance_options),
             None,
-            variations,
+            font_instance_details.variations.clone(),
         );
+    }
+
+    /// Change how text is antialiased. WebRender does not allow changing the options of an
+    /// existing font instance, so every font instance is recreated with the new options.
+    /// Rebuilding the scene then picks up the new instances for all existing text.
+    pub(crate) fn set_text_antialiasing(&mut self, text_antialiasing: TextAntialiasing) {
+        if self.text_antialiasing == text_antialiasing {
+            return;
+        }
+        self.text_antialiasing = text_antialiasing;
+
+        let mut transaction = Transaction::new();
+        for (instance_key, font_instance_details) in &self.font_instances {
+            transaction.delete_font_instance(*instance_key);
+            self.add_font_instance_to_transaction(
+                &mut transaction,
+                *instance_key,
+                font_instance_details,
+            );
+        }
+        self.send_transaction(transaction);
+        self.send_root_pipeline_display_list();
+    }
+
+    pub(crate) fn remove_fonts(&mut self, keys: Vec<FontKey>, instance_keys: Vec<FontInstanceKey>) {
+        let mut transaction = Transaction::new();
+
+        for instance in instance_keys.into_iter() {
+            self.font_instances.remove(&instance);
+            transaction.delete_font_instance(instance);
+        }
+        for key in keys.into_iter() {
+            self.fonts.remove(&key);
+            transaction.delete_font(key);
+        }
 
         self.send_transaction(transaction);
     }
 
-    pub(crate) fn remove_fonts(&mut self, keys: Vec<FontKey>, instance_keys: Vec<FontInstanceKey>) {
+    pub(crate) fn set_viewport_description(
+        &mut self,
+        webview_id: WebViewId,
+        viewport_description: ViewportDescription,
+    ) {
+        if let Some(webview) = self.webview_renderers.get_mut(&webview_id) {
+            webview.set_viewport_description(viewport_description);
+        }
+    }
+
+    pub(crate) fn handle_screenshot_readiness_reply(
+        &self,
+        webview_id: WebViewId,
+        expected_epochs: FxHashMap<PipelineId, Epoch>,
+    ) {
+        self.screenshot_taker
+            .handle_screenshot_readiness_reply(webview_id, expected_epochs, self);
+    }
+
+    /// Add the given `WebView` to this [`Painter`], returning `false` if it was already known.
+    pub(crate) fn add_webview(
+        &mut self,
+        webview: Box<dyn WebViewTrait>,
+        viewport_details: ViewportDetails,
+    ) -> bool {
+        let webview_id = webview.id();
+        let Entry::Vacant(entry) = self.webview_renderers.entry(webview_id) else {
+            return false;
+        };
+        entry.insert(WebViewRenderer::new(
+            webview,
+            viewport_details,
+            self.embedder_to_constellation_sender.clone(),
+            self.refresh_driver.clone(),
+            self.webrender_document,
+        ));
+        self.painting_order.push(webview_id);
+        self.recently_shown.push(webview_id);
+        self.update_throttling();
+        true
+    }
+
+    /// Remove the given `WebView` from this [`Painter`], returning `true` if it was known.
+    pub(crate) fn remove_webview(&mut self, webview_id: WebViewId) -> bool {
+        if self.webview_renderers.remove(&webview_id).is_none() {
+            warn!("Tried removing unknown WebView: {webview_id:?}");
+            return false;
+        };
+        self.painting_order.retain(|id| *id != webview_id);
+        self.recently_shown.retain(|id| *id != webview_id);
+        self.suspended_webviews.remove(&webview_id);
+        self.embedder_throttled_webviews.remove(&webview_id);
+        self.mirrored_webviews.remove(&webview_id);
+        self.mirror_snapshots.retain(|(id, _)| *id != webview_id);
+        self.frame_presented_callbacks
+            .retain(|request| request.webview_id != webview_id);
+        if let Some(synchronized_resize) = self.synchronized_resize.as_mut() {
+            synchronized_resize.display_lists.remove(&webview_id);
+        }
+        self.overlay_scrollbars.remove(&webview_id);
+        self.screenshot_taker.remove_webview(webview_id);
+        self.input_latency_tracker.remove_webview(webview_id);
+        self.capture_streams
+            .retain(|capture_stream| capture_stream.webview_id != Some(webview_id));
+        self.update_isolated_webview();
+
+        self.send_root_pipeline_display_list();
+        self.update_throttling();
+        self.lcp_calculator.note_webview_removed(webview_id);
+        true
+    }
+
+    pub(crate) fn throttled(&self) -> bool {
+        self.throttled.get()
+    }
+
+    /// Set whether or not the [`RenderingContext`] of this [`Painter`] is occluded. Frame
+    /// generation is throttled while it is.
+    pub(crate) fn set_occluded(&mut self, occluded: bool) {
+        self.occluded = occluded;
+        self.update_throttling();
+    }
+
+    /// Pause or resume rendering for this [`Painter`]. When pausing, WebRender is asked to
+    /// release as much memory as possible. When resuming, all transactions queued while
+    /// paused are sent to WebRender and a new frame is generated.
+    pub(crate) fn set_paused(&mut self, paused: bool) {
+        if self.paused == paused {
+            return;
+        }
+
+        debug!(
+            "Setting rendering paused for {:?} to {paused}",
+            self.painter_id
+        );
+        if paused {
+            if let Err(error) = self.rendering_context.make_current() {
+                error!("Failed to make the rendering context current: {error:?}");
+            }
+            if let Some(renderer) = self.webrender_renderer.as_mut() {
+                renderer.update();
+            }
+            self.flush_pending_transactions();
+            self.webrender_api.notify_memory_pressure();
+            self.paused = true;
+            self.update_throttling();
+            return;
+        }
+
+        self.paused = false;
+        let paused_transactions = std::mem::take(&mut self.paused_transactions);
+        self.pending_transactions.splice(0..0, paused_transactions);
+        self.update_throttling();
+        self.flush_pending_transactions();
+        self.set_needs_repaint(RepaintReason::Resize);
+    }
+
+    /// Ask WebRender to clear its texture cache and release its render targets if nothing
+    /// painted by this [`Painter`] can currently be seen. The caches are rebuilt lazily when
+    /// the next frame is generated after a `WebView` is shown again. Returns `true` if memory
+    /// was released.
+    pub(crate) fn release_memory_if_hidden(&mut self) -> bool {
+        if !self.throttled() {
+            return false;
+        }
+
+        debug!("Releasing GPU memory of hidden {:?}", self.painter_id);
+        self.webrender_api.notify_memory_pressure();
+        true
+    }
+
+    /// Throttle frame generation when nothing painted by this [`Painter`] can be seen. When
+    /// frame generation is no longer throttled, a new frame is generated immediately, so that
+    /// it reflects all transactions sent while throttled.
+    fn update_throttling(&mut self) {
+        let throttled = self.paused ||
+            self.occluded ||
+            (self.isolated_webview.is_none() &&
+                self.webview_renderers().all(WebViewRenderer::hidden));
+        if self.throttled.replace(throttled) == throttled {
+            return;
+        }
+
+        debug!(
+            "Setting frame throttling for {:?} to {throttled}",
+            self.painter_id
+        );
+        if !throttled {
+            self.generate_frame(RenderReasons::SCENE);
+            self.resume_animation_ticks();
+        }
+    }
+
+    /// Start ticking the animations of the `WebView`s of this [`Painter`] again once it is
+    /// no longer throttled, if any of them started animating while it was.
+    fn resume_animation_ticks(&self) {
+        let observer = &self.animation_refresh_driver_observer;
+        if observer.animating.get() || self.animating_webviews().is_empty() {
+            return;
+        }
+        observer.animating.set(true);
+        self.refresh_driver.add_observer(observer.clone());
+    }
+
+    /// Iterate over the [`WebViewRenderer`]s of this [`Painter`] in the order that they are
+    /// composited, from bottom to top. Unlike iterating over the underlying hash map, this
+    /// order is deterministic.
+    pub(crate) fn webview_renderers(&self) -> impl DoubleEndedIterator<Item = &WebViewRenderer> {
+        self.compositing_order()
+            .filter_map(|webview_id| self.webview_renderers.get(webview_id))
+    }
+
+    /// The ids of the `WebView`s of this [`Painter`] in the order that they are composited,
+    /// from bottom to top. This is the painting order, except that floating `WebView`s are
+    /// always composited above all other `WebView`s.
+    fn compositing_order(&self) -> impl DoubleEndedIterator<Item = &WebViewId> {
+        let is_floating = |webview_id: &&WebViewId| {
+            self.webview_renderers
+                .get(*webview_id)
+                .is_some_and(WebViewRenderer::floating)
+        };
+        self.painting_order
+            .iter()
+            .filter(move |webview_id| !is_floating(webview_id))
+            .chain(self.painting_order.iter().filter(is_floating))
+    }
+
+    /// Find the topmost visible `WebView` of this [`Painter`] whose painted area contains the
+    /// given point in the coordinate space of the [`RenderingContext`], along with the
+    /// WebRender hit test results for its pipelines at that point. Unlike hit testing a
+    /// single `WebView`, this respects the compositing order, rectangles, transforms and
+    /// clips of all `WebView`s, so it finds the `WebView` that actually shows what is at the
+    /// point when several of them overlap.
+    pub(crate) fn hit_test(
+        &self,
+        point: DevicePoint,
+    ) -> Option<(WebViewId, Vec<PaintHitTestResult>)> {
+        let webview_renderer = self
+            .compositing_order()
+            .rev()
+            .filter_map(|webview_id| self.webview_renderers.get(webview_id))
+            .find(|webview_renderer| {
+                !webview_renderer.hidden() && webview_renderer.painted_rect().contains(point)
+            })?;
+
+        let results = Self::hit_test_at_point_with_api_and_document(
+            &self.webrender_api,
+            self.webrender_document,
+            point,
+        )
+        .into_iter()
+        .filter(|result| webview_renderer.pipelines.contains_key(&result.pipeline_id))
+        .collect();
+        Some((webview_renderer.id, results))
+    }
+
+    /// The ids of the `WebView`s of this [`Painter`] in painting order, from bottom to top.
+    pub(crate) fn painting_order(&self) -> &[WebViewId] {
+        &self.painting_order
+    }
+
+    fn painting_index(&self, webview_id: WebViewId) -> Result<usize, UnknownWebView> {
+        self.painting_order
+            .iter()
+            .position(|id| *id == webview_id)
+            .ok_or(UnknownWebView(webview_id))
+    }
+
+    /// Move the given `WebView` to `index` in the painting order, where index 0 is painted
+    /// first and thus appears below all other `WebView`s. Indices past the end of the painting
+    /// order move the `WebView` to the top.
+    pub(crate) fn move_webview_to_painting_index(
+        &mut self,
+        webview_id: WebViewId,
+        index: usize,
+    ) -> Result<(), UnknownWebView> {
+        let old_index = self.painting_index(webview_id)?;
+        self.painting_order.remove(old_index);
+
+        let new_index = index.min(self.painting_order.len());
+        self.painting_order.insert(new_index, webview_id);
+        if new_index != old_index {
+            self.send_root_pipeline_display_list();
+        }
+        Ok(())
+    }
+
+    /// Move the given `WebView` so that it is painted directly above `other_webview_id`.
+    pub(crate) fn move_webview_above(
+        &mut self,
+        webview_id: WebViewId,
+        other_webview_id: WebViewId,
+    ) -> Result<(), UnknownWebView> {
+        self.move_webview_next_to(webview_id, other_webview_id, true)
+    }
+
+    /// Move the given `WebView` so that it is painted directly below `other_webview_id`.
+    pub(crate) fn move_webview_below(
+        &mut self,
+        webview_id: WebViewId,
+        other_webview_id: WebViewId,
+    ) -> Result<(), UnknownWebView> {
+        self.move_webview_next_to(webview_id, other_webview_id, false)
+    }
+
+    fn move_webview_next_to(
+        &mut self,
+        webview_id: WebViewId,
+        other_webview_id: WebViewId,
+        above: bool,
+    ) -> Result<(), UnknownWebView> {
+        self.painting_index(other_webview_id)?;
+        if webview_id == other_webview_id {
+            return Ok(());
+        }
+
+        let old_index = self.painting_index(webview_id)?;
+        self.painting_order.remove(old_index);
+
+        let other_index = self.painting_index(other_webview_id)?;
+        let new_index = if above { other_index + 1 } else { other_index };
+        self.painting_order.insert(new_index, webview_id);
+        if new_index != old_index {
+            self.send_root_pipeline_display_list();
+        }
+        Ok(())
+    }
+
+    pub(crate) fn is_empty(&mut self) -> bool {
+        self.webview_renderers.is_empty()
+    }
+
+    /// Hide every visible `WebView` of this [`Painter`], for instance because its window was
+    /// minimized, returning the `WebView`s that were hidden. The painting order is unaffected.
+    pub(crate) fn hide_all_webviews(&mut self) -> Vec<WebViewId> {
+        let mut newly_hidden = Vec::new();
+        for webview_id in self.painting_order.iter() {
+            let Some(webview_renderer) = self.webview_renderers.get_mut(webview_id) else {
+                continue;
+            };
+            if webview_renderer.set_hidden(true) {
+                newly_hidden.push(*webview_id);
+            }
+        }
+        if newly_hidden.is_empty() {
+            return newly_hidden;
+        }
+
+        self.webviews_hidden_by_hide_all = newly_hidden.clone();
+        self.send_root_pipeline_display_list();
+        self.update_throttling();
+        newly_hidden
+    }
+
+    /// Show every `WebView` that was hidden by the last call to
+    /// [`Painter::hide_all_webviews`], returning the `WebView`s that were shown. `WebView`s
+    /// that were already hidden at that point stay hidden.
+    pub(crate) fn show_all_webviews(&mut self) -> Vec<WebViewId> {
+        let mut newly_shown = Vec::new();
+        for webview_id in std::mem::take(&mut self.webviews_hidden_by_hide_all) {
+            let Some(webview_renderer) = self.webview_renderers.get_mut(&webview_id) else {
+                continue;
+            };
+            if webview_renderer.set_hidden(false) {
+                newly_shown.push(webview_id);
+            }
+        }
+        if newly_shown.is_empty() {
+            return newly_shown;
+        }
+
+        for webview_id in &newly_shown {
+            self.note_webview_shown(*webview_id);
+        }
+
+        self.send_root_pipeline_display_list();
+        self.update_throttling();
+        newly_shown
+    }
+
+    /// Set whether or not the given `WebView` is hidden, returning `true` if the value
+    /// actually changed.
+    pub(crate) fn set_webview_hidden(
+        &mut self,
+        webview_id: WebViewId,
+        hidden: bool,
+    ) -> Result<bool, UnknownWebView> {
+        debug!("Setting WebView visiblity for {webview_id:?} to hidden={hidden}");
+        let Some(webview_renderer) = self.webview_renderer_mut(webview_id) else {
+            return Err(UnknownWebView(webview_id));
+        };
+        if !webview_renderer.set_hidden(hidden) {
+            return Ok(false);
+        }
+        if !hidden {
+            self.note_webview_shown(webview_id);
+        }
+        self.send_root_pipeline_display_list();
+        self.update_throttling();
+        Ok(true)
+    }
+
+    fn note_webview_shown(&mut self, webview_id: WebViewId) {
+        self.recently_shown.retain(|id| *id != webview_id);
+        self.recently_shown.push(webview_id);
+    }
+
+    /// Set the maximum number of `WebView`s of this [`Painter`] that are not suspended, or
+    /// `None` for no limit. Call [`Painter::update_webview_suspension`] to apply it.
+    pub(crate) fn set_webview_limit(&mut self, webview_limit: Option<usize>) {
+        self.webview_limit = webview_limit;
+    }
+
+    /// Throttle or unthrottle the given `WebView` at the request of the embedder. This is
+    /// tracked separately from suspension, so that a suspended `WebView` stays throttled when
+    /// the embedder unthrottles it and a `WebView` throttled by the embedder stays throttled
+    /// when it is resumed.
+    pub(crate) fn set_webview_throttled(&mut self, webview_id: WebViewId, throttled: bool) {
+        if throttled {
+            self.embedder_throttled_webviews.insert(webview_id);
+        } else {
+            self.embedder_throttled_webviews.remove(&webview_id);
+        }
+        let throttled = throttled || self.suspended_webviews.contains(&webview_id);
+        self.send_to_constellation(EmbedderToConstellationMessage::SetWebViewThrottled(
+            webview_id, throttled,
+        ));
+    }
+
+    /// Suspend or resume `WebView`s so that no more than the limit set with
+    /// [`Painter::set_webview_limit`] are active, returning the resulting lifecycle events.
+    /// Hidden `WebView`s are suspended in order from least to most recently shown and
+    /// suspended `WebView`s are throttled, so that they stop running animations and producing
+    /// new display lists. Visible `WebView`s are never suspended, so the limit may be exceeded
+    /// when more `WebView`s than that are visible. Resumed `WebView`s are only unthrottled if
+    /// the embedder has not throttled them with [`Painter::set_webview_throttled`].
+    pub(crate) fn update_webview_suspension(&mut self) -> Vec<(WebViewId, WebViewLifecycleEvent)> {
+        let mut events = Vec::new();
+        for webview_id in self.recently_shown.clone() {
+            if !self.suspended_webviews.contains(&webview_id) {
+                continue;
+            }
+            let visible = self
+                .webview_renderers
+                .get(&webview_id)
+                .is_some_and(|webview_renderer| !webview_renderer.hidden());
+            if visible || self.webview_limit.is_none() {
+                self.suspended_webviews.remove(&webview_id);
+                if !self.embedder_throttled_webviews.contains(&webview_id) {
+                    self.send_to_constellation(
+                        EmbedderToConstellationMessage::SetWebViewThrottled(webview_id, false),
+                    );
+                }
+                events.push((webview_id, WebViewLifecycleEvent::Resumed));
+            }
+        }
+
+        let Some(webview_limit) = self.webview_limit else {
+            return events;
+        };
+        let mut active_webviews = self.recently_shown.len() - self.suspended_webviews.len();
+        for webview_id in self.recently_shown.clone() {
+            if active_webviews <= webview_limit {
+                break;
+            }
+            let hidden = self
+                .webview_renderers
+                .get(&webview_id)
+                .is_some_and(WebViewRenderer::hidden);
+            if !hidden || !self.suspended_webviews.insert(webview_id) {
+                continue;
+            }
+            self.send_to_constellation(EmbedderToConstellationMessage::SetWebViewThrottled(
+                webview_id, true,
+            ));
+            events.push((webview_id, WebViewLifecycleEvent::Suspended));
+            active_webviews -= 1;
+        }
+        events
+    }
+
+    pub(crate) fn set_hidpi_scale_factor(
+        &mut self,
+        webview_id: WebViewId,
+        new_scale_factor: Scale<f32, DeviceIndependentPixel, DevicePixel>,
+    ) {
+        let Some(webview_renderer) = self.webview_renderers.get_mut(&webview_id) else {
+            return;
+        };
+        if !webview_renderer.set_hidpi_scale_factor(new_scale_factor) {
+            return;
+        }
+
+        self.send_root_pipeline_display_list();
+        self.set_needs_repaint(RepaintReason::Resize);
+    }
+
+    pub(crate) fn resize_rendering_context(&mut self, new_size: PhysicalSize<u32>) {
+        if self.rendering_context.size() == new_size {
+            return;
+        }
+
+        if let Err(error) = self.rendering_context.make_current() {
+            error!("Failed to make the rendering context current: {error:?}");
+        }
+        self.rendering_context.resize(new_size);
+        self.update_viewport_for_rendering_context_size();
+        if self.resize_present_timeout.is_some() {
+            self.start_synchronized_resize(new_size, None);
+        }
+    }
+
+    /// Start painting to a new [`RenderingContext`]. When it uses the same OpenGL context as
+    /// the old one, the existing WebRender instance is kept along with all of its state.
+    /// Otherwise, for instance when the native surface of a window was lost along with its
+    /// OpenGL context, the WebRender instance is recreated for the new [`RenderingContext`].
+    pub(crate) fn replace_rendering_context(
+        &mut self,
+        rendering_context: Rc<dyn RenderingContext>,
+    ) {
+        if !Rc::ptr_eq(&self.webrender_gl, &rendering_context.gleam_gl_api()) {
+            self.recreate_webrender_instance(rendering_context);
+            return;
+        }
+
+        self.rendering_context = rendering_context;
+        if let Err(error) = self.rendering_context.make_current() {
+            error!("Failed to make the rendering context current: {error:?}");
+        }
+        self.rendering_context.prepare_for_rendering();
+        self.update_viewport_for_rendering_context_size();
+    }
+
+    /// Shut down the WebRender instance of this [`Painter`], releasing all of its resources.
+    fn shut_down_webrender_instance(&mut self) {
+        if let Err(error) = self.rendering_context.make_current() {
+            error!("Failed to make the rendering context current: {error:?}");
+        }
+
+        self.webrender_api.delete_document(self.webrender_document);
+        self.webrender_api.stop_render_backend();
+        self.webrender_api.shut_down(true);
+
+        if let Some(renderer) = self.webrender_renderer.take() {
+            renderer.deinit();
+        }
+    }
+
+    /// Replace the WebRender instance of this [`Painter`] with a new one that renders to
+    /// `rendering_context`. Every font, image and display list that WebRender knew about is
+    /// sent to the new instance, so that `WebView`s keep their contents without having to
+    /// lay out their pages again, and a new frame is generated.
+    fn recreate_webrender_instance(&mut self, rendering_context: Rc<dyn RenderingContext>) {
+        info!("Recreating the WebRender instance of {:?}", self.painter_id);
+        let debug_flags = self
+            .webrender_renderer
+            .as_ref()
+            .map(|renderer| renderer.get_debug_flags())
+            .unwrap_or_default();
+        let last_image_key = self.webrender_api.generate_image_key();
+        self.shut_down_webrender_instance();
+
+        self.rendering_context = rendering_context;
+        self.ready_frames.lock().clear();
+        let webrender_instance = self.webrender_instance_factory.create_instance(
+            self.painter_id,
+            &self.rendering_context,
+            self.ready_frames.clone(),
+            self.clear_color,
+            Some(last_image_key),
+        );
+        let mut webrender_renderer = webrender_instance.renderer;
+        webrender_renderer.set_debug_flags(debug_flags);
+        self.webrender_renderer = Some(webrender_renderer);
+        self.webrender_api = webrender_instance.api;
+        self.webrender_document = webrender_instance.document;
+        self.webrender_gl = webrender_instance.gl;
+        for webview_renderer in self.webview_renderers.values_mut() {
+            webview_renderer.set_webrender_document(self.webrender_document);
+        }
+
+        // The old instance will not report the frames that it was still building, and the
+        // transactions that were not sent to it yet are replaced by the ones below.
+        self.pending_frames.set(0);
+        self.frames_ready.set(self.frames_requested.get());
+        self.pending_transactions.clear();
+        self.paused_transactions.clear();
+        self.last_frame_damage.clear();
+        self.rendered_offscreen = true;
+
         let mut transaction = Transaction::new();
+        for (font_key, font_data) in &self.fonts {
+            match font_data {
+                FontData::Raw(data, index) => {
+                    transaction.add_raw_font(*font_key, (***data).into(), *index)
+                },
+                FontData::Native(native_handle) => {
+                    transaction.add_native_font(*font_key, native_handle.clone())
+                },
+            }
+        }
+        for (instance_key, font_instance_details) in &self.font_instances {
+            self.add_font_instance_to_transaction(
+                &mut transaction,
+                *instance_key,
+                font_instance_details,
+            );
+        }
+        for (image_key, (descriptor, data)) in &self.images {
+            transaction.add_image(*image_key, *descriptor, data.clone(), None);
+        }
+        for webview_renderer in self.webview_renderers.values() {
+            for (pipeline_id, details) in webview_renderer.pipelines.iter() {
+                if let Some(display_list) = details.display_list.as_ref() {
+                    transaction.set_display_list(
+                        display_list.epoch,
+                        ((*pipeline_id).into(), display_list.to_built_display_list()),
+                    );
+                }
+            }
+        }
+        self.update_transaction_with_all_scroll_offsets(&mut transaction);
+        self.send_transaction(transaction);
 
-        for instance in instance_keys.into_iter() {
-            transaction.delete_font_instance(instance);
+        // Images owned by this `Painter` are added again the next time that they are needed.
+        self.background_image_key = None;
+        self.set_background(self.background.clone());
+        for mirror in self.mirrors.iter_mut() {
+            mirror.image = None;
         }
-        for key in keys.into_iter() {
-            transaction.delete_font(key);
+
+        self.assert_gl_framebuffer_complete();
+        self.clear_background();
+        self.update_viewport_for_rendering_context_size();
+        self.generate_frame(RenderReasons::SCENE);
+    }
+
+    /// Update the WebRender document and the rectangles of all `WebView`s without an explicit
+    /// rectangle to match the current size of the [`RenderingContext`].
+    fn update_viewport_for_rendering_context_size(&mut self) {
+        let new_size = self.rendering_context.size2d().to_f32();
+        let new_viewport_rect = Rect::from(new_size).to_box2d();
+        for webview_id in self.painting_order.iter() {
+            let Some(webview_renderer) = self.webview_renderers.get_mut(webview_id) else {
+                continue;
+            };
+            if !webview_renderer.has_explicit_rect() {
+                webview_renderer.set_rect(new_viewport_rect);
+            }
         }
 
+        let mut transaction = Transaction::new();
+        transaction.set_document_view(new_viewport_rect.to_i32());
         self.send_transaction(transaction);
+
+        self.send_root_pipeline_display_list();
+        self.set_needs_repaint(RepaintReason::Resize);
     }
 
-    pub(crate) fn set_viewport_description(
+    /// Position the given `WebView` at `rect` within the [`RenderingContext`] of this
+    /// [`Painter`], or make it fill the entire [`RenderingContext`] when `rect` is `None`.
+    pub(crate) fn set_webview_rect(
         &mut self,
         webview_id: WebViewId,
-        viewport_description: ViewportDescription,
-    ) {
-        if let Some(webview) = self.webview_renderers.get_mut(&webview_id) {
-            webview.set_viewport_description(viewport_description);
+        rect: Option<DeviceRect>,
+    ) -> Result<(), UnknownWebView> {
+        let rendering_context_rect =
+            Rect::from(self.rendering_context.size2d().to_f32()).to_box2d();
+        let Some(webview_renderer) = self.webview_renderers.get_mut(&webview_id) else {
+            return Err(UnknownWebView(webview_id));
+        };
+        if !webview_renderer.set_explicit_rect(rect, rendering_context_rect) {
+            return Ok(());
         }
-    }
 
-    pub(crate) fn handle_screenshot_readiness_reply(
-        &self,
-        webview_id: WebViewId,
-        expected_epochs: FxHashMap<PipelineId, Epoch>,
-    ) {
-        self.screenshot_taker
-            .handle_screenshot_readiness_reply(webview_id, expected_epochs, self);
+        self.send_root_pipeline_display_list();
+        self.set_needs_repaint(RepaintReason::Resize);
+        Ok(())
     }
 
-    pub(crate) fn add_webview(
+    pub(crate) fn set_webview_transform(
         &mut self,
-        webview: Box<dyn WebViewTrait>,
-        viewport_details: ViewportDetails,
-    ) {
-        self.webview_renderers
-            .entry(webview.id())
-            .or_insert(WebViewRenderer::new(
-                webview,
-                viewport_details,
-                self.embedder_to_constellation_sender.clone(),
-                self.refresh_driver.clone(),
-                self.webrender_document,
-            ));
+        webview_id: WebViewId,
+        transform: Transform2D<f32, DevicePixel, DevicePixel>,
+    ) -> Result<(), UnknownWebView> {
+        let Some(webview_renderer) = self.webview_renderers.get_mut(&webview_id) else {
+            return Err(UnknownWebView(webview_id));
+        };
+        if !webview_renderer.set_transform(transform) {
+            return Ok(());
+        }
+
+        self.send_root_pipeline_display_list();
+        Ok(())
     }
 
-    pub(crate) fn remove_webview(&mut self, webview_id: WebViewId) {
-        if self.webview_renderers.remove(&webview_id).is_none() {
-            warn!("Tried removing unknown WebView: {webview_id:?}");
-            return;
+    pub(crate) fn set_webview_floating(
+        &mut self,
+        webview_id: WebViewId,
+        floating: bool,
+    ) -> Result<(), UnknownWebView> {
+        let Some(webview_renderer) = self.webview_renderers.get_mut(&webview_id) else {
+            return Err(UnknownWebView(webview_id));
         };
+        if !webview_renderer.set_floating(floating) {
+            return Ok(());
+        }
 
         self.send_root_pipeline_display_list();
-        self.lcp_calculator.note_webview_removed(webview_id);
+        Ok(())
     }
 
-    pub(crate) fn is_empty(&mut self) -> bool {
-        self.webview_renderers.is_empty()
+    pub(crate) fn set_webview_floating_offset(
+        &mut self,
+        webview_id: WebViewId,
+        offset: DeviceVector2D,
+    ) -> Result<(), UnknownWebView> {
+        let Some(webview_renderer) = self.webview_renderers.get_mut(&webview_id) else {
+            return Err(UnknownWebView(webview_id));
+        };
+        if !webview_renderer.set_floating_offset(offset) {
+            return Ok(());
+        }
+
+        self.send_root_pipeline_display_list();
+        Ok(())
     }
 
-    pub(crate) fn set_webview_hidden(
+    pub(crate) fn set_webview_clip(
         &mut self,
         webview_id: WebViewId,
-        hidden: bool,
+        clip: Option<WebViewClip>,
     ) -> Result<(), UnknownWebView> {
-        debug!("Setting WebView visiblity for {webview_id:?} to hidden={hidden}");
-        let Some(webview_renderer) = self.webview_renderer_mut(webview_id) else {
+        let Some(webview_renderer) = self.webview_renderers.get_mut(&webview_id) else {
             return Err(UnknownWebView(webview_id));
         };
-        if !webview_renderer.set_hidden(hidden) {
+        if !webview_renderer.set_clip(clip) {
             return Ok(());
         }
+
         self.send_root_pipeline_display_list();
         Ok(())
     }
 
-    pub(crate) fn set_hidpi_scale_factor(
+    pub(crate) fn set_webview_decorations(
         &mut self,
         webview_id: WebViewId,
-        new_scale_factor: Scale<f32, DeviceIndependentPixel, DevicePixel>,
-    ) {
+        decorations: WebViewDecorations,
+    ) -> Result<(), UnknownWebView> {
         let Some(webview_renderer) = self.webview_renderers.get_mut(&webview_id) else {
-            return;
+            return Err(UnknownWebView(webview_id));
         };
-        if !webview_renderer.set_hidpi_scale_factor(new_scale_factor) {
-            return;
+        if !webview_renderer.set_decorations(decorations) {
+            return Ok(());
         }
 
         self.send_root_pipeline_display_list();
-        self.set_needs_repaint(RepaintReason::Resize);
+        Ok(())
     }
 
-    pub(crate) fn resize_rendering_context(&mut self, new_size: PhysicalSize<u32>) {
-        if self.rendering_context.size() == new_size {
-            return;
+    pub(crate) fn set_webview_opacity(
+        &mut self,
+        webview_id: WebViewId,
+        opacity: f32,
+    ) -> Result<(), UnknownWebView> {
+        let Some(webview_renderer) = self.webview_renderers.get_mut(&webview_id) else {
+            return Err(UnknownWebView(webview_id));
+        };
+        if !webview_renderer.set_opacity(opacity) {
+            return Ok(());
         }
 
-        if let Err(error) = self.rendering_context.make_current() {
-            error!("Failed to make the rendering context current: {error:?}");
-        }
-        self.rendering_context.resize(new_size);
+        self.send_root_pipeline_display_list();
+        Ok(())
+    }
 
-        let new_size = Size2D::new(new_size.width as f32, new_size.height as f32);
-        let new_viewport_rect = Rect::from(new_size).to_box2d();
-        for webview_renderer in self.webview_renderers.values_mut() {
-            webview_renderer.set_rect(new_viewport_rect);
-        }
+    /// Cap the rate at which the given `WebView` produces new frames to `max_frame_rate`
+    /// frames per second, or remove the cap when `None`. Other `WebView`s of this [`Painter`]
+    /// are not affected.
+    pub(crate) fn set_webview_max_frame_rate(
+        &mut self,
+        webview_id: WebViewId,
+        max_frame_rate: Option<f32>,
+    ) -> Result<(), UnknownWebView> {
+        let Some(webview_renderer) = self.webview_renderers.get_mut(&webview_id) else {
+            return Err(UnknownWebView(webview_id));
+        };
+        webview_renderer.set_max_frame_rate(max_frame_rate);
+        Ok(())
+    }
 
-        let mut transaction = Transaction::new();
-        transaction.set_document_view(new_viewport_rect.to_i32());
-        self.send_transaction(transaction);
+    pub(crate) fn set_webview_opaque(
+        &mut self,
+        webview_id: WebViewId,
+        opaque: bool,
+    ) -> Result<(), UnknownWebView> {
+        let Some(webview_renderer) = self.webview_renderers.get_mut(&webview_id) else {
+            return Err(UnknownWebView(webview_id));
+        };
+        if !webview_renderer.set_opaque(opaque) {
+            return Ok(());
+        }
 
         self.send_root_pipeline_display_list();
-        self.set_needs_repaint(RepaintReason::Resize);
+        Ok(())
     }
 
+    /// Set the page zoom of the given `WebView`. The new page zoom is applied to the latest
+    /// display list of the `WebView` right away by scaling it, while the page is only laid
+    /// out again once the page zoom has settled. See
+    /// [`WebViewRenderer::set_page_zoom_asynchronously`].
     pub(crate) fn set_page_zoom(&mut self, webview_id: WebViewId, new_zoom: f32) {
-        if let Some(webview_renderer) = self.webview_renderers.get_mut(&webview_id) {
-            webview_renderer.set_page_zoom(Scale::new(new_zoom));
+        let Some(webview_renderer) = self.webview_renderers.get_mut(&webview_id) else {
+            return;
+        };
+        if webview_renderer.set_page_zoom_asynchronously(Scale::new(new_zoom)) {
+            self.send_root_pipeline_display_list();
+        }
+    }
+
+    /// Lay out the page of the given `WebView` at its final page zoom during a
+    /// `RefreshDriver`-based frame tick, once the page zoom has settled. Returns `true` if
+    /// the page zoom has not settled yet.
+    pub(crate) fn update_page_zoom_at_new_frame_start(&mut self, webview_id: WebViewId) -> bool {
+        let Some(webview_renderer) = self.webview_renderers.get_mut(&webview_id) else {
+            return false;
+        };
+        let keep_observing = webview_renderer.update_page_zoom_at_new_frame_start();
+        if keep_observing {
+            // Request another frame, so that this is checked again once it starts.
+            self.generate_frame(RenderReasons::ANIMATED_PROPERTY);
         }
+        keep_observing
     }
 
     pub(crate) fn page_zoom(&self, webview_id: WebViewId) -> f32 {
         self.webview_renderers
             .get(&webview_id)
             .map(|webview_renderer| webview_renderer.page_zoom.get())
-            .unwrap_or_default()
+            .unwrap_or(1.0)
     }
 
     pub(crate) fn notify_input_event(&mut self, webview_id: WebViewId, event: InputEventAndId) {
+        let old_mouse_move_position = self.last_mouse_move_position;
         if let Some(webview_renderer) = self.webview_renderers.get_mut(&webview_id) {
             match &event.event {
                 InputEvent::MouseMove(event) => {
@@ -1259,7 +3550,8 @@ impl Painter {
                         let event_point = event
                             .point
                             .as_device_point(webview_renderer.device_pixels_per_page_pixel());
-                        self.last_mouse_move_position = Some(event_point);
+                        self.last_mouse_move_position =
+                            Some(webview_renderer.to_rendering_context_point(event_point));
                     }
                 },
                 InputEvent::MouseLeftViewport(_) => {
@@ -1268,8 +3560,13 @@ impl Painter {
                 _ => {},
             }
 
+            self.input_latency_tracker
+                .input_event_received(webview_id, event.id);
             webview_renderer.notify_input_event(&self.webrender_api, &self.needs_repaint, event);
         }
+        if self.draws_cursor && old_mouse_move_position != self.last_mouse_move_position {
+            self.send_root_pipeline_display_list();
+        }
         self.disable_lcp_calculation_for_webview(webview_id);
     }
 
@@ -1303,7 +3600,54 @@ impl Painter {
         self.webview_renderers
             .get(&webview_id)
             .map(WebViewRenderer::device_pixels_per_page_pixel)
-            .unwrap_or_default()
+            .unwrap_or(Scale::new(1.0))
+    }
+
+    /// Read back the current contents of the entire `RenderingContext` of this [`Painter`].
+    /// Unlike screenshots, this does not wait for any pending rendering.
+    pub(crate) fn read_pixels(&self) -> Option<RgbaImage> {
+        if let Err(error) = self.rendering_context.make_current() {
+            error!("Failed to make the rendering context current: {error:?}");
+            return None;
+        }
+        let size = self.rendering_context.size2d().to_i32();
+        self.rendering_context
+            .read_to_image(DeviceIntRect::from_size(size))
+    }
+
+    /// Read back the current contents of the entire `RenderingContext` of this [`Painter`]
+    /// straight into shared memory, like [`Painter::read_pixels`].
+    pub(crate) fn read_pixels_to_shared_memory(&self) -> Option<SharedMemoryCapture> {
+        if let Err(error) = self.rendering_context.make_current() {
+            error!("Failed to make the rendering context current: {error:?}");
+            return None;
+        }
+        let size = self.rendering_context.size2d().to_i32();
+        let pixels = self
+            .rendering_context
+            .read_to_shared_memory(DeviceIntRect::from_size(size))?;
+        Some(SharedMemoryCapture { size, pixels })
+    }
+
+    /// Request a capture of the entire `RenderingContext` of this [`Painter`], which is taken
+    /// after the next paint once there are no more pending WebRender frames.
+    pub(crate) fn request_rendering_context_capture(
+        &self,
+        callback: Box<dyn FnOnce(Result<RgbaImage, ScreenshotCaptureError>) + 'static>,
+    ) {
+        self.screenshot_taker
+            .request_rendering_context_capture(callback, self);
+    }
+
+    /// Request a capture of the entire `RenderingContext` of this [`Painter`] that is read
+    /// straight into shared memory. It is taken at the same time as
+    /// [`Painter::request_rendering_context_capture`].
+    pub(crate) fn request_rendering_context_capture_to_shared_memory(
+        &self,
+        callback: SharedMemoryCaptureCallback,
+    ) {
+        self.screenshot_taker
+            .request_rendering_context_capture_to_shared_memory(callback, self);
     }
 
     pub(crate) fn request_screenshot(
@@ -1313,6 +3657,7 @@ impl Painter {
         callback: Box<dyn FnOnce(Result<RgbaImage, ScreenshotCaptureError>) + 'static>,
     ) {
         let Some(webview) = self.webview_renderers.get(&webview_id) else {
+            callback(Err(ScreenshotCaptureError::WebViewDoesNotExist));
             return;
         };
 
@@ -1324,6 +3669,103 @@ impl Painter {
         ));
     }
 
+    /// Request a capture of the given `WebView` on its own, without any other content of the
+    /// [`RenderingContext`] and even if it is hidden. `rect` is relative to the origin of the
+    /// `WebView`. Unlike [`Painter::request_screenshot`], this does not wait for the page to be
+    /// ready. Captures are taken one `WebView` at a time. For each one, a frame with only that
+    /// `WebView` is rendered offscreen, after which the normal frame is restored.
+    pub(crate) fn request_webview_capture(
+        &mut self,
+        webview_id: WebViewId,
+        rect: Option<DeviceRect>,
+        callback: Box<dyn FnOnce(Result<RgbaImage, ScreenshotCaptureError>) + 'static>,
+    ) {
+        if !self.webview_renderers.contains_key(&webview_id) {
+            callback(Err(ScreenshotCaptureError::WebViewDoesNotExist));
+            return;
+        }
+
+        self.screenshot_taker
+            .request_webview_capture(webview_id, rect, callback);
+        self.update_isolated_webview();
+    }
+
+    /// Isolate the next `WebView` with pending capture requests, or stop isolating `WebView`s
+    /// if there are none.
+    fn update_isolated_webview(&mut self) {
+        let next_webview_to_capture = self.screenshot_taker.next_webview_to_capture();
+        if self.isolated_webview == next_webview_to_capture {
+            return;
+        }
+        self.isolated_webview = next_webview_to_capture;
+        self.update_throttling();
+        self.send_root_pipeline_display_list();
+    }
+
+    /// Once the frame containing only the isolated `WebView` is ready, render it into a
+    /// temporary framebuffer and resolve the capture requests for that `WebView`.
+    fn capture_isolated_webview(&mut self) {
+        let Some(webview_id) = self.isolated_webview else {
+            return;
+        };
+        if self.has_pending_frames() {
+            return;
+        }
+
+        let capture_requests = self
+            .screenshot_taker
+            .take_webview_capture_requests(webview_id);
+        let Some(webview_rect) = self
+            .webview_renderers
+            .get(&webview_id)
+            .map(|webview_renderer| webview_renderer.rect)
+        else {
+            for capture_request in capture_requests {
+                (capture_request.callback)(Err(ScreenshotCaptureError::WebViewDoesNotExist));
+            }
+            self.update_isolated_webview();
+            return;
+        };
+
+        if let Err(error) = self.rendering_context.make_current() {
+            error!("Failed to make the rendering context current: {error:?}");
+        }
+        let size = self.rendering_context.size();
+        let framebuffer = Framebuffer::new(self.webrender_gl.clone(), size);
+        framebuffer.bind();
+        self.clear_background();
+        if let Some(renderer) = self.webrender_renderer.as_mut() {
+            renderer.update();
+            if let Err(errors) = renderer.render(self.rendering_context.size2d().to_i32(), 0) {
+                warn!("Failed to render WebView for capture: {errors:?}");
+            }
+        }
+        self.rendered_offscreen = true;
+
+        // We need to convert to the bottom-left origin coordinate system used by OpenGL.
+        let rendering_context_height = size.height as f32;
+        for capture_request in capture_requests {
+            let rect = capture_request.rect.map_or(webview_rect, |rect| {
+                rect.translate(webview_rect.min.to_vector())
+            });
+            let rect = DeviceIntRect::from_origin_and_size(
+                Point2D::new(
+                    rect.min.x as i32,
+                    0.max((rendering_context_height - rect.max.y) as i32),
+                ),
+                rect.size().to_i32(),
+            );
+            let result = framebuffer
+                .read_to_image(rect)
+                .ok_or(ScreenshotCaptureError::CouldNotReadImage);
+            (capture_request.callback)(result);
+        }
+
+        drop(framebuffer);
+        self.rendering_context.prepare_for_rendering();
+        self.update_isolated_webview();
+    }
+
     pub(crate) fn notify_input_event_handled(
         &mut self,
         webview_id: WebViewId,
@@ -1334,6 +3776,14 @@ impl Painter {
             warn!("Handled input event for unknown webview: {webview_id}");
             return;
         };
+
+        // Any changes made by the page while handling the event will only be part of a frame
+        // requested from now on.
+        self.input_latency_tracker.input_event_handled(
+            webview_id,
+            input_event_id,
+            self.frames_requested.get() + 1,
+        );
         webview_renderer.notify_input_event_handled(
             &self.webrender_api,
             &self.needs_repaint,
@@ -1342,6 +3792,23 @@ impl Painter {
         );
     }
 
+    /// Set whether or not this [`Painter`] draws the cursor on top of its `WebView`s, at the
+    /// last position that the mouse moved over. This is meant for `RenderingContext`s that
+    /// are not shown in a native window with an OS cursor.
+    pub(crate) fn set_draws_cursor(&mut self, draws_cursor: bool) {
+        if std::mem::replace(&mut self.draws_cursor, draws_cursor) != draws_cursor {
+            self.send_root_pipeline_display_list();
+        }
+    }
+
+    /// Note that the page under the mouse in one of the `WebView`s of this [`Painter`]
+    /// requested the given [`Cursor`].
+    pub(crate) fn set_cursor(&mut self, cursor: Cursor) {
+        if std::mem::replace(&mut self.cursor, cursor) != cursor && self.draws_cursor {
+            self.send_root_pipeline_display_list();
+        }
+    }
+
     pub(crate) fn refresh_cursor(&self) {
         let Some(last_mouse_move_position) = self.last_mouse_move_position else {
             return;
@@ -1364,7 +3831,12 @@ impl Painter {
         }
     }
 
-    pub(crate) fn handle_new_webrender_frame_ready(&self, repaint_needed: bool) {
+    pub(crate) fn handle_new_webrender_frame_ready(&mut self, repaint_needed: bool) {
+        if self.isolated_webview.is_some() {
+            self.capture_isolated_webview();
+            return;
+        }
+
         if repaint_needed {
             self.refresh_cursor()
         }
@@ -1382,12 +3854,28 @@ impl Painter {
         }
     }
 
+    /// Handle a wake up from WebRender that did not come with a new frame. Any pending work
+    /// of the renderer, such as resource updates, is processed right away and, if WebRender
+    /// asks for it, the `RenderingContext` is repainted on the next opportunity.
+    pub(crate) fn handle_webrender_wake_up(&mut self, composite_needed: bool) {
+        if self.paused {
+            return;
+        }
+        if let Err(error) = self.rendering_context.make_current() {
+            error!("Failed to make the rendering context current: {error:?}");
+            return;
+        }
+        if let Some(renderer) = self.webrender_renderer.as_mut() {
+            renderer.update();
+        }
+        if composite_needed && !self.throttled() {
+            self.set_needs_repaint(RepaintReason::NewWebRenderFrame);
+        }
+    }
+
     pub(crate) fn webviews_needing_repaint(&self) -> Vec<WebViewId> {
         if self.needs_repaint() {
-            self.webview_renderers
-                .values()
-                .map(|webview_renderer| webview_renderer.id)
-                .collect()
+            self.painting_order.clone()
         } else {
             Vec::new()
         }
@@ -1397,8 +3885,7 @@ impl Painter {
         &self,
         ops: &mut malloc_size_of::MallocSizeOfOps,
     ) -> usize {
-        self.webview_renderers
-            .values()
+        self.webview_renderers()
             .map(|renderer| renderer.scroll_trees_memory_usage(ops))
             .sum::<usize>()
     }
@@ -1429,6 +3916,28 @@ impl Painter {
     }
 }
 
+/// The default color used to clear a [`RenderingContext`], which is derived from the
+/// `shell_background_color_rgba` preference.
+fn default_clear_color() -> ColorF {
+    let color = pref!(shell_background_color_rgba);
+    ColorF::new(
+        color[0] as f32,
+        color[1] as f32,
+        color[2] as f32,
+        color[3] as f32,
+    )
+}
+
+/// The number of display items in the given display list.
+fn display_item_count(display_list: &BuiltDisplayList) -> usize {
+    let mut iter = display_list.iter();
+    let mut count = 0;
+    while iter.next().is_some() {
+        count += 1;
+    }
+    count
+}
+
 /// A struct that is reponsible for delaying frame requests until all new canvas images
 /// for a particular "update the rendering" call in the `ScriptThread` have been
 /// sent to WebRender.
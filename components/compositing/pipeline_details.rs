@@ -11,9 +11,36 @@ use compositing_traits::{CompositionPipeline, PipelineExitSource};
 use euclid::Scale;
 use style_traits::CSSPixel;
 use webrender_api::units::DevicePixel;
+use webrender_api::{
+    BuiltDisplayList, BuiltDisplayListDescriptor, DisplayListPayload, Epoch as WebRenderEpoch,
+};
 
+use crate::paint::WebViewPaintTiming;
 use crate::painter::PaintMetricState;
 
+/// The latest display list received for a `Pipeline`, which is kept so that it can be sent to
+/// a new WebRender instance. See `Painter::recreate_webrender_instance`.
+pub(crate) struct DisplayListData {
+    pub epoch: WebRenderEpoch,
+    pub descriptor: BuiltDisplayListDescriptor,
+    pub items_data: Vec<u8>,
+    pub cache_data: Vec<u8>,
+    pub spatial_tree: Vec<u8>,
+}
+
+impl DisplayListData {
+    pub(crate) fn to_built_display_list(&self) -> BuiltDisplayList {
+        BuiltDisplayList::from_data(
+            DisplayListPayload {
+                items_data: self.items_data.clone(),
+                cache_data: self.cache_data.clone(),
+                spatial_tree: self.spatial_tree.clone(),
+            },
+            self.descriptor,
+        )
+    }
+}
+
 pub(crate) struct PipelineDetails {
     /// The pipeline associated with this PipelineDetails object.
     pub pipeline: Option<CompositionPipeline>,
@@ -43,6 +70,10 @@ pub(crate) struct PipelineDetails {
     /// The paint metric status of the largest contentful paint.
     pub largest_contentful_paint_metric: Cell<PaintMetricState>,
 
+    /// The times at which this pipeline was first painted, recorded when its paint metrics
+    /// are sent.
+    pub paint_timing: Cell<WebViewPaintTiming>,
+
     /// The CSS pixel to device pixel scale of the viewport of this pipeline, including
     /// page zoom, but not including any pinch zoom amount. This is used to detect
     /// situations where the current display list is for an old scale.
@@ -55,6 +86,16 @@ pub(crate) struct PipelineDetails {
     /// The [`Epoch`] of the latest display list received for this `Pipeline` or `None` if no
     /// display list has been received.
     pub display_list_epoch: Option<Epoch>,
+
+    /// The size in bytes of the serialized latest display list of this `Pipeline`.
+    pub display_list_size_in_bytes: usize,
+
+    /// The number of display items in the latest display list of this `Pipeline`.
+    pub display_list_item_count: usize,
+
+    /// The latest display list of this `Pipeline` or `None` if no display list has been
+    /// received.
+    pub display_list: Option<DisplayListData>,
 }
 
 impl PipelineDetails {
@@ -80,8 +121,12 @@ impl PipelineDetails {
             first_paint_metric: Cell::new(PaintMetricState::Waiting),
             first_contentful_paint_metric: Cell::new(PaintMetricState::Waiting),
             largest_contentful_paint_metric: Cell::new(PaintMetricState::Waiting),
+            paint_timing: Default::default(),
             exited: PipelineExitSource::empty(),
             display_list_epoch: None,
+            display_list_size_in_bytes: 0,
+            display_list_item_count: 0,
+            display_list: None,
         }
     }
 
@@ -0,0 +1,92 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use std::time::{Duration, Instant};
+
+use base::id::{PainterId, WebViewId};
+use embedder_traits::InputEvent;
+use serde::{Deserialize, Serialize};
+
+/// What happened in a [`RecordedPaintEvent`].
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum RecordedPaintEventKind {
+    /// `Paint` handled a message with the given name. Only the name is recorded, because
+    /// messages carry channels and shared memory that cannot outlive the session.
+    Message(String),
+    /// The embedder sent an input event to a `WebView`.
+    InputEvent(InputEvent),
+    /// WebRender finished building a new frame for a `Painter`.
+    FrameReady {
+        /// Whether the new frame needs to be repainted.
+        repaint_needed: bool,
+    },
+}
+
+/// An event recorded by `Paint` while recording was enabled. See
+/// `Paint::start_recording`.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct RecordedPaintEvent {
+    /// The time at which the event happened, relative to the start of the recording.
+    pub time: Duration,
+    /// The [`PainterId`] of the renderer that the event was addressed to, if any.
+    pub painter_id: Option<PainterId>,
+    /// The [`WebViewId`] of the `WebView` that the event was about, if any.
+    pub webview_id: Option<WebViewId>,
+    /// What happened.
+    pub kind: RecordedPaintEventKind,
+}
+
+/// A recording of the messages, input events and new frames handled by `Paint`, in the order
+/// in which they happened. Recordings can be serialized, in order to save them and replay
+/// their input events in another session to reproduce timing-dependent issues.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct PaintRecording {
+    pub events: Vec<RecordedPaintEvent>,
+}
+
+impl PaintRecording {
+    /// The recorded input events, in order, along with the time at which they were sent
+    /// and the `WebView` that they were sent to.
+    pub fn input_events(&self) -> impl Iterator<Item = (Duration, WebViewId, &InputEvent)> {
+        self.events.iter().filter_map(|event| match &event.kind {
+            RecordedPaintEventKind::InputEvent(input_event) => {
+                Some((event.time, event.webview_id?, input_event))
+            },
+            _ => None,
+        })
+    }
+}
+
+/// Records the events handled by `Paint` into a [`PaintRecording`].
+pub(crate) struct PaintRecorder {
+    start: Instant,
+    recording: PaintRecording,
+}
+
+impl PaintRecorder {
+    pub(crate) fn new() -> Self {
+        Self {
+            start: Instant::now(),
+            recording: Default::default(),
+        }
+    }
+
+    pub(crate) fn record(
+        &mut self,
+        painter_id: Option<PainterId>,
+        webview_id: Option<WebViewId>,
+        kind: RecordedPaintEventKind,
+    ) {
+        self.recording.events.push(RecordedPaintEvent {
+            time: self.start.elapsed(),
+            painter_id,
+            webview_id,
+            kind,
+        });
+    }
+
+    pub(crate) fn finish(self) -> PaintRecording {
+        self.recording
+    }
+}
@@ -7,13 +7,14 @@ use std::rc::Rc;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::thread::{self, JoinHandle};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use constellation_traits::EmbedderToConstellationMessage;
 use crossbeam_channel::{Sender, select};
 use embedder_traits::{EventLoopWaker, RefreshDriver};
 use log::warn;
 use timers::{BoxedTimerCallback, TimerEventRequest, TimerScheduler};
+use webrender_api::RenderReasons;
 
 use crate::painter::Painter;
 use crate::webview_renderer::WebViewRenderer;
@@ -154,6 +155,12 @@ impl AnimationRefreshDriverObserver {
 
 impl RefreshDriverObserver for AnimationRefreshDriverObserver {
     fn frame_started(&self, painter: &mut Painter) -> bool {
+        // Throttled painters do not tick their animations, for instance while occluded, but
+        // keep observing so that ticks resume once they are no longer throttled.
+        if painter.throttled() {
+            return true;
+        }
+
         // If any WebViews are animating ask them to paint again for another animation tick.
         let animating_webviews = painter.animating_webviews();
 
@@ -164,12 +171,18 @@ impl RefreshDriverObserver for AnimationRefreshDriverObserver {
             return false;
         }
 
-        // Request new animation frames from all animating WebViews.
-        if let Err(error) =
-            self.constellation_sender
-                .send(EmbedderToConstellationMessage::TickAnimation(
-                    animating_webviews,
-                ))
+        // Request new animation frames from all animating WebViews whose frame rate cap
+        // allows another frame. When none of them is due, request a frame anyway, so that this
+        // observer is notified of the next frame, at which point one may be.
+        let due_webviews = painter.take_animating_webviews_due_for_tick(Instant::now());
+        if due_webviews.is_empty() {
+            painter.generate_frame(RenderReasons::ANIMATED_PROPERTY);
+            self.animating.set(true);
+            return true;
+        }
+        if let Err(error) = self
+            .constellation_sender
+            .send(EmbedderToConstellationMessage::TickAnimation(due_webviews))
         {
             warn!("Sending tick to constellation failed ({error:?}).");
             return false;
@@ -2,21 +2,47 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
 
+use std::sync::Arc;
+
 use base::id::PainterId;
 use compositing_traits::{PaintMessage, PaintProxy};
+use parking_lot::Mutex;
+use rustc_hash::FxHashMap;
 use webrender_api::{DocumentId, FramePublishId, FrameReadyParams};
 
+/// The WebRender frames of a single document that are ready, but have not yet been handled by
+/// `Paint`.
+#[derive(Clone, Copy, Default)]
+pub(crate) struct ReadyFrames {
+    /// The number of frames that are ready.
+    pub count: usize,
+    /// Whether any of the frames that are ready needs to be composited.
+    pub repaint_needed: bool,
+}
+
+/// The [`ReadyFrames`] of each WebRender document of a `Painter`, shared between its
+/// [`RenderNotifier`] and the `Painter`. At most one [`PaintMessage::NewWebRenderFrameReady`]
+/// is in flight for each document and all frames that become ready before it is handled are
+/// coalesced into it, so that animations do not flood the `Paint` channel.
+pub(crate) type SharedReadyFrames = Arc<Mutex<FxHashMap<DocumentId, ReadyFrames>>>;
+
 #[derive(Clone)]
 pub(crate) struct RenderNotifier {
     painter_id: PainterId,
     paint_proxy: PaintProxy,
+    ready_frames: SharedReadyFrames,
 }
 
 impl RenderNotifier {
-    pub(crate) fn new(painter_id: PainterId, paint_proxy: PaintProxy) -> RenderNotifier {
+    pub(crate) fn new(
+        painter_id: PainterId,
+        paint_proxy: PaintProxy,
+        ready_frames: SharedReadyFrames,
+    ) -> RenderNotifier {
         RenderNotifier {
             painter_id,
             paint_proxy,
+            ready_frames,
         }
     }
 }
@@ -26,10 +52,18 @@ impl webrender_api::RenderNotifier for RenderNotifier {
         Box::new(RenderNotifier::new(
             self.painter_id,
             self.paint_proxy.clone(),
+            self.ready_frames.clone(),
         ))
     }
 
-    fn wake_up(&self, _composite_needed: bool) {}
+    fn wake_up(&self, composite_needed: bool) {
+        // Sending a message also wakes up the embedder's event loop using its
+        // `EventLoopWaker`, so that the message is handled without waiting for other events.
+        self.paint_proxy.send(PaintMessage::WebRenderWakeUp(
+            self.painter_id,
+            composite_needed,
+        ));
+    }
 
     fn new_frame_ready(
         &self,
@@ -37,10 +71,20 @@ impl webrender_api::RenderNotifier for RenderNotifier {
         _: FramePublishId,
         frame_ready_params: &FrameReadyParams,
     ) {
+        let message_in_flight = {
+            let mut ready_frames = self.ready_frames.lock();
+            let ready_frames = ready_frames.entry(document_id).or_default();
+            ready_frames.count += 1;
+            ready_frames.repaint_needed |= frame_ready_params.render;
+            ready_frames.count > 1
+        };
+        if message_in_flight {
+            return;
+        }
+
         self.paint_proxy.send(PaintMessage::NewWebRenderFrameReady(
             self.painter_id,
             document_id,
-            frame_ready_params.render,
         ));
     }
 }
@@ -6,13 +6,14 @@ use std::cell::RefCell;
 use std::rc::Rc;
 
 use base::Epoch;
+use base::generic_channel::GenericSharedMemory;
 use base::id::{PipelineId, WebViewId};
 use embedder_traits::ScreenshotCaptureError;
 use euclid::{Point2D, Size2D};
 use image::RgbaImage;
 use log::error;
 use rustc_hash::FxHashMap;
-use webrender_api::units::{DeviceIntRect, DeviceRect};
+use webrender_api::units::{DeviceIntRect, DeviceIntSize, DeviceRect};
 
 use crate::paint::RepaintReason;
 use crate::painter::Painter;
@@ -20,7 +21,7 @@ use crate::painter::Painter;
 pub(crate) struct ScreenshotRequest {
     webview_id: WebViewId,
     rect: Option<DeviceRect>,
-    callback: Box<dyn FnOnce(Result<RgbaImage, ScreenshotCaptureError>) + 'static>,
+    callback: ScreenshotCallback,
     phase: ScreenshotRequestPhase,
 }
 
@@ -47,12 +48,46 @@ pub(crate) enum ScreenshotRequestPhase {
     /// sent to WebRender.
     WaitingOnPipelineDisplayLists(Rc<FxHashMap<PipelineId, Epoch>>),
     /// Once the renderer has received all of the Pipeline display lists necessary to take
-    /// the screenshot and uploaded them to WebRender, it waits for an appropriate frame to
-    /// be ready. Currently this just waits for the [`FrameDelayer`] to stop delaying frames
-    /// and for there to be no pending WebRender frames (ones sent to WebRender that are not
-    /// ready yet). Once that happens, and a potential extra repaint is triggered, the renderer
-    /// will take the screenshot and fufill the request.
-    WaitingOnFrame,
+    /// the screenshot and uploaded them to WebRender, it waits for the first frame that
+    /// contains them to be ready. The request is tagged with that frame once the
+    /// [`FrameDelayer`] has stopped delaying frames. Frames requested after it do not delay
+    /// the screenshot, so that several screenshots can be in flight at once, even while the
+    /// page is animating. Once the frame is ready, and a potential extra repaint is
+    /// triggered, the renderer will take the screenshot and fufill the request.
+    WaitingOnFrame(u64),
+}
+
+type ScreenshotCallback = Box<dyn FnOnce(Result<RgbaImage, ScreenshotCaptureError>) + 'static>;
+
+pub(crate) type SharedMemoryCaptureCallback =
+    Box<dyn FnOnce(Result<SharedMemoryCapture, ScreenshotCaptureError>) + 'static>;
+
+/// A capture of a `RenderingContext` read straight into shared memory, so that it can be
+/// handed to another thread or process, such as a content process or the WebDriver server,
+/// without being copied again. See [`crate::Paint::capture_rendering_context_to_shared_memory`].
+#[derive(Clone)]
+pub struct SharedMemoryCapture {
+    /// The size of the capture in device pixels.
+    pub size: DeviceIntSize,
+    /// The captured pixels in RGBA format, row by row starting at the top-left.
+    pub pixels: GenericSharedMemory,
+}
+
+/// A pending capture of the entire `RenderingContext`, along with the form in which the
+/// captured pixels should be delivered.
+enum RenderingContextCaptureRequest {
+    Image(ScreenshotCallback),
+    SharedMemory(SharedMemoryCaptureCallback),
+}
+
+/// A request to capture a single `WebView` on its own, without any of the other content of
+/// its `RenderingContext`. See [`Painter::request_webview_capture`].
+pub(crate) struct WebViewCaptureRequest {
+    webview_id: WebViewId,
+    /// The rectangle to capture relative to the origin of the `WebView` or `None` to capture
+    /// the entire `WebView`.
+    pub(crate) rect: Option<DeviceRect>,
+    pub(crate) callback: ScreenshotCallback,
 }
 
 #[derive(Default)]
@@ -61,6 +96,13 @@ pub(crate) struct ScreenshotTaker {
     /// pages have finished loading all content and the rendering reflects the finished
     /// state. See [`ScreenshotRequestPhase`] for more information.
     requests: RefCell<Vec<ScreenshotRequest>>,
+    /// A vector of pending captures of the entire `RenderingContext`. Unlike
+    /// [`ScreenshotRequest`]s, these do not wait for pages to finish loading and are
+    /// resolved after the next paint that happens when there are no pending frames.
+    rendering_context_requests: RefCell<Vec<RenderingContextCaptureRequest>>,
+    /// A vector of pending captures of single `WebView`s. These are resolved one `WebView`
+    /// at a time, once a frame that contains only that `WebView` is ready.
+    webview_capture_requests: RefCell<Vec<WebViewCaptureRequest>>,
 }
 
 impl ScreenshotTaker {
@@ -68,7 +110,7 @@ impl ScreenshotTaker {
         &self,
         webview_id: WebViewId,
         rect: Option<DeviceRect>,
-        callback: Box<dyn FnOnce(Result<RgbaImage, ScreenshotCaptureError>) + 'static>,
+        callback: ScreenshotCallback,
     ) {
         self.requests.borrow_mut().push(ScreenshotRequest {
             webview_id,
@@ -78,6 +120,85 @@ impl ScreenshotTaker {
         });
     }
 
+    /// Fail all pending screenshot requests for the given `WebView`, because it was removed
+    /// and they would otherwise never be resolved.
+    pub(crate) fn remove_webview(&self, webview_id: WebViewId) {
+        let (removed_requests, requests): (Vec<_>, Vec<_>) = self
+            .requests
+            .take()
+            .into_iter()
+            .partition(|screenshot_request| screenshot_request.webview_id == webview_id);
+        *self.requests.borrow_mut() = requests;
+
+        for screenshot_request in removed_requests {
+            (screenshot_request.callback)(Err(ScreenshotCaptureError::WebViewDoesNotExist));
+        }
+
+        for capture_request in self.take_webview_capture_requests(webview_id) {
+            (capture_request.callback)(Err(ScreenshotCaptureError::WebViewDoesNotExist));
+        }
+    }
+
+    pub(crate) fn request_webview_capture(
+        &self,
+        webview_id: WebViewId,
+        rect: Option<DeviceRect>,
+        callback: ScreenshotCallback,
+    ) {
+        self.webview_capture_requests
+            .borrow_mut()
+            .push(WebViewCaptureRequest {
+                webview_id,
+                rect,
+                callback,
+            });
+    }
+
+    /// The `WebView` that should be captured next, if there are any pending captures of
+    /// single `WebView`s.
+    pub(crate) fn next_webview_to_capture(&self) -> Option<WebViewId> {
+        self.webview_capture_requests
+            .borrow()
+            .first()
+            .map(|capture_request| capture_request.webview_id)
+    }
+
+    /// Remove and return all pending capture requests of the given `WebView`.
+    pub(crate) fn take_webview_capture_requests(
+        &self,
+        webview_id: WebViewId,
+    ) -> Vec<WebViewCaptureRequest> {
+        let (taken_requests, requests) = self
+            .webview_capture_requests
+            .take()
+            .into_iter()
+            .partition(|capture_request| capture_request.webview_id == webview_id);
+        *self.webview_capture_requests.borrow_mut() = requests;
+        taken_requests
+    }
+
+    pub(crate) fn request_rendering_context_capture(
+        &self,
+        callback: ScreenshotCallback,
+        renderer: &Painter,
+    ) {
+        self.rendering_context_requests
+            .borrow_mut()
+            .push(RenderingContextCaptureRequest::Image(callback));
+        renderer.set_needs_repaint(RepaintReason::ReadyForScreenshot);
+    }
+
+    pub(crate) fn request_rendering_context_capture_to_shared_memory(
+        &self,
+        callback: SharedMemoryCaptureCallback,
+        renderer: &Painter,
+    ) {
+        self.rendering_context_requests
+            .borrow_mut()
+            .push(RenderingContextCaptureRequest::SharedMemory(callback));
+        renderer.set_needs_repaint(RepaintReason::ReadyForScreenshot);
+    }
+
     pub(crate) fn handle_screenshot_readiness_reply(
         &self,
         webview_id: WebViewId,
@@ -116,6 +237,9 @@ impl ScreenshotTaker {
                 continue;
             };
 
+            let Some(frame) = renderer.latest_requested_frame() else {
+                continue;
+            };
             if pipelines.iter().all(|(pipeline_id, expected_epoch)| {
                 webview
                     .pipelines
@@ -123,37 +247,39 @@ impl ScreenshotTaker {
                     .and_then(|pipeline| pipeline.display_list_epoch)
                     .is_some_and(|epoch| epoch >= *expected_epoch)
             }) {
-                screenshot_request.phase = ScreenshotRequestPhase::WaitingOnFrame;
+                screenshot_request.phase = ScreenshotRequestPhase::WaitingOnFrame(frame);
                 any_became_ready = true;
             }
         }
 
-        // If there are now screenshots waiting on a frame, and there are no pending frames,
-        // immediately trigger a repaint so that screenshots can be taken when the repaint
-        // is done.
+        // If there are now screenshots waiting on a frame that is already ready, immediately
+        // trigger a repaint so that screenshots can be taken when the repaint is done.
         if any_became_ready {
             self.maybe_trigger_paint_for_screenshot(renderer);
         }
     }
 
     pub(crate) fn maybe_trigger_paint_for_screenshot(&self, renderer: &Painter) {
-        if renderer.has_pending_frames() {
-            return;
+        if self
+            .requests
+            .borrow()
+            .iter()
+            .any(|screenshot_request| Self::frame_ready_for_request(screenshot_request, renderer))
+        {
+            renderer.set_needs_repaint(RepaintReason::ReadyForScreenshot);
         }
+    }
 
-        if self.requests.borrow().iter().any(|screenshot_request| {
-            matches!(
-                screenshot_request.phase,
-                ScreenshotRequestPhase::WaitingOnFrame
-            )
-        }) {
-            renderer.set_needs_repaint(RepaintReason::ReadyForScreenshot);
+    fn frame_ready_for_request(screenshot_request: &ScreenshotRequest, renderer: &Painter) -> bool {
+        match screenshot_request.phase {
+            ScreenshotRequestPhase::WaitingOnFrame(frame) => renderer.frame_is_ready(frame),
+            _ => false,
         }
     }
 
     pub(crate) fn maybe_take_screenshots(&self, renderer: &Painter) {
-        if renderer.has_pending_frames() {
-            return;
+        if !renderer.has_pending_frames() {
+            self.take_rendering_context_captures(renderer);
         }
 
         let mut requests = self.requests.borrow_mut();
@@ -166,10 +292,7 @@ impl ScreenshotTaker {
         let screenshots = requests.drain(..);
         *requests = screenshots
             .filter_map(|screenshot_request| {
-                if !matches!(
-                    screenshot_request.phase,
-                    ScreenshotRequestPhase::WaitingOnFrame
-                ) {
+                if !Self::frame_ready_for_request(&screenshot_request, renderer) {
                     return Some(screenshot_request);
                 }
 
@@ -181,22 +304,24 @@ impl ScreenshotTaker {
                     return None;
                 };
 
-                let viewport_rect = webview_renderer.rect.to_i32();
-                let viewport_size = viewport_rect.size();
-                let rect = screenshot_request.rect.map_or(viewport_rect, |rect| {
-                    // We need to convert to the bottom-left origin coordinate
-                    // system used by OpenGL
-                    // If dpi > 1, y can be computed to be -1 due to rounding issue, resulting in panic.
-                    // https://github.com/servo/servo/issues/39306#issuecomment-3342204869
-                    let x = rect.min.x as i32;
-                    let y = 0.max(
-                        (viewport_size.height as f32 - rect.min.y - rect.size().height) as i32,
-                    );
-                    let w = rect.size().width as i32;
-                    let h = rect.size().height as i32;
-
-                    DeviceIntRect::from_origin_and_size(Point2D::new(x, y), Size2D::new(w, h))
+                // The requested rectangle is relative to the `WebView`, which may be positioned
+                // anywhere within the `RenderingContext`.
+                let webview_rect = webview_renderer.rect;
+                let rect = screenshot_request.rect.map_or(webview_rect, |rect| {
+                    rect.translate(webview_rect.min.to_vector())
                 });
+
+                // We need to convert to the bottom-left origin coordinate
+                // system used by OpenGL
+                // If dpi > 1, y can be computed to be -1 due to rounding issue, resulting in panic.
+                // https://github.com/servo/servo/issues/39306#issuecomment-3342204869
+                let rendering_context_height = renderer.rendering_context.size2d().height as f32;
+                let x = rect.min.x as i32;
+                let y = 0.max((rendering_context_height - rect.min.y - rect.size().height) as i32);
+                let w = rect.size().width as i32;
+                let h = rect.size().height as i32;
+                let rect =
+                    DeviceIntRect::from_origin_and_size(Point2D::new(x, y), Size2D::new(w, h));
                 if let Err(error) = renderer.rendering_context.make_current() {
                     error!("Failed to make the rendering context current: {error:?}");
                 }
@@ -209,4 +334,29 @@ impl ScreenshotTaker {
             })
             .collect();
     }
+
+    fn take_rendering_context_captures(&self, renderer: &Painter) {
+        let requests = std::mem::take(&mut *self.rendering_context_requests.borrow_mut());
+        if requests.is_empty() {
+            return;
+        }
+
+        // Images are only read back once, no matter how many of them were requested.
+        let mut image = None;
+        for request in requests {
+            match request {
+                RenderingContextCaptureRequest::Image(callback) => callback(
+                    image
+                        .get_or_insert_with(|| renderer.read_pixels())
+                        .clone()
+                        .ok_or(ScreenshotCaptureError::CouldNotReadImage),
+                ),
+                RenderingContextCaptureRequest::SharedMemory(callback) => callback(
+                    renderer
+                        .read_pixels_to_shared_memory()
+                        .ok_or(ScreenshotCaptureError::CouldNotReadImage),
+                ),
+            }
+        }
+    }
 }
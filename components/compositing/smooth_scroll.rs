@@ -0,0 +1,67 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use std::time::{Duration, Instant};
+
+use base::id::WebViewId;
+use webrender_api::units::LayoutVector2D;
+
+use crate::painter::Painter;
+use crate::refresh_driver::RefreshDriverObserver;
+
+/// An animated scroll of the viewport of a `WebView` towards a target scroll offset. The
+/// animation is advanced on every frame of the [`Painter`] of the `WebView`, independently of
+/// script and layout, so that it stays smooth even when they are busy.
+pub(crate) struct SmoothScroll {
+    /// The scroll offset of the viewport when the animation started.
+    start_offset: LayoutVector2D,
+    /// The scroll offset of the viewport at the end of the animation.
+    target_offset: LayoutVector2D,
+    /// The time at which the animation started.
+    start_time: Instant,
+    /// The duration of the animation.
+    duration: Duration,
+}
+
+impl SmoothScroll {
+    pub(crate) fn new(
+        start_offset: LayoutVector2D,
+        target_offset: LayoutVector2D,
+        duration: Duration,
+    ) -> Self {
+        Self {
+            start_offset,
+            target_offset,
+            start_time: Instant::now(),
+            duration,
+        }
+    }
+
+    /// The scroll offset of the viewport at the given time, along with whether or not the
+    /// animation has finished. The offset follows an ease-out curve, so that the scroll
+    /// decelerates towards the target.
+    pub(crate) fn offset_at(&self, now: Instant) -> (LayoutVector2D, bool) {
+        let elapsed = now.saturating_duration_since(self.start_time);
+        if elapsed >= self.duration {
+            return (self.target_offset, true);
+        }
+
+        let progress = elapsed.as_secs_f32() / self.duration.as_secs_f32();
+        let eased_progress = 1.0 - (1.0 - progress).powi(3);
+        (
+            self.start_offset.lerp(self.target_offset, eased_progress),
+            false,
+        )
+    }
+}
+
+pub(crate) struct SmoothScrollRefreshDriverObserver {
+    pub webview_id: WebViewId,
+}
+
+impl RefreshDriverObserver for SmoothScrollRefreshDriverObserver {
+    fn frame_started(&self, painter: &mut Painter) -> bool {
+        painter.update_smooth_scroll_at_new_frame_start(self.webview_id)
+    }
+}
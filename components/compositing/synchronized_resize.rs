@@ -0,0 +1,17 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use crate::painter::Painter;
+use crate::refresh_driver::RefreshDriverObserver;
+
+/// Keeps frames coming while the frames of a [`Painter`] are held back after a resize, so that
+/// they are shown again once the timeout of the resize has passed, even if the content of its
+/// `WebView`s never catches up with the new size.
+pub(crate) struct SynchronizedResizeRefreshDriverObserver;
+
+impl RefreshDriverObserver for SynchronizedResizeRefreshDriverObserver {
+    fn frame_started(&self, painter: &mut Painter) -> bool {
+        painter.update_synchronized_resize_at_new_frame_start()
+    }
+}
@@ -10,6 +10,7 @@ use embedder_traits::{InputEventId, PaintHitTestResult, Scroll, TouchEventType,
 use euclid::{Point2D, Scale, Vector2D};
 use log::{debug, error, warn};
 use rustc_hash::FxHashMap;
+use servo_config::pref;
 use style_traits::CSSPixel;
 use webrender_api::units::{DevicePixel, DevicePoint, DeviceVector2D};
 
@@ -45,12 +46,27 @@ impl TouchSequenceId {
 // constants adjusted accordingly.
 /// Minimum number of `DeviceIndependentPixel` to begin touch scrolling.
 const TOUCH_PAN_MIN_SCREEN_PX: f32 = 20.0;
-/// Factor by which the flinging velocity changes on each tick.
-const FLING_SCALING_FACTOR: f32 = 0.95;
-/// Minimum velocity required for transitioning to fling when panning ends.
-const FLING_MIN_SCREEN_PX: f32 = 3.0;
-/// Maximum velocity when flinging.
-const FLING_MAX_SCREEN_PX: f32 = 4000.0;
+
+/// The physics of touch flings, which are configured via the `touch_fling_*` preferences.
+#[derive(Clone, Copy, Debug)]
+struct FlingParameters {
+    /// Factor by which the flinging velocity changes on each tick.
+    deceleration_factor: f32,
+    /// Minimum velocity required for transitioning to fling when panning ends.
+    min_velocity: f32,
+    /// Maximum velocity when flinging.
+    max_velocity: f32,
+}
+
+impl FlingParameters {
+    fn from_prefs() -> Self {
+        Self {
+            deceleration_factor: pref!(touch_fling_deceleration_factor) as f32,
+            min_velocity: pref!(touch_fling_min_velocity) as f32,
+            max_velocity: pref!(touch_fling_max_velocity) as f32,
+        }
+    }
+}
 
 pub struct TouchHandler {
     /// The [`WebViewId`] of the `WebView` this [`TouchHandler`] is associated with.
@@ -63,6 +79,10 @@ pub struct TouchHandler {
     pub(crate) pending_touch_input_events: RefCell<FxHashMap<InputEventId, PendingTouchInputEvent>>,
     /// Whether or not the [`FlingRefreshDriverObserver`] is currently observing frames for fling.
     observing_frames_for_fling: Cell<bool>,
+    /// The [`FlingParameters`] of the current touch sequence. These are read from the
+    /// preferences at the start of every touch sequence, so that changes apply to the next
+    /// gesture without affecting an ongoing fling.
+    fling_parameters: FlingParameters,
 }
 
 /// Whether the default move action is allowed or not.
@@ -229,6 +249,7 @@ impl TouchHandler {
             touch_sequence_map,
             pending_touch_input_events: Default::default(),
             observing_frames_for_fling: Default::default(),
+            fling_parameters: FlingParameters::from_prefs(),
         }
     }
 
@@ -337,6 +358,7 @@ impl TouchHandler {
                 .is_finished()
         {
             self.current_sequence_id.next();
+            self.fling_parameters = FlingParameters::from_prefs();
             debug!("Entered new touch sequence: {:?}", self.current_sequence_id);
             let active_touch_points = vec![TouchPoint::new(id, point)];
             self.touch_sequence_map.insert(
@@ -371,6 +393,7 @@ impl TouchHandler {
     }
 
     pub fn notify_new_frame_start(&mut self) -> Option<FlingAction> {
+        let fling_parameters = self.fling_parameters;
         let touch_sequence = self.touch_sequence_map.get_mut(&self.current_sequence_id)?;
 
         let Flinging {
@@ -382,19 +405,19 @@ impl TouchHandler {
             return None;
         };
 
-        if velocity.length().abs() < FLING_MIN_SCREEN_PX {
+        if velocity.length().abs() < fling_parameters.min_velocity {
             self.stop_fling_if_needed();
             None
         } else {
             // TODO: Probably we should multiply with the current refresh rate (and divide on each frame)
             // or save a timestamp to account for a potentially changing display refresh rate.
-            *velocity *= FLING_SCALING_FACTOR;
+            *velocity *= fling_parameters.deceleration_factor;
             let _span = profile_traits::info_span!(
                 "TouchHandler::Flinging",
                 velocity = ?velocity,
             )
             .entered();
-            debug_assert!(velocity.length() <= FLING_MAX_SCREEN_PX);
+            debug_assert!(velocity.length() <= fling_parameters.max_velocity);
             Some(FlingAction {
                 delta: DeviceVector2D::new(velocity.x, velocity.y),
                 cursor: *cursor,
@@ -521,6 +544,7 @@ impl TouchHandler {
     }
 
     pub fn on_touch_up(&mut self, id: TouchId, point: Point2D<f32, DevicePixel>) {
+        let fling_parameters = self.fling_parameters;
         let Some(touch_sequence) = self.try_get_current_touch_sequence_mut() else {
             warn!("Current touch sequence not found");
             return;
@@ -545,7 +569,7 @@ impl TouchHandler {
                 }
             },
             Panning { velocity } => {
-                if velocity.length().abs() >= FLING_MIN_SCREEN_PX {
+                if velocity.length().abs() >= fling_parameters.min_velocity {
                     let _span = profile_traits::info_span!(
                         "TouchHandler::FlingStart",
                         velocity = ?velocity,
@@ -559,7 +583,7 @@ impl TouchHandler {
 
                     // Multiplying the initial velocity gives the fling a much more snappy feel
                     // and serves well as a poor-mans acceleration algorithm.
-                    let velocity = (velocity * 2.0).with_max_length(FLING_MAX_SCREEN_PX);
+                    let velocity = (velocity * 2.0).with_max_length(fling_parameters.max_velocity);
                     match touch_sequence.prevent_move {
                         TouchMoveAllowed::Allowed => {
                             touch_sequence.state = Flinging { velocity, point }
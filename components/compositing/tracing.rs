@@ -2,6 +2,15 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
 
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::fmt::{self, Display, Formatter};
+
+use base::cross_process_instant::CrossProcessInstant;
+use base::id::{PainterId, WebViewId};
+use compositing_traits::PaintMessage;
+use webrender_api::DocumentId;
+
 /// Log an event from constellation at trace level.
 /// - To disable tracing: RUST_LOG='paint<constellation@=off'
 /// - To enable tracing: RUST_LOG='paint<constellation@'
@@ -34,6 +43,7 @@ mod from_constellation {
                 Self::SetFrameTreeForWebView(..) => target!("SetFrameTreeForWebView"),
                 Self::SetThrottled(..) => target!("SetThrottled"),
                 Self::NewWebRenderFrameReady(..) => target!("NewWebRenderFrameReady"),
+                Self::WebRenderWakeUp(..) => target!("WebRenderWakeUp"),
                 Self::PipelineExited(..) => target!("PipelineExited"),
                 Self::SendInitialTransaction(..) => target!("SendInitialTransaction"),
                 Self::ScrollNodeByDelta(..) => target!("ScrollNodeByDelta"),
@@ -49,12 +59,148 @@ mod from_constellation {
                 Self::AddFontInstance(..) => target!("AddFontInstance"),
                 Self::RemoveFonts(..) => target!("RemoveFonts"),
                 Self::CollectMemoryReport(..) => target!("CollectMemoryReport"),
+                Self::CollectRendererStatistics(..) => target!("CollectRendererStatistics"),
                 Self::Viewport(..) => target!("Viewport"),
                 Self::GenerateImageKeysForPipeline(..) => target!("GenerateImageKeysForPipeline"),
                 Self::DelayNewFrameForCanvas(..) => target!("DelayFramesForCanvas"),
                 Self::ScreenshotReadinessReponse(..) => target!("ScreenshotReadinessResponse"),
                 Self::SendLCPCandidate(..) => target!("SendLCPCandidate"),
+                Self::SetOccluded(..) => target!("SetOccluded"),
             }
         }
     }
 }
+
+/// The number of most recent messages kept by a [`PaintMessageTrace`].
+const MESSAGE_TRACE_CAPACITY: usize = 1024;
+
+/// A message handled by `Paint` while message tracing was enabled, along with the ids of
+/// the renderer, WebRender document and `WebView` that it was addressed to. See
+/// `Paint::set_message_tracing_enabled`.
+#[derive(Clone, Debug)]
+pub struct PaintMessageTraceEntry {
+    /// When `Paint` started handling the message.
+    pub time: CrossProcessInstant,
+    /// The name of the message.
+    pub message: &'static str,
+    /// The [`PainterId`] of the renderer that the message was addressed to, if any.
+    pub painter_id: Option<PainterId>,
+    /// The WebRender [`DocumentId`] of that renderer, if it still existed.
+    pub document_id: Option<DocumentId>,
+    /// The [`WebViewId`] of the `WebView` that the message was about, if any.
+    pub webview_id: Option<WebViewId>,
+    /// Whether the message was dropped because its renderer no longer existed.
+    pub dropped: bool,
+}
+
+impl Display for PaintMessageTraceEntry {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "{:?} {} painter={:?} document={:?} webview={:?}",
+            self.time, self.message, self.painter_id, self.document_id, self.webview_id
+        )?;
+        if self.dropped {
+            write!(formatter, " (dropped)")?;
+        }
+        Ok(())
+    }
+}
+
+/// A ring buffer of the most recent messages handled by `Paint`, which can be enabled at
+/// runtime. Unlike logging every message, this costs almost nothing while enabled and the
+/// trace is still available when something goes wrong: it can be queried by the embedder
+/// and it is logged if `Paint` is dropped because of a panic.
+#[derive(Default)]
+pub(crate) struct PaintMessageTrace {
+    enabled: Cell<bool>,
+    entries: RefCell<VecDeque<PaintMessageTraceEntry>>,
+}
+
+impl PaintMessageTrace {
+    pub(crate) fn enabled(&self) -> bool {
+        self.enabled.get()
+    }
+
+    /// Enable or disable recording messages. Disabling the trace clears it.
+    pub(crate) fn set_enabled(&self, enabled: bool) {
+        self.enabled.set(enabled);
+        if !enabled {
+            self.entries.borrow_mut().clear();
+        }
+    }
+
+    pub(crate) fn record(&self, entry: PaintMessageTraceEntry) {
+        if !self.enabled.get() {
+            return;
+        }
+        let mut entries = self.entries.borrow_mut();
+        if entries.len() >= MESSAGE_TRACE_CAPACITY {
+            entries.pop_front();
+        }
+        entries.push_back(entry);
+    }
+
+    /// Mark the most recently recorded message as dropped.
+    pub(crate) fn note_last_message_dropped(&self) {
+        if let Some(entry) = self.entries.borrow_mut().back_mut() {
+            entry.dropped = true;
+        }
+    }
+
+    pub(crate) fn entries(&self) -> Vec<PaintMessageTraceEntry> {
+        self.entries.borrow().iter().cloned().collect()
+    }
+}
+
+impl Drop for PaintMessageTrace {
+    fn drop(&mut self) {
+        if !std::thread::panicking() || self.entries.get_mut().is_empty() {
+            return;
+        }
+        log::error!("Most recent messages handled by Paint before the panic:");
+        for entry in self.entries.get_mut().iter() {
+            log::error!("    {entry}");
+        }
+    }
+}
+
+/// Get the renderer and `WebView` that a message is addressed to.
+pub(crate) trait MessageTarget {
+    fn painter_and_webview_ids(&self) -> (Option<PainterId>, Option<WebViewId>);
+}
+
+impl MessageTarget for PaintMessage {
+    fn painter_and_webview_ids(&self) -> (Option<PainterId>, Option<WebViewId>) {
+        let webview_id = match self {
+            Self::ChangeRunningAnimationsState(webview_id, ..) |
+            Self::SetFrameTreeForWebView(webview_id, ..) |
+            Self::SetThrottled(webview_id, ..) |
+            Self::PipelineExited(webview_id, ..) |
+            Self::SendInitialTransaction(webview_id, ..) |
+            Self::ScrollNodeByDelta(webview_id, ..) |
+            Self::ScrollViewportByDelta(webview_id, ..) |
+            Self::UpdateEpoch { webview_id, .. } |
+            Self::SendDisplayList { webview_id, .. } |
+            Self::GenerateImageKey(webview_id, ..) |
+            Self::GenerateImageKeysForPipeline(webview_id, ..) |
+            Self::DelayNewFrameForCanvas(webview_id, ..) |
+            Self::Viewport(webview_id, ..) |
+            Self::ScreenshotReadinessReponse(webview_id, ..) |
+            Self::SendLCPCandidate(_, webview_id, ..) => *webview_id,
+            Self::NewWebRenderFrameReady(painter_id, ..) |
+            Self::WebRenderWakeUp(painter_id, ..) |
+            Self::UpdateImages(painter_id, ..) |
+            Self::GenerateFontKeys(_, _, _, painter_id) |
+            Self::AddFont(painter_id, ..) |
+            Self::AddSystemFont(painter_id, ..) |
+            Self::AddFontInstance(painter_id, ..) |
+            Self::RemoveFonts(painter_id, ..) |
+            Self::CollectRendererStatistics(painter_id, ..) |
+            Self::SetOccluded(painter_id, ..) => return (Some(*painter_id), None),
+            Self::GenerateFrame(painter_ids) => return (painter_ids.first().copied(), None),
+            Self::CollectMemoryReport(..) => return (None, None),
+        };
+        (Some(webview_id.into()), Some(webview_id))
+    }
+}
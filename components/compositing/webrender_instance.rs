@@ -0,0 +1,207 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use std::rc::Rc;
+use std::sync::Arc;
+
+use base::id::PainterId;
+use canvas_traits::webgl::{WebGLContextId, WebGLThreads};
+use compositing_traits::rendering_context::RenderingContext;
+use compositing_traits::{
+    PaintProxy, WebRenderExternalImageHandlers, WebRenderExternalImageIdManager,
+    WebRenderImageHandlerType,
+};
+use gleam::gl::RENDERER;
+use log::{info, warn};
+use media::WindowGLContext;
+use servo_config::{opts, pref};
+use surfman::Device;
+use surfman::chains::SwapChains;
+use webgl::webgl_thread::WebGLContextBusyMap;
+#[cfg(feature = "webgpu")]
+use webgpu::canvas_context::WebGpuExternalImageMap;
+use webrender::{
+    CompositorConfig, ONE_TIME_USAGE_HINT, RenderApi, ShaderPrecacheFlags, UploadMethod,
+};
+use webrender_api::{ColorF, DocumentId, ImageKey};
+
+use crate::Paint;
+use crate::paint::{PainterOptions, ShaderPrecachePolicy};
+use crate::render_notifier::{RenderNotifier, SharedReadyFrames};
+use crate::webrender_external_images::WebGLExternalImages;
+
+/// A WebRender instance of a `Painter`, rendering to a single [`RenderingContext`].
+pub(crate) struct WebRenderInstance {
+    /// The GL bindings of the [`RenderingContext`] that WebRender renders to.
+    pub gl: Rc<dyn gleam::gl::Gl>,
+    /// The WebRender renderer.
+    pub renderer: webrender::Renderer,
+    /// The [`RenderApi`] used to communicate with WebRender.
+    pub api: RenderApi,
+    /// The WebRender document that is rendered.
+    pub document: DocumentId,
+}
+
+/// Everything needed to create the WebRender instance of a `Painter`. This is taken from
+/// [`Paint`] when the `Painter` is created, so that the `Painter` can replace its WebRender
+/// instance later on without involving [`Paint`], for instance after its renderer failed.
+pub(crate) struct WebRenderInstanceFactory {
+    options: PainterOptions,
+    paint_proxy: PaintProxy,
+    webgl_threads: WebGLThreads,
+    swap_chains: SwapChains<WebGLContextId, Device>,
+    busy_webgl_contexts_map: WebGLContextBusyMap,
+    #[cfg(feature = "webgpu")]
+    webgpu_image_map: WebGpuExternalImageMap,
+    external_image_id_manager: WebRenderExternalImageIdManager,
+    workers: Arc<rayon::ThreadPool>,
+}
+
+impl WebRenderInstanceFactory {
+    pub(crate) fn new(paint: &Paint, options: &PainterOptions) -> Self {
+        Self {
+            options: options.clone(),
+            paint_proxy: paint.paint_proxy.clone(),
+            webgl_threads: paint.webgl_threads(),
+            swap_chains: paint.swap_chains.clone(),
+            busy_webgl_contexts_map: paint.busy_webgl_contexts_map.clone(),
+            #[cfg(feature = "webgpu")]
+            webgpu_image_map: paint.webgpu_image_map(),
+            external_image_id_manager: paint.webrender_external_image_id_manager(),
+            workers: paint.webrender_workers(),
+        }
+    }
+
+    /// Create a new WebRender instance for the `Painter` with the given [`PainterId`] that
+    /// renders to `rendering_context`. When this replaces an earlier instance of the same
+    /// `Painter`, `last_image_key` is a key generated by that instance. Keys of both instances
+    /// share the namespace of the `Painter`, so the new instance only hands out keys with
+    /// higher ids, as keys of the earlier instance may still be in use.
+    pub(crate) fn create_instance(
+        &self,
+        painter_id: PainterId,
+        rendering_context: &Rc<dyn RenderingContext>,
+        ready_frames: SharedReadyFrames,
+        clear_color: ColorF,
+        last_image_key: Option<ImageKey>,
+    ) -> WebRenderInstance {
+        let gl = rendering_context.gleam_gl_api();
+
+        // Make sure the gl context is made current.
+        if let Err(err) = rendering_context.make_current() {
+            warn!("Failed to make the rendering context current: {:?}", err);
+        }
+        debug_assert_eq!(gl.get_error(), gleam::gl::NO_ERROR,);
+
+        let mut external_image_handlers = Box::new(WebRenderExternalImageHandlers::new(
+            self.external_image_id_manager.clone(),
+        ));
+
+        // Set WebRender external image handler for WebGL textures.
+        let image_handler = Box::new(WebGLExternalImages::new(
+            self.webgl_threads.clone(),
+            rendering_context.clone(),
+            self.swap_chains.clone(),
+            self.busy_webgl_contexts_map.clone(),
+        ));
+        external_image_handlers.set_handler(image_handler, WebRenderImageHandlerType::WebGl);
+
+        #[cfg(feature = "webgpu")]
+        external_image_handlers.set_handler(
+            Box::new(webgpu::WebGpuExternalImages::new(
+                self.webgpu_image_map.clone(),
+            )),
+            WebRenderImageHandlerType::WebGpu,
+        );
+
+        WindowGLContext::initialize_image_handler(&mut external_image_handlers);
+
+        rendering_context.prepare_for_rendering();
+
+        // Use same texture upload method as Gecko with ANGLE:
+        // https://searchfox.org/mozilla-central/source/gfx/webrender_bindings/src/bindings.rs#1215-1219
+        let upload_method = if self
+            .options
+            .immediate_texture_upload
+            .unwrap_or_else(|| gl.get_string(RENDERER).starts_with("ANGLE"))
+        {
+            UploadMethod::Immediate
+        } else {
+            UploadMethod::PixelBuffer(ONE_TIME_USAGE_HINT)
+        };
+
+        let (mut renderer, api_sender) = webrender::create_webrender_instance(
+            gl.clone(),
+            Box::new(RenderNotifier::new(
+                painter_id,
+                self.paint_proxy.clone(),
+                ready_frames,
+            )),
+            webrender::WebRenderOptions {
+                // We force the use of optimized shaders here because rendering is broken
+                // on Android emulators with unoptimized shaders. This is due to a known
+                // issue in the emulator's OpenGL emulation layer.
+                // See: https://github.com/servo/servo/issues/31726
+                use_optimized_shaders: self.options.use_optimized_shaders.unwrap_or(true),
+                panic_on_gl_error: self.options.panic_on_gl_error.unwrap_or_default(),
+                resource_override_path: opts::get().shaders_path.clone(),
+                debug_flags: webrender::DebugFlags::empty(),
+                precache_flags: match self.options.shader_precache_policy.unwrap_or_else(|| {
+                    if pref!(gfx_precache_shaders) {
+                        ShaderPrecachePolicy::Full
+                    } else {
+                        ShaderPrecachePolicy::Lazy
+                    }
+                }) {
+                    ShaderPrecachePolicy::Lazy => ShaderPrecacheFlags::empty(),
+                    ShaderPrecachePolicy::Async => ShaderPrecacheFlags::ASYNC_COMPILE,
+                    ShaderPrecachePolicy::Full => ShaderPrecacheFlags::FULL_COMPILE,
+                },
+                enable_aa: pref!(gfx_text_antialiasing_enabled),
+                enable_subpixel_aa: pref!(gfx_subpixel_text_antialiasing_enabled),
+                allow_texture_swizzling: pref!(gfx_texture_swizzling_enabled),
+                clear_color,
+                upload_method,
+                workers: Some(self.workers.clone()),
+                // Only track damage rectangles when the `RenderingContext` can make use of them.
+                compositor_config: CompositorConfig::Draw {
+                    max_partial_present_rects: if rendering_context.buffer_age().is_some() {
+                        1
+                    } else {
+                        0
+                    },
+                    draw_previous_partial_present_regions: false,
+                    partial_present: None,
+                },
+                size_of_op: Some(servo_allocator::usable_size),
+                // This ensures that we can use the `PainterId` as the `IdNamespace`, which allows mapping
+                // from `FontKey`, `FontInstanceKey`, and `ImageKey` back to `PainterId`.
+                namespace_alloc_by_client: true,
+                shared_font_namespace: Some(painter_id.into()),
+                ..Default::default()
+            },
+            None,
+        )
+        .expect("Unable to initialize WebRender.");
+
+        renderer.set_external_image_handler(external_image_handlers);
+
+        let api = api_sender.create_api_by_client(painter_id.into());
+        if let Some(last_image_key) = last_image_key {
+            while api.generate_image_key().1 <= last_image_key.1 {}
+        }
+        let document = api.add_document(rendering_context.size2d().to_i32());
+
+        let gl_renderer = gl.get_string(gleam::gl::RENDERER);
+        let gl_version = gl.get_string(gleam::gl::VERSION);
+        info!("Running on {gl_renderer} with OpenGL version {gl_version}");
+
+        WebRenderInstance {
+            gl,
+            renderer,
+            api,
+            document,
+        }
+    }
+}
@@ -5,6 +5,7 @@
 use std::cell::Cell;
 use std::collections::hash_map::Entry;
 use std::rc::Rc;
+use std::time::{Duration, Instant};
 
 use base::id::{PipelineId, WebViewId};
 use compositing_traits::display_list::ScrollType;
@@ -20,7 +21,7 @@ use embedder_traits::{
     ScrollEvent as EmbedderScrollEvent, TouchEvent, TouchEventType, ViewportDetails, WebViewPoint,
     WheelEvent,
 };
-use euclid::{Scale, Vector2D};
+use euclid::{Scale, Transform2D, Vector2D};
 use log::{debug, warn};
 use malloc_size_of::MallocSizeOf;
 use rustc_hash::FxHashMap;
@@ -30,11 +31,13 @@ use webrender::RenderApi;
 use webrender_api::units::{DevicePixel, DevicePoint, DeviceRect, DeviceVector2D, LayoutVector2D};
 use webrender_api::{DocumentId, ExternalScrollId, ScrollLocation};
 
-use crate::paint::RepaintReason;
+use crate::page_zoom::{PAGE_ZOOM_REFLOW_DELAY, PageZoomRefreshDriverObserver};
+use crate::paint::{DisplayListStatistics, RepaintReason, WebViewClip, WebViewDecorations};
 use crate::painter::Painter;
 use crate::pinch_zoom::PinchZoom;
 use crate::pipeline_details::PipelineDetails;
 use crate::refresh_driver::BaseRefreshDriver;
+use crate::smooth_scroll::{SmoothScroll, SmoothScrollRefreshDriverObserver};
 use crate::touch::{PendingTouchInputEvent, TouchHandler, TouchMoveAllowed, TouchSequenceState};
 
 #[derive(Clone, Copy)]
@@ -88,6 +91,10 @@ pub(crate) struct WebViewRenderer {
     pub root_pipeline_id: Option<PipelineId>,
     /// The rectangle of the [`WebView`] in device pixels, which is the viewport.
     pub rect: DeviceRect,
+    /// Whether or not the embedder has explicitly positioned this [`WebView`] within its
+    /// `RenderingContext`. When this is `false`, the [`WebView`] always fills the entire
+    /// `RenderingContext`.
+    has_explicit_rect: bool,
     /// Tracks details about each active pipeline that `Paint` knows about.
     pub pipelines: FxHashMap<PipelineId, PipelineDetails>,
     /// Pending scroll/zoom events.
@@ -98,8 +105,24 @@ pub(crate) struct WebViewRenderer {
     pending_wheel_events: FxHashMap<InputEventId, WheelEvent>,
     /// Touch input state machine
     touch_handler: TouchHandler,
+    /// The ongoing smooth scroll of the viewport of this [`WebViewRenderer`], if any.
+    smooth_scroll: Option<SmoothScroll>,
+    /// Whether or not a [`SmoothScrollRefreshDriverObserver`] is currently registered for
+    /// this [`WebViewRenderer`].
+    observing_frames_for_smooth_scroll: bool,
+    /// The size in bytes and number of items of the display lists received for this
+    /// [`WebViewRenderer`] since it was last painted.
+    display_lists_since_last_paint: (usize, usize),
+    /// The size in bytes and number of items of the display lists received for this
+    /// [`WebViewRenderer`] before it was last painted.
+    display_lists_of_last_paint: (usize, usize),
     /// "Desktop-style" zoom that resizes the viewport to fit the window.
     pub page_zoom: Scale<f32, CSSPixel, DeviceIndependentPixel>,
+    /// When the page should be laid out again after a page zoom change made with
+    /// [`Self::set_page_zoom_asynchronously`], if it has not been yet. A
+    /// [`PageZoomRefreshDriverObserver`] is registered for this [`WebViewRenderer`] while
+    /// this is set.
+    page_zoom_reflow_deadline: Option<Instant>,
     /// "Mobile-style" zoom that does not reflow the page. When there is no [`PinchZoom`] a
     /// zoom factor of 1.0 is implied and the [`PinchZoom::transform`] will be the identity.
     pinch_zoom: PinchZoom,
@@ -108,6 +131,31 @@ pub(crate) struct WebViewRenderer {
     hidpi_scale_factor: Scale<f32, DeviceIndependentPixel, DevicePixel>,
     /// Whether or not this [`WebViewRenderer`] is hidden.
     hidden: bool,
+    /// The opacity used when compositing this [`WebViewRenderer`] into its
+    /// `RenderingContext`, in the range `0.0..=1.0`.
+    opacity: f32,
+    /// A 2D transformation applied to this [`WebViewRenderer`] when compositing it into its
+    /// `RenderingContext`. The transformation is relative to the origin of `rect`.
+    transform: Transform2D<f32, DevicePixel, DevicePixel>,
+    /// Whether or not the embedder has declared that the contents of this
+    /// [`WebViewRenderer`] are fully opaque, so that it hides everything painted below it.
+    opaque: bool,
+    /// Whether or not this [`WebViewRenderer`] floats above all other non-floating renderers
+    /// of its `Painter`, regardless of the painting order.
+    floating: bool,
+    /// The minimum time between two animation ticks of this [`WebViewRenderer`], if the
+    /// embedder has capped its frame rate.
+    min_frame_interval: Option<Duration>,
+    /// When the animations of this [`WebViewRenderer`] last ticked, if ever.
+    last_animation_tick: Option<Instant>,
+    /// An offset applied to this [`WebViewRenderer`] when compositing it while it is floating,
+    /// for instance because the user is dragging it.
+    floating_offset: DeviceVector2D,
+    /// A clip applied to this [`WebViewRenderer`] when compositing it into its
+    /// `RenderingContext`, if any.
+    clip: Option<WebViewClip>,
+    /// The decorations painted around this [`WebViewRenderer`] when compositing it.
+    decorations: WebViewDecorations,
     /// Whether or not this [`WebViewRenderer`] isn't throttled and has a pipeline with
     /// active animations or animation frame callbacks.
     animating: bool,
@@ -143,14 +191,29 @@ impl WebViewRenderer {
             webview: renderer_webview,
             root_pipeline_id: None,
             rect,
+            has_explicit_rect: false,
             pipelines: Default::default(),
             touch_handler: TouchHandler::new(webview_id),
+            smooth_scroll: None,
+            observing_frames_for_smooth_scroll: false,
+            display_lists_since_last_paint: (0, 0),
+            display_lists_of_last_paint: (0, 0),
             pending_scroll_zoom_events: Default::default(),
             pending_wheel_events: Default::default(),
             page_zoom: DEFAULT_PAGE_ZOOM,
+            page_zoom_reflow_deadline: None,
             pinch_zoom: PinchZoom::new(rect),
             hidpi_scale_factor: Scale::new(hidpi_scale_factor.0),
             hidden: false,
+            opacity: 1.0,
+            transform: Transform2D::identity(),
+            opaque: false,
+            floating: false,
+            min_frame_interval: None,
+            last_animation_tick: None,
+            floating_offset: DeviceVector2D::zero(),
+            clip: None,
+            decorations: Default::default(),
             animating: false,
             viewport_description: None,
             embedder_to_constellation_sender,
@@ -163,10 +226,28 @@ impl WebViewRenderer {
         Painter::hit_test_at_point_with_api_and_document(
             webrender_api,
             self.webrender_document,
-            point,
+            self.to_rendering_context_point(point),
         )
     }
 
+    /// Use the given WebRender document from now on, after the WebRender instance of the
+    /// `Painter` of this [`WebViewRenderer`] was recreated.
+    pub(crate) fn set_webrender_document(&mut self, webrender_document: DocumentId) {
+        self.webrender_document = webrender_document;
+    }
+
+    /// Convert a point relative to the origin of this [`WebViewRenderer`] to a point in the
+    /// coordinate space of its `RenderingContext`, taking into account the offset of a
+    /// floating renderer.
+    pub(crate) fn to_rendering_context_point(&self, point: DevicePoint) -> DevicePoint {
+        let point = point + self.rect.min.to_vector();
+        if self.floating {
+            point + self.floating_offset
+        } else {
+            point
+        }
+    }
+
     pub(crate) fn animation_callbacks_running(&self) -> bool {
         self.pipelines
             .values()
@@ -181,6 +262,12 @@ impl WebViewRenderer {
         self.hidden
     }
 
+    /// The rectangle set by the embedder for this [`WebViewRenderer`] or `None` if it fills
+    /// the entire `RenderingContext`.
+    pub(crate) fn explicit_rect(&self) -> Option<DeviceRect> {
+        self.has_explicit_rect.then_some(self.rect)
+    }
+
     /// Set whether this [`WebViewRenderer`] is in the hidden state or not. Return `true` if the
     /// value changed or `false` otherwise.
     pub(crate) fn set_hidden(&mut self, new_value: bool) -> bool {
@@ -198,6 +285,43 @@ impl WebViewRenderer {
             .or_insert_with(PipelineDetails::new)
     }
 
+    /// Note that a new display list of the given size was received for the given pipeline.
+    pub(crate) fn note_display_list_received(
+        &mut self,
+        pipeline_id: PipelineId,
+        size_in_bytes: usize,
+        item_count: usize,
+    ) {
+        let details = self.ensure_pipeline_details(pipeline_id);
+        details.display_list_size_in_bytes = size_in_bytes;
+        details.display_list_item_count = item_count;
+        self.display_lists_since_last_paint.0 += size_in_bytes;
+        self.display_lists_since_last_paint.1 += item_count;
+    }
+
+    /// Note that this [`WebViewRenderer`] was painted, starting a new frame for the purpose of
+    /// [`DisplayListStatistics`].
+    pub(crate) fn note_painted(&mut self) {
+        self.display_lists_of_last_paint = std::mem::take(&mut self.display_lists_since_last_paint);
+    }
+
+    pub(crate) fn display_list_statistics(&self) -> DisplayListStatistics {
+        DisplayListStatistics {
+            size_in_bytes: self
+                .pipelines
+                .values()
+                .map(|details| details.display_list_size_in_bytes)
+                .sum(),
+            item_count: self
+                .pipelines
+                .values()
+                .map(|details| details.display_list_item_count)
+                .sum(),
+            last_frame_size_in_bytes: self.display_lists_of_last_paint.0,
+            last_frame_item_count: self.display_lists_of_last_paint.1,
+        }
+    }
+
     pub(crate) fn pipeline_exited(&mut self, pipeline_id: PipelineId, source: PipelineExitSource) {
         let pipeline = self.pipelines.entry(pipeline_id);
         let Entry::Occupied(mut pipeline) = pipeline else {
@@ -669,6 +793,8 @@ impl WebViewRenderer {
     }
 
     pub(crate) fn notify_scroll_event(&mut self, scroll: Scroll, point: WebViewPoint) {
+        // Scrolling by the user takes over from any ongoing smooth scroll.
+        self.smooth_scroll = None;
         let point = point.as_device_point(self.device_pixels_per_page_pixel());
         self.on_scroll_window_event(scroll, point);
     }
@@ -899,6 +1025,95 @@ impl WebViewRenderer {
         (pinch_zoom_result, vec![scroll_result])
     }
 
+    /// Start smoothly scrolling the viewport of this [`WebViewRenderer`] to `target_offset`
+    /// over `duration`, replacing any ongoing smooth scroll. The scroll is advanced on every
+    /// frame by [`Self::update_smooth_scroll_at_new_frame_start`].
+    pub(crate) fn smooth_scroll_viewport_to(
+        &mut self,
+        target_offset: LayoutVector2D,
+        duration: Duration,
+        repaint_reason: &Cell<RepaintReason>,
+    ) {
+        let Some(root_pipeline_id) = self.root_pipeline_id else {
+            return;
+        };
+        let Some(start_offset) = self.pipelines.get(&root_pipeline_id).and_then(|pipeline| {
+            pipeline
+                .scroll_tree
+                .scroll_offset(ExternalScrollId(0, root_pipeline_id.into()))
+        }) else {
+            return;
+        };
+        self.smooth_scroll = Some(SmoothScroll::new(start_offset, target_offset, duration));
+
+        if self.observing_frames_for_smooth_scroll {
+            return;
+        }
+        self.refresh_driver
+            .add_observer(Rc::new(SmoothScrollRefreshDriverObserver {
+                webview_id: self.id,
+            }));
+        self.observing_frames_for_smooth_scroll = true;
+        repaint_reason.set(
+            repaint_reason
+                .get()
+                .union(RepaintReason::StartedSmoothScrolling),
+        );
+    }
+
+    /// Advance the ongoing smooth scroll during a `RefreshDriver`-based frame tick. Returns
+    /// whether or not we should continue observing frames, along with a [`ScrollResult`] if
+    /// the viewport was scrolled.
+    pub(crate) fn update_smooth_scroll_at_new_frame_start(
+        &mut self,
+    ) -> (bool, Option<ScrollResult>) {
+        let Some(smooth_scroll) = self.smooth_scroll.as_ref() else {
+            self.observing_frames_for_smooth_scroll = false;
+            return (false, None);
+        };
+
+        let (offset, finished) = smooth_scroll.offset_at(Instant::now());
+        if finished {
+            self.smooth_scroll = None;
+            self.observing_frames_for_smooth_scroll = false;
+        }
+
+        let Some(root_pipeline_id) = self.root_pipeline_id else {
+            return (!finished, None);
+        };
+        let Some(root_pipeline) = self.pipelines.get_mut(&root_pipeline_id) else {
+            return (!finished, None);
+        };
+        let external_scroll_id = ExternalScrollId(0, root_pipeline_id.into());
+        let Some(offset) = root_pipeline
+            .scroll_tree
+            .set_scroll_offset_for_node_with_external_scroll_id(
+                external_scroll_id,
+                offset,
+                ScrollType::InputEvents,
+            )
+        else {
+            return (!finished, None);
+        };
+
+        let hit_test_result = PaintHitTestResult {
+            pipeline_id: root_pipeline_id,
+            point_in_viewport: Default::default(),
+            external_scroll_id,
+        };
+        self.send_scroll_positions_to_layout_for_pipeline(root_pipeline_id);
+        self.dispatch_scroll_event(external_scroll_id, hit_test_result.clone());
+
+        (
+            !finished,
+            Some(ScrollResult {
+                hit_test_result,
+                external_scroll_id,
+                offset,
+            }),
+        )
+    }
+
     fn dispatch_scroll_event(
         &self,
         external_id: ExternalScrollId,
@@ -939,6 +1154,49 @@ impl WebViewRenderer {
         }
     }
 
+    /// Set the page zoom of this [`WebViewRenderer`] without laying out its page again right
+    /// away. Until the page zoom has stayed unchanged for [`PAGE_ZOOM_REFLOW_DELAY`], the latest
+    /// display list is scaled to the new page zoom instead, after which the page is laid
+    /// out at the final page zoom by [`Self::update_page_zoom_at_new_frame_start`]. Returns
+    /// `true` if the page zoom changed.
+    pub(crate) fn set_page_zoom_asynchronously(
+        &mut self,
+        new_page_zoom: Scale<f32, CSSPixel, DeviceIndependentPixel>,
+    ) -> bool {
+        let new_page_zoom = new_page_zoom.clamp(MIN_PAGE_ZOOM, MAX_PAGE_ZOOM);
+        if std::mem::replace(&mut self.page_zoom, new_page_zoom) == new_page_zoom {
+            return false;
+        }
+
+        let was_waiting_for_reflow = self
+            .page_zoom_reflow_deadline
+            .replace(Instant::now() + PAGE_ZOOM_REFLOW_DELAY)
+            .is_some();
+        if !was_waiting_for_reflow {
+            self.refresh_driver
+                .add_observer(Rc::new(PageZoomRefreshDriverObserver {
+                    webview_id: self.id,
+                }));
+        }
+        true
+    }
+
+    /// Lay out the page at the current page zoom during a `RefreshDriver`-based frame tick,
+    /// once it has settled after [`Self::set_page_zoom_asynchronously`]. Returns whether or
+    /// not we should continue observing frames.
+    pub(crate) fn update_page_zoom_at_new_frame_start(&mut self) -> bool {
+        let Some(deadline) = self.page_zoom_reflow_deadline else {
+            return false;
+        };
+        if Instant::now() < deadline {
+            return true;
+        }
+
+        self.page_zoom_reflow_deadline = None;
+        self.send_window_size_message();
+        false
+    }
+
     /// The scale to use when displaying this [`WebViewRenderer`] in WebRender
     /// including both viewport scale (page zoom and hidpi scale) as well as any
     /// pinch zoom applied. This is based on the latest display list received,
@@ -973,18 +1231,23 @@ impl WebViewRenderer {
             .push(ScrollZoomEvent::PinchZoom(magnification, center));
     }
 
-    fn send_window_size_message(&self) {
+    /// The [`ViewportDetails`] of the root pipeline of this renderer, given its current
+    /// rectangle and zoom.
+    pub(crate) fn viewport_details(&self) -> ViewportDetails {
         // The device pixel ratio used by the style system should include the scale from page pixels
         // to device pixels, but not including any pinch zoom.
         let device_pixel_ratio = self.device_pixels_per_page_pixel_not_including_pinch_zoom();
-        let initial_viewport = self.rect.size().to_f32() / device_pixel_ratio;
+        ViewportDetails {
+            hidpi_scale_factor: device_pixel_ratio,
+            size: self.rect.size().to_f32() / device_pixel_ratio,
+        }
+    }
+
+    fn send_window_size_message(&self) {
         let _ = self.embedder_to_constellation_sender.send(
             EmbedderToConstellationMessage::ChangeViewportDetails(
                 self.id,
-                ViewportDetails {
-                    hidpi_scale_factor: device_pixel_ratio,
-                    size: initial_viewport,
-                },
+                self.viewport_details(),
                 WindowSizeType::Resize,
             ),
         );
@@ -1004,6 +1267,185 @@ impl WebViewRenderer {
         true
     }
 
+    pub(crate) fn opacity(&self) -> f32 {
+        self.opacity
+    }
+
+    /// Set the opacity used when compositing this renderer, clamping it to the range
+    /// `0.0..=1.0`. `NaN` values are ignored. Returns `true` if the value actually changed.
+    pub(crate) fn set_opacity(&mut self, opacity: f32) -> bool {
+        if opacity.is_nan() {
+            return false;
+        }
+        let opacity = opacity.clamp(0.0, 1.0);
+        let old_opacity = std::mem::replace(&mut self.opacity, opacity);
+        old_opacity != self.opacity
+    }
+
+    /// Set the 2D transformation applied when compositing this renderer, returning `true` if
+    /// the value actually changed.
+    pub(crate) fn set_transform(
+        &mut self,
+        transform: Transform2D<f32, DevicePixel, DevicePixel>,
+    ) -> bool {
+        let old_transform = std::mem::replace(&mut self.transform, transform);
+        old_transform != self.transform
+    }
+
+    /// The 2D transformation applied when compositing this renderer into its
+    /// `RenderingContext`, expressed relative to the origin of the `RenderingContext`
+    /// rather than the origin of `rect`.
+    pub(crate) fn compositing_transform(&self) -> Transform2D<f32, DevicePixel, DevicePixel> {
+        let origin = self.rect.min.to_vector();
+        let transform = Transform2D::translation(-origin.x, -origin.y)
+            .then(&self.transform)
+            .then_translate(origin);
+        if self.floating {
+            transform.then_translate(self.floating_offset)
+        } else {
+            transform
+        }
+    }
+
+    pub(crate) fn floating(&self) -> bool {
+        self.floating
+    }
+
+    /// Set whether or not this renderer floats above all non-floating renderers, returning
+    /// `true` if the value actually changed.
+    pub(crate) fn set_floating(&mut self, floating: bool) -> bool {
+        std::mem::replace(&mut self.floating, floating) != floating
+    }
+
+    /// Set the offset applied when compositing this renderer while it is floating, returning
+    /// `true` if the value actually changed.
+    pub(crate) fn set_floating_offset(&mut self, offset: DeviceVector2D) -> bool {
+        std::mem::replace(&mut self.floating_offset, offset) != offset
+    }
+
+    /// Cap the rate at which the animations of this renderer tick, and thus the rate at which
+    /// it produces new display lists, to `max_frame_rate` frames per second. Pass `None` to
+    /// remove the cap.
+    pub(crate) fn set_max_frame_rate(&mut self, max_frame_rate: Option<f32>) {
+        self.min_frame_interval = max_frame_rate
+            .map(|max_frame_rate| Duration::from_secs_f32(1.0 / max_frame_rate.max(0.001)));
+    }
+
+    /// Whether the animations of this renderer are due for a tick at `now`, given its frame
+    /// rate cap. If they are, the tick is recorded.
+    pub(crate) fn take_animation_tick(&mut self, now: Instant) -> bool {
+        let due = match (self.min_frame_interval, self.last_animation_tick) {
+            (Some(min_frame_interval), Some(last_animation_tick)) => {
+                now.saturating_duration_since(last_animation_tick) >= min_frame_interval
+            },
+            _ => true,
+        };
+        if due {
+            self.last_animation_tick = Some(now);
+        }
+        due
+    }
+
+    /// Set whether or not the contents of this renderer are fully opaque, returning `true` if
+    /// the value actually changed.
+    pub(crate) fn set_opaque(&mut self, opaque: bool) -> bool {
+        std::mem::replace(&mut self.opaque, opaque) != opaque
+    }
+
+    pub(crate) fn clip(&self) -> Option<WebViewClip> {
+        self.clip
+    }
+
+    /// Set the clip applied when compositing this renderer, returning `true` if the value
+    /// actually changed.
+    pub(crate) fn set_clip(&mut self, clip: Option<WebViewClip>) -> bool {
+        std::mem::replace(&mut self.clip, clip) != clip
+    }
+
+    pub(crate) fn decorations(&self) -> &WebViewDecorations {
+        &self.decorations
+    }
+
+    /// Set the decorations painted around this renderer, returning `true` if the value
+    /// actually changed.
+    pub(crate) fn set_decorations(&mut self, decorations: WebViewDecorations) -> bool {
+        std::mem::replace(&mut self.decorations, decorations) != decorations
+    }
+
+    /// The part of `rect` that is not clipped away by the clip of this renderer, before
+    /// applying the compositing transformation.
+    pub(crate) fn clipped_rect(&self) -> DeviceRect {
+        let Some(clip) = self.clip else {
+            return self.rect;
+        };
+        self.rect
+            .intersection(&clip.rect.translate(self.rect.min.to_vector()))
+            .unwrap_or_default()
+    }
+
+    /// The area of the `RenderingContext` that this renderer is guaranteed to completely
+    /// cover when painted, if any. This is only the case for visible, opaque renderers that
+    /// are not rotated or skewed and whose clip, if any, does not have rounded corners.
+    pub(crate) fn opaque_rect(&self) -> Option<DeviceRect> {
+        if !self.opaque || self.hidden || self.opacity < 1.0 {
+            return None;
+        }
+        if self.clip.is_some_and(|clip| !clip.radii.is_zero()) {
+            return None;
+        }
+        let transform = self.compositing_transform();
+        if transform.m12 != 0.0 || transform.m21 != 0.0 {
+            return None;
+        }
+        Some(transform.outer_transformed_box(&self.clipped_rect()))
+    }
+
+    /// The area of the `RenderingContext` that this renderer may paint to.
+    pub(crate) fn painted_rect(&self) -> DeviceRect {
+        self.compositing_transform()
+            .outer_transformed_box(&self.clipped_rect())
+    }
+
+    /// The area of the `RenderingContext` that this renderer may paint to, including its
+    /// [`WebViewDecorations`]. These are painted outside of [`Self::clipped_rect`], before
+    /// applying the compositing transformation, just like the content of the renderer.
+    pub(crate) fn decorated_rect(&self) -> DeviceRect {
+        let clipped_rect = self.clipped_rect();
+        let border_width: f32 = [self.decorations.border, self.decorations.focus_ring]
+            .into_iter()
+            .flatten()
+            .map(|border| border.width)
+            .sum();
+        let mut decorated_rect = clipped_rect.inflate(border_width, border_width);
+        if let Some(shadow) = self.decorations.shadow {
+            // WebRender blurs up to three times the blur radius beyond the shadow's box.
+            let extent = shadow.spread_radius + 3.0 * shadow.blur_radius;
+            decorated_rect = decorated_rect.union(
+                &clipped_rect
+                    .translate(shadow.offset)
+                    .inflate(extent, extent),
+            );
+        }
+        self.compositing_transform()
+            .outer_transformed_box(&decorated_rect)
+    }
+
+    pub(crate) fn has_explicit_rect(&self) -> bool {
+        self.has_explicit_rect
+    }
+
+    /// Set the `rect` for this renderer explicitly, so that it is no longer automatically
+    /// sized to fill its `RenderingContext`, or pass `None` to restore that behavior with the
+    /// given `rendering_context_rect`. Returns `true` if the rectangle actually changed.
+    pub(crate) fn set_explicit_rect(
+        &mut self,
+        explicit_rect: Option<DeviceRect>,
+        rendering_context_rect: DeviceRect,
+    ) -> bool {
+        self.has_explicit_rect = explicit_rect.is_some();
+        self.set_rect(explicit_rect.unwrap_or(rendering_context_rect))
+    }
+
     /// Set the `rect` for this renderer, returning `true` if the value actually changed.
     pub(crate) fn set_rect(&mut self, new_rect: DeviceRect) -> bool {
         let old_rect = std::mem::replace(&mut self.rect, new_rect);
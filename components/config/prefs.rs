@@ -295,6 +295,13 @@ pub struct Preferences {
     /// default), then `rustls-platform-verifier` will be used, except on Android where
     /// `rust-webpki` is always used.
     pub network_use_webpki_roots: bool,
+    /// Whether `WebView`s draw overlay scrollbars for their viewport, which appear while
+    /// the viewport is scrolled and then fade out.
+    pub overlay_scrollbars_enabled: bool,
+    /// The width of overlay scrollbars, in device pixels.
+    pub overlay_scrollbars_width: f64,
+    /// The color of overlay scrollbars while they are fully visible.
+    pub overlay_scrollbars_color_rgba: [f64; 4],
     pub session_history_max_length: i64,
     /// The background color of shell's viewport. This will be used by OpenGL's `glClearColor`.
     pub shell_background_color_rgba: [f64; 4],
@@ -314,6 +321,14 @@ pub struct Preferences {
     pub threadpools_resource_workers_max: i64,
     /// Maximum number of workers for webrender
     pub threadpools_webrender_workers_max: i64,
+    /// The factor by which the velocity of a touch fling is multiplied on every frame.
+    /// Lower values make flings stop sooner.
+    pub touch_fling_deceleration_factor: f64,
+    /// The velocity, in device pixels per frame, above which a touch pan turns into a fling
+    /// when the finger is lifted and below which a fling stops.
+    pub touch_fling_min_velocity: f64,
+    /// The maximum velocity of a touch fling, in device pixels per frame.
+    pub touch_fling_max_velocity: f64,
     /// The user-agent to use for Servo. This can also be set via [`UserAgentPlatform`] in
     /// order to set the value to the default value for the given platform.
     pub user_agent: String,
@@ -483,6 +498,9 @@ impl Preferences {
             network_local_directory_listing_enabled: true,
             network_mime_sniff: false,
             network_use_webpki_roots: false,
+            overlay_scrollbars_enabled: false,
+            overlay_scrollbars_width: 6.0,
+            overlay_scrollbars_color_rgba: [0.0, 0.0, 0.0, 0.5],
             session_history_max_length: 20,
             shell_background_color_rgba: [1.0, 1.0, 1.0, 1.0],
             threadpools_async_runtime_workers_max: 6,
@@ -492,6 +510,9 @@ impl Preferences {
             threadpools_webstorage_workers_max: 4,
             threadpools_resource_workers_max: 4,
             threadpools_webrender_workers_max: 4,
+            touch_fling_deceleration_factor: 0.95,
+            touch_fling_min_velocity: 3.0,
+            touch_fling_max_velocity: 4000.0,
             webgl_testing_context_creation_error: false,
             user_agent: String::new(),
             viewport_meta_enabled: false,
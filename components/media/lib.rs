@@ -142,14 +142,20 @@ impl WindowGLContext {
         window_gl_context.api = api;
     }
 
+    /// Install the handler for GL accelerated video frames into the external image handlers of
+    /// a WebRender instance. This is called once for every WebRender instance. The first call
+    /// starts the GLPlayer thread and later calls share it, so that the decoded frames of a
+    /// video are handed to the WebRender instance that paints it as textures, without being
+    /// copied through the CPU.
     pub fn initialize_image_handler(external_image_handlers: &mut WebRenderExternalImageHandlers) {
         if !pref!(media_glvideo_enabled) {
             return;
         }
 
         let mut window_gl_context = WINDOW_GL_CONTEXT.lock().unwrap();
-        if window_gl_context.glplayer_thread_sender.is_some() {
-            warn!("Not going to initialize GL accelerated media playback more than once.");
+        if let Some(thread_sender) = window_gl_context.glplayer_thread_sender.as_ref() {
+            let image_handler = Box::new(GLPlayerExternalImages::new(thread_sender.clone()));
+            external_image_handlers.set_handler(image_handler, WebRenderImageHandlerType::Media);
             return;
         }
 
@@ -262,6 +262,13 @@ pub(crate) fn is_relevant_attribute(namespace: &Namespace, local_name: &LocalNam
     namespace == &ns!() || (namespace == &ns!(xlink) && local_name == &local_name!("href"))
 }
 
+/// A helper function to parse a legacy color attribute value, like `AttrValue::from_legacy_color`,
+/// but without converting the value to utf8 before parsing it.
+pub(crate) fn parse_legacy_color_attribute(value: DOMString) -> AttrValue {
+    let color = value.parse_legacy_color();
+    AttrValue::Color(value.into(), color)
+}
+
 /// A help function to check if an attribute is a boolean attribute.
 pub(crate) fn is_boolean_attribute(name: &str) -> bool {
     // The full list of attributes can be found in [1]. All attributes marked as "Boolean
@@ -9,7 +9,7 @@ use js::rust::HandleObject;
 use style::attr::AttrValue;
 use style::color::AbsoluteColor;
 
-use crate::dom::attr::Attr;
+use crate::dom::attr::{Attr, parse_legacy_color_attribute};
 use crate::dom::bindings::codegen::Bindings::HTMLBodyElementBinding::HTMLBodyElementMethods;
 use crate::dom::bindings::codegen::Bindings::WindowBinding::WindowMethods;
 use crate::dom::bindings::inheritance::Castable;
@@ -141,9 +141,7 @@ impl VirtualMethods for HTMLBodyElement {
 
     fn parse_plain_attribute(&self, name: &LocalName, value: DOMString) -> AttrValue {
         match *name {
-            local_name!("bgcolor") | local_name!("text") => {
-                AttrValue::from_legacy_color(value.into())
-            },
+            local_name!("bgcolor") | local_name!("text") => parse_legacy_color_attribute(value),
             _ => self
                 .super_type()
                 .unwrap()
@@ -14,7 +14,7 @@ use style::values::computed::font::{
 };
 use stylo_atoms::Atom;
 
-use crate::dom::attr::Attr;
+use crate::dom::attr::{Attr, parse_legacy_color_attribute};
 use crate::dom::bindings::codegen::Bindings::HTMLFontElementBinding::HTMLFontElementMethods;
 use crate::dom::bindings::inheritance::Castable;
 use crate::dom::bindings::root::{DomRoot, LayoutDom};
@@ -134,7 +134,7 @@ impl VirtualMethods for HTMLFontElement {
     fn parse_plain_attribute(&self, name: &LocalName, value: DOMString) -> AttrValue {
         match *name {
             local_name!("face") => AttrValue::from_atomic(value.into()),
-            local_name!("color") => AttrValue::from_legacy_color(value.into()),
+            local_name!("color") => parse_legacy_color_attribute(value),
             local_name!("size") => parse_size(&value),
             _ => self
                 .super_type()
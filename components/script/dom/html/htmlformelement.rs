@@ -1440,7 +1440,7 @@ pub(crate) struct FormDatum {
 
 impl FormDatum {
     pub(crate) fn replace_value(&self, charset: &str) -> String {
-        if self.name.to_ascii_lowercase() == "_charset_" && self.ty == "hidden" {
+        if self.name.eq_ignore_ascii_case("_charset_") && self.ty == "hidden" {
             return charset.to_string();
         }
 
@@ -14,7 +14,7 @@ use style::values::specified::border::BorderSideWidth;
 use style::values::specified::length::Size;
 use style::values::specified::{LengthPercentage, NoCalcLength};
 
-use crate::dom::attr::Attr;
+use crate::dom::attr::{Attr, parse_legacy_color_attribute};
 use crate::dom::bindings::codegen::Bindings::HTMLHRElementBinding::HTMLHRElementMethods;
 use crate::dom::bindings::inheritance::Castable;
 use crate::dom::bindings::root::{DomRoot, LayoutDom};
@@ -171,7 +171,7 @@ impl VirtualMethods for HTMLHRElement {
     fn parse_plain_attribute(&self, name: &LocalName, value: DOMString) -> AttrValue {
         match *name {
             local_name!("align") => AttrValue::from_dimension(value.into()),
-            local_name!("color") => AttrValue::from_legacy_color(value.into()),
+            local_name!("color") => parse_legacy_color_attribute(value),
             local_name!("width") => AttrValue::from_dimension(value.into()),
             _ => self
                 .super_type()
@@ -2269,7 +2269,7 @@ impl HTMLInputElement {
 
             // Step 5.10: it's a hidden field named _charset_
             InputType::Hidden => {
-                if name.to_ascii_lowercase() == "_charset_" {
+                if name.eq_ignore_ascii_case("_charset_") {
                     return vec![FormDatum {
                         ty: ty.clone(),
                         name,
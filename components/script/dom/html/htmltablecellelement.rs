@@ -8,7 +8,7 @@ use js::rust::HandleObject;
 use style::attr::{AttrValue, LengthOrPercentageOrAuto};
 use style::color::AbsoluteColor;
 
-use crate::dom::attr::Attr;
+use crate::dom::attr::{Attr, parse_legacy_color_attribute};
 use crate::dom::bindings::codegen::Bindings::HTMLTableCellElementBinding::HTMLTableCellElementMethods;
 use crate::dom::bindings::codegen::Bindings::NodeBinding::NodeMethods;
 use crate::dom::bindings::inheritance::Castable;
@@ -226,7 +226,7 @@ impl VirtualMethods for HTMLTableCellElement {
                 }
                 attr
             },
-            local_name!("bgcolor") => AttrValue::from_legacy_color(value.into()),
+            local_name!("bgcolor") => parse_legacy_color_attribute(value),
             local_name!("width") => AttrValue::from_nonzero_dimension(value.into()),
             local_name!("height") => AttrValue::from_nonzero_dimension(value.into()),
             _ => self
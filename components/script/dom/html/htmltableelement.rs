@@ -10,7 +10,7 @@ use js::rust::HandleObject;
 use style::attr::{AttrValue, LengthOrPercentageOrAuto, parse_unsigned_integer};
 use style::color::AbsoluteColor;
 
-use crate::dom::attr::Attr;
+use crate::dom::attr::{Attr, parse_legacy_color_attribute};
 use crate::dom::bindings::codegen::Bindings::HTMLCollectionBinding::HTMLCollectionMethods;
 use crate::dom::bindings::codegen::Bindings::HTMLTableElementBinding::HTMLTableElementMethods;
 use crate::dom::bindings::codegen::Bindings::NodeBinding::NodeMethods;
@@ -586,7 +586,7 @@ impl VirtualMethods for HTMLTableElement {
             local_name!("border") => AttrValue::from_u32(value.into(), 1),
             local_name!("width") => AttrValue::from_nonzero_dimension(value.into()),
             local_name!("height") => AttrValue::from_dimension(value.into()),
-            local_name!("bgcolor") => AttrValue::from_legacy_color(value.into()),
+            local_name!("bgcolor") => parse_legacy_color_attribute(value),
             _ => self
                 .super_type()
                 .unwrap()
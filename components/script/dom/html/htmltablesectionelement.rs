@@ -8,7 +8,7 @@ use js::rust::HandleObject;
 use style::attr::{AttrValue, LengthOrPercentageOrAuto};
 use style::color::AbsoluteColor;
 
-use crate::dom::attr::Attr;
+use crate::dom::attr::{Attr, parse_legacy_color_attribute};
 use crate::dom::bindings::codegen::Bindings::HTMLTableSectionElementBinding::HTMLTableSectionElementMethods;
 use crate::dom::bindings::codegen::Bindings::NodeBinding::NodeMethods;
 use crate::dom::bindings::error::{ErrorResult, Fallible};
@@ -151,7 +151,7 @@ impl VirtualMethods for HTMLTableSectionElement {
 
     fn parse_plain_attribute(&self, local_name: &LocalName, value: DOMString) -> AttrValue {
         match *local_name {
-            local_name!("bgcolor") => AttrValue::from_legacy_color(value.into()),
+            local_name!("bgcolor") => parse_legacy_color_attribute(value),
             local_name!("height") => AttrValue::from_dimension(value.into()),
             _ => self
                 .super_type()
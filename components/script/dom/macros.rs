@@ -355,10 +355,9 @@ macro_rules! make_legacy_color_setter(
         fn $attr(&self, value: DOMString) {
             use $crate::dom::bindings::inheritance::Castable;
             use $crate::dom::element::Element;
-            use style::attr::AttrValue;
             use $crate::script_runtime::CanGc;
             let element = self.upcast::<Element>();
-            let value = AttrValue::from_legacy_color(value.into());
+            let value = $crate::dom::attr::parse_legacy_color_attribute(value);
             element.set_attribute(&html5ever::local_name!($htmlname), value, CanGc::note())
         }
     );
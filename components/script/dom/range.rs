@@ -9,6 +9,7 @@ use std::iter;
 use dom_struct::dom_struct;
 use js::jsapi::JSTracer;
 use js::rust::HandleObject;
+use script_bindings::domstring::DOMStringBuilder;
 
 use crate::dom::abstractrange::{AbstractRange, BoundaryPoint, bp_position};
 use crate::dom::bindings::cell::DomRefCell;
@@ -1063,7 +1064,7 @@ impl RangeMethods<crate::DomTypeHolder> for Range {
         let end_node = self.end_container();
 
         // Step 1.
-        let mut s = DOMString::new();
+        let mut s = DOMStringBuilder::new();
 
         if let Some(text_node) = start_node.downcast::<Text>() {
             let char_data = text_node.upcast::<CharacterData>();
@@ -1076,14 +1077,13 @@ impl RangeMethods<crate::DomTypeHolder> for Range {
             }
 
             // Step 3.
-            s.push_str(
-                &char_data
+            s.push_domstring(
+                char_data
                     .SubstringData(
                         self.start_offset(),
                         char_data.Length() - self.start_offset(),
                     )
-                    .unwrap()
-                    .str(),
+                    .unwrap(),
             );
         }
 
@@ -1095,18 +1095,18 @@ impl RangeMethods<crate::DomTypeHolder> for Range {
 
         for child in iter {
             if self.contains(child.upcast()) {
-                s.push_str(&child.upcast::<CharacterData>().Data().str());
+                s.push_domstring(child.upcast::<CharacterData>().Data());
             }
         }
 
         // Step 5.
         if let Some(text_node) = end_node.downcast::<Text>() {
             let char_data = text_node.upcast::<CharacterData>();
-            s.push_str(&char_data.SubstringData(0, self.end_offset()).unwrap().str());
+            s.push_domstring(char_data.SubstringData(0, self.end_offset()).unwrap());
         }
 
         // Step 6.
-        s
+        s.build()
     }
 
     /// <https://html.spec.whatwg.org/multipage/#dom-range-createcontextualfragment>
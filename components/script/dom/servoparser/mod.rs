@@ -1909,9 +1909,9 @@ fn attach_declarative_shadow_inner(host: &Node, template: &Node, attributes: &[A
         .iter()
         .for_each(|attr: &ElementAttribute| match attr.name.local {
             local_name!("shadowrootmode") => {
-                if attr.value.str().eq_ignore_ascii_case("open") {
+                if attr.value.eq_ignore_ascii_case("open") {
                     shadow_root_mode = ShadowRootMode::Open;
-                } else if attr.value.str().eq_ignore_ascii_case("closed") {
+                } else if attr.value.eq_ignore_ascii_case("closed") {
                     shadow_root_mode = ShadowRootMode::Closed;
                 } else {
                     unreachable!("shadowrootmode value is not open nor closed");
@@ -0,0 +1,66 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use criterion::*;
+use script_bindings::domstring::DOMString;
+
+/// The attribute values of a typical element of an attribute-heavy page, such as a list of
+/// buttons in a web application. Most of them are short enough to be stored inline.
+const ATTRIBUTE_VALUES: [&str; 10] = [
+    "button",
+    "btn btn-primary",
+    "true",
+    "presentation",
+    "-1",
+    "item-1234",
+    "click",
+    "off",
+    "https://example.com/products/category/item-1234?ref=list",
+    "display: flex; align-items: center; justify-content: space-between;",
+];
+
+/// The attribute values of a page with `number_of_elements` such elements.
+fn create_page(number_of_elements: usize) -> Vec<&'static str> {
+    (0..number_of_elements)
+        .flat_map(|_| ATTRIBUTE_VALUES)
+        .collect()
+}
+
+fn bench(c: &mut Criterion) {
+    let page = create_page(10_000);
+
+    c.bench_function("domstring_from_attribute_values", |b| {
+        b.iter(|| {
+            page.iter()
+                .map(|value| DOMString::from(black_box(*value)))
+                .collect::<Vec<_>>()
+        })
+    });
+
+    // The same values, always stored on the heap, for comparison.
+    c.bench_function("domstring_from_attribute_values_on_heap", |b| {
+        b.iter(|| {
+            page.iter()
+                .map(|value| DOMString::from_string(String::from(black_box(*value))))
+                .collect::<Vec<_>>()
+        })
+    });
+
+    let strings: Vec<_> = page.iter().map(|value| DOMString::from(*value)).collect();
+    c.bench_function("domstring_clone_attribute_values", |b| {
+        b.iter(|| strings.iter().map(DOMString::clone).collect::<Vec<_>>())
+    });
+
+    c.bench_function("domstring_compare_attribute_values", |b| {
+        b.iter(|| {
+            strings
+                .iter()
+                .filter(|string| **string == black_box("true"))
+                .count()
+        })
+    });
+}
+
+criterion_group!(benches, bench);
+criterion_main!(benches);
@@ -9,28 +9,30 @@ use std::default::Default;
 use std::ops::Deref;
 use std::ptr::{self, NonNull};
 use std::str::{Chars, FromStr};
-use std::sync::LazyLock;
 use std::{fmt, slice, str};
 
 use base::text::{Utf8CodeUnitLength, Utf16CodeUnitLength};
 use html5ever::{LocalName, Namespace};
 use js::conversions::{ToJSValConvertible, jsstr_to_string};
 use js::gc::MutableHandleValue;
-use js::jsapi::{Heap, JS_GetLatin1StringCharsAndLength, JSContext, JSString};
+use js::jsapi::{
+    Heap, JS_GetLatin1StringCharsAndLength, JS_GetStringLength, JS_NewUCStringCopyN, JSContext,
+    JSString,
+};
 use js::jsval::StringValue;
 use js::rust::{Runtime, Trace};
 use malloc_size_of::MallocSizeOfOps;
 use num_traits::{ToPrimitive, Zero};
-use regex::Regex;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use smallvec::SmallVec;
 use style::Atom;
+use style::color::AbsoluteColor;
 use style::str::HTML_SPACE_CHARACTERS;
 
 use crate::script_runtime::JSContext as SafeJSContext;
 use crate::trace::RootedTraceableBox;
 
 const ASCII_END: u8 = 0x7E;
-const ASCII_CAPITAL_A: u8 = 0x41;
-const ASCII_CAPITAL_Z: u8 = 0x5A;
 const ASCII_LOWERCASE_A: u8 = 0x61;
 const ASCII_LOWERCASE_Z: u8 = 0x7A;
 const ASCII_TAB: u8 = 0x09;
@@ -58,24 +60,96 @@ unsafe fn get_latin1_string_bytes(
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 /// A type representing the underlying encoded bytes. Either Latin1 or Utf8.
 pub enum EncodedBytes<'a> {
     /// These bytes are Latin1 encoded.
     Latin1Bytes(&'a [u8]),
     /// This is a normal utf8 string given in bytes.
     Utf8Bytes(&'a [u8]),
+    /// These bytes are WTF-8 encoded, i.e., utf8 that also contains unpaired surrogates.
+    /// They are never valid utf8.
+    Wtf8Bytes(&'a [u8]),
+}
+
+/// What to do with unpaired surrogates when creating a [`DOMString`] from UTF-16 with
+/// [`DOMString::from_utf16`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UnpairedSurrogatePolicy {
+    /// Replace every unpaired surrogate with a U+FFFD replacement character, like the
+    /// conversion of JS Strings to `DOMString`s does.
+    ReplaceWithFFFD,
+    /// Keep unpaired surrogates by storing the string as WTF-8, so that they survive being
+    /// passed back to JavaScript. They are still replaced with U+FFFD as soon as the string
+    /// is used as a rust string, for example through [`DOMString::str`].
+    PreserveAsWTF8,
+}
+
+/// The number of utf8 bytes that can be stored in an [`InlineString`]. Together with its length,
+/// this makes it as large as a `String` on 64-bit platforms, so that inline strings do not make
+/// [`DOMStringType`] any larger. This is checked by the `domstring_size` test.
+const INLINE_CAPACITY: usize = 23;
+
+/// A short utf8 string that is stored inline instead of on the heap, because most strings in the
+/// DOM, like element names, event types and attribute values like "true", are very short.
+#[derive(Clone, Copy)]
+struct InlineString {
+    length: u8,
+    bytes: [u8; INLINE_CAPACITY],
+}
+
+impl InlineString {
+    /// Stores the given string inline, if it is short enough.
+    fn new(s: &str) -> Option<InlineString> {
+        if s.len() > INLINE_CAPACITY {
+            return None;
+        }
+        let mut bytes = [0; INLINE_CAPACITY];
+        bytes[..s.len()].copy_from_slice(s.as_bytes());
+        Some(InlineString {
+            length: s.len() as u8,
+            bytes,
+        })
+    }
+
+    fn as_bytes(&self) -> &[u8] {
+        &self.bytes[..self.length as usize]
+    }
+
+    fn as_str(&self) -> &str {
+        // Safety: The bytes were copied from a str in `new` and are only modified by
+        // `make_ascii_lowercase`, which keeps them valid utf8.
+        unsafe { str::from_utf8_unchecked(self.as_bytes()) }
+    }
+
+    fn make_ascii_lowercase(&mut self) {
+        self.bytes[..self.length as usize].make_ascii_lowercase()
+    }
 }
 
 enum DOMStringType {
     /// A simple rust string
     Rust(String),
+    /// A short rust string stored without a heap allocation.
+    Inline(InlineString),
+    /// An interned string, which shares its storage with all other interned strings with the
+    /// same contents. See [`DOMString::intern`].
+    Atom(Atom),
     /// A JS String stored in mozjs.
     JSString(RootedTraceableBox<Heap<*mut JSString>>),
-    #[cfg(test)]
-    /// This is used for testing of the bindings to give
-    /// a raw u8 Latin1 encoded string without having a js engine.
+    /// A JS String that has been converted to utf8, but not modified since. The JS String is
+    /// kept so that it can be handed back to JavaScript without creating a new one, and is
+    /// dropped as soon as the string is modified. The utf8 string is boxed, as it is never
+    /// modified in place, which keeps this variant as small as the others.
+    ConvertedJSString(Box<str>, RootedTraceableBox<Heap<*mut JSString>>),
+    /// A Latin1 encoded string owned by us. The chars of a JS String are copied into this
+    /// when they need to be modified without converting them to utf8. This is also used for
+    /// testing of the bindings to give a raw u8 Latin1 encoded string without having a js
+    /// engine.
     Latin1Vec(Vec<u8>),
+    /// A WTF-8 encoded string that contains at least one unpaired surrogate. See
+    /// [`UnpairedSurrogatePolicy::PreserveAsWTF8`].
+    Wtf8Vec(Vec<u8>),
 }
 
 impl DOMStringType {
@@ -83,11 +157,14 @@ impl DOMStringType {
     fn str(&self) -> &str {
         match self {
             DOMStringType::Rust(s) => s,
+            DOMStringType::Inline(s) => s.as_str(),
+            DOMStringType::Atom(atom) => atom,
+            DOMStringType::ConvertedJSString(s, _) => s,
             DOMStringType::JSString(_rooted_traceable_box) => {
                 panic!("Cannot do a string")
             },
-            #[cfg(test)]
             &DOMStringType::Latin1Vec(_) => panic!("Cannot do a string"),
+            &DOMStringType::Wtf8Vec(_) => panic!("Cannot do a string"),
         }
     }
 
@@ -98,11 +175,14 @@ impl DOMStringType {
     fn as_raw_bytes(&self) -> &[u8] {
         match self {
             DOMStringType::Rust(s) => s.as_bytes(),
+            DOMStringType::Inline(s) => s.as_bytes(),
+            DOMStringType::Atom(atom) => atom.as_bytes(),
+            DOMStringType::ConvertedJSString(s, _) => s.as_bytes(),
             DOMStringType::JSString(rooted_traceable_box) => unsafe {
                 get_latin1_string_bytes(rooted_traceable_box)
             },
-            #[cfg(test)]
             DOMStringType::Latin1Vec(items) => items,
+            DOMStringType::Wtf8Vec(items) => items,
         }
     }
 }
@@ -187,9 +267,14 @@ unsafe impl Trace for DOMStringType {
         unsafe {
             match self {
                 DOMStringType::Rust(_s) => {},
-                DOMStringType::JSString(rooted_traceable_box) => rooted_traceable_box.trace(tracer),
-                #[cfg(test)]
+                DOMStringType::Inline(_s) => {},
+                DOMStringType::Atom(_atom) => {},
+                DOMStringType::JSString(rooted_traceable_box) |
+                DOMStringType::ConvertedJSString(_, rooted_traceable_box) => {
+                    rooted_traceable_box.trace(tracer)
+                },
                 DOMStringType::Latin1Vec(_s) => {},
+                DOMStringType::Wtf8Vec(_s) => {},
             }
         }
     }
@@ -199,12 +284,40 @@ impl malloc_size_of::MallocSizeOf for DOMStringType {
     fn size_of(&self, ops: &mut MallocSizeOfOps) -> usize {
         match self {
             DOMStringType::Rust(s) => s.size_of(ops),
-            DOMStringType::JSString(_rooted_traceable_box) => {
-                // Managed by JS Engine
+            DOMStringType::Inline(_s) => 0,
+            DOMStringType::Atom(_atom) => {
+                // Shared with all other users of the atom
                 0
             },
-            #[cfg(test)]
+            DOMStringType::JSString(rooted_traceable_box) => {
+                // Clones of this string share the JS String, so it is only reported once.
+                if ops.have_seen_ptr(rooted_traceable_box.get() as *const JSString) {
+                    0
+                } else {
+                    js_string_size_of(rooted_traceable_box)
+                }
+            },
+            // The JS String is only kept to hand it back to JavaScript, so it is not reported.
+            DOMStringType::ConvertedJSString(s, _) => unsafe { ops.malloc_size_of(s.as_ptr()) },
             DOMStringType::Latin1Vec(s) => s.size_of(ops),
+            DOMStringType::Wtf8Vec(s) => s.size_of(ops),
+        }
+    }
+}
+
+/// The size of the characters of a JS String backing a [`DOMString`]. This is JS heap memory,
+/// which is shared with the JS engine and may be shared with other JS Strings, but it is
+/// attributed to the `DOMString`, so that the memory of the DOM is not under-reported.
+fn js_string_size_of(js_string: &RootedTraceableBox<Heap<*mut JSString>>) -> usize {
+    let js_string = js_string.get();
+    // Safety: The JS String is rooted by the `RootedTraceableBox`, so it is alive, and it is
+    // never null, as only successfully converted JS Strings are kept.
+    unsafe {
+        let length = JS_GetStringLength(js_string);
+        if js::jsapi::JS_DeprecatedStringHasLatin1Chars(js_string) {
+            length
+        } else {
+            length * 2
         }
     }
 }
@@ -213,12 +326,23 @@ impl std::fmt::Debug for DOMStringType {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             DOMStringType::Rust(s) => f.debug_struct("DOMString").field("rust_string", s).finish(),
+            DOMStringType::Inline(s) => f
+                .debug_struct("DOMString")
+                .field("inline_string", &s.as_str())
+                .finish(),
+            DOMStringType::Atom(atom) => f.debug_struct("DOMString").field("atom", atom).finish(),
             DOMStringType::JSString(_rooted_traceable_box) => f.debug_struct("DOMString").finish(),
-            #[cfg(test)]
+            DOMStringType::ConvertedJSString(s, _) => f
+                .debug_struct("DOMString")
+                .field("converted_js_string", s)
+                .finish(),
             DOMStringType::Latin1Vec(s) => f
                 .debug_struct("DOMString")
                 .field("latin1_string", s)
                 .finish(),
+            DOMStringType::Wtf8Vec(s) => {
+                f.debug_struct("DOMString").field("wtf8_string", s).finish()
+            },
         }
     }
 }
@@ -227,23 +351,111 @@ impl std::fmt::Debug for DOMStringType {
 /// A view of the underlying string. This is never converted to Utf8
 pub struct EncodedBytesView<'a>(Ref<'a, DOMStringType>);
 
+impl<'a> EncodedBytes<'a> {
+    /// Compares these bytes with `needle` encoded the same way, so that no conversion to utf8 is
+    /// needed. A needle with characters outside of latin1 can never match latin1 bytes. WTF-8
+    /// only differs from utf8 in the encoding of unpaired surrogates, which a utf8 needle can
+    /// never contain.
+    fn matches_encoded(self, needle: &str, matches: impl Fn(&[u8], &[u8]) -> bool) -> bool {
+        match self {
+            EncodedBytes::Utf8Bytes(s) | EncodedBytes::Wtf8Bytes(s) => {
+                matches(s, needle.as_bytes())
+            },
+            EncodedBytes::Latin1Bytes(items) => {
+                if needle.is_ascii() {
+                    matches(items, needle.as_bytes())
+                } else if tendril::encoding_rs::mem::is_str_latin1(needle) {
+                    matches(
+                        items,
+                        &tendril::encoding_rs::mem::encode_latin1_lossy(needle),
+                    )
+                } else {
+                    false
+                }
+            },
+        }
+    }
+
+    /// Whether these bytes are an ASCII case-insensitive match for `other`, comparing them
+    /// byte-wise in their own encoding.
+    pub fn eq_ignore_ascii_case(self, other: &str) -> bool {
+        self.matches_encoded(other, <[u8]>::eq_ignore_ascii_case)
+    }
+
+    /// Splits the bytes at [`HTML_SPACE_CHARACTERS`], skipping empty tokens. The tokens are
+    /// encoded like the bytes they come from. All HTML space characters are ASCII, which is
+    /// encoded the same way in every encoding, so this never needs to convert the bytes.
+    pub fn split_html_space_characters(self) -> impl Iterator<Item = EncodedBytes<'a>> {
+        let (bytes, variant): (&[u8], fn(&'a [u8]) -> EncodedBytes<'a>) = match self {
+            EncodedBytes::Latin1Bytes(items) => (items, EncodedBytes::Latin1Bytes),
+            EncodedBytes::Utf8Bytes(s) => (s, EncodedBytes::Utf8Bytes),
+            EncodedBytes::Wtf8Bytes(s) => (s, EncodedBytes::Wtf8Bytes),
+        };
+        bytes
+            .split(|byte| {
+                matches!(
+                    *byte,
+                    ASCII_TAB | ASCII_NEWLINE | ASCII_FORMFEED | ASCII_CR | ASCII_SPACE
+                )
+            })
+            .filter(|token| !token.is_empty())
+            .map(variant)
+    }
+
+    /// Maps every byte to the `char` with the same value. ASCII bytes map to themselves in
+    /// every encoding, and every other byte maps to a non-ASCII `char`, so this is enough for
+    /// parsers that only give meaning to ASCII characters.
+    fn ascii_chars(self) -> impl Iterator<Item = char> + 'a {
+        let bytes = match self {
+            EncodedBytes::Latin1Bytes(items) |
+            EncodedBytes::Utf8Bytes(items) |
+            EncodedBytes::Wtf8Bytes(items) => items,
+        };
+        bytes.iter().map(|byte| *byte as char)
+    }
+
+    /// The code points of these bytes, including unpaired surrogates. This is used to compare
+    /// strings in different encodings without converting them, as converting WTF-8 to utf8
+    /// would replace the unpaired surrogates.
+    fn code_points(self) -> impl Iterator<Item = u32> + 'a {
+        // Utf8 is a subset of WTF-8, so both are decoded the same way.
+        let (latin1, wtf8): (&[u8], &[u8]) = match self {
+            EncodedBytes::Latin1Bytes(items) => (items, &[]),
+            EncodedBytes::Utf8Bytes(s) | EncodedBytes::Wtf8Bytes(s) => (&[], s),
+        };
+        latin1
+            .iter()
+            .map(|byte| *byte as u32)
+            .chain(decode_wtf8(wtf8))
+    }
+}
+
 impl EncodedBytesView<'_> {
+    /// Splits the string at [`HTML_SPACE_CHARACTERS`], skipping empty tokens, without
+    /// converting it. See [`EncodedBytes::split_html_space_characters`].
+    pub fn split_html_space_characters(&self) -> impl Iterator<Item = EncodedBytes<'_>> {
+        self.encoded_bytes().split_html_space_characters()
+    }
+
     /// Get the bytes of the string in either latin1 or utf8 without costly conversion.
     pub fn encoded_bytes(&self) -> EncodedBytes<'_> {
         match *self.0 {
             DOMStringType::Rust(ref s) => EncodedBytes::Utf8Bytes(s.as_bytes()),
+            DOMStringType::Inline(ref s) => EncodedBytes::Utf8Bytes(s.as_bytes()),
+            DOMStringType::Atom(ref atom) => EncodedBytes::Utf8Bytes(atom.as_bytes()),
+            DOMStringType::ConvertedJSString(ref s, _) => EncodedBytes::Utf8Bytes(s.as_bytes()),
             DOMStringType::JSString(ref rooted_traceable_box) => {
                 EncodedBytes::Latin1Bytes(unsafe { get_latin1_string_bytes(rooted_traceable_box) })
             },
-            #[cfg(test)]
             DOMStringType::Latin1Vec(ref s) => EncodedBytes::Latin1Bytes(s),
+            DOMStringType::Wtf8Vec(ref s) => EncodedBytes::Wtf8Bytes(s),
         }
     }
 
     fn is_empty(&self) -> bool {
         match self.encoded_bytes() {
             EncodedBytes::Latin1Bytes(items) => items.is_empty(),
-            EncodedBytes::Utf8Bytes(s) => s.is_empty(),
+            EncodedBytes::Utf8Bytes(s) | EncodedBytes::Wtf8Bytes(s) => s.is_empty(),
         }
     }
 
@@ -253,7 +465,8 @@ impl EncodedBytesView<'_> {
                 .iter()
                 .map(|b| if *b <= ASCII_END { 1 } else { 2 })
                 .sum(),
-            EncodedBytes::Utf8Bytes(s) => s.len(),
+            // An unpaired surrogate takes three bytes, just like the U+FFFD replacing it in utf8.
+            EncodedBytes::Utf8Bytes(s) | EncodedBytes::Wtf8Bytes(s) => s.len(),
         }
     }
 }
@@ -287,24 +500,43 @@ impl EncodedBytesView<'_> {
 /// and in Servo.)
 ///
 /// This string class will keep either the Reference to the mozjs object alive
-/// or will have an internal rust string.
+/// or will have an internal rust string. An unmodified JS String is handed back to
+/// JavaScript without creating a new one. Latin1 JS Strings are kept until they are
+/// converted to a rust string, other JS Strings until the string is modified.
 /// We currently default to doing most of the string operation on the rust side.
 /// You should use `str()` to get the Rust string (represented by a `StringView`
 /// which you can deref to a string). You should assume that this conversion costs.
 /// You should assume that all the functions incur the conversion cost.
 ///
+/// As it can keep a JS String alive, a `DOMString` is neither `Send` nor `Sync`. Use
+/// [`DOMString::into_sendable`] to pass it to another thread.
+///
 #[repr(transparent)]
 #[derive(Debug, MallocSizeOf, JSTraceable)]
 pub struct DOMString(RefCell<DOMStringType>);
 
 impl Clone for DOMString {
+    /// Clones the string without converting it, so that Latin1 and WTF-8 contents, including
+    /// unpaired surrogates, are kept as they are. JS strings are immutable, so a clone of a
+    /// string that is backed by one shares it.
     fn clone(&self) -> Self {
-        self.make_rust();
-        if let DOMStringType::Rust(ref s) = *self.0.borrow() {
-            DOMString::from_string(s.to_owned())
-        } else {
-            unreachable!()
-        }
+        let inner = match *self.0.borrow() {
+            DOMStringType::Rust(ref string) => DOMStringType::Rust(string.clone()),
+            DOMStringType::Inline(inline_string) => DOMStringType::Inline(inline_string),
+            DOMStringType::Atom(ref atom) => DOMStringType::Atom(atom.clone()),
+            DOMStringType::JSString(ref js_string) => {
+                DOMStringType::JSString(RootedTraceableBox::from_box(Heap::boxed(js_string.get())))
+            },
+            DOMStringType::ConvertedJSString(ref s, ref js_string) => {
+                DOMStringType::ConvertedJSString(
+                    s.clone(),
+                    RootedTraceableBox::from_box(Heap::boxed(js_string.get())),
+                )
+            },
+            DOMStringType::Latin1Vec(ref bytes) => DOMStringType::Latin1Vec(bytes.clone()),
+            DOMStringType::Wtf8Vec(ref bytes) => DOMStringType::Wtf8Vec(bytes.clone()),
+        };
+        DOMString(RefCell::new(inner))
     }
 }
 
@@ -312,10 +544,109 @@ pub enum DOMStringErrorType {
     JSConversionError,
 }
 
+/// The contents of a [`DOMString`], converted to utf8 so that they no longer depend on the JS
+/// engine and can be sent to other threads. See [`DOMString::into_sendable`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SendableDOMString {
+    String(String),
+    Atom(Atom),
+}
+
+impl Deref for SendableDOMString {
+    type Target = str;
+    fn deref(&self) -> &str {
+        match self {
+            SendableDOMString::String(s) => s,
+            SendableDOMString::Atom(atom) => atom,
+        }
+    }
+}
+
+impl From<SendableDOMString> for String {
+    fn from(value: SendableDOMString) -> String {
+        match value {
+            SendableDOMString::String(s) => s,
+            SendableDOMString::Atom(atom) => String::from(&*atom),
+        }
+    }
+}
+
+impl From<SendableDOMString> for DOMString {
+    fn from(value: SendableDOMString) -> DOMString {
+        match value {
+            SendableDOMString::String(s) => DOMString::from_string(s),
+            SendableDOMString::Atom(atom) => DOMString::from(atom),
+        }
+    }
+}
+
+/// A segment of a [`DOMStringBuilder`].
+enum DOMStringSegment<'a> {
+    Str(&'a str),
+    Char(char),
+    DOMString(DOMString),
+}
+
+/// Builds a [`DOMString`] out of segments. The segments are only copied once the length of the
+/// result is known, so that it is allocated once instead of growing with every segment.
+/// `DOMString` segments are copied without converting them to utf8 first.
+#[derive(Default)]
+pub struct DOMStringBuilder<'a> {
+    segments: SmallVec<[DOMStringSegment<'a>; 8]>,
+    /// The length of the result in utf8 bytes.
+    len: usize,
+}
+
+impl<'a> DOMStringBuilder<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push_str(&mut self, s: &'a str) {
+        self.len += s.len();
+        self.segments.push(DOMStringSegment::Str(s));
+    }
+
+    pub fn push(&mut self, c: char) {
+        self.len += c.len_utf8();
+        self.segments.push(DOMStringSegment::Char(c));
+    }
+
+    pub fn push_domstring(&mut self, s: DOMString) {
+        self.len += s.len();
+        self.segments.push(DOMStringSegment::DOMString(s));
+    }
+
+    pub fn build(self) -> DOMString {
+        let mut string = String::with_capacity(self.len);
+        for segment in self.segments {
+            match segment {
+                DOMStringSegment::Str(s) => string.push_str(s),
+                DOMStringSegment::Char(c) => string.push(c),
+                DOMStringSegment::DOMString(s) => match s.view().encoded_bytes() {
+                    EncodedBytes::Latin1Bytes(items) => {
+                        string.extend(items.iter().map(|byte| *byte as char))
+                    },
+                    // Save because we know it was a utf8 string
+                    EncodedBytes::Utf8Bytes(s) => {
+                        string.push_str(unsafe { str::from_utf8_unchecked(s) })
+                    },
+                    EncodedBytes::Wtf8Bytes(s) => string.push_str(&wtf8_to_string_lossy(s)),
+                },
+            }
+        }
+        DOMString::from_string(string)
+    }
+}
+
 impl DOMString {
     /// Creates a new `DOMString`.
     pub fn new() -> DOMString {
-        DOMString(RefCell::new(DOMStringType::Rust(String::new())))
+        DOMString::from_inner(DOMStringType::Rust(String::new()))
+    }
+
+    fn from_inner(inner: DOMStringType) -> DOMString {
+        DOMString(RefCell::new(inner))
     }
 
     /// Creates the string from js. If the string can be encoded in latin1, just take the reference
@@ -330,21 +661,43 @@ impl DOMString {
             Err(DOMStringErrorType::JSConversionError)
         } else {
             let latin1 = unsafe { js::jsapi::JS_DeprecatedStringHasLatin1Chars(string_ptr) };
-            let inner = if latin1 {
+            if latin1 {
                 let h = RootedTraceableBox::from_box(Heap::boxed(string_ptr));
-                DOMStringType::JSString(h)
+                Ok(DOMString::from_inner(DOMStringType::JSString(h)))
             } else {
-                // We need to convert the string anyway as it is not just latin1
-                DOMStringType::Rust(unsafe {
-                    jsstr_to_string(*cx, ptr::NonNull::new(string_ptr).unwrap())
-                })
-            };
-            Ok(DOMString(RefCell::new(inner)))
+                // We need to convert the string anyway as it is not just latin1, but we can
+                // keep the JS String, in case the string is returned to JS unmodified. This also
+                // gives its length in UTF-16 code units.
+                let converted =
+                    unsafe { jsstr_to_string(*cx, ptr::NonNull::new(string_ptr).unwrap()) };
+                let string = DOMString::from_inner(DOMStringType::ConvertedJSString(
+                    converted.into_boxed_str(),
+                    RootedTraceableBox::from_box(Heap::boxed(string_ptr)),
+                ));
+                Ok(string)
+            }
         }
     }
 
     pub fn from_string(s: String) -> DOMString {
-        DOMString(RefCell::new(DOMStringType::Rust(s)))
+        DOMString::from_inner(DOMStringType::Rust(s))
+    }
+
+    /// Creates the string from UTF-16 code units, which may contain unpaired surrogates,
+    /// handling those according to `policy`. Strings that only contain latin1 characters are
+    /// stored as latin1.
+    pub fn from_utf16(units: &[u16], policy: UnpairedSurrogatePolicy) -> DOMString {
+        if units.iter().all(|unit| *unit <= 0xFF) {
+            DOMString::from_inner(DOMStringType::Latin1Vec(
+                units.iter().map(|unit| *unit as u8).collect(),
+            ))
+        } else if policy == UnpairedSurrogatePolicy::ReplaceWithFFFD ||
+            char::decode_utf16(units.iter().copied()).all(|c| c.is_ok())
+        {
+            DOMString::from_string(String::from_utf16_lossy(units))
+        } else {
+            DOMString::from_inner(DOMStringType::Wtf8Vec(encode_wtf8(units)))
+        }
     }
 
     /// Transforms the string into rust string if not yet a rust string.
@@ -352,14 +705,18 @@ impl DOMString {
         let string = {
             let inner = self.0.borrow();
             match *inner {
-                DOMStringType::Rust(_) => return,
+                DOMStringType::Rust(_) |
+                DOMStringType::Inline(_) |
+                DOMStringType::Atom(_) |
+                DOMStringType::ConvertedJSString(..) => {
+                    return;
+                },
                 DOMStringType::JSString(ref rooted_traceable_box) => unsafe {
                     jsstr_to_string(
                         Runtime::get().expect("JS runtime has shut down").as_ptr(),
                         NonNull::new(rooted_traceable_box.get()).unwrap(),
                     )
                 },
-                #[cfg(test)]
                 DOMStringType::Latin1Vec(ref items) => {
                     let mut v = vec![0; items.len() * 2];
                     let real_size = tendril::encoding_rs::mem::convert_latin1_to_utf8(
@@ -372,16 +729,61 @@ impl DOMString {
                     // buffer is the size specified in the documentation, so this should be safe.
                     unsafe { String::from_utf8_unchecked(v) }
                 },
+                DOMStringType::Wtf8Vec(ref items) => wtf8_to_string_lossy(items),
             }
         };
         *self.0.borrow_mut() = DOMStringType::Rust(string);
     }
 
+    /// Transforms the string into a heap allocated rust string, so that it can be modified.
+    /// This drops a JS String that is kept for an unmodified string.
+    fn make_owned_rust(&mut self) {
+        self.make_rust();
+        let inner = self.0.get_mut();
+        *inner = match std::mem::replace(inner, DOMStringType::Rust(String::new())) {
+            DOMStringType::Inline(s) => DOMStringType::Rust(String::from(s.as_str())),
+            DOMStringType::Atom(atom) => DOMStringType::Rust(String::from(&*atom)),
+            DOMStringType::ConvertedJSString(s, _) => DOMStringType::Rust(s.into_string()),
+            other => other,
+        };
+    }
+
+    /// Converts this string to utf8, so that it can be sent to another thread. Strings that
+    /// are already rust strings or interned are moved without copying them.
+    pub fn into_sendable(self) -> SendableDOMString {
+        self.make_rust();
+        match self.0.into_inner() {
+            DOMStringType::Rust(s) => SendableDOMString::String(s),
+            DOMStringType::Atom(atom) => SendableDOMString::Atom(atom),
+            DOMStringType::Inline(s) => SendableDOMString::String(String::from(s.as_str())),
+            DOMStringType::ConvertedJSString(s, _) => SendableDOMString::String(s.into_string()),
+            DOMStringType::JSString(_) |
+            DOMStringType::Latin1Vec(_) |
+            DOMStringType::Wtf8Vec(_) => {
+                unreachable!()
+            },
+        }
+    }
+
+    /// Interns this string, so that it shares its storage with all other interned strings with
+    /// the same contents. Comparing two interned strings only compares pointers. This is meant
+    /// for values that are repeated a lot throughout the DOM, like "none", "hidden" or "ltr".
+    /// Interned strings move to their own storage when they are modified.
+    pub fn intern(self) -> DOMString {
+        if matches!(*self.0.borrow(), DOMStringType::Atom(_)) {
+            return self;
+        }
+        DOMString::from(Atom::from(self))
+    }
+
     /// Debug the current  state of the string without modifying it.
     #[expect(unused)]
     fn debug_js(&self) {
         match *self.0.borrow() {
             DOMStringType::Rust(ref s) => info!("Rust String ({})", s),
+            DOMStringType::Inline(ref s) => info!("Inline String ({})", s.as_str()),
+            DOMStringType::Atom(ref atom) => info!("Atom ({})", atom),
+            DOMStringType::ConvertedJSString(ref s, _) => info!("Converted JSString ({})", s),
             DOMStringType::JSString(ref rooted_traceable_box) => {
                 let s = unsafe {
                     jsstr_to_string(
@@ -391,8 +793,8 @@ impl DOMString {
                 };
                 info!("JSString ({})", s);
             },
-            #[cfg(test)]
-            DOMStringType::Latin1Vec(ref items) => info!("Latin1 string"),
+            DOMStringType::Latin1Vec(_) => info!("Latin1 string"),
+            DOMStringType::Wtf8Vec(_) => info!("WTF-8 string"),
         }
     }
 
@@ -402,6 +804,28 @@ impl DOMString {
         StringView(self.0.borrow())
     }
 
+    /// Returns the string as utf8 without keeping the conversion. The string is borrowed if it
+    /// is already utf8 or only contains ASCII characters, and converted just for the returned
+    /// view otherwise. Unlike [`DOMString::str`], this never replaces a JS String with its
+    /// conversion, so use it for reading strings once.
+    ///
+    /// This cannot return a `Cow<str>`, because the borrowed string lives in a `RefCell`.
+    pub fn as_cow(&self) -> CowStringView<'_> {
+        let view = self.view();
+        let converted = match view.encoded_bytes() {
+            EncodedBytes::Utf8Bytes(_) => None,
+            EncodedBytes::Latin1Bytes(items) if items.is_ascii() => None,
+            EncodedBytes::Latin1Bytes(items) => {
+                Some(tendril::encoding_rs::mem::decode_latin1(items).into_owned())
+            },
+            EncodedBytes::Wtf8Bytes(s) => Some(wtf8_to_string_lossy(s)),
+        };
+        match converted {
+            Some(s) => CowStringView(CowStringViewInner::Owned(s)),
+            None => CowStringView(CowStringViewInner::Borrowed(view.0)),
+        }
+    }
+
     /// Use this if you want to work on the `EncodedBytes` directly.
     /// This will not do any conversions for you.
     pub fn view(&self) -> EncodedBytesView<'_> {
@@ -409,7 +833,7 @@ impl DOMString {
     }
 
     pub fn clear(&mut self) {
-        *self.0.borrow_mut() = DOMStringType::Rust(String::new())
+        *self.0.borrow_mut() = DOMStringType::Rust(String::new());
     }
 
     pub fn is_empty(&self) -> bool {
@@ -435,61 +859,162 @@ impl DOMString {
     }
 
     /// The length of this string in UTF-16 code units, each one being one two bytes in size.
+    /// This is the `length` of the string in JavaScript and what the IDL operations that
+    /// index into a string by code units use, unlike [`DOMString::len`].
+    ///
+    /// This takes constant time for strings from JavaScript, latin1 strings and ASCII strings.
+    /// The length of other strings is computed from their code points, as caching it would
+    /// make every `DOMString` larger.
     ///
     /// Note: This is different than the number of Unicode characters (or code points). A
     /// character may require multiple UTF-16 code units.
     pub fn len_utf16(&self) -> Utf16CodeUnitLength {
-        Utf16CodeUnitLength(self.str().chars().map(char::len_utf16).sum())
+        if let DOMStringType::ConvertedJSString(_, ref js_string) = *self.0.borrow() {
+            // Safety: The JS String is rooted, so it is alive, and it is not null.
+            return Utf16CodeUnitLength(unsafe { JS_GetStringLength(js_string.get()) });
+        }
+        let length = match self.view().encoded_bytes() {
+            // Every latin1 character is a single UTF-16 code unit.
+            EncodedBytes::Latin1Bytes(items) => items.len(),
+            EncodedBytes::Wtf8Bytes(s) => decode_wtf8(s)
+                .map(|code_point| if code_point >= 0x10000 { 2 } else { 1 })
+                .sum(),
+            // Every ASCII character is a single UTF-16 code unit.
+            EncodedBytes::Utf8Bytes(s) if s.is_ascii() => s.len(),
+            // Save because we know it was a utf8 string
+            EncodedBytes::Utf8Bytes(s) => unsafe { str::from_utf8_unchecked(s) }
+                .chars()
+                .map(char::len_utf16)
+                .sum(),
+        };
+        Utf16CodeUnitLength(length)
     }
 
+    /// Converts this string to its ASCII lower case equivalent in-place. A latin1 JS String
+    /// is copied into a latin1 buffer and converted there, without converting it to utf8.
     pub fn make_ascii_lowercase(&mut self) {
-        self.make_rust();
-        if let DOMStringType::Rust(ref mut s) = *self.0.borrow_mut() {
-            s.make_ascii_lowercase();
+        let inner = self.0.get_mut();
+        match *inner {
+            DOMStringType::JSString(ref rooted_traceable_box) => {
+                let items = unsafe { get_latin1_string_bytes(rooted_traceable_box) }.to_vec();
+                *inner = DOMStringType::Latin1Vec(items);
+            },
+            DOMStringType::Atom(ref atom) => *inner = DOMStringType::Rust(String::from(&**atom)),
+            DOMStringType::ConvertedJSString(ref s, _) => {
+                *inner = DOMStringType::Rust(String::from(&**s))
+            },
+            _ => {},
+        }
+        match *inner {
+            DOMStringType::Rust(ref mut s) => s.make_ascii_lowercase(),
+            DOMStringType::Inline(ref mut s) => s.make_ascii_lowercase(),
+            DOMStringType::Latin1Vec(ref mut items) | DOMStringType::Wtf8Vec(ref mut items) => {
+                items.make_ascii_lowercase()
+            },
+            DOMStringType::JSString(_) |
+            DOMStringType::ConvertedJSString(..) |
+            DOMStringType::Atom(_) => unreachable!(),
         }
     }
 
     pub fn push_str(&mut self, s: &str) {
-        self.make_rust();
+        self.make_owned_rust();
         if let DOMStringType::Rust(ref mut string) = *self.0.borrow_mut() {
             string.push_str(s)
         }
     }
 
-    pub fn strip_leading_and_trailing_ascii_whitespace(&mut self) {
-        if self.is_empty() {
-            return;
+    pub fn push(&mut self, c: char) {
+        self.make_owned_rust();
+        if let DOMStringType::Rust(ref mut string) = *self.0.borrow_mut() {
+            string.push(c)
         }
+    }
 
-        self.make_rust();
-        if let DOMStringType::Rust(ref mut s) = *self.0.borrow_mut() {
-            let trailing_whitespace_len = s
-                .trim_end_matches(|ref c| char::is_ascii_whitespace(c))
-                .len();
-            s.truncate(trailing_whitespace_len);
-            if s.is_empty() {
-                return;
-            }
+    /// Inserts `s` at the byte index `idx` of the utf8 representation of this string.
+    ///
+    /// Panics if `idx` does not lie on a char boundary, like [`String::insert_str`].
+    pub fn insert_str(&mut self, idx: usize, s: &str) {
+        self.make_owned_rust();
+        if let DOMStringType::Rust(ref mut string) = *self.0.borrow_mut() {
+            string.insert_str(idx, s)
+        }
+    }
 
-            let first_non_whitespace = s.find(|ref c| !char::is_ascii_whitespace(c)).unwrap();
-            s.replace_range(0..first_non_whitespace, "");
+    /// Shortens this string to `new_len` bytes of its utf8 representation. This has no effect
+    /// if the string is not longer than that.
+    ///
+    /// Panics if `new_len` does not lie on a char boundary, like [`String::truncate`].
+    pub fn truncate(&mut self, new_len: usize) {
+        if new_len >= self.len() {
+            return;
+        }
+        self.make_owned_rust();
+        if let DOMStringType::Rust(ref mut string) = *self.0.borrow_mut() {
+            string.truncate(new_len)
         }
     }
 
-    /// This is a dom spec
-    pub fn is_valid_floating_point_number_string(&self) -> bool {
-        static RE: LazyLock<Regex> = LazyLock::new(|| {
-            Regex::new(r"^-?(?:\d+\.\d+|\d+|\.\d+)(?:(e|E)(\+|\-)?\d+)?$").unwrap()
-        });
-        self.make_rust();
+    /// Removes ASCII whitespace from the start and the end of this string. ASCII whitespace is
+    /// encoded the same way in all representations, so this works on the encoded bytes and
+    /// never converts latin1 strings to utf8.
+    pub fn strip_leading_and_trailing_ascii_whitespace(&mut self) {
+        let (start, end, len) = {
+            let bytes = self.0.get_mut().as_raw_bytes();
+            let start = bytes
+                .iter()
+                .position(|byte| !byte.is_ascii_whitespace())
+                .unwrap_or(bytes.len());
+            let end = bytes
+                .iter()
+                .rposition(|byte| !byte.is_ascii_whitespace())
+                .map_or(start, |last| last + 1);
+            (start, end, bytes.len())
+        };
+        if start == 0 && end == len {
+            return;
+        }
 
-        if let DOMStringType::Rust(ref s) = *self.0.borrow() {
-            RE.is_match(s) && self.parse_floating_point_number().is_some()
-        } else {
-            unreachable!()
+        let inner = self.0.get_mut();
+        match *inner {
+            DOMStringType::Rust(ref mut s) => {
+                s.truncate(end);
+                s.replace_range(0..start, "");
+            },
+            DOMStringType::Latin1Vec(ref mut items) | DOMStringType::Wtf8Vec(ref mut items) => {
+                items.truncate(end);
+                items.drain(0..start);
+            },
+            DOMStringType::JSString(ref rooted_traceable_box) => {
+                let items =
+                    unsafe { get_latin1_string_bytes(rooted_traceable_box) }[start..end].to_vec();
+                *inner = DOMStringType::Latin1Vec(items);
+            },
+            DOMStringType::Inline(_) |
+            DOMStringType::Atom(_) |
+            DOMStringType::ConvertedJSString(..) => {
+                let trimmed = DOMStringType::from(&inner.str()[start..end]);
+                *inner = trimmed;
+            },
         }
     }
 
+    /// <https://html.spec.whatwg.org/multipage/#valid-floating-point-number>
+    ///
+    /// This works on the encoded bytes and never converts the string.
+    pub fn is_valid_floating_point_number_string(&self) -> bool {
+        let view = self.view();
+        let bytes = match view.encoded_bytes() {
+            EncodedBytes::Latin1Bytes(items) |
+            EncodedBytes::Utf8Bytes(items) |
+            EncodedBytes::Wtf8Bytes(items) => items,
+        };
+        // Values that are too large for an f64 are not valid either.
+        is_valid_floating_point_number(bytes) &&
+            // Safety: A valid floating-point number only consists of ASCII characters.
+            parse_floating_point_number(unsafe { str::from_utf8_unchecked(bytes) }).is_some()
+    }
+
     pub fn parse<T: FromStr>(&self) -> Result<T, <T as FromStr>::Err> {
         self.make_rust();
         self.str().parse::<T>()
@@ -501,6 +1026,61 @@ impl DOMString {
         parse_floating_point_number(&self.str())
     }
 
+    /// <https://html.spec.whatwg.org/multipage/#rules-for-parsing-integers>
+    ///
+    /// This uses [`style::attr::parse_integer`] on the encoded bytes, without converting them
+    /// to utf8. Returns `None` on errors and for values that do not fit into an `i32`.
+    pub fn parse_html_integer(&self) -> Option<i32> {
+        style::attr::parse_integer(self.view().encoded_bytes().ascii_chars()).ok()
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#rules-for-parsing-non-negative-integers>
+    ///
+    /// This uses [`style::attr::parse_unsigned_integer`] on the encoded bytes, without
+    /// converting them to utf8. Returns `None` on errors and for values that do not fit into
+    /// a `u32`.
+    pub fn parse_html_non_negative_integer(&self) -> Option<u32> {
+        style::attr::parse_unsigned_integer(self.view().encoded_bytes().ascii_chars()).ok()
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#rules-for-parsing-a-legacy-colour-value>
+    ///
+    /// Latin1 strings are parsed on their latin1 bytes, without converting them to utf8.
+    pub fn parse_legacy_color(&self) -> Option<AbsoluteColor> {
+        match self.view().encoded_bytes() {
+            EncodedBytes::Latin1Bytes(items) => {
+                // Step 2. If input is the empty string, then return failure.
+                if items.is_empty() {
+                    return None;
+                }
+                // Step 3. Strip leading and trailing ASCII whitespace from input.
+                let input = items.trim_ascii();
+                // Named colors only consist of ASCII characters.
+                let name = input
+                    .is_ascii()
+                    // Safety: The string only has ascii chars, hence this is ok.
+                    .then(|| unsafe { str::from_utf8_unchecked(input) });
+                parse_legacy_color(name, input.iter().map(|byte| *byte as char))
+            },
+            EncodedBytes::Utf8Bytes(s) => {
+                // Save because we know it was a utf8 string
+                let s = unsafe { str::from_utf8_unchecked(s) };
+                if s.is_empty() {
+                    return None;
+                }
+                let input = s.trim_matches(|c: char| c.is_ascii_whitespace());
+                parse_legacy_color(Some(input), input.chars())
+            },
+            EncodedBytes::Wtf8Bytes(s) => {
+                // Unpaired surrogates are replaced with zeros in step 10, just like the U+FFFD
+                // replacing them.
+                let s = wtf8_to_string_lossy(s);
+                let input = s.trim_matches(|c: char| c.is_ascii_whitespace());
+                parse_legacy_color(Some(input), input.chars())
+            },
+        }
+    }
+
     /// <https://html.spec.whatwg.org/multipage/#best-representation-of-the-number-as-a-floating-point-number>
     pub fn set_best_representation_of_the_floating_point_number(&mut self) {
         if let Some(val) = self.parse_floating_point_number() {
@@ -524,7 +1104,7 @@ impl DOMString {
     pub fn strip_newlines(&mut self) {
         // > To strip newlines from a string, remove any U+000A LF and U+000D CR code
         // > points from the string.
-        self.make_rust();
+        self.make_owned_rust();
         if let DOMStringType::Rust(ref mut s) = *self.0.borrow_mut() {
             s.retain(|c| c != '\r' && c != '\n');
         }
@@ -532,7 +1112,7 @@ impl DOMString {
 
     /// Normalize newlines according to <https://infra.spec.whatwg.org/#normalize-newlines>.
     pub fn normalize_newlines(&mut self) {
-        self.make_rust();
+        self.make_owned_rust();
         // > To normalize newlines in a string, replace every U+000D CR U+000A LF code point
         // > pair with a single U+000A LF code point, and then replace every remaining
         // > U+000D CR code point with a U+000A LF code point.
@@ -544,9 +1124,9 @@ impl DOMString {
     pub fn replace(self, needle: &str, replace_char: &str) -> DOMString {
         self.make_rust();
         let new_string = self.str().to_owned();
-        DOMString(RefCell::new(DOMStringType::Rust(
+        DOMString::from_inner(DOMStringType::Rust(
             new_string.replace(needle, replace_char),
-        )))
+        ))
     }
 
     /// Pattern is not yet stable in rust, hence, we need different methods for str and char
@@ -557,57 +1137,54 @@ impl DOMString {
         } else {
             match self.view().encoded_bytes() {
                 EncodedBytes::Latin1Bytes(items) => items,
-                EncodedBytes::Utf8Bytes(s) => s,
+                EncodedBytes::Utf8Bytes(s) | EncodedBytes::Wtf8Bytes(s) => s,
             }
-            // For both cases as we tested the char being ascii we can safely convert to a single u8.
+            // For all cases as we tested the char being ascii we can safely convert to a single u8.
             .starts_with(&[c as u8])
         }
     }
 
     pub fn starts_with_str(&self, needle: &str) -> bool {
-        self.make_rust();
-        self.str().starts_with(needle)
+        self.matches_encoded(needle, |haystack, needle| haystack.starts_with(needle))
+    }
+
+    pub fn ends_with_str(&self, needle: &str) -> bool {
+        self.matches_encoded(needle, |haystack, needle| haystack.ends_with(needle))
     }
 
     pub fn contains(&self, needle: &str) -> bool {
-        self.make_rust();
-        self.str().contains(needle)
+        self.matches_encoded(needle, |haystack, needle| {
+            needle.is_empty() ||
+                haystack
+                    .windows(needle.len())
+                    .any(|window| window == needle)
+        })
+    }
+
+    /// Compares the encoded bytes of this string with `needle` encoded the same way. See
+    /// [`EncodedBytes::matches_encoded`].
+    fn matches_encoded(&self, needle: &str, matches: impl Fn(&[u8], &[u8]) -> bool) -> bool {
+        self.view().encoded_bytes().matches_encoded(needle, matches)
+    }
+
+    /// Whether this string is an ASCII case-insensitive match for `other`. This compares the
+    /// encoded bytes of both strings and never converts latin1 strings to utf8.
+    pub fn eq_ignore_ascii_case(&self, other: &str) -> bool {
+        self.view().encoded_bytes().eq_ignore_ascii_case(other)
     }
 
+    /// Returns the ASCII lower case equivalent of this string. Latin1 strings are lowercased
+    /// on their latin1 bytes, which are only converted to utf8 for the result.
     pub fn to_ascii_lowercase(&self) -> String {
-        let conversion = match self.view().encoded_bytes() {
+        match self.view().encoded_bytes() {
             EncodedBytes::Latin1Bytes(items) => {
-                if items.iter().all(|c| *c <= ASCII_END) {
-                    // We are just simple ascii
-                    Some(unsafe {
-                        String::from_utf8_unchecked(
-                            items
-                                .iter()
-                                .map(|c| {
-                                    if *c >= ASCII_CAPITAL_A && *c <= ASCII_CAPITAL_Z {
-                                        c + 32
-                                    } else {
-                                        *c
-                                    }
-                                })
-                                .collect(),
-                        )
-                    })
-                } else {
-                    None
-                }
+                tendril::encoding_rs::mem::decode_latin1(&items.to_ascii_lowercase()).into_owned()
             },
             EncodedBytes::Utf8Bytes(s) => unsafe {
                 // Save because we know it was a utf8 string
-                Some(str::from_utf8_unchecked(s).to_ascii_lowercase())
+                str::from_utf8_unchecked(s).to_ascii_lowercase()
             },
-        };
-        // We otherwise would double borrow the refcell
-        if let Some(conversion) = conversion {
-            conversion
-        } else {
-            self.make_rust();
-            self.str().to_ascii_lowercase()
+            EncodedBytes::Wtf8Bytes(s) => wtf8_to_string_lossy(s).to_ascii_lowercase(),
         }
     }
 
@@ -620,7 +1197,9 @@ impl DOMString {
             ASCII_SPACE,
         ];
         match self.view().encoded_bytes() {
-            EncodedBytes::Latin1Bytes(items) => SPACE_BYTES.iter().any(|byte| items.contains(byte)),
+            EncodedBytes::Latin1Bytes(items) | EncodedBytes::Wtf8Bytes(items) => {
+                SPACE_BYTES.iter().any(|byte| items.contains(byte))
+            },
             EncodedBytes::Utf8Bytes(s) => {
                 // Save because we know it was a utf8 string
                 let s = unsafe { str::from_utf8_unchecked(s) };
@@ -648,7 +1227,7 @@ impl DOMString {
             EncodedBytes::Latin1Bytes(items) => items
                 .iter()
                 .all(|c| (ASCII_LOWERCASE_A..=ASCII_LOWERCASE_Z).contains(c)),
-            EncodedBytes::Utf8Bytes(s) => s
+            EncodedBytes::Utf8Bytes(s) | EncodedBytes::Wtf8Bytes(s) => s
                 .iter()
                 .map(|c| c.to_u8().unwrap_or(ASCII_LOWERCASE_A - 1))
                 .all(|c| (ASCII_LOWERCASE_A..=ASCII_LOWERCASE_Z).contains(&c)),
@@ -660,6 +1239,7 @@ impl DOMString {
         match self.view().encoded_bytes() {
             EncodedBytes::Latin1Bytes(items) => items,
             EncodedBytes::Utf8Bytes(items) => items,
+            EncodedBytes::Wtf8Bytes(items) => items,
         }
         .is_ascii()
     }
@@ -669,13 +1249,174 @@ impl DOMString {
     /// Not using ServoCookie::is_valid_name_or_value to prevent dependency on the net crate.
     pub fn is_valid_for_cookie(&self) -> bool {
         match self.view().encoded_bytes() {
-            EncodedBytes::Latin1Bytes(items) | EncodedBytes::Utf8Bytes(items) => !items
+            EncodedBytes::Latin1Bytes(items) |
+            EncodedBytes::Utf8Bytes(items) |
+            EncodedBytes::Wtf8Bytes(items) => !items
                 .iter()
                 .any(|c| *c == 0x7f || (*c <= 0x1f && *c != 0x09)),
         }
     }
 }
 
+/// <https://html.spec.whatwg.org/multipage/#rules-for-parsing-a-legacy-colour-value>
+///
+/// This implements the steps after stripping whitespace from the input, which are given both as
+/// a str, if the input can be a named color, and as its code points.
+fn parse_legacy_color(
+    name: Option<&str>,
+    input: impl Iterator<Item = char>,
+) -> Option<AbsoluteColor> {
+    if let Some(name) = name {
+        // Step 4. If input is an ASCII case-insensitive match for "transparent", then return
+        // failure.
+        if name.eq_ignore_ascii_case("transparent") {
+            return None;
+        }
+
+        // Step 5. If input is an ASCII case-insensitive match for one of the named colors, then
+        // return the CSS color corresponding to that keyword.
+        if let Ok((red, green, blue)) = cssparser::color::parse_named_color(name) {
+            return Some(AbsoluteColor::srgb_legacy(red, green, blue, 1.0));
+        }
+    }
+
+    let input: Vec<char> = input.collect();
+    let hex_digit = |c: &char| c.to_digit(16).map(|digit| digit as u8);
+
+    // Step 6. If input's code point length is four, and the first character in input is
+    // U+0023 (#), and the last three characters of input are all ASCII hex digits, then:
+    if let ['#', red, green, blue] = input[..] {
+        if let (Some(red), Some(green), Some(blue)) =
+            (hex_digit(&red), hex_digit(&green), hex_digit(&blue))
+        {
+            // Interpret the digits as hexadecimal numbers and multiply them by 17.
+            return Some(AbsoluteColor::srgb_legacy(
+                red * 17,
+                green * 17,
+                blue * 17,
+                1.0,
+            ));
+        }
+    }
+
+    // Step 7. Replace any code points greater than U+FFFF in input (i.e., any characters that
+    // are not in the basic multilingual plane) with "00".
+    // Step 8. If input's code point length is greater than 128, truncate input, leaving only
+    // the first 128 characters.
+    let mut input = input.into_iter().fold(Vec::new(), |mut input, c| {
+        if c as u32 > 0xFFFF {
+            input.extend(['0', '0']);
+        } else {
+            input.push(c);
+        }
+        input
+    });
+    input.truncate(128);
+
+    // Step 9. If the first character in input is a U+0023 NUMBER SIGN character (#), remove it.
+    if input.first() == Some(&'#') {
+        input.remove(0);
+    }
+
+    // Step 10. Replace any character in input that is not an ASCII hex digit with U+0030
+    // DIGIT ZERO (0).
+    let mut digits: Vec<u8> = input.iter().map(|c| hex_digit(c).unwrap_or(0)).collect();
+
+    // Step 11. While input's code point length is zero or not a multiple of three, append a
+    // U+0030 DIGIT ZERO (0) character to input.
+    while digits.is_empty() || digits.len() % 3 != 0 {
+        digits.push(0);
+    }
+
+    // Step 12. Split input into three strings of equal code point length, to obtain three
+    // components. Let length be the code point length that all of those components have (one
+    // third the code point length of input).
+    let mut length = digits.len() / 3;
+    let mut components: Vec<&[u8]> = digits.chunks(length).collect();
+
+    // Step 13. If length is greater than eight, then remove the leading length-8 characters in
+    // each component, and let length be 8.
+    if length > 8 {
+        for component in components.iter_mut() {
+            *component = &component[length - 8..];
+        }
+        length = 8;
+    }
+
+    // Step 14. While length is greater than two and the first character in each component is
+    // a U+0030 DIGIT ZERO (0) character, remove that character and reduce length by one.
+    while length > 2 && components.iter().all(|component| component[0] == 0) {
+        for component in components.iter_mut() {
+            *component = &component[1..];
+        }
+        length -= 1;
+    }
+
+    // Step 15. If length is still greater than two, truncate each component, leaving only the
+    // first two characters in each.
+    // Step 16. Let red, green, and blue be the components interpreted as base-16 numbers.
+    let component_value = |component: &[u8]| {
+        component
+            .iter()
+            .take(2)
+            .fold(0, |value, digit| value * 16 + digit)
+    };
+
+    // Step 17. Return a new sRGB color whose red, green, and blue components are red, green,
+    // and blue, respectively, and whose alpha component is 1.
+    Some(AbsoluteColor::srgb_legacy(
+        component_value(components[0]),
+        component_value(components[1]),
+        component_value(components[2]),
+        1.0,
+    ))
+}
+
+/// <https://html.spec.whatwg.org/multipage/#valid-floating-point-number>
+///
+/// Checks that the input consists of an optional "-", digits with an optional fraction or only
+/// a fraction, and an optional exponent, i.e., `-?(\d+\.\d+|\d+|\.\d+)([eE][+-]?\d+)?`. Any
+/// byte that is not ASCII makes the input invalid, so it can be either latin1 or utf8 encoded.
+fn is_valid_floating_point_number(input: &[u8]) -> bool {
+    let digits = |position: usize| {
+        input[position..]
+            .iter()
+            .take_while(|byte| byte.is_ascii_digit())
+            .count()
+    };
+
+    let mut position = 0;
+    if input.first() == Some(&b'-') {
+        position += 1;
+    }
+
+    let integer_digits = digits(position);
+    position += integer_digits;
+    if input.get(position) == Some(&b'.') {
+        let fraction_digits = digits(position + 1);
+        if fraction_digits == 0 {
+            return false;
+        }
+        position += 1 + fraction_digits;
+    } else if integer_digits == 0 {
+        return false;
+    }
+
+    if matches!(input.get(position), Some(b'e' | b'E')) {
+        position += 1;
+        if matches!(input.get(position), Some(b'+' | b'-')) {
+            position += 1;
+        }
+        let exponent_digits = digits(position);
+        if exponent_digits == 0 {
+            return false;
+        }
+        position += exponent_digits;
+    }
+
+    position == input.len()
+}
+
 /// <https://html.spec.whatwg.org/multipage/#rules-for-parsing-floating-point-number-values>
 pub fn parse_floating_point_number(input: &str) -> Option<f64> {
     // Steps 15-16 are telling us things about IEEE rounding modes
@@ -690,6 +1431,72 @@ pub fn parse_floating_point_number(input: &str) -> Option<f64> {
     })
 }
 
+/// Encodes UTF-16 code units as WTF-8, which is utf8 where unpaired surrogates are encoded
+/// like any other code point. <https://simonsapin.github.io/wtf-8/#encoding-ill-formed-utf-16>
+fn encode_wtf8(units: &[u16]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(units.len());
+    for result in char::decode_utf16(units.iter().copied()) {
+        match result {
+            Ok(c) => bytes.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes()),
+            Err(error) => {
+                let surrogate = error.unpaired_surrogate();
+                bytes.extend_from_slice(&[
+                    0xE0 | (surrogate >> 12) as u8,
+                    0x80 | ((surrogate >> 6) & 0x3F) as u8,
+                    0x80 | (surrogate & 0x3F) as u8,
+                ]);
+            },
+        }
+    }
+    bytes
+}
+
+/// Decodes the code points of WTF-8 bytes, including unpaired surrogates. The bytes must be
+/// well-formed WTF-8, as produced by [`encode_wtf8`].
+fn decode_wtf8(bytes: &[u8]) -> impl Iterator<Item = u32> + '_ {
+    let mut index = 0;
+    std::iter::from_fn(move || {
+        let first = *bytes.get(index)? as u32;
+        let continuation = |offset: usize| (bytes[index + offset] & 0x3F) as u32;
+        let (code_point, length) = match first {
+            0x00..=0x7F => (first, 1),
+            0xC0..=0xDF => (((first & 0x1F) << 6) | continuation(1), 2),
+            0xE0..=0xEF => (
+                ((first & 0x0F) << 12) | (continuation(1) << 6) | continuation(2),
+                3,
+            ),
+            _ => (
+                ((first & 0x07) << 18) |
+                    (continuation(1) << 12) |
+                    (continuation(2) << 6) |
+                    continuation(3),
+                4,
+            ),
+        };
+        index += length;
+        Some(code_point)
+    })
+}
+
+/// Converts WTF-8 bytes to utf8, replacing unpaired surrogates with U+FFFD.
+fn wtf8_to_string_lossy(bytes: &[u8]) -> String {
+    decode_wtf8(bytes)
+        .map(|code_point| char::from_u32(code_point).unwrap_or(char::REPLACEMENT_CHARACTER))
+        .collect()
+}
+
+/// Converts WTF-8 bytes back to the UTF-16 code units they were encoded from.
+fn wtf8_to_utf16(bytes: &[u8]) -> Vec<u16> {
+    let mut units = Vec::with_capacity(bytes.len());
+    for code_point in decode_wtf8(bytes) {
+        match char::from_u32(code_point) {
+            Some(c) => units.extend_from_slice(c.encode_utf16(&mut [0; 2])),
+            None => units.push(code_point as u16),
+        }
+    }
+    units
+}
+
 pub struct BytesView<'a>(Ref<'a, DOMStringType>);
 
 impl Deref for BytesView<'_> {
@@ -701,25 +1508,63 @@ impl Deref for BytesView<'_> {
     }
 }
 
+/// A utf8 view of a [`DOMString`] that either borrows the string or owns a temporary conversion
+/// of it. See [`DOMString::as_cow`].
+pub struct CowStringView<'a>(CowStringViewInner<'a>);
+
+enum CowStringViewInner<'a> {
+    /// The string is utf8 or ASCII-only latin1, which is valid utf8.
+    Borrowed(Ref<'a, DOMStringType>),
+    Owned(String),
+}
+
+impl CowStringView<'_> {
+    pub fn into_owned(self) -> String {
+        match self.0 {
+            CowStringViewInner::Borrowed(_) => String::from(self.deref()),
+            CowStringViewInner::Owned(s) => s,
+        }
+    }
+}
+
+impl Deref for CowStringView<'_> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        match self.0 {
+            // This is valid utf8 by the construction of CowStringView in `DOMString::as_cow`.
+            CowStringViewInner::Borrowed(ref inner) => unsafe {
+                str::from_utf8_unchecked(inner.as_raw_bytes())
+            },
+            CowStringViewInner::Owned(ref s) => s,
+        }
+    }
+}
+
+/// Strings are ordered by their code points, like rust strings, without converting them.
 impl Ord for DOMString {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.make_rust();
-        other.make_rust();
-        self.str().cmp(&other.str())
+        let (view, other_view) = (self.view(), other.view());
+        match (view.encoded_bytes(), other_view.encoded_bytes()) {
+            // Utf8 and WTF-8 bytes are ordered like the code points they encode.
+            (
+                EncodedBytes::Utf8Bytes(s) | EncodedBytes::Wtf8Bytes(s),
+                EncodedBytes::Utf8Bytes(other_s) | EncodedBytes::Wtf8Bytes(other_s),
+            ) => s.cmp(other_s),
+            (bytes, other_bytes) => bytes.code_points().cmp(other_bytes.code_points()),
+        }
     }
 }
 
 impl PartialOrd for DOMString {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        self.make_rust();
-        other.make_rust();
-        self.str().partial_cmp(&other.str())
+        Some(self.cmp(other))
     }
 }
 
 impl Extend<char> for DOMString {
     fn extend<T: IntoIterator<Item = char>>(&mut self, iter: T) {
-        self.make_rust();
+        self.make_owned_rust();
         if let DOMStringType::Rust(ref mut s) = *self.0.borrow_mut() {
             s.extend(iter)
         }
@@ -733,30 +1578,54 @@ impl ToJSValConvertible for DOMString {
             DOMStringType::Rust(ref s) => unsafe {
                 s.to_jsval(cx, rval);
             },
-            DOMStringType::JSString(ref rooted_traceable_box) => unsafe {
+            DOMStringType::Inline(ref s) => unsafe {
+                s.as_str().to_jsval(cx, rval);
+            },
+            DOMStringType::Atom(ref atom) => unsafe {
+                (**atom).to_jsval(cx, rval);
+            },
+            DOMStringType::JSString(ref rooted_traceable_box) |
+            DOMStringType::ConvertedJSString(_, ref rooted_traceable_box) => unsafe {
                 rval.set(StringValue(&*rooted_traceable_box.get()));
             },
-            #[cfg(test)]
-            DOMStringType::Latin1Vec(ref items) => {
-                let mut v = vec![0; items.len() * 2];
-                let real_size = tendril::encoding_rs::mem::convert_latin1_to_utf8(
-                    items.as_slice(),
-                    v.as_mut_slice(),
-                );
-                v.truncate(real_size);
-
-                String::from_utf8(v)
-                    .expect("Error in constructin test string")
-                    .to_jsval(cx, rval);
+            DOMStringType::Latin1Vec(ref items) => unsafe {
+                tendril::encoding_rs::mem::decode_latin1(items).to_jsval(cx, rval);
+            },
+            DOMStringType::Wtf8Vec(ref items) => unsafe {
+                let units = wtf8_to_utf16(items);
+                let string = JS_NewUCStringCopyN(cx, units.as_ptr(), units.len());
+                assert!(!string.is_null());
+                rval.set(StringValue(&*string));
             },
         };
     }
 }
 
+/// Strings are hashed as WTF-8, which is their utf8 encoding unless they contain unpaired
+/// surrogates. This is the same for every representation of equal strings and never converts
+/// the string, as converting WTF-8 to utf8 would replace the unpaired surrogates.
 impl std::hash::Hash for DOMString {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-        self.make_rust();
-        self.str().hash(state);
+        match self.view().encoded_bytes() {
+            EncodedBytes::Latin1Bytes(items) => {
+                state.write(tendril::encoding_rs::mem::decode_latin1(items).as_bytes())
+            },
+            EncodedBytes::Utf8Bytes(s) | EncodedBytes::Wtf8Bytes(s) => state.write(s),
+        }
+        state.write_u8(0xff);
+    }
+}
+
+/// `DOMString`s are serialized as a rust string, which converts them to utf8 first.
+impl Serialize for DOMString {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.str())
+    }
+}
+
+impl<'de> Deserialize<'de> for DOMString {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<DOMString, D::Error> {
+        String::deserialize(deserializer).map(DOMString::from_string)
     }
 }
 
@@ -775,46 +1644,19 @@ impl Default for DOMString {
 
 impl std::cmp::PartialEq<str> for DOMString {
     fn eq(&self, other: &str) -> bool {
-        if other.is_ascii() {
-            other.as_bytes() ==
-                match self.view().encoded_bytes() {
-                    EncodedBytes::Latin1Bytes(items) => items,
-                    EncodedBytes::Utf8Bytes(s) => s,
-                }
-        } else {
-            self.make_rust();
-            self.str().deref() == other
-        }
+        self.matches_encoded(other, |s, other| s == other)
     }
 }
 
 impl std::cmp::PartialEq<&str> for DOMString {
     fn eq(&self, other: &&str) -> bool {
-        if other.is_ascii() {
-            other.as_bytes() ==
-                match self.view().encoded_bytes() {
-                    EncodedBytes::Latin1Bytes(items) => items,
-                    EncodedBytes::Utf8Bytes(s) => s,
-                }
-        } else {
-            self.make_rust();
-            self.str().deref() == *other
-        }
+        self.eq(*other)
     }
 }
 
 impl std::cmp::PartialEq<String> for DOMString {
     fn eq(&self, other: &String) -> bool {
-        if other.is_ascii() {
-            other.as_bytes() ==
-                match self.view().encoded_bytes() {
-                    EncodedBytes::Latin1Bytes(items) => items,
-                    EncodedBytes::Utf8Bytes(s) => s,
-                }
-        } else {
-            self.make_rust();
-            self.str().deref() == other
-        }
+        self.eq(other.as_str())
     }
 }
 
@@ -832,30 +1674,34 @@ impl std::cmp::PartialEq<DOMString> for str {
 
 impl std::cmp::PartialEq for DOMString {
     fn eq(&self, other: &DOMString) -> bool {
-        let result = match (self.view().encoded_bytes(), other.view().encoded_bytes()) {
+        if let (DOMStringType::Atom(atom), DOMStringType::Atom(other_atom)) =
+            (&*self.0.borrow(), &*other.0.borrow())
+        {
+            return atom == other_atom;
+        }
+        let (view, other_view) = (self.view(), other.view());
+        match (view.encoded_bytes(), other_view.encoded_bytes()) {
             (EncodedBytes::Latin1Bytes(items), EncodedBytes::Latin1Bytes(other_items)) => {
-                Some(items == other_items)
+                items == other_items
             },
             (EncodedBytes::Latin1Bytes(items), EncodedBytes::Utf8Bytes(other_s))
                 if other_s.is_ascii() =>
             {
-                Some(items == other_s)
+                items == other_s
             },
             (EncodedBytes::Utf8Bytes(s), EncodedBytes::Latin1Bytes(other_items))
                 if s.is_ascii() =>
             {
-                Some(s == other_items)
+                s == other_items
             },
-            (EncodedBytes::Utf8Bytes(s), EncodedBytes::Utf8Bytes(other_s)) => Some(s == other_s),
-            _ => None,
-        };
-
-        if let Some(eq_result) = result {
-            eq_result
-        } else {
-            self.make_rust();
-            other.make_rust();
-            self.str() == other.str()
+            // Every string has a single WTF-8 encoding, which is its utf8 encoding unless it
+            // contains unpaired surrogates.
+            (
+                EncodedBytes::Utf8Bytes(s) | EncodedBytes::Wtf8Bytes(s),
+                EncodedBytes::Utf8Bytes(other_s) | EncodedBytes::Wtf8Bytes(other_s),
+            ) => s == other_s,
+            // Compare the code points, like `Hash` does, instead of converting the strings.
+            (bytes, other_bytes) => bytes.code_points().eq(other_bytes.code_points()),
         }
     }
 }
@@ -882,6 +1728,7 @@ impl From<DOMString> for LocalName {
                     }
                 },
                 EncodedBytes::Utf8Bytes(s) => Some(unsafe { str::from_utf8_unchecked(s) }),
+                EncodedBytes::Wtf8Bytes(_) => None,
             };
             if let Some(s) = str {
                 return LocalName::from(s);
@@ -907,6 +1754,7 @@ impl From<&DOMString> for LocalName {
                     }
                 },
                 EncodedBytes::Utf8Bytes(s) => Some(unsafe { str::from_utf8_unchecked(s) }),
+                EncodedBytes::Wtf8Bytes(_) => None,
             };
             if let Some(s) = str {
                 return LocalName::from(s);
@@ -932,6 +1780,7 @@ impl From<DOMString> for Namespace {
                     }
                 },
                 EncodedBytes::Utf8Bytes(s) => Some(unsafe { str::from_utf8_unchecked(s) }),
+                EncodedBytes::Wtf8Bytes(_) => None,
             };
             if let Some(s) = str {
                 return Namespace::from(s);
@@ -944,6 +1793,9 @@ impl From<DOMString> for Namespace {
 
 impl From<DOMString> for Atom {
     fn from(contents: DOMString) -> Atom {
+        if let DOMStringType::Atom(ref atom) = *contents.0.borrow() {
+            return atom.clone();
+        }
         {
             let view = contents.view();
             let bytes = view.encoded_bytes();
@@ -957,6 +1809,7 @@ impl From<DOMString> for Atom {
                     }
                 },
                 EncodedBytes::Utf8Bytes(s) => Some(unsafe { str::from_utf8_unchecked(s) }),
+                EncodedBytes::Wtf8Bytes(_) => None,
             };
             if let Some(s) = str {
                 return Atom::from(s);
@@ -967,16 +1820,30 @@ impl From<DOMString> for Atom {
     }
 }
 
+impl From<&str> for DOMStringType {
+    fn from(contents: &str) -> DOMStringType {
+        match InlineString::new(contents) {
+            Some(inline) => DOMStringType::Inline(inline),
+            None => DOMStringType::Rust(String::from(contents)),
+        }
+    }
+}
+
 impl From<&str> for DOMString {
     fn from(contents: &str) -> DOMString {
-        DOMString(RefCell::new(DOMStringType::Rust(String::from(contents))))
+        DOMString::from_inner(DOMStringType::from(contents))
+    }
+}
+
+impl From<Atom> for DOMString {
+    fn from(atom: Atom) -> DOMString {
+        DOMString::from_inner(DOMStringType::Atom(atom))
     }
 }
 
 impl From<DOMString> for String {
     fn from(val: DOMString) -> Self {
-        val.make_rust();
-        val.str().to_owned()
+        String::from(val.into_sendable())
     }
 }
 
@@ -989,7 +1856,7 @@ impl From<DOMString> for Vec<u8> {
 
 impl From<Cow<'_, str>> for DOMString {
     fn from(value: Cow<'_, str>) -> Self {
-        DOMString(RefCell::new(DOMStringType::Rust(value.into_owned())))
+        DOMString::from_inner(DOMStringType::Rust(value.into_owned()))
     }
 }
 
@@ -1053,7 +1920,14 @@ mod tests {
     const LATIN1_POWER2: u8 = 0xB2;
 
     fn from_latin1(l1vec: Vec<u8>) -> DOMString {
-        DOMString(RefCell::new(DOMStringType::Latin1Vec(l1vec)))
+        DOMString::from_inner(DOMStringType::Latin1Vec(l1vec))
+    }
+
+    fn hash_value(d: &DOMString) -> u64 {
+        use std::hash::{DefaultHasher, Hash, Hasher};
+        let mut hasher = DefaultHasher::new();
+        d.hash(&mut hasher);
+        hasher.finish()
     }
 
     #[test]
@@ -1094,6 +1968,19 @@ mod tests {
         }
     }
 
+    #[test]
+    fn make_ascii_lowercase_latin1() {
+        let mut s = from_latin1(vec![b'A', b'b', b'B', b'&', 0xC0, 0xB2]);
+        s.make_ascii_lowercase();
+        // Only ASCII characters are lowercased and the string stays latin1.
+        assert!(matches!(*s.0.borrow(), DOMStringType::Latin1Vec(_)));
+        assert_eq!(s, "abb&À²");
+
+        let mut s = DOMString::from("AbB❤");
+        s.make_ascii_lowercase();
+        assert_eq!(s, "abb❤");
+    }
+
     #[test]
     fn test_length() {
         let s1 = from_latin1(vec![
@@ -1149,6 +2036,403 @@ mod tests {
         assert_eq!(s6.len(), s6_utf8.len());
     }
 
+    #[test]
+    fn comparisons_latin1() {
+        let s = from_latin1(vec![b'a', b'b', b'c', b'%', b'$', LATIN1_POWER2]);
+        assert!(s.starts_with_str("abc"));
+        assert!(!s.starts_with_str("bc"));
+        assert!(s.ends_with_str("$²"));
+        assert!(!s.ends_with_str("%"));
+        assert!(s.contains("c%$"));
+        assert!(s.contains(""));
+        assert!(!s.contains("❤"));
+        assert!(s == "abc%$²");
+        assert!(s != "abc%$❤");
+        // None of these needed a conversion to utf8.
+        assert!(matches!(*s.0.borrow(), DOMStringType::Latin1Vec(_)));
+
+        let s = DOMString::from("abc%$❤");
+        assert!(s.starts_with_str("abc"));
+        assert!(s.ends_with_str("$❤"));
+        assert!(s.contains("%$❤"));
+        assert!(!s.contains("²"));
+        assert!(s == "abc%$❤");
+    }
+
+    #[test]
+    fn test_length_utf16() {
+        let s = from_latin1(vec![b'a', b'b', LATIN1_PILLCROW]);
+        assert_eq!(s.len_utf16(), Utf16CodeUnitLength(3));
+        assert_eq!(s.len(), 4);
+
+        let mut s = DOMString::from("a❤😀");
+        assert_eq!(s.len_utf16(), Utf16CodeUnitLength(4));
+        // The length follows changes to the string.
+        s.push_str("😀");
+        assert_eq!(s.len_utf16(), Utf16CodeUnitLength(6));
+        s.clear();
+        assert_eq!(s.len_utf16(), Utf16CodeUnitLength(0));
+    }
+
+    #[test]
+    fn from_utf16() {
+        let latin1 = [0x61, 0x62, 0xB2];
+        let s = DOMString::from_utf16(&latin1, UnpairedSurrogatePolicy::PreserveAsWTF8);
+        assert!(matches!(*s.0.borrow(), DOMStringType::Latin1Vec(_)));
+        assert_eq!(s, "ab²");
+        assert!(matches!(*s.clone().0.borrow(), DOMStringType::Latin1Vec(_)));
+
+        let units = [0x61, 0xD800, 0x2764, 0xD83D, 0xDE00];
+        let s = DOMString::from_utf16(&units, UnpairedSurrogatePolicy::ReplaceWithFFFD);
+        assert_eq!(s, "a\u{FFFD}❤😀");
+        assert_eq!(s.len_utf16(), Utf16CodeUnitLength(5));
+
+        let s = DOMString::from_utf16(&units, UnpairedSurrogatePolicy::PreserveAsWTF8);
+        assert!(matches!(*s.0.borrow(), DOMStringType::Wtf8Vec(_)));
+        assert_eq!(s.len_utf16(), Utf16CodeUnitLength(5));
+        assert_eq!(s.len(), "a\u{FFFD}❤😀".len());
+        assert!(s.contains("❤😀"));
+        assert!(s != "a\u{FFFD}❤😀");
+        if let DOMStringType::Wtf8Vec(ref items) = *s.0.borrow() {
+            assert_eq!(wtf8_to_utf16(items), units);
+        }
+
+        // Cloning keeps the unpaired surrogate.
+        let cloned = s.clone();
+        assert!(matches!(*cloned.0.borrow(), DOMStringType::Wtf8Vec(_)));
+        if let DOMStringType::Wtf8Vec(ref items) = *cloned.0.borrow() {
+            assert_eq!(wtf8_to_utf16(items), units);
+        }
+        // Using the string as a rust string replaces the unpaired surrogate.
+        assert_eq!(&*s.str(), "a\u{FFFD}❤😀");
+    }
+
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    fn domstring_size() {
+        // No representation is larger than a `String`, so a `DOMStringType` is a `String` and
+        // its tag, and a `DOMString` only adds the borrow flag of its `RefCell`.
+        assert_eq!(size_of::<InlineString>(), size_of::<String>());
+        assert_eq!(
+            size_of::<DOMStringType>(),
+            size_of::<String>() + size_of::<usize>()
+        );
+        assert_eq!(
+            size_of::<DOMString>(),
+            size_of::<DOMStringType>() + size_of::<usize>()
+        );
+    }
+
+    #[test]
+    fn inline_strings() {
+        let s = DOMString::from("click");
+        assert!(matches!(*s.0.borrow(), DOMStringType::Inline(_)));
+        assert_eq!(s, "click");
+        assert_eq!(&*s.str(), "click");
+        assert_eq!(s.len_utf16(), Utf16CodeUnitLength(5));
+
+        let cloned = s.clone();
+        assert!(matches!(*cloned.0.borrow(), DOMStringType::Inline(_)));
+        assert_eq!(cloned, s);
+
+        let mut s = DOMString::from("ÄBC");
+        s.make_ascii_lowercase();
+        assert!(matches!(*s.0.borrow(), DOMStringType::Inline(_)));
+        assert_eq!(s, "Äbc");
+
+        // Modifying an inline string moves it to the heap.
+        let mut s = DOMString::from("a".repeat(INLINE_CAPACITY).as_str());
+        assert!(matches!(*s.0.borrow(), DOMStringType::Inline(_)));
+        s.push_str("b");
+        assert!(matches!(*s.0.borrow(), DOMStringType::Rust(_)));
+        assert_eq!(s.len(), INLINE_CAPACITY + 1);
+
+        let s = DOMString::from("a".repeat(INLINE_CAPACITY + 1).as_str());
+        assert!(matches!(*s.0.borrow(), DOMStringType::Rust(_)));
+    }
+
+    #[test]
+    fn interned_strings() {
+        let s = DOMString::from("hidden").intern();
+        assert!(matches!(*s.0.borrow(), DOMStringType::Atom(_)));
+        assert_eq!(s, "hidden");
+        assert_eq!(s, DOMString::from_string(String::from("hidden")).intern());
+        assert_eq!(s, DOMString::from("hidden"));
+        assert_ne!(s, DOMString::from("none").intern());
+        assert_eq!(s, from_latin1(b"hidden".to_vec()));
+        assert_eq!(Atom::from(s.clone()), Atom::from("hidden"));
+        assert!(matches!(*s.clone().0.borrow(), DOMStringType::Atom(_)));
+
+        let mut s = s;
+        s.push_str("-until-found");
+        assert!(matches!(*s.0.borrow(), DOMStringType::Rust(_)));
+        assert_eq!(s, "hidden-until-found");
+
+        let mut s = DOMString::from("LTR").intern();
+        s.make_ascii_lowercase();
+        assert_eq!(s, "ltr");
+    }
+
+    #[test]
+    fn html_integers() {
+        assert_eq!(DOMString::from("42").parse_html_integer(), Some(42));
+        assert_eq!(
+            DOMString::from(" \t\n-17px").parse_html_integer(),
+            Some(-17)
+        );
+        assert_eq!(DOMString::from("+5.9").parse_html_integer(), Some(5));
+        assert_eq!(DOMString::from("").parse_html_integer(), None);
+        assert_eq!(DOMString::from("   ").parse_html_integer(), None);
+        assert_eq!(DOMString::from("-").parse_html_integer(), None);
+        assert_eq!(DOMString::from("x1").parse_html_integer(), None);
+        assert_eq!(DOMString::from("+-1").parse_html_integer(), None);
+        assert_eq!(DOMString::from("2147483648").parse_html_integer(), None);
+        assert_eq!(
+            DOMString::from("-2147483648").parse_html_integer(),
+            Some(i32::MIN)
+        );
+        assert_eq!(
+            from_latin1(vec![b' ', b'1', b'2', LATIN1_POWER2]).parse_html_integer(),
+            Some(12)
+        );
+
+        assert_eq!(
+            DOMString::from("4294967295").parse_html_non_negative_integer(),
+            Some(u32::MAX)
+        );
+        assert_eq!(
+            DOMString::from("-0").parse_html_non_negative_integer(),
+            Some(0)
+        );
+        assert_eq!(
+            DOMString::from("-1").parse_html_non_negative_integer(),
+            None
+        );
+        assert_eq!(
+            from_latin1(vec![b'\x0C', b'7', LATIN1_PILLCROW]).parse_html_non_negative_integer(),
+            Some(7)
+        );
+    }
+
+    #[test]
+    fn legacy_colors() {
+        let color = |red, green, blue| Some(AbsoluteColor::srgb_legacy(red, green, blue, 1.0));
+        assert_eq!(DOMString::from("").parse_legacy_color(), None);
+        assert_eq!(DOMString::from("Transparent").parse_legacy_color(), None);
+        assert_eq!(
+            DOMString::from(" RED\n").parse_legacy_color(),
+            color(255, 0, 0)
+        );
+        assert_eq!(
+            DOMString::from("#f0a").parse_legacy_color(),
+            color(255, 0, 170)
+        );
+        assert_eq!(
+            DOMString::from("#00ff00").parse_legacy_color(),
+            color(0, 255, 0)
+        );
+        assert_eq!(DOMString::from("   ").parse_legacy_color(), color(0, 0, 0));
+        assert_eq!(
+            DOMString::from("chucknorris").parse_legacy_color(),
+            color(192, 0, 0)
+        );
+        assert_eq!(
+            DOMString::from("#1234567890ab").parse_legacy_color(),
+            color(0x12, 0x56, 0x90)
+        );
+        assert_eq!(
+            DOMString::from("0000000001000000001").parse_legacy_color(),
+            color(0, 0x10, 0)
+        );
+        // Characters outside of the BMP are replaced by two zeros.
+        assert_eq!(
+            DOMString::from("😀f").parse_legacy_color(),
+            color(0, 0, 0xf)
+        );
+        assert_eq!(
+            from_latin1(vec![b'#', LATIN1_PILLCROW, b'f', b'f']).parse_legacy_color(),
+            color(0, 0xf, 0xf)
+        );
+        assert_eq!(
+            from_latin1(b"\tNavy ".to_vec()).parse_legacy_color(),
+            color(0, 0, 128)
+        );
+    }
+
+    #[test]
+    fn split_html_space_characters() {
+        let s = from_latin1(vec![
+            b' ',
+            b'a',
+            ASCII_TAB,
+            ASCII_NEWLINE,
+            LATIN1_PILLCROW,
+            b'b',
+            ASCII_FORMFEED,
+            b'c',
+            ASCII_CR,
+            b' ',
+        ]);
+        let view = s.view();
+        let tokens: Vec<_> = view.split_html_space_characters().collect();
+        assert_eq!(
+            tokens,
+            vec![
+                EncodedBytes::Latin1Bytes(b"a"),
+                EncodedBytes::Latin1Bytes(&[LATIN1_PILLCROW, b'b']),
+                EncodedBytes::Latin1Bytes(b"c"),
+            ]
+        );
+
+        let s = DOMString::from_string(String::from("  noopener \t❤\n noreferrer"));
+        let view = s.view();
+        let tokens: Vec<_> = view.split_html_space_characters().collect();
+        assert_eq!(
+            tokens,
+            vec![
+                EncodedBytes::Utf8Bytes(b"noopener"),
+                EncodedBytes::Utf8Bytes("❤".as_bytes()),
+                EncodedBytes::Utf8Bytes(b"noreferrer"),
+            ]
+        );
+
+        let s = DOMString::from(" \t\n");
+        assert_eq!(s.view().split_html_space_characters().count(), 0);
+    }
+
+    #[test]
+    fn eq_ignore_ascii_case() {
+        assert!(DOMString::from("GET").eq_ignore_ascii_case("get"));
+        assert!(DOMString::from("utf-8").eq_ignore_ascii_case("UTF-8"));
+        assert!(!DOMString::from("utf-8").eq_ignore_ascii_case("UTF-16"));
+        assert!(DOMString::from("Ä-ok").eq_ignore_ascii_case("Ä-OK"));
+        assert!(!DOMString::from("ä").eq_ignore_ascii_case("Ä"));
+
+        let s = from_latin1(vec![b'P', b'o', b'S', b'T']);
+        assert!(s.eq_ignore_ascii_case("post"));
+        assert!(matches!(*s.0.borrow(), DOMStringType::Latin1Vec(_)));
+        let s = from_latin1(vec![LATIN1_PILLCROW, b'A']);
+        assert!(s.eq_ignore_ascii_case("¶a"));
+        assert!(!s.eq_ignore_ascii_case("❤a"));
+        assert!(EncodedBytes::Latin1Bytes(&[LATIN1_POWER2, b'x']).eq_ignore_ascii_case("²X"));
+    }
+
+    #[test]
+    fn serde() {
+        let s = from_latin1(vec![b'a', LATIN1_PILLCROW]);
+        let json = serde_json::to_string(&s).unwrap();
+        assert_eq!(json, "\"a¶\"");
+        let s: DOMString = serde_json::from_str(&json).unwrap();
+        assert!(matches!(*s.0.borrow(), DOMStringType::Rust(_)));
+        assert_eq!(s, "a¶");
+    }
+
+    #[test]
+    fn into_sendable() {
+        let sendable = from_latin1(vec![b'a', LATIN1_PILLCROW]).into_sendable();
+        assert_eq!(sendable, SendableDOMString::String(String::from("a¶")));
+
+        let sendable = DOMString::from("ltr").intern().into_sendable();
+        assert_eq!(sendable, SendableDOMString::Atom(Atom::from("ltr")));
+        assert_eq!(&*sendable, "ltr");
+
+        let sendable = std::thread::spawn(move || {
+            assert_eq!(&*sendable, "ltr");
+            sendable
+        })
+        .join()
+        .unwrap();
+        assert_eq!(DOMString::from(sendable), "ltr");
+    }
+
+    #[test]
+    fn valid_floating_point_number_strings() {
+        for valid in [
+            "0", "-1", "1.5", ".5", "-.5", "1e10", "1E-3", "2.5e+2", "007",
+        ] {
+            assert!(
+                DOMString::from(valid).is_valid_floating_point_number_string(),
+                "{valid}"
+            );
+        }
+        for invalid in [
+            "", "-", ".", "1.", "+1", "1e", "1e+", "e1", " 1", "1 ", "--1", "1.2.3", "1e400", "١",
+        ] {
+            assert!(
+                !DOMString::from(invalid).is_valid_floating_point_number_string(),
+                "{invalid}"
+            );
+        }
+
+        let s = from_latin1(b"-12.5e3".to_vec());
+        assert!(s.is_valid_floating_point_number_string());
+        assert!(matches!(*s.0.borrow(), DOMStringType::Latin1Vec(_)));
+        assert!(!from_latin1(vec![b'1', LATIN1_POWER2]).is_valid_floating_point_number_string());
+    }
+
+    #[test]
+    fn push_insert_truncate() {
+        let mut s = from_latin1(vec![b'a', LATIN1_PILLCROW]);
+        s.push('❤');
+        assert_eq!(s, "a¶❤");
+        s.insert_str(1, "bc");
+        assert_eq!(s, "abc¶❤");
+        assert_eq!(s.len_utf16(), Utf16CodeUnitLength(5));
+        s.truncate(5);
+        assert_eq!(s, "abc¶");
+        s.truncate(10);
+        assert_eq!(s, "abc¶");
+
+        // Truncating to the current length keeps the string as it is.
+        let mut s = from_latin1(vec![b'a', LATIN1_PILLCROW]);
+        s.truncate(3);
+        assert!(matches!(*s.0.borrow(), DOMStringType::Latin1Vec(_)));
+
+        let mut s = DOMString::from("ltr").intern();
+        s.push('!');
+        assert_eq!(s, "ltr!");
+    }
+
+    #[test]
+    fn builder() {
+        let mut builder = DOMStringBuilder::new();
+        builder.push_str("a");
+        builder.push_domstring(from_latin1(vec![b'b', LATIN1_PILLCROW]));
+        builder.push('❤');
+        builder.push_domstring(DOMString::from("c"));
+        builder.push_domstring(DOMString::from_utf16(
+            &[0xD800],
+            UnpairedSurrogatePolicy::PreserveAsWTF8,
+        ));
+        let len = builder.len;
+        let s = builder.build();
+        assert_eq!(s, "ab¶❤c\u{FFFD}");
+        assert_eq!(s.len(), len);
+        assert_eq!(DOMStringBuilder::new().build(), "");
+    }
+
+    #[test]
+    fn as_cow() {
+        let s = from_latin1(vec![b'a', LATIN1_PILLCROW]);
+        {
+            let cow = s.as_cow();
+            assert!(matches!(cow.0, CowStringViewInner::Owned(_)));
+            assert_eq!(&*cow, "a¶");
+        }
+        assert!(matches!(*s.0.borrow(), DOMStringType::Latin1Vec(_)));
+        assert_eq!(s.as_cow().into_owned(), "a¶");
+
+        let s = from_latin1(b"abc".to_vec());
+        let cow = s.as_cow();
+        assert!(matches!(cow.0, CowStringViewInner::Borrowed(_)));
+        assert_eq!(&*cow, "abc");
+
+        let s = DOMString::from_string(String::from("❤"));
+        let cow = s.as_cow();
+        assert!(matches!(cow.0, CowStringViewInner::Borrowed(_)));
+        assert_eq!(cow.into_owned(), "❤");
+    }
+
     #[test]
     fn test_convert() {
         let s = from_latin1(vec![b'a', b'b', b'c', b'%', b'$']);
@@ -1187,17 +2471,8 @@ mod tests {
 
     // We need to be extra careful here as two strings that have different
     // representation need to have the same hash.
-    // Additionally, the interior mutability is only used for the conversion
-    // which is forced by Hash. Hence, it is safe to have this interior mutability.
     #[test]
     fn test_hash() {
-        use std::hash::{DefaultHasher, Hash, Hasher};
-        fn hash_value(d: &DOMString) -> u64 {
-            let mut hasher = DefaultHasher::new();
-            d.hash(&mut hasher);
-            hasher.finish()
-        }
-
         let s = from_latin1(vec![b'a', b'b', b'c', b'%', b'$', 0xB2]);
         let s_converted = from_latin1(vec![b'a', b'b', b'c', b'%', b'$', 0xB2]);
         s_converted.make_rust();
@@ -1211,6 +2486,32 @@ mod tests {
         assert_eq!(hash_s, hash_s_converted);
     }
 
+    #[test]
+    fn unpaired_surrogates_are_compared_and_hashed_without_conversion() {
+        let high = DOMString::from_utf16(&[0x61, 0xD800], UnpairedSurrogatePolicy::PreserveAsWTF8);
+        let low = DOMString::from_utf16(&[0x61, 0xDC00], UnpairedSurrogatePolicy::PreserveAsWTF8);
+        let replaced = DOMString::from("a\u{FFFD}");
+
+        assert_ne!(hash_value(&high), hash_value(&low));
+        assert_ne!(hash_value(&high), hash_value(&replaced));
+        assert_eq!(hash_value(&high), hash_value(&high.clone()));
+        assert!(high != low);
+        assert!(high != replaced);
+        assert!(high == high.clone());
+        assert!(high < low);
+        assert!(low < replaced);
+
+        // Hashing and comparing keeps the unpaired surrogates, so the strings still differ.
+        assert!(matches!(*high.0.borrow(), DOMStringType::Wtf8Vec(_)));
+        assert!(matches!(*low.0.borrow(), DOMStringType::Wtf8Vec(_)));
+        assert!(high != low);
+        assert_ne!(hash_value(&high), hash_value(&low));
+
+        // Latin1 and utf8 strings still compare by their code points.
+        assert!(from_latin1(vec![b'a', LATIN1_POWER2]) < DOMString::from("a❤"));
+        assert!(from_latin1(vec![b'a', LATIN1_POWER2]) == DOMString::from("a²"));
+    }
+
     // Testing match_lazydomstring if it executes the statements in the match correctly
     #[test]
     fn test_match_executing() {
@@ -1356,9 +2657,22 @@ mod tests {
             ]);
 
             s.strip_leading_and_trailing_ascii_whitespace();
+            assert!(matches!(*s.0.borrow(), DOMStringType::Latin1Vec(_)));
+            assert_eq!(s.len_utf16(), Utf16CodeUnitLength(6));
             s.make_rust();
             assert_eq!(&*s.str(), "abc%$²");
         }
+        {
+            let mut s = from_latin1(vec![b' ', b'\t', b'\n']);
+            s.strip_leading_and_trailing_ascii_whitespace();
+            assert!(s.is_empty());
+        }
+        {
+            let mut s = DOMString::from("\t❤ ❤\n").intern();
+            s.strip_leading_and_trailing_ascii_whitespace();
+            assert!(matches!(*s.0.borrow(), DOMStringType::Inline(_)));
+            assert_eq!(s, "❤ ❤");
+        }
         {
             let mut s = DOMString::from_string(String::from("   \n  abc%$ "));
 
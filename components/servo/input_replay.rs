@@ -0,0 +1,70 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use base::id::WebViewId;
+use compositing::PaintRecording;
+use embedder_traits::InputEvent;
+
+use crate::WebView;
+
+/// Sends the input events of a [`PaintRecording`], returned by
+/// [`crate::Servo::stop_paint_recording`], again against a virtual clock. The clock only moves
+/// when the embedder calls [`InputEventReplayer::advance`], so the events are sent in the same
+/// order relative to the frames the embedder paints each time, no matter how long each step
+/// really takes.
+///
+/// This does not replay the renderer itself: messages and frames of the recording are not
+/// sent again, but they can be compared against a new recording taken while replaying.
+pub struct InputEventReplayer {
+    /// The recorded input events that have not been sent yet, in order.
+    pending_events: VecDeque<(Duration, WebViewId, InputEvent)>,
+    /// The time of the virtual clock, relative to the start of the recording.
+    now: Duration,
+}
+
+impl InputEventReplayer {
+    pub fn new(recording: &PaintRecording) -> Self {
+        Self {
+            pending_events: recording
+                .input_events()
+                .map(|(time, webview_id, event)| (time, webview_id, event.clone()))
+                .collect(),
+            now: Duration::ZERO,
+        }
+    }
+
+    /// Move the virtual clock forward by `delta` and send every input event that was
+    /// recorded up to the new time. Since [`WebView`] ids change across sessions,
+    /// `webview_for_id` is used to find the [`WebView`] that replaces each recorded one.
+    /// Events for recorded [`WebView`]s without a replacement are skipped. Returns `true` if
+    /// there are events left to replay.
+    pub fn advance(
+        &mut self,
+        delta: Duration,
+        webview_for_id: impl Fn(WebViewId) -> Option<WebView>,
+    ) -> bool {
+        self.now += delta;
+        while self
+            .pending_events
+            .front()
+            .is_some_and(|(time, _, _)| *time <= self.now)
+        {
+            let Some((_, webview_id, event)) = self.pending_events.pop_front() else {
+                break;
+            };
+            if let Some(webview) = webview_for_id(webview_id) {
+                webview.notify_input_event(event);
+            }
+        }
+        !self.pending_events.is_empty()
+    }
+
+    /// The time of the virtual clock, relative to the start of the recording.
+    pub fn time(&self) -> Duration {
+        self.now
+    }
+}
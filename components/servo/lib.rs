@@ -13,9 +13,12 @@
 //! `ScriptThread` and the `LayoutThread`, as well maintains the navigation context.
 
 mod clipboard_delegate;
+mod input_replay;
 mod javascript_evaluator;
 mod network_manager;
+mod paint_state;
 mod proxies;
+mod rendering_group;
 mod responders;
 mod servo;
 mod servo_delegate;
@@ -26,9 +29,17 @@ mod webview_delegate;
 
 // These are Servo's public exports. Everything (apart from a couple exceptions below)
 // should be exported at the root. See <https://github.com/servo/servo/issues/18475>.
-pub use base::generic_channel::GenericSender;
-pub use base::id::WebViewId;
-pub use compositing::WebRenderDebugOption;
+pub use base::cross_process_instant::CrossProcessInstant;
+pub use base::generic_channel::{GenericSender, GenericSharedMemory};
+pub use base::id::{PainterId, WebViewId};
+pub use compositing::{
+    CapturedFrame, DisplayListStatistics, INPUT_LATENCY_BUCKET_BOUNDS, InputLatencyHistogram,
+    OcclusionNotifier, PaintBackground, PaintMessageTraceEntry, PaintRecording, PainterDebugState,
+    RecordedPaintEvent, RecordedPaintEventKind, RenderingPriority, ShaderPrecachePolicy,
+    SharedMemoryCapture, TextAntialiasing, WebRenderDebugOption, WebViewBorder, WebViewClip,
+    WebViewDecorations, WebViewLifecycleEvent, WebViewPaintTiming, WebViewShadow,
+};
+pub use compositing_traits::RendererStatistics;
 pub use compositing_traits::rendering_context::{
     OffscreenRenderingContext, RenderingContext, SoftwareRenderingContext, WindowRenderingContext,
 };
@@ -56,11 +67,16 @@ pub use servo_geometry::{
 pub use servo_url::ServoUrl;
 pub use style::Zero;
 pub use style_traits::CSSPixel;
+pub use webrender::RendererError;
 pub use webrender_api::units::{
     DeviceIntPoint, DeviceIntRect, DeviceIntSize, DevicePixel, DevicePoint, DeviceVector2D,
 };
+pub use webrender_api::{BorderRadius, ColorF};
 
+pub use crate::input_replay::InputEventReplayer;
 pub use crate::network_manager::{CacheEntry, NetworkManager};
+pub use crate::paint_state::{PaintState, RenderingGroupState, WebViewPaintState};
+pub use crate::rendering_group::{RenderingGroup, RenderingGroupBuilder};
 pub use crate::servo::{Servo, ServoBuilder, run_content_process};
 pub use crate::servo_delegate::{ServoDelegate, ServoError};
 pub use crate::site_data_manager::{SiteData, SiteDataManager, StorageType};
@@ -0,0 +1,52 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use base::id::WebViewId;
+use serde::{Deserialize, Serialize};
+use webrender_api::units::DeviceIntRect;
+
+/// A serializable snapshot of how Servo arranges `WebView`s, returned by
+/// [`crate::Servo::serialize_paint_state`]. Embedders can persist this and pass it to
+/// [`crate::Servo::restore_paint_state`] to restore a multi-window, multi-`WebView` layout
+/// after a restart.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct PaintState {
+    /// The state of every [`crate::RenderingGroup`] that has at least one `WebView`.
+    pub rendering_groups: Vec<RenderingGroupState>,
+}
+
+impl PaintState {
+    /// The saved state of the [`crate::RenderingGroup`] with the given key, if any. See
+    /// [`crate::RenderingGroupBuilder::key`].
+    pub fn rendering_group(&self, key: &str) -> Option<&RenderingGroupState> {
+        self.rendering_groups
+            .iter()
+            .find(|rendering_group| rendering_group.key.as_deref() == Some(key))
+    }
+}
+
+/// The arrangement of the `WebView`s of a single [`crate::RenderingGroup`].
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct RenderingGroupState {
+    /// The key of this group, set with [`crate::RenderingGroupBuilder::key`]. Unlike the
+    /// order of the groups, which depends on when their WebRender instances were created, the
+    /// key stays the same across restarts, so embedders should use it to find the group that
+    /// corresponds to this state. This is `None` for groups created without a key.
+    #[serde(default)]
+    pub key: Option<String>,
+    /// The `WebView`s of this group in painting order, from bottom to top.
+    pub webviews: Vec<WebViewPaintState>,
+}
+
+/// The arrangement of a single `WebView` within its [`crate::RenderingGroup`].
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct WebViewPaintState {
+    /// The id of the `WebView` when the state was serialized. `WebView` ids are not stable
+    /// across restarts, so embedders must map this to a new `WebView` when restoring.
+    pub webview_id: WebViewId,
+    /// The rectangle of the `WebView` or `None` if it fills its entire `RenderingContext`.
+    pub rect: Option<DeviceIntRect>,
+    /// Whether or not the `WebView` is hidden.
+    pub hidden: bool,
+}
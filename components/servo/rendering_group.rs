@@ -0,0 +1,789 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use std::cell::{Cell, RefCell};
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::sync::mpsc::Receiver;
+use std::time::Duration;
+
+use base::id::{PainterId, WebViewId};
+use compositing::{CapturedFrame, OcclusionNotifier, Paint, PaintError, PainterOptions};
+use compositing_traits::RendererStatistics;
+use compositing_traits::rendering_context::RenderingContext;
+use dpi::PhysicalSize;
+use embedder_traits::{PaintHitTestResult, ScreenshotCaptureError};
+use euclid::Scale;
+use image::RgbaImage;
+use log::warn;
+use servo_geometry::DeviceIndependentPixel;
+use webrender_api::units::{DeviceIntRect, DevicePixel, DevicePoint, DeviceRect};
+
+use crate::webview::MINIMUM_WEBVIEW_SIZE;
+use crate::{
+    PaintBackground, RenderingPriority, Servo, ShaderPrecachePolicy, SharedMemoryCapture,
+    TextAntialiasing, WebRenderDebugOption, WebView,
+};
+
+/// A [`RenderingGroup`] represents a single [`RenderingContext`], such as one for a native
+/// window, and all of the `WebView`s that paint to it. Each [`RenderingGroup`] has its own
+/// WebRender instance. Embedders that support multiple windows should create one
+/// [`RenderingGroup`] per window and place `WebView`s into it using
+/// [`crate::WebViewBuilder::new_in_group`]. To split a single window into several panes with
+/// independent WebRender instances, create a [`RenderingGroup`] for each pane using a
+/// [`crate::WindowRenderingContext::viewport_context`].
+#[derive(Clone)]
+pub struct RenderingGroup {
+    servo: Servo,
+    /// The [`RenderingContext`] of this [`RenderingGroup`]. This is shared between all clones
+    /// of the [`RenderingGroup`], so that [`RenderingGroup::replace_rendering_context`] affects
+    /// all of them.
+    rendering_context: Rc<RefCell<Rc<dyn RenderingContext>>>,
+    /// The [`PainterOptions`] used to create the WebRender instance of this group.
+    painter_options: PainterOptions,
+    /// The HiDPI scale factor of the display that this [`RenderingGroup`] is shown on, if set
+    /// with [`RenderingGroup::set_hidpi_scale_factor`]. This is shared between all clones of
+    /// the [`RenderingGroup`].
+    hidpi_scale_factor: Rc<Cell<Option<Scale<f32, DeviceIndependentPixel, DevicePixel>>>>,
+    /// The settings made on this [`RenderingGroup`], which are shared between all of its
+    /// clones. They are applied again whenever its `Painter` is recreated.
+    settings: Rc<RefCell<RenderingGroupSettings>>,
+    /// The key of this [`RenderingGroup`]. See [`RenderingGroupBuilder::key`].
+    key: Option<String>,
+}
+
+/// An overlay drawing callback set with [`RenderingGroup::set_overlay_drawing_callback`]. It
+/// is shared, so that it can be handed to every `Painter` created for the group.
+type OverlayDrawingCallback = Rc<RefCell<dyn FnMut(&dyn RenderingContext)>>;
+
+/// The settings of a [`RenderingGroup`] that belong to its `Painter`. The `Painter` is removed
+/// when the last `WebView` of the group is closed, so these are kept here and applied to the
+/// new `Painter` when a `WebView` is added again. `None` means that the embedder did not
+/// change the setting, so the default of the `Painter` is kept.
+#[derive(Default)]
+struct RenderingGroupSettings {
+    resize_present_timeout: Option<Option<Duration>>,
+    rendering_paused: Option<bool>,
+    background: Option<PaintBackground>,
+    webview_limit: Option<Option<usize>>,
+    vsync_driven: Option<bool>,
+    priority: Option<RenderingPriority>,
+    text_antialiasing: Option<TextAntialiasing>,
+    webrender_debug: Vec<(WebRenderDebugOption, bool)>,
+    draws_cursor: Option<bool>,
+    overlay_drawing_callback: Option<OverlayDrawingCallback>,
+    focused: Option<bool>,
+    all_hidden: Option<bool>,
+}
+
+impl RenderingGroupSettings {
+    fn set_webrender_debug(&mut self, option: WebRenderDebugOption, enabled: bool) {
+        self.webrender_debug
+            .retain(|(existing_option, _)| *existing_option != option);
+        self.webrender_debug.push((option, enabled));
+    }
+
+    fn webrender_debug(&self, option: WebRenderDebugOption) -> Option<bool> {
+        self.webrender_debug
+            .iter()
+            .find(|(existing_option, _)| *existing_option == option)
+            .map(|(_, enabled)| *enabled)
+    }
+}
+
+impl RenderingGroup {
+    pub fn new(servo: &Servo, rendering_context: Rc<dyn RenderingContext>) -> Self {
+        RenderingGroupBuilder::new(servo, rendering_context).build()
+    }
+
+    pub(crate) fn painter_options(&self) -> &PainterOptions {
+        &self.painter_options
+    }
+
+    /// The [`PainterId`] of the `Painter` for this [`RenderingGroup`], which identifies it in
+    /// [`crate::ServoError`]s. This can change over time, as the `Painter` is removed when the
+    /// last `WebView` of a `RenderingContext` is closed and recreated when a new `WebView` is
+    /// added. Returns `None` while there is no `Painter`.
+    pub fn painter_id(&self) -> Option<PainterId> {
+        self.servo
+            .paint()
+            .painter_id_for_rendering_context(&self.rendering_context.borrow())
+    }
+
+    /// Call `callback` with the `Painter` of this [`RenderingGroup`] and return its result.
+    /// Returns `None` if the group has no `Painter` at the moment or if `callback` fails, in
+    /// which case the failure to perform `action` is logged.
+    fn with_painter<T>(
+        &self,
+        action: &str,
+        callback: impl FnOnce(&Paint, PainterId) -> Result<T, PaintError>,
+    ) -> Option<T> {
+        let painter_id = self.painter_id()?;
+        callback(&self.servo.paint(), painter_id)
+            .inspect_err(|error| warn!("Could not {action}: {error:?}"))
+            .ok()
+    }
+
+    /// Apply the settings made on this [`RenderingGroup`] to its `Painter`. This is called
+    /// when a `WebView` placed into the group creates a new `Painter`, so that settings made
+    /// before the group had any `WebView`s, or before its previous `Painter` was removed, are
+    /// not lost.
+    pub(crate) fn apply_settings(&self) {
+        let settings = self.settings.borrow();
+        if let Some(timeout) = settings.resize_present_timeout {
+            self.apply_resize_present_timeout(timeout);
+        }
+        if let Some(paused) = settings.rendering_paused {
+            self.apply_rendering_paused(paused);
+        }
+        if let Some(background) = settings.background.clone() {
+            self.apply_background(background);
+        }
+        if let Some(webview_limit) = settings.webview_limit {
+            self.apply_webview_limit(webview_limit);
+        }
+        if let Some(vsync_driven) = settings.vsync_driven {
+            self.apply_vsync_driven(vsync_driven);
+        }
+        if let Some(priority) = settings.priority {
+            self.apply_priority(priority);
+        }
+        if let Some(text_antialiasing) = settings.text_antialiasing {
+            self.apply_text_antialiasing(text_antialiasing);
+        }
+        for (option, enabled) in settings.webrender_debug.iter().copied() {
+            self.apply_webrender_debug(option, enabled);
+        }
+        if let Some(draws_cursor) = settings.draws_cursor {
+            self.apply_draws_cursor(draws_cursor);
+        }
+        if let Some(callback) = settings.overlay_drawing_callback.clone() {
+            self.apply_overlay_drawing_callback(Some(callback));
+        }
+        // Another group may have been focused since, in which case it keeps the focus.
+        if settings.focused == Some(true) && self.servo.paint().focused_painter().is_none() {
+            self.apply_focused(true);
+        }
+        if settings.all_hidden == Some(true) {
+            self.apply_all_hidden(true);
+        }
+    }
+
+    /// The key of this [`RenderingGroup`], if it was created with one. See
+    /// [`RenderingGroupBuilder::key`].
+    pub fn key(&self) -> Option<&str> {
+        self.key.as_deref()
+    }
+
+    /// The [`RenderingContext`] that every `WebView` in this [`RenderingGroup`] paints to.
+    pub fn rendering_context(&self) -> Rc<dyn RenderingContext> {
+        self.rendering_context.borrow().clone()
+    }
+
+    /// Replace the [`RenderingContext`] of this [`RenderingGroup`], for instance after the
+    /// native surface of its window was lost. See [`Servo::replace_rendering_context`].
+    pub fn replace_rendering_context(&self, new_rendering_context: Rc<dyn RenderingContext>) {
+        let old_rendering_context = self.rendering_context();
+        self.servo
+            .replace_rendering_context(&old_rendering_context, new_rendering_context.clone());
+        *self.rendering_context.borrow_mut() = new_rendering_context;
+    }
+
+    /// The `WebView`s in this [`RenderingGroup`] in painting order, from bottom to top.
+    pub fn webviews(&self) -> Vec<WebView> {
+        self.with_painter("get WebViews", |paint, painter_id| {
+            paint.webviews_in_painter(painter_id)
+        })
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|webview_id| self.servo.get_webview_handle(webview_id))
+        .collect()
+    }
+
+    /// Resize the [`RenderingContext`] of this [`RenderingGroup`]. See
+    /// [`Servo::resize_rendering_context`].
+    pub fn resize(&self, new_size: PhysicalSize<u32>) {
+        self.servo
+            .resize_rendering_context(&self.rendering_context(), new_size);
+    }
+
+    /// Resize the [`RenderingContext`] of this [`RenderingGroup`] like
+    /// [`RenderingGroup::resize`], and call `callback` right after the first frame at the new
+    /// size, with every visible `WebView` that fills the [`RenderingContext`] laid out at that
+    /// size, has been presented.
+    ///
+    /// This is meant for embedders with their own compositor, which present each
+    /// [`RenderingGroup`] in a child surface of their window, such as a Wayland subsurface, a
+    /// child `HWND` or a `CALayer`, using a `WindowRenderingContext` created for that child
+    /// surface. They can wait for `callback` before committing the new size of the parent
+    /// surface, so that the window never shows the contents of this [`RenderingGroup`]
+    /// stretched or cropped. A pending resize is superseded by the next one, in which case its
+    /// `callback` is never called.
+    pub fn resize_synchronized(
+        &self,
+        new_size: PhysicalSize<u32>,
+        callback: impl FnOnce() + 'static,
+    ) {
+        let new_size = PhysicalSize {
+            width: new_size.width.max(MINIMUM_WEBVIEW_SIZE.width as u32),
+            height: new_size.height.max(MINIMUM_WEBVIEW_SIZE.height as u32),
+        };
+        self.with_painter("resize RenderingContext", |paint, painter_id| {
+            paint.resize_painter_rendering_context_synchronized(
+                painter_id,
+                new_size,
+                Box::new(callback),
+            )
+        });
+    }
+
+    /// Hold back the frames of this [`RenderingGroup`] for up to `timeout` after each resize,
+    /// until every visible `WebView` that fills its [`RenderingContext`] has been laid out at
+    /// the new size, or stop doing so with `None`, which is the default. This is meant for
+    /// interactive window resizes, during which the contents laid out for the previous size
+    /// would otherwise be shown stretched or cropped over the resized surface. While frames
+    /// are held back, [`RenderingGroup::present`] and [`crate::WebView::paint`] do nothing and
+    /// leave the previous frame in place. Once `timeout` has passed, frames are shown again
+    /// even if the content has not caught up, so that a slow page cannot freeze the window.
+    pub fn set_resize_present_timeout(&self, timeout: Option<Duration>) {
+        self.settings.borrow_mut().resize_present_timeout = Some(timeout);
+        self.apply_resize_present_timeout(timeout);
+    }
+
+    fn apply_resize_present_timeout(&self, timeout: Option<Duration>) {
+        self.with_painter("set resize present timeout", |paint, painter_id| {
+            paint.set_painter_resize_present_timeout(painter_id, timeout)
+        });
+    }
+
+    /// The HiDPI scale factor set with [`RenderingGroup::set_hidpi_scale_factor`], if any.
+    /// `WebView`s placed into this [`RenderingGroup`] with
+    /// [`crate::WebViewBuilder::new_in_group`] start with this scale factor.
+    pub fn hidpi_scale_factor(&self) -> Option<Scale<f32, DeviceIndependentPixel, DevicePixel>> {
+        self.hidpi_scale_factor.get()
+    }
+
+    /// Notify Servo that the window of this [`RenderingGroup`] moved to a display with a
+    /// different HiDPI scale factor, for instance when it is dragged from a 1x to a 2x
+    /// monitor. Every `WebView` of this [`RenderingGroup`] is laid out again for the new
+    /// device pixel ratio and re-rasterized, and hit testing uses the new scale factor right
+    /// away. `WebView`s of other [`RenderingGroup`]s are not affected. See
+    /// [`WebView::set_hidpi_scale_factor`].
+    pub fn set_hidpi_scale_factor(
+        &self,
+        new_scale_factor: Scale<f32, DeviceIndependentPixel, DevicePixel>,
+    ) {
+        self.hidpi_scale_factor.set(Some(new_scale_factor));
+        for webview in self.webviews() {
+            webview.set_hidpi_scale_factor(new_scale_factor);
+        }
+    }
+
+    /// Notify Servo that the window of this [`RenderingGroup`] is occluded or visible again.
+    /// See [`Servo::set_rendering_context_occluded`].
+    pub fn set_occluded(&self, occluded: bool) {
+        self.servo
+            .set_rendering_context_occluded(&self.rendering_context(), occluded);
+    }
+
+    /// Get an [`OcclusionNotifier`] for this [`RenderingGroup`], which can be moved to another
+    /// thread in order to forward occlusion and minimization events for its window as soon as
+    /// the operating system reports them. While occluded, this [`RenderingGroup`] generates
+    /// and presents no new frames and the animations of its `WebView`s do not tick. Returns
+    /// `None` if this [`RenderingGroup`] has no `WebView`s yet.
+    pub fn occlusion_notifier(&self) -> Option<OcclusionNotifier> {
+        let painter_id = self.painter_id()?;
+        Some(self.servo.paint().occlusion_notifier(painter_id))
+    }
+
+    /// Pause rendering for this [`RenderingGroup`]. No transactions are sent to its WebRender
+    /// instance and nothing is rendered until [`RenderingGroup::resume_rendering`] is called,
+    /// so a paused [`RenderingGroup`] does not use any CPU or GPU time for painting.
+    pub fn pause_rendering(&self) {
+        self.set_rendering_paused(true);
+    }
+
+    /// Resume rendering for this [`RenderingGroup`] after a call to
+    /// [`RenderingGroup::pause_rendering`]. Any changes to the contents of its `WebView`s made
+    /// while paused are applied and a new frame is produced.
+    pub fn resume_rendering(&self) {
+        self.set_rendering_paused(false);
+    }
+
+    fn set_rendering_paused(&self, paused: bool) {
+        self.settings.borrow_mut().rendering_paused = Some(paused);
+        self.apply_rendering_paused(paused);
+    }
+
+    fn apply_rendering_paused(&self, paused: bool) {
+        self.with_painter("change rendering paused state", |paint, painter_id| {
+            paint.set_rendering_paused(painter_id, paused)
+        });
+    }
+
+    /// Set the [`PaintBackground`] painted below all of the `WebView`s of this
+    /// [`RenderingGroup`], for instance a branded backdrop that is visible before the first
+    /// `WebView` has finished loading.
+    pub fn set_background(&self, background: PaintBackground) {
+        self.settings.borrow_mut().background = Some(background.clone());
+        self.apply_background(background);
+    }
+
+    fn apply_background(&self, background: PaintBackground) {
+        self.with_painter("set background", |paint, painter_id| {
+            paint.set_painter_background(painter_id, background)
+        });
+    }
+
+    /// Hide every visible `WebView` in this [`RenderingGroup`], for instance when its window
+    /// is minimized. Use [`RenderingGroup::show_all`] to restore them. The painting order of
+    /// the `WebView`s is not affected.
+    pub fn hide_all(&self) {
+        self.set_all_hidden(true);
+    }
+
+    /// Show every `WebView` that was hidden by the last call to [`RenderingGroup::hide_all`],
+    /// so that the [`RenderingGroup`] looks exactly like it did before. `WebView`s that were
+    /// already hidden at that point stay hidden.
+    pub fn show_all(&self) {
+        self.set_all_hidden(false);
+    }
+
+    fn set_all_hidden(&self, all_hidden: bool) {
+        self.settings.borrow_mut().all_hidden = Some(all_hidden);
+        self.apply_all_hidden(all_hidden);
+    }
+
+    fn apply_all_hidden(&self, all_hidden: bool) {
+        if all_hidden {
+            self.with_painter("hide all WebViews", |paint, painter_id| {
+                paint.hide_all_webviews(painter_id)
+            });
+        } else {
+            self.with_painter("show all WebViews", |paint, painter_id| {
+                paint.show_all_webviews(painter_id)
+            });
+        }
+    }
+
+    /// Limit the number of `WebView`s of this [`RenderingGroup`] that are not suspended, or
+    /// pass `None` to remove the limit. When there are more, hidden `WebView`s are suspended,
+    /// starting with the one that was least recently shown. Suspended `WebView`s are throttled
+    /// until they are shown again. The [`crate::ServoDelegate`] is notified of both with
+    /// [`crate::WebViewLifecycleEvent`]s.
+    pub fn set_webview_limit(&self, webview_limit: Option<usize>) {
+        self.settings.borrow_mut().webview_limit = Some(webview_limit);
+        self.apply_webview_limit(webview_limit);
+    }
+
+    fn apply_webview_limit(&self, webview_limit: Option<usize>) {
+        self.with_painter("set WebView limit", |paint, painter_id| {
+            paint.set_webview_limit(painter_id, webview_limit)
+        });
+    }
+
+    /// Show a copy of a `WebView` from another [`RenderingGroup`] in `rect` of this one, for
+    /// instance to show a presentation both in a presenter window and on a projector. The
+    /// copy is updated every time the `WebView` is painted in its own group, without loading
+    /// its content twice. Mirrors are painted above all `WebView`s of this group and do not
+    /// receive input events. Calling this again for the same `WebView` moves the mirror.
+    pub fn mirror_webview(&self, webview: &WebView, rect: DeviceIntRect) {
+        self.set_webview_mirror(webview, Some(rect.to_f32()));
+    }
+
+    /// Stop showing a copy of a `WebView` that was started with
+    /// [`RenderingGroup::mirror_webview`].
+    pub fn stop_mirroring_webview(&self, webview: &WebView) {
+        self.set_webview_mirror(webview, None);
+    }
+
+    fn set_webview_mirror(&self, webview: &WebView, rect: Option<DeviceRect>) {
+        self.with_painter("mirror WebView", |paint, painter_id| {
+            paint.mirror_webview(webview.id(), painter_id, rect)
+        });
+    }
+
+    /// Render and present the contents of this [`RenderingGroup`], but only if one of its
+    /// `WebView`s has produced a new frame since the last time it was painted. Unlike calling
+    /// [`WebView::paint`] and [`RenderingContext::present`] for every window, this does not
+    /// swap the buffers of windows whose contents did not change. Returns `true` if a new
+    /// frame was presented.
+    pub fn present(&self) -> bool {
+        self.with_painter("present RenderingGroup", |paint, painter_id| {
+            paint.present_painter(painter_id)
+        })
+        .unwrap_or(false)
+    }
+
+    /// Make the next `frames` frames of this [`RenderingGroup`] fail to render, as if
+    /// WebRender had run out of memory. This allows embedders to test how they handle
+    /// [`crate::ServoError::RendererError`] and [`crate::ServoError::RenderingFailed`].
+    pub fn simulate_renderer_failure(&self, frames: u32) {
+        self.with_painter("simulate renderer failure", |paint, painter_id| {
+            paint.simulate_renderer_failure(painter_id, frames)
+        });
+    }
+
+    /// Immediately send all changes to the WebRender scene of this [`RenderingGroup`] that are
+    /// waiting to be sent. Servo batches these changes and normally sends them once for each
+    /// turn of the event loop, so this is only needed when an embedder wants changes, such as
+    /// those made by [`WebView::set_opacity`], to reach WebRender before that.
+    pub fn flush_pending(&self) {
+        self.with_painter("flush pending transactions", |paint, painter_id| {
+            paint.flush_pending_transactions(painter_id)
+        });
+    }
+
+    /// Set whether this [`RenderingGroup`] only generates new frames when the embedder calls
+    /// [`RenderingGroup::notify_vsync`]. Windows may be shown on displays with different
+    /// refresh rates, and this allows painting each one in time with its own display instead
+    /// of whenever new content is ready. Screenshots and captures also wait for vsync ticks
+    /// while this is enabled.
+    pub fn set_vsync_driven(&self, vsync_driven: bool) {
+        self.settings.borrow_mut().vsync_driven = Some(vsync_driven);
+        self.apply_vsync_driven(vsync_driven);
+    }
+
+    fn apply_vsync_driven(&self, vsync_driven: bool) {
+        self.with_painter("change vsync driven state", |paint, painter_id| {
+            paint.set_vsync_driven(painter_id, vsync_driven)
+        });
+    }
+
+    /// Notify this [`RenderingGroup`] of a vsync tick of the display that shows its window.
+    /// If new content is ready, a frame is generated for it. This has no effect unless the
+    /// group was made vsync driven with [`RenderingGroup::set_vsync_driven`].
+    pub fn notify_vsync(&self) {
+        self.with_painter("notify vsync", |paint, painter_id| {
+            paint.notify_vsync(painter_id)
+        });
+    }
+
+    /// Capture the entire contents of this [`RenderingGroup`]. See
+    /// [`Servo::capture_rendering_context`].
+    pub fn capture(
+        &self,
+        callback: impl FnOnce(Result<RgbaImage, ScreenshotCaptureError>) + 'static,
+    ) {
+        self.servo
+            .capture_rendering_context(&self.rendering_context(), callback);
+    }
+
+    /// Capture the entire contents of this [`RenderingGroup`] straight into shared memory.
+    /// See [`Servo::capture_rendering_context_to_shared_memory`].
+    pub fn capture_to_shared_memory(
+        &self,
+        callback: impl FnOnce(Result<SharedMemoryCapture, ScreenshotCaptureError>) + 'static,
+    ) {
+        self.servo
+            .capture_rendering_context_to_shared_memory(&self.rendering_context(), callback);
+    }
+
+    /// Wait for the shaders of this [`RenderingGroup`] that are compiled in the background to
+    /// be ready. See [`RenderingGroupBuilder::shader_precache_policy`]. Embedders should call
+    /// this right before the window of the group is first shown, after doing any other work
+    /// needed to set up the window, so that shader compilation overlaps with that work.
+    /// Returns `true` once the shaders are ready, or `false` if they could not be waited for,
+    /// for instance because the group has no WebRender instance before its first `WebView`
+    /// is created.
+    pub fn warm_up(&self) -> bool {
+        self.with_painter("warm up RenderingGroup", |paint, painter_id| {
+            paint.warm_up_painter(painter_id)
+        })
+        .unwrap_or(false)
+    }
+
+    /// Set whether the window of this [`RenderingGroup`] is focused. The frames of the
+    /// focused [`RenderingGroup`] are built and presented before those of all others. See
+    /// [`Servo::present_rendering_groups`].
+    pub fn set_focused(&self, focused: bool) {
+        self.settings.borrow_mut().focused = Some(focused);
+        self.apply_focused(focused);
+    }
+
+    fn apply_focused(&self, focused: bool) {
+        let Some(painter_id) = self.painter_id() else {
+            return;
+        };
+        let paint = self.servo.paint();
+        if focused {
+            paint.set_focused_painter(Some(painter_id));
+        } else if paint.focused_painter() == Some(painter_id) {
+            paint.set_focused_painter(None);
+        }
+    }
+
+    /// Whether the window of this [`RenderingGroup`] is focused. See
+    /// [`RenderingGroup::set_focused`].
+    pub fn focused(&self) -> bool {
+        self.painter_id()
+            .is_some_and(|painter_id| self.servo.paint().focused_painter() == Some(painter_id))
+    }
+
+    /// Set the [`RenderingPriority`] of this [`RenderingGroup`]. Embedders should give the
+    /// group of the focused window a high priority and those of background windows a low
+    /// one, so that heavy animations in the background cannot delay the frames of the
+    /// focused window.
+    pub fn set_priority(&self, priority: RenderingPriority) {
+        self.settings.borrow_mut().priority = Some(priority);
+        self.apply_priority(priority);
+    }
+
+    fn apply_priority(&self, priority: RenderingPriority) {
+        self.with_painter("set rendering priority", |paint, painter_id| {
+            paint.set_painter_priority(painter_id, priority)
+        });
+    }
+
+    /// Change how text is antialiased in this [`RenderingGroup`]. Subpixel antialiasing should
+    /// be disabled for groups that paint to transparent or rotated surfaces.
+    pub fn set_text_antialiasing(&self, text_antialiasing: TextAntialiasing) {
+        self.settings.borrow_mut().text_antialiasing = Some(text_antialiasing);
+        self.apply_text_antialiasing(text_antialiasing);
+    }
+
+    fn apply_text_antialiasing(&self, text_antialiasing: TextAntialiasing) {
+        self.with_painter("set text antialiasing", |paint, painter_id| {
+            paint.set_painter_text_antialiasing(painter_id, text_antialiasing)
+        });
+    }
+
+    /// Toggle a WebRender debugging option, such as the profiler overlay, for this
+    /// [`RenderingGroup`] only. This makes it possible to compare the rendering performance
+    /// of several windows side by side.
+    pub fn toggle_webrender_debug(&self, option: WebRenderDebugOption) {
+        let enabled = self.settings.borrow().webrender_debug(option).or_else(|| {
+            self.with_painter("get WebRender debugging", |paint, painter_id| {
+                paint.painter_webrender_debug_enabled(painter_id, option)
+            })
+        });
+        self.set_webrender_debug(option, !enabled.unwrap_or(false));
+    }
+
+    /// Enable or disable a WebRender debugging option for this [`RenderingGroup`] only.
+    pub fn set_webrender_debug(&self, option: WebRenderDebugOption, enabled: bool) {
+        self.settings
+            .borrow_mut()
+            .set_webrender_debug(option, enabled);
+        self.apply_webrender_debug(option, enabled);
+    }
+
+    fn apply_webrender_debug(&self, option: WebRenderDebugOption, enabled: bool) {
+        self.with_painter("set WebRender debugging", |paint, painter_id| {
+            paint.set_painter_webrender_debug(painter_id, option, enabled)
+        });
+    }
+
+    /// Start a continuous capture of the frames painted by this [`RenderingGroup`], sending
+    /// at most `frames_per_second` [`CapturedFrame`]s per second over the returned
+    /// [`Receiver`], which can be moved to another thread. Frames are only captured when the
+    /// group paints, so nothing is sent while its contents do not change. The capture stops
+    /// once the [`Receiver`] is dropped. Frames that are painted while a few earlier frames
+    /// are still waiting to be received are dropped. Returns `None` if nothing can be
+    /// captured because this [`RenderingGroup`] has no `WebView`s yet.
+    pub fn start_capture_stream(&self, frames_per_second: f32) -> Option<Receiver<CapturedFrame>> {
+        self.with_painter("start capture stream", |paint, painter_id| {
+            paint.start_painter_capture_stream(painter_id, frames_per_second)
+        })
+    }
+
+    /// Set whether or not this [`RenderingGroup`] draws the cursor on top of its `WebView`s, at
+    /// the last position that the mouse moved over and with the shape most recently requested
+    /// by the page under it. Embedders should enable this for groups that are not shown in a
+    /// native window with an OS cursor, such as headless or remote sessions, so that captured
+    /// frames and streams show pointer feedback. This is disabled by default.
+    pub fn set_draws_cursor(&self, draws_cursor: bool) {
+        self.settings.borrow_mut().draws_cursor = Some(draws_cursor);
+        self.apply_draws_cursor(draws_cursor);
+    }
+
+    fn apply_draws_cursor(&self, draws_cursor: bool) {
+        self.with_painter(
+            "set whether RenderingGroup draws the cursor",
+            |paint, painter_id| paint.set_painter_draws_cursor(painter_id, draws_cursor),
+        );
+    }
+
+    /// Start writing every frame painted by this [`RenderingGroup`] to `directory` as a
+    /// sequence of numbered PNG images, whose names also contain the time at which each frame
+    /// was painted. The directory is created if needed. This slows down painting, so it is
+    /// only meant for producing artifacts when debugging rendering issues.
+    pub fn start_frame_recording(&self, directory: PathBuf) {
+        self.with_painter("start frame recording", |paint, painter_id| {
+            paint.start_painter_frame_recording(painter_id, directory)
+        });
+    }
+
+    /// Stop the frame recording started by [`RenderingGroup::start_frame_recording`], waiting
+    /// for all recorded frames to be written.
+    pub fn stop_frame_recording(&self) {
+        self.with_painter("stop frame recording", |paint, painter_id| {
+            paint.stop_painter_frame_recording(painter_id)
+        });
+    }
+
+    /// Draw on top of every frame of this [`RenderingGroup`] with `callback`, which is
+    /// called after Servo has rendered a frame and right before it is presented. The
+    /// [`RenderingContext`] of the group is passed to the callback and is current, so
+    /// embedders can use its OpenGL API to draw their own user interface, such as toolbars,
+    /// input method candidate windows or debug overlays, into the same surface as the web
+    /// content. The callback must restore any OpenGL state it changes and must not call
+    /// into Servo. This replaces any previous callback.
+    ///
+    /// While a callback is set, every frame is repainted in full, because the overlay may
+    /// have changed anywhere.
+    pub fn set_overlay_drawing_callback(
+        &self,
+        callback: impl FnMut(&dyn RenderingContext) + 'static,
+    ) {
+        self.set_painter_overlay_drawing_callback(Some(Rc::new(RefCell::new(callback))));
+    }
+
+    /// Stop drawing the overlay set with [`RenderingGroup::set_overlay_drawing_callback`].
+    pub fn clear_overlay_drawing_callback(&self) {
+        self.set_painter_overlay_drawing_callback(None);
+    }
+
+    fn set_painter_overlay_drawing_callback(&self, callback: Option<OverlayDrawingCallback>) {
+        self.settings.borrow_mut().overlay_drawing_callback = callback.clone();
+        self.apply_overlay_drawing_callback(callback);
+    }
+
+    fn apply_overlay_drawing_callback(&self, callback: Option<OverlayDrawingCallback>) {
+        let callback = callback.map(|callback| {
+            Box::new(move |rendering_context: &dyn RenderingContext| {
+                (&mut *callback.borrow_mut())(rendering_context)
+            }) as Box<dyn FnMut(&dyn RenderingContext)>
+        });
+        self.with_painter("set overlay drawing callback", |paint, painter_id| {
+            paint.set_painter_overlay_drawing_callback(painter_id, callback)
+        });
+    }
+
+    /// Find the `WebView` of this [`RenderingGroup`] that shows what is painted at `point`,
+    /// given in the coordinate space of its [`RenderingContext`], along with the hit test
+    /// results for that `WebView`. When `WebView`s overlap, this is the topmost visible one
+    /// whose painted area contains the point, taking into account their rectangles,
+    /// transforms and clips, so embedders can use this to decide which `WebView` should
+    /// receive an input event. Returns `None` if no `WebView` is painted at the point.
+    pub fn hit_test(&self, point: DevicePoint) -> Option<(WebViewId, Vec<PaintHitTestResult>)> {
+        self.with_painter("hit test RenderingGroup", |paint, painter_id| {
+            paint.hit_test(painter_id, point)
+        })
+        .flatten()
+    }
+
+    /// The [`RendererStatistics`] reported by WebRender for the most recent frame of this
+    /// [`RenderingGroup`], such as the number of draw calls, or `None` if nothing has been
+    /// rendered yet. Other threads can request the same data via
+    /// `CrossProcessPaintApi::renderer_statistics_blocking`.
+    pub fn renderer_statistics(&self) -> Option<RendererStatistics> {
+        self.with_painter("get renderer statistics", |paint, painter_id| {
+            paint.renderer_statistics(painter_id)
+        })
+        .flatten()
+    }
+
+    /// Save a WebRender capture of this [`RenderingGroup`] for debugging. Captures are saved
+    /// into a `webrender-captures` directory in the current working directory, or the
+    /// temporary directory if that fails, with a subdirectory for each `RenderingGroup`.
+    pub fn capture_webrender(&self) {
+        self.with_painter("capture WebRender", |paint, painter_id| {
+            paint.capture_painter_webrender(painter_id)
+        });
+    }
+
+    /// Close every `WebView` in this [`RenderingGroup`] and release its WebRender resources.
+    /// This should be called when the window of this [`RenderingGroup`] is closed. See
+    /// [`Servo::unregister_rendering_context`].
+    pub fn close(&self) {
+        self.servo
+            .unregister_rendering_context(&self.rendering_context());
+    }
+}
+
+/// A builder for [`RenderingGroup`]s, which allows overriding the options used to create the
+/// WebRender instance of the group.
+pub struct RenderingGroupBuilder {
+    servo: Servo,
+    rendering_context: Rc<dyn RenderingContext>,
+    painter_options: PainterOptions,
+    key: Option<String>,
+}
+
+impl RenderingGroupBuilder {
+    pub fn new(servo: &Servo, rendering_context: Rc<dyn RenderingContext>) -> Self {
+        Self {
+            servo: servo.clone(),
+            rendering_context,
+            painter_options: Default::default(),
+            key: None,
+        }
+    }
+
+    /// A key that identifies the [`RenderingGroup`] across restarts, for instance the id that
+    /// the embedder uses to save the window of the group. It is included in the
+    /// [`crate::PaintState`] returned by [`Servo::serialize_paint_state`], so that the saved
+    /// state of each group can be matched to the group that replaces it. Keys should be unique.
+    pub fn key(mut self, key: impl Into<String>) -> Self {
+        self.key = Some(key.into());
+        self
+    }
+
+    /// Whether to use optimized shaders. This is enabled by default, because unoptimized
+    /// shaders do not work on some Android emulators.
+    pub fn use_optimized_shaders(mut self, use_optimized_shaders: bool) -> Self {
+        self.painter_options.use_optimized_shaders = Some(use_optimized_shaders);
+        self
+    }
+
+    /// Whether to panic when WebRender encounters an OpenGL error. This is disabled by
+    /// default and is mainly useful while debugging.
+    pub fn panic_on_gl_error(mut self, panic_on_gl_error: bool) -> Self {
+        self.painter_options.panic_on_gl_error = Some(panic_on_gl_error);
+        self
+    }
+
+    /// When to compile the shaders of the WebRender instance of this group. By default, all
+    /// shaders are compiled up front if the `gfx_precache_shaders` preference is enabled and
+    /// lazily otherwise. Embedders that open several windows can compile the shaders of
+    /// secondary windows with [`ShaderPrecachePolicy::Async`] and then call
+    /// [`RenderingGroup::warm_up`] before showing them.
+    pub fn shader_precache_policy(mut self, shader_precache_policy: ShaderPrecachePolicy) -> Self {
+        self.painter_options.shader_precache_policy = Some(shader_precache_policy);
+        self
+    }
+
+    /// Whether to upload textures immediately rather than through pixel buffer objects. This
+    /// is only enabled by default when running on ANGLE.
+    pub fn immediate_texture_upload(mut self, immediate_texture_upload: bool) -> Self {
+        self.painter_options.immediate_texture_upload = Some(immediate_texture_upload);
+        self
+    }
+
+    /// Create the [`RenderingGroup`]. If Servo is already painting to the
+    /// [`RenderingContext`], its existing WebRender instance is kept and the options of this
+    /// builder only apply once that instance is recreated.
+    pub fn build(self) -> RenderingGroup {
+        self.servo
+            .paint_mut()
+            .register_rendering_context_with_options(
+                self.rendering_context.clone(),
+                &self.painter_options,
+            );
+        let rendering_context = Rc::new(RefCell::new(self.rendering_context));
+        if let Some(key) = self.key.clone() {
+            self.servo
+                .register_rendering_group_key(&rendering_context, key);
+        }
+        RenderingGroup {
+            servo: self.servo,
+            rendering_context,
+            painter_options: self.painter_options,
+            hidpi_scale_factor: Default::default(),
+            settings: Default::default(),
+            key: self.key,
+        }
+    }
+}
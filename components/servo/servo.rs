@@ -16,7 +16,10 @@ use base::id::{PipelineNamespace, PipelineNamespaceId};
 use bluetooth::BluetoothThreadFactory;
 #[cfg(feature = "bluetooth")]
 use bluetooth_traits::BluetoothRequest;
-use compositing::{InitialPaintState, Paint};
+use compositing::{
+    InitialPaintState, Paint, PaintMessageTraceEntry, PaintRecording, PainterDebugState,
+    SharedMemoryCapture,
+};
 pub use compositing_traits::rendering_context::RenderingContext;
 use compositing_traits::{CrossProcessPaintApi, PaintMessage, PaintProxy};
 #[cfg(all(
@@ -34,6 +37,7 @@ use constellation::{
 };
 use constellation_traits::{EmbedderToConstellationMessage, ScriptToConstellationSender};
 use crossbeam_channel::{Receiver, Sender, unbounded};
+use dpi::PhysicalSize;
 pub use embedder_traits::*;
 use env_logger::Builder as EnvLoggerBuilder;
 use fonts::SystemFontService;
@@ -46,6 +50,7 @@ use fonts::SystemFontService;
     not(target_env = "ohos"),
 ))]
 use gaol::sandbox::{ChildSandbox, ChildSandboxMethods};
+use image::RgbaImage;
 use ipc_channel::ipc::{self, IpcSender};
 use layout::LayoutFactoryImpl;
 use layout_api::ScriptThreadFactory;
@@ -75,6 +80,7 @@ use style::global_style_data::StyleThreadPool;
 use crate::clipboard_delegate::StringRequest;
 use crate::javascript_evaluator::JavaScriptEvaluator;
 use crate::network_manager::NetworkManager;
+use crate::paint_state::{PaintState, RenderingGroupState, WebViewPaintState};
 use crate::proxies::ConstellationProxy;
 use crate::responders::ServoErrorChannel;
 use crate::servo_delegate::{DefaultServoDelegate, ServoDelegate, ServoError};
@@ -151,6 +157,10 @@ struct ServoInner {
     /// When accessed, `Servo` will be reponsible for cleaning up the invalid `Weak`
     /// references.
     webviews: RefCell<FxHashMap<WebViewId, Weak<RefCell<WebViewInner>>>>,
+    /// The keys of the [`crate::RenderingGroup`]s created with a key, along with their shared
+    /// `RenderingContext`. These are stored as `Weak` references, so that they do not keep
+    /// the groups alive. See [`Servo::serialize_paint_state`].
+    rendering_group_keys: RefCell<Vec<(Weak<RefCell<Rc<dyn RenderingContext>>>, String)>>,
     servo_errors: ServoErrorChannel,
     /// For single-process Servo instances, this field controls the initialization
     /// and deinitialization of the JS Engine. Multiprocess Servo instances have their
@@ -201,6 +211,8 @@ impl ServoInner {
         }
 
         self.paint.borrow_mut().perform_updates();
+        self.send_webview_lifecycle_events();
+        self.send_renderer_errors();
         self.send_new_frame_ready_messages();
         self.handle_delegate_errors();
         self.clean_up_destroyed_webview_handles();
@@ -212,6 +224,31 @@ impl ServoInner {
         true
     }
 
+    fn send_webview_lifecycle_events(&self) {
+        let events = self.paint.borrow().take_webview_lifecycle_events();
+        for (webview_id, event) in events {
+            self.delegate
+                .borrow()
+                .notify_webview_lifecycle_event(webview_id, event);
+        }
+    }
+
+    fn send_renderer_errors(&self) {
+        let errors = self.paint.borrow().take_renderer_errors();
+        for (painter_id, error) in errors {
+            self.delegate
+                .borrow()
+                .notify_error(ServoError::RendererError { painter_id, error });
+        }
+
+        let failed_painters = self.paint.borrow().take_failed_painters();
+        for painter_id in failed_painters {
+            self.delegate
+                .borrow()
+                .notify_error(ServoError::RenderingFailed(painter_id));
+        }
+    }
+
     fn send_new_frame_ready_messages(&self) {
         let webviews_needing_repaint = self.paint.borrow().webviews_needing_repaint();
 
@@ -802,6 +839,7 @@ impl Servo {
             embedder_receiver,
             shutdown_state,
             webviews: Default::default(),
+            rendering_group_keys: Default::default(),
             servo_errors: ServoErrorChannel::default(),
             _js_engine_setup: js_engine_setup,
         }))
@@ -817,6 +855,10 @@ impl Servo {
 
     /// **EXPERIMENTAL:** Intialize GL accelerated media playback. This currently only works on a limited number
     /// of platforms. This should be run *before* calling [`Servo::new`] and creating the first [`WebView`].
+    ///
+    /// Decoded video frames are shared with every `RenderingContext` as OpenGL textures, so when
+    /// there are several `RenderingContext`s, their OpenGL contexts must share objects with
+    /// `context`.
     pub fn initialize_gl_accelerated_media(display: NativeDisplay, api: GlApi, context: GlContext) {
         WindowGLContext::initialize(display, api, context)
     }
@@ -873,6 +915,288 @@ impl Servo {
         self.0.site_data_manager.borrow()
     }
 
+    /// Stop painting to the given [`RenderingContext`]. This closes every [`WebView`] that
+    /// paints to this [`RenderingContext`] and releases all of the WebRender resources
+    /// associated with it. This is useful when an application window is closed, in order to
+    /// avoid keeping GPU resources alive until every [`WebView`] handle has been dropped.
+    pub fn unregister_rendering_context(&self, rendering_context: &Rc<dyn RenderingContext>) {
+        let mut paint = self.paint_mut();
+        let Some(painter_id) = paint.painter_id_for_rendering_context(rendering_context) else {
+            return;
+        };
+        paint.unregister_rendering_context(painter_id);
+    }
+
+    /// Replace `old_rendering_context` with `new_rendering_context`, for instance because the
+    /// native surface of a window was lost and had to be recreated, which can happen on
+    /// Android. Every [`WebView`] that painted to the old [`RenderingContext`] paints to the
+    /// new one from now on and a new frame is produced. When the new [`RenderingContext`] uses
+    /// a different OpenGL context, WebRender is restarted for it and the current contents of
+    /// every [`WebView`] are painted again without reloading or laying out any page.
+    pub fn replace_rendering_context(
+        &self,
+        old_rendering_context: &Rc<dyn RenderingContext>,
+        new_rendering_context: Rc<dyn RenderingContext>,
+    ) {
+        let webview_ids = {
+            let paint = self.paint();
+            let Some(painter_id) = paint.painter_id_for_rendering_context(old_rendering_context)
+            else {
+                return;
+            };
+            if let Err(error) =
+                paint.replace_rendering_context(painter_id, new_rendering_context.clone())
+            {
+                warn!("Could not replace RenderingContext: {error:?}");
+                return;
+            }
+            paint.webviews_in_painter(painter_id).unwrap_or_default()
+        };
+
+        for webview in webview_ids
+            .into_iter()
+            .filter_map(|webview_id| self.get_webview_handle(webview_id))
+        {
+            webview.set_rendering_context(new_rendering_context.clone());
+        }
+    }
+
+    /// Resize the given [`RenderingContext`]. Every [`WebView`] that paints to it and that
+    /// has not been explicitly positioned with [`WebView::set_rect`] is resized to fill it.
+    /// This is equivalent to calling [`WebView::resize`] on any of those [`WebView`]s.
+    pub fn resize_rendering_context(
+        &self,
+        rendering_context: &Rc<dyn RenderingContext>,
+        new_size: PhysicalSize<u32>,
+    ) {
+        let new_size = PhysicalSize {
+            width: new_size.width.max(MINIMUM_WEBVIEW_SIZE.width as u32),
+            height: new_size.height.max(MINIMUM_WEBVIEW_SIZE.height as u32),
+        };
+        let paint = self.paint();
+        let Some(painter_id) = paint.painter_id_for_rendering_context(rendering_context) else {
+            return;
+        };
+        if let Err(error) = paint.resize_painter_rendering_context(painter_id, new_size) {
+            warn!("Could not resize RenderingContext: {error:?}");
+        }
+    }
+
+    /// Notify Servo that the given [`RenderingContext`] is occluded or visible again, for
+    /// instance when its window is minimized or restored. Servo stops generating new frames
+    /// for occluded [`RenderingContext`]s, as well as for those where every [`WebView`] is
+    /// hidden.
+    pub fn set_rendering_context_occluded(
+        &self,
+        rendering_context: &Rc<dyn RenderingContext>,
+        occluded: bool,
+    ) {
+        let paint = self.paint();
+        let Some(painter_id) = paint.painter_id_for_rendering_context(rendering_context) else {
+            return;
+        };
+        if let Err(error) = paint.set_rendering_context_occluded(painter_id, occluded) {
+            warn!("Could not set RenderingContext occlusion: {error:?}");
+        }
+    }
+
+    /// Capture the entire contents of the given [`RenderingContext`], including every
+    /// [`WebView`] that paints to it. Unlike [`WebView::take_screenshot`], this does not wait
+    /// for pages to finish loading, but only for the next frame to be painted. The provided
+    /// `callback` is called once the capture is complete.
+    pub fn capture_rendering_context(
+        &self,
+        rendering_context: &Rc<dyn RenderingContext>,
+        callback: impl FnOnce(Result<RgbaImage, ScreenshotCaptureError>) + 'static,
+    ) {
+        let paint = self.paint();
+        let Some(painter_id) = paint.painter_id_for_rendering_context(rendering_context) else {
+            callback(Err(ScreenshotCaptureError::RenderingContextDoesNotExist));
+            return;
+        };
+        paint.capture_rendering_context(painter_id, Box::new(callback));
+    }
+
+    /// Capture the entire contents of the given [`RenderingContext`] like
+    /// [`Servo::capture_rendering_context`], but read the pixels straight into shared memory.
+    /// The resulting [`SharedMemoryCapture`] can be sent to another process, for instance
+    /// to hand it to a page or to the WebDriver server, without copying the pixels again.
+    pub fn capture_rendering_context_to_shared_memory(
+        &self,
+        rendering_context: &Rc<dyn RenderingContext>,
+        callback: impl FnOnce(Result<SharedMemoryCapture, ScreenshotCaptureError>) + 'static,
+    ) {
+        let paint = self.paint();
+        let Some(painter_id) = paint.painter_id_for_rendering_context(rendering_context) else {
+            callback(Err(ScreenshotCaptureError::RenderingContextDoesNotExist));
+            return;
+        };
+        paint.capture_rendering_context_to_shared_memory(painter_id, Box::new(callback));
+    }
+
+    /// Notify Servo that the system is running low on memory. Servo releases the GPU caches
+    /// of every `RenderingContext` that currently has nothing visible, such as background
+    /// windows, and rebuilds them once one of their [`WebView`]s is shown again.
+    pub fn notify_memory_pressure(&self) {
+        self.paint().notify_memory_pressure();
+    }
+
+    /// Remember the key of a [`crate::RenderingGroup`] whose `RenderingContext` is stored in
+    /// `rendering_context`, so that it is included in the [`PaintState`].
+    pub(crate) fn register_rendering_group_key(
+        &self,
+        rendering_context: &Rc<RefCell<Rc<dyn RenderingContext>>>,
+        key: String,
+    ) {
+        let mut rendering_group_keys = self.0.rendering_group_keys.borrow_mut();
+        rendering_group_keys.retain(|(rendering_context, _)| rendering_context.strong_count() > 0);
+        rendering_group_keys.push((Rc::downgrade(rendering_context), key));
+    }
+
+    /// The key of the [`crate::RenderingGroup`] that currently paints to `rendering_context`,
+    /// if it was created with one.
+    fn rendering_group_key(&self, rendering_context: &Rc<dyn RenderingContext>) -> Option<String> {
+        self.0
+            .rendering_group_keys
+            .borrow()
+            .iter()
+            .find(|(group_rendering_context, _)| {
+                group_rendering_context
+                    .upgrade()
+                    .is_some_and(|group_rendering_context| {
+                        Rc::ptr_eq(&group_rendering_context.borrow(), rendering_context)
+                    })
+            })
+            .map(|(_, key)| key.clone())
+    }
+
+    /// Serialize the arrangement of all [`WebView`]s, including which `RenderingContext` they
+    /// paint to, their painting order, their rectangles and whether they are hidden. Each
+    /// [`RenderingGroupState`] carries the key of its [`crate::RenderingGroup`], if it has one.
+    /// See [`PaintState`].
+    pub fn serialize_paint_state(&self) -> PaintState {
+        let paint = self.paint();
+        let rendering_groups = paint
+            .rendering_contexts()
+            .into_iter()
+            .filter_map(|(painter_id, rendering_context)| {
+                let webviews = paint.webviews_in_painter(painter_id).ok()?;
+                Some(RenderingGroupState {
+                    key: self.rendering_group_key(&rendering_context),
+                    webviews: webviews
+                        .into_iter()
+                        .map(|webview_id| WebViewPaintState {
+                            webview_id,
+                            rect: paint
+                                .webview_explicit_rect(webview_id)
+                                .ok()
+                                .flatten()
+                                .map(|rect| rect.round().to_i32()),
+                            hidden: paint.webview_hidden(webview_id).unwrap_or_default(),
+                        })
+                        .collect(),
+                })
+            })
+            .collect();
+        PaintState { rendering_groups }
+    }
+
+    /// Restore an arrangement of [`WebView`]s previously returned by
+    /// [`Servo::serialize_paint_state`]. Since [`WebView`] ids change across restarts,
+    /// `webview_for_id` is used to find the [`WebView`] that replaces each saved one. Saved
+    /// [`WebView`]s without a replacement are skipped. A [`WebView`] cannot move between
+    /// `RenderingContext`s, so embedders should create each replacement in the
+    /// [`crate::RenderingGroup`] with the key of its saved [`RenderingGroupState`]. See
+    /// [`PaintState::rendering_group`].
+    pub fn restore_paint_state(
+        &self,
+        state: &PaintState,
+        webview_for_id: impl Fn(WebViewId) -> Option<WebView>,
+    ) {
+        for rendering_group_state in &state.rendering_groups {
+            for webview_state in &rendering_group_state.webviews {
+                let Some(webview) = webview_for_id(webview_state.webview_id) else {
+                    continue;
+                };
+                webview.set_rect(webview_state.rect);
+                if webview_state.hidden {
+                    webview.hide();
+                } else {
+                    webview.show();
+                }
+                // Moving every restored `WebView` to the top in turn recreates the saved
+                // painting order.
+                webview.set_painting_index(usize::MAX);
+            }
+        }
+    }
+
+    /// Render and present every [`crate::RenderingGroup`] with a new frame, like calling
+    /// [`crate::RenderingGroup::present`] for each of them. The focused group, set with
+    /// [`crate::RenderingGroup::set_focused`], is presented first, followed by the others
+    /// from highest to lowest [`crate::RenderingPriority`]. The first group is always
+    /// presented, but once presenting has taken longer than `frame_budget`, the remaining
+    /// groups are deferred to the next turn of the event loop, which is woken up for that
+    /// purpose. Returns `true` if any group was deferred.
+    pub fn present_rendering_groups(&self, frame_budget: Duration) -> bool {
+        self.paint().present_painters(frame_budget)
+    }
+
+    /// A snapshot of the internal state of Servo's renderer, including which [`WebView`]s
+    /// paint to which `RenderingContext` and in which order. This is meant for diagnostics
+    /// and its contents may change at any time.
+    pub fn paint_debug_state(&self) -> Vec<PainterDebugState> {
+        self.paint().debug_state()
+    }
+
+    /// Enable or disable tracing of the messages handled by Servo's renderer. While enabled,
+    /// the most recent messages are kept in a ring buffer along with the renderer, WebRender
+    /// document and [`WebView`] each was addressed to. They can be retrieved with
+    /// [`Servo::paint_message_trace`] and are logged if the renderer is dropped during a
+    /// panic. Disabling tracing clears the recorded messages.
+    pub fn set_paint_message_tracing_enabled(&self, enabled: bool) {
+        self.paint().set_message_tracing_enabled(enabled);
+    }
+
+    /// The most recent messages handled by Servo's renderer while message tracing was
+    /// enabled, from oldest to newest. See [`Servo::set_paint_message_tracing_enabled`].
+    pub fn paint_message_trace(&self) -> Vec<PaintMessageTraceEntry> {
+        self.paint().message_trace()
+    }
+
+    /// Start recording the messages handled by Servo's renderer, the input events sent to
+    /// [`WebView`]s and the frames built by WebRender, along with the time at which each
+    /// happened. This replaces any ongoing recording. See [`Servo::stop_paint_recording`].
+    pub fn start_paint_recording(&self) {
+        self.paint().start_recording();
+    }
+
+    /// Stop recording and return what was recorded since [`Servo::start_paint_recording`],
+    /// or `None` if nothing was being recorded. The input events of the recording can be
+    /// replayed with an [`crate::InputEventReplayer`].
+    pub fn stop_paint_recording(&self) -> Option<PaintRecording> {
+        self.paint().stop_recording()
+    }
+
+    /// Every [`WebView`] known to the renderer along with the `RenderingContext` it paints
+    /// to. `RenderingContext`s are visited in the order in which they received their first
+    /// [`WebView`], and the [`WebView`]s of each in painting order from bottom to top. This
+    /// is useful for operations that need to visit all [`WebView`]s, such as taking a
+    /// screenshot of every window.
+    pub fn painting_order_all(&self) -> Vec<(Rc<dyn RenderingContext>, WebView)> {
+        self.paint()
+            .painting_order_all()
+            .into_iter()
+            .filter_map(|(rendering_context, webview_id)| {
+                Some((rendering_context, self.get_webview_handle(webview_id)?))
+            })
+            .collect()
+    }
+
+    pub(crate) fn get_webview_handle(&self, id: WebViewId) -> Option<WebView> {
+        self.0.get_webview_handle(id)
+    }
+
     pub(crate) fn paint<'a>(&'a self) -> Ref<'a, Paint> {
         self.0.paint.borrow()
     }
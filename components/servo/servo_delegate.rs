@@ -2,7 +2,10 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
 use base::generic_channel;
+use base::id::{PainterId, WebViewId};
+use compositing::WebViewLifecycleEvent;
 use embedder_traits::{ConsoleLogLevel, Notification};
+use webrender::RendererError;
 
 use crate::webview_delegate::{AllowOrDenyRequest, WebResourceLoad};
 
@@ -16,11 +19,34 @@ pub enum ServoError {
     DevtoolsFailedToStart,
     /// Failed to send response to delegate request.
     ResponseFailedToSend(generic_channel::SendError),
+    /// WebRender failed to render a frame of the [`crate::RenderingGroup`] with the given
+    /// [`PainterId`], for instance because of a transient OpenGL error. Servo recovers by
+    /// restarting WebRender for that group alone and painting the current contents of its
+    /// `WebView`s again, so at most a single frame is lost, but repeated errors indicate that
+    /// rendering is degraded. See [`crate::RenderingGroup::painter_id`].
+    RendererError {
+        painter_id: PainterId,
+        error: RendererError,
+    },
+    /// Too many frames in a row of the [`crate::RenderingGroup`] with the given [`PainterId`]
+    /// failed to render, so Servo stopped rendering it. Embedders should close the group and
+    /// create a new one with a fresh `RenderingContext`.
+    RenderingFailed(PainterId),
 }
 
 pub trait ServoDelegate {
     /// Notification that Servo has received a major error.
     fn notify_error(&self, _error: ServoError) {}
+    /// Notification that the `WebView` with the given [`WebViewId`] was added, removed,
+    /// shown, hidden, or moved within the painting order of its `RenderingContext`. This
+    /// can be used to keep window titles, taskbar previews, or tab strips up-to-date. Note
+    /// that the `WebView` may no longer exist when this is called.
+    fn notify_webview_lifecycle_event(
+        &self,
+        _webview_id: WebViewId,
+        _event: WebViewLifecycleEvent,
+    ) {
+    }
     /// Report that the DevTools server has started on the given `port`. The `token` that
     /// be used to bypass the permission prompt from the DevTools client.
     fn notify_devtools_server_started(&self, _port: u16, _token: String) {}
@@ -12,8 +12,8 @@ use compositing_traits::rendering_context::{RenderingContext, SoftwareRenderingC
 use dpi::PhysicalSize;
 use embedder_traits::EventLoopWaker;
 use servo::{
-    EmbedderControl, JSValue, JavaScriptEvaluationError, LoadStatus, Preferences, Servo,
-    ServoBuilder, SimpleDialog, WebView, WebViewDelegate,
+    EmbedderControl, JSValue, JavaScriptEvaluationError, LoadStatus, Preferences, RenderingGroup,
+    Servo, ServoBuilder, SimpleDialog, WebView, WebViewDelegate,
 };
 
 pub struct ServoTest {
@@ -70,6 +70,19 @@ impl ServoTest {
         &self.servo
     }
 
+    /// Create a [`RenderingGroup`] that paints to a new 300x200 [`SoftwareRenderingContext`].
+    #[allow(dead_code)] // Used by some tests and not others
+    pub(crate) fn new_rendering_group(&self) -> RenderingGroup {
+        let rendering_context = Rc::new(
+            SoftwareRenderingContext::new(PhysicalSize {
+                width: 300,
+                height: 200,
+            })
+            .expect("Could not create SoftwareRenderingContext"),
+        );
+        RenderingGroup::new(&self.servo, rendering_context)
+    }
+
     /// Spin the Servo event loop until one of:
     ///  - The given callback returns `Ok(false)`.
     ///  - The given callback returns an `Error`, in which case the `Error` will be returned.
@@ -8,24 +8,33 @@ mod common;
 use std::cell::{Cell, RefCell};
 use std::collections::HashMap;
 use std::rc::Rc;
+use std::time::{Duration, Instant};
 
 use dpi::PhysicalSize;
-use euclid::{Point2D, Size2D};
+use euclid::{Point2D, Scale, Size2D};
 use http_body_util::combinators::BoxBody;
 use hyper::body::{Bytes, Incoming};
 use hyper::{Request as HyperRequest, Response as HyperResponse};
 use net::test_util::{make_body, make_server, replace_host_table};
 use servo::{
-    ContextMenuAction, ContextMenuElementInformation, ContextMenuElementInformationFlags,
-    ContextMenuItem, CreateNewWebViewRequest, Cursor, EmbedderControl, InputEvent, InputMethodType,
-    JSValue, JavaScriptEvaluationError, LoadStatus, MouseButton, MouseButtonAction,
-    MouseButtonEvent, MouseLeftViewportEvent, MouseMoveEvent, RenderingContext, Servo,
-    SimpleDialog, Theme, UserContentManager, WebView, WebViewBuilder, WebViewDelegate,
+    BorderRadius, ColorF, ContextMenuAction, ContextMenuElementInformation,
+    ContextMenuElementInformationFlags, ContextMenuItem, CreateNewWebViewRequest, Cursor,
+    EmbedderControl, InputEvent, InputEventReplayer, InputMethodType, JSValue,
+    JavaScriptEvaluationError, LoadStatus, MouseButton, MouseButtonAction, MouseButtonEvent,
+    MouseLeftViewportEvent, MouseMoveEvent, PaintBackground, PainterId, RecordedPaintEventKind,
+    RenderingContext, RenderingGroup, RenderingGroupBuilder, RenderingPriority, RgbaImage,
+    ScreenshotCaptureError, Servo, ServoDelegate, ServoError, ShaderPrecachePolicy, SimpleDialog,
+    SoftwareRenderingContext, TextAntialiasing, Theme, UserContentManager, WebRenderDebugOption,
+    WebView, WebViewBorder, WebViewBuilder, WebViewClip, WebViewDecorations, WebViewDelegate,
+    WebViewId, WebViewLifecycleEvent,
 };
 use servo_config::prefs::Preferences;
 use servo_url::ServoUrl;
 use url::Url;
-use webrender_api::units::{DeviceIntSize, DevicePoint};
+use webrender_api::units::{
+    DeviceIntRect, DeviceIntSize, DevicePoint, DeviceRect, DeviceSize, DeviceVector2D,
+    LayoutVector2D,
+};
 
 use crate::common::{ServoTest, WebViewDelegateImpl, evaluate_javascript};
 
@@ -68,6 +77,23 @@ fn show_webview_and_wait_for_rendering_to_be_ready(
     servo_test.spin(move || !captured_delegate.new_frame_ready.get());
 }
 
+/// Capture the [`RenderingContext`] of `rendering_group`, waiting for the capture to finish.
+fn capture_group(servo_test: &ServoTest, rendering_group: &RenderingGroup) -> RgbaImage {
+    let capture = Rc::new(RefCell::new(None));
+    let callback_capture = capture.clone();
+    rendering_group.capture(move |result| {
+        *callback_capture.borrow_mut() = Some(result);
+    });
+    let spin_capture = capture.clone();
+    servo_test.spin(move || spin_capture.borrow().is_none());
+
+    capture
+        .borrow_mut()
+        .take()
+        .expect("Should have waited for capture")
+        .expect("Capture should succeed")
+}
+
 fn click_at_point(webview: &WebView, point: DevicePoint) {
     let point = point.into();
     webview.notify_input_event(InputEvent::MouseMove(MouseMoveEvent::new(point)));
@@ -277,6 +303,25 @@ fn test_create_webview_and_immediately_drop_webview_before_shutdown() {
     WebViewBuilder::new(servo_test.servo(), servo_test.rendering_context.clone()).build();
 }
 
+#[test]
+fn test_unregister_rendering_context_before_dropping_webview() {
+    let servo_test = ServoTest::new();
+    let delegate = Rc::new(WebViewDelegateImpl::default());
+    let webview = WebViewBuilder::new(servo_test.servo(), servo_test.rendering_context.clone())
+        .delegate(delegate.clone())
+        .build();
+
+    let captured_delegate = delegate.clone();
+    servo_test.spin(move || !captured_delegate.url_changed.get());
+
+    servo_test
+        .servo()
+        .unregister_rendering_context(&servo_test.rendering_context);
+
+    // Dropping the WebView after its RenderingContext has been unregistered should not panic.
+    drop(webview);
+}
+
 #[test]
 fn test_theme_change() {
     let servo_test = ServoTest::new();
@@ -541,29 +586,1762 @@ fn test_control_show_and_hide() {
 }
 
 #[test]
-fn test_page_zoom() {
+fn test_webview_rect() {
     let servo_test = ServoTest::new();
     let delegate = Rc::new(WebViewDelegateImpl::default());
     let webview = WebViewBuilder::new(servo_test.servo(), servo_test.rendering_context.clone())
         .delegate(delegate.clone())
+        .url(Url::parse("data:text/html,<body style='background: green'>").unwrap())
         .build();
+    show_webview_and_wait_for_rendering_to_be_ready(&servo_test, &webview, &delegate);
 
-    // Default zoom should be 1.0
-    assert_eq!(webview.page_zoom(), 1.0);
+    webview.set_rect(Some(DeviceIntRect::from_origin_and_size(
+        Point2D::new(100, 100),
+        Size2D::new(200, 150),
+    )));
 
-    webview.set_page_zoom(1.5);
-    assert_eq!(webview.page_zoom(), 1.5);
+    let screenshot = Rc::new(RefCell::new(None));
+    let callback_screenshot = screenshot.clone();
+    webview.take_screenshot(None, move |result| {
+        *callback_screenshot.borrow_mut() = Some(result);
+    });
+    let spin_screenshot = screenshot.clone();
+    servo_test.spin(move || spin_screenshot.borrow().is_none());
 
-    webview.set_page_zoom(0.5);
-    assert_eq!(webview.page_zoom(), 0.5);
+    let image = screenshot
+        .borrow_mut()
+        .take()
+        .expect("Should have waited for screenshot")
+        .expect("Screenshot should succeed");
+    assert_eq!((image.width(), image.height()), (200, 150));
+}
 
-    // Should clamp to minimum
-    webview.set_page_zoom(-1.0);
-    assert_eq!(webview.page_zoom(), 0.1);
+#[test]
+fn test_concurrent_screenshots_of_animating_page() {
+    let servo_test = ServoTest::new();
+    let delegate = Rc::new(WebViewDelegateImpl::default());
+    let webview = WebViewBuilder::new(servo_test.servo(), servo_test.rendering_context.clone())
+        .delegate(delegate.clone())
+        .url(
+            Url::parse(
+                "data:text/html,<body style='background: green'>\
+                 <div id='box' style='background: green; height: 10px'></div><script>\
+                 function step(time) { \
+                   box.style.width = (Math.floor(time) & 255) + 'px'; \
+                   requestAnimationFrame(step); \
+                 } \
+                 requestAnimationFrame(step);\
+                 </script>",
+            )
+            .unwrap(),
+        )
+        .build();
+    show_webview_and_wait_for_rendering_to_be_ready(&servo_test, &webview, &delegate);
 
-    // Should clamp to maximum
-    webview.set_page_zoom(100.0);
-    assert_eq!(webview.page_zoom(), 10.0);
+    // The page never stops producing new frames, so each screenshot must resolve once the
+    // frame that it is waiting for is ready, rather than once there are no pending frames.
+    let screenshots = Rc::new(RefCell::new(Vec::new()));
+    for _ in 0..3 {
+        let callback_screenshots = screenshots.clone();
+        webview.take_screenshot(None, move |result| {
+            callback_screenshots.borrow_mut().push(result);
+        });
+    }
+    let spin_screenshots = screenshots.clone();
+    servo_test.spin(move || spin_screenshots.borrow().len() < 3);
+
+    for screenshot in screenshots.borrow_mut().drain(..) {
+        let image = screenshot.expect("Screenshot should succeed");
+        assert_eq!(image.get_pixel(10, 50).0, [0, 128, 0, 255]);
+    }
+}
+
+#[test]
+fn test_webview_max_frame_rate() {
+    let servo_test = ServoTest::new();
+    let delegate = Rc::new(WebViewDelegateImpl::default());
+    let webview = WebViewBuilder::new(servo_test.servo(), servo_test.rendering_context.clone())
+        .delegate(delegate.clone())
+        .url(
+            Url::parse(
+                "data:text/html,<body style='background: green'><script>\
+                 window.frameCount = 0; \
+                 function step() { \
+                   window.frameCount += 1; \
+                   requestAnimationFrame(step); \
+                 } \
+                 requestAnimationFrame(step);\
+                 </script>",
+            )
+            .unwrap(),
+        )
+        .build();
+    show_webview_and_wait_for_rendering_to_be_ready(&servo_test, &webview, &delegate);
+    webview.set_max_frame_rate(Some(5.));
+
+    let frames = || match evaluate_javascript(&servo_test, webview.clone(), "window.frameCount") {
+        Ok(JSValue::Number(frames)) => frames,
+        result => panic!("Unexpected result: {result:?}"),
+    };
+    let start_frames = frames();
+    let start = std::time::Instant::now();
+    servo_test.spin(move || start.elapsed() < Duration::from_secs(1));
+
+    // Allow for some scheduling slack, but far less than the uncapped frame rate.
+    let capped_frames = frames() - start_frames;
+    assert!(capped_frames > 0.);
+    assert!(
+        capped_frames <= 10.,
+        "Ran {capped_frames} frames in one second"
+    );
+}
+
+#[test]
+fn test_notify_memory_pressure_with_hidden_webview() {
+    let servo_test = ServoTest::new();
+    let delegate = Rc::new(WebViewDelegateImpl::default());
+    let webview = WebViewBuilder::new(servo_test.servo(), servo_test.rendering_context.clone())
+        .delegate(delegate.clone())
+        .url(Url::parse("data:text/html,<body style='background: green'>").unwrap())
+        .build();
+    show_webview_and_wait_for_rendering_to_be_ready(&servo_test, &webview, &delegate);
+
+    webview.hide();
+    servo_test.servo().notify_memory_pressure();
+    webview.show();
+
+    let screenshot = Rc::new(RefCell::new(None));
+    let callback_screenshot = screenshot.clone();
+    webview.take_screenshot(None, move |result| {
+        *callback_screenshot.borrow_mut() = Some(result);
+    });
+    let spin_screenshot = screenshot.clone();
+    servo_test.spin(move || spin_screenshot.borrow().is_none());
+
+    let image = screenshot
+        .borrow_mut()
+        .take()
+        .expect("Should have waited for screenshot")
+        .expect("Screenshot should succeed");
+    assert_eq!(image.get_pixel(10, 10).0, [0, 128, 0, 255]);
+}
+
+#[test]
+fn test_pending_screenshot_fails_when_webview_is_removed() {
+    let servo_test = ServoTest::new();
+    let webview = WebViewBuilder::new(servo_test.servo(), servo_test.rendering_context.clone())
+        .url(Url::parse("data:text/html,").unwrap())
+        .build();
+
+    let screenshot = Rc::new(RefCell::new(None));
+    let callback_screenshot = screenshot.clone();
+    webview.take_screenshot(None, move |result| {
+        *callback_screenshot.borrow_mut() = Some(result);
+    });
+    drop(webview);
+
+    // Removing the `WebView` fails its pending screenshot requests immediately.
+    assert_eq!(
+        screenshot.borrow_mut().take(),
+        Some(Err(ScreenshotCaptureError::WebViewDoesNotExist))
+    );
+}
+
+#[test]
+fn test_serialize_and_restore_paint_state() {
+    let servo_test = ServoTest::new();
+    let rendering_group =
+        RenderingGroupBuilder::new(servo_test.servo(), servo_test.rendering_context.clone())
+            .key("main-window")
+            .build();
+    assert_eq!(rendering_group.key(), Some("main-window"));
+    let create_webview = || {
+        WebViewBuilder::new_in_group(servo_test.servo(), &rendering_group)
+            .url(Url::parse("data:text/html,").unwrap())
+            .build()
+    };
+    let bottom = create_webview();
+    let top = create_webview();
+    top.set_rect(Some(DeviceIntRect::from_origin_and_size(
+        Point2D::new(10, 20),
+        Size2D::new(100, 50),
+    )));
+    bottom.hide();
+
+    let state = servo_test.servo().serialize_paint_state();
+    let group_state = state
+        .rendering_group("main-window")
+        .expect("Should have serialized the RenderingGroup");
+    let webview_ids: Vec<_> = group_state
+        .webviews
+        .iter()
+        .map(|webview_state| webview_state.webview_id)
+        .collect();
+    assert_eq!(webview_ids, vec![bottom.id(), top.id()]);
+
+    let new_bottom = create_webview();
+    let new_top = create_webview();
+    new_bottom.set_painting_index(usize::MAX);
+    let webviews = HashMap::from([
+        (bottom.id(), new_bottom.clone()),
+        (top.id(), new_top.clone()),
+    ]);
+    servo_test
+        .servo()
+        .restore_paint_state(&state, |webview_id| webviews.get(&webview_id).cloned());
+
+    let restored_state = servo_test.servo().serialize_paint_state();
+    let restored_group_state = restored_state
+        .rendering_group("main-window")
+        .expect("Should have serialized the RenderingGroup");
+    let restored_webviews: Vec<_> = restored_group_state
+        .webviews
+        .iter()
+        .filter(|webview_state| {
+            webview_state.webview_id == new_bottom.id() || webview_state.webview_id == new_top.id()
+        })
+        .collect();
+    assert_eq!(restored_webviews.len(), 2);
+    assert_eq!(restored_webviews[0].webview_id, new_bottom.id());
+    assert!(restored_webviews[0].hidden);
+    assert_eq!(restored_webviews[1].webview_id, new_top.id());
+    assert_eq!(
+        restored_webviews[1].rect,
+        Some(DeviceIntRect::from_origin_and_size(
+            Point2D::new(10, 20),
+            Size2D::new(100, 50),
+        ))
+    );
+}
+
+#[test]
+fn test_headless_webview_read_pixels() {
+    let servo_test = ServoTest::new();
+    let delegate = Rc::new(WebViewDelegateImpl::default());
+    let webview = WebViewBuilder::new_headless(
+        servo_test.servo(),
+        PhysicalSize {
+            width: 300,
+            height: 200,
+        },
+    )
+    .expect("Could not create headless WebView")
+    .delegate(delegate.clone())
+    .url(Url::parse("data:text/html,<body style='background: green'>").unwrap())
+    .build();
+    show_webview_and_wait_for_rendering_to_be_ready(&servo_test, &webview, &delegate);
+
+    let image = webview
+        .read_pixels()
+        .expect("Should be able to read pixels");
+    assert_eq!((image.width(), image.height()), (300, 200));
+}
+
+#[test]
+fn test_capture_rendering_context() {
+    let servo_test = ServoTest::new();
+    let delegate = Rc::new(WebViewDelegateImpl::default());
+    let webview = WebViewBuilder::new(servo_test.servo(), servo_test.rendering_context.clone())
+        .delegate(delegate.clone())
+        .url(Url::parse("data:text/html,<body style='background: green'>").unwrap())
+        .build();
+    show_webview_and_wait_for_rendering_to_be_ready(&servo_test, &webview, &delegate);
+
+    let capture = Rc::new(RefCell::new(None));
+    let callback_capture = capture.clone();
+    servo_test
+        .servo()
+        .capture_rendering_context(&servo_test.rendering_context, move |result| {
+            *callback_capture.borrow_mut() = Some(result);
+        });
+    let spin_capture = capture.clone();
+    servo_test.spin(move || spin_capture.borrow().is_none());
+
+    let image = capture
+        .borrow_mut()
+        .take()
+        .expect("Should have waited for capture")
+        .expect("Capture should succeed");
+    assert_eq!((image.width(), image.height()), (500, 500));
+}
+
+#[test]
+fn test_rendering_group() {
+    let servo_test = ServoTest::new();
+    let rendering_group = servo_test.new_rendering_group();
+
+    let delegate = Rc::new(WebViewDelegateImpl::default());
+    let webview = WebViewBuilder::new_in_group(servo_test.servo(), &rendering_group)
+        .delegate(delegate.clone())
+        .url(Url::parse("data:text/html,<body style='background: green'>").unwrap())
+        .build();
+    show_webview_and_wait_for_rendering_to_be_ready(&servo_test, &webview, &delegate);
+    assert_eq!(webview.size(), DeviceSize::new(300., 200.));
+    assert_eq!(rendering_group.webviews(), vec![webview.clone()]);
+
+    // Once the latest frame has been presented, there is nothing new to present.
+    rendering_group.present();
+    assert!(!rendering_group.present());
+
+    let image = capture_group(&servo_test, &rendering_group);
+    assert_eq!((image.width(), image.height()), (300, 200));
+}
+
+#[test]
+fn test_rendering_group_replace_rendering_context() {
+    let servo_test = ServoTest::new();
+    let rendering_group = servo_test.new_rendering_group();
+    let delegate = Rc::new(WebViewDelegateImpl::default());
+    let webview = WebViewBuilder::new_in_group(servo_test.servo(), &rendering_group)
+        .delegate(delegate.clone())
+        .url(Url::parse("data:text/html,<body style='background: green'>").unwrap())
+        .build();
+    show_webview_and_wait_for_rendering_to_be_ready(&servo_test, &webview, &delegate);
+
+    // The new `RenderingContext` has its own OpenGL context, so WebRender is recreated for it
+    // and the existing contents of the `WebView` are painted again.
+    let new_rendering_context = Rc::new(
+        SoftwareRenderingContext::new(PhysicalSize::new(400, 300))
+            .expect("Could not create SoftwareRenderingContext"),
+    );
+    rendering_group.replace_rendering_context(new_rendering_context);
+    assert_eq!(webview.size(), DeviceSize::new(400., 300.));
+
+    let image = capture_group(&servo_test, &rendering_group);
+    assert_eq!((image.width(), image.height()), (400, 300));
+    assert_eq!(image.get_pixel(150, 100).0, [0, 128, 0, 255]);
+}
+
+#[test]
+fn test_rendering_group_renderer_failure() {
+    #[derive(Default)]
+    struct ServoDelegateImpl {
+        renderer_errors: RefCell<Vec<PainterId>>,
+        failed_painters: RefCell<Vec<PainterId>>,
+    }
+    impl ServoDelegate for ServoDelegateImpl {
+        fn notify_error(&self, error: ServoError) {
+            match error {
+                ServoError::RendererError { painter_id, .. } => {
+                    self.renderer_errors.borrow_mut().push(painter_id)
+                },
+                ServoError::RenderingFailed(painter_id) => {
+                    self.failed_painters.borrow_mut().push(painter_id)
+                },
+                _ => {},
+            }
+        }
+    }
+
+    let servo_test = ServoTest::new();
+    let servo_delegate = Rc::new(ServoDelegateImpl::default());
+    servo_test.servo().set_delegate(servo_delegate.clone());
+
+    let rendering_group = servo_test.new_rendering_group();
+    let delegate = Rc::new(WebViewDelegateImpl::default());
+    let webview = WebViewBuilder::new_in_group(servo_test.servo(), &rendering_group)
+        .delegate(delegate.clone())
+        .url(Url::parse("data:text/html,<body style='background: green'>").unwrap())
+        .build();
+    show_webview_and_wait_for_rendering_to_be_ready(&servo_test, &webview, &delegate);
+    let painter_id = rendering_group.painter_id().expect("Should have a Painter");
+
+    // A failed frame is reported and WebRender is recreated, painting the `WebView` again.
+    rendering_group.simulate_renderer_failure(1);
+    rendering_group.present();
+    let spin_delegate = servo_delegate.clone();
+    servo_test.spin(move || spin_delegate.renderer_errors.borrow().is_empty());
+    assert_eq!(*servo_delegate.renderer_errors.borrow(), vec![painter_id]);
+
+    let image = capture_group(&servo_test, &rendering_group);
+    assert_eq!(image.get_pixel(150, 100).0, [0, 128, 0, 255]);
+    assert!(servo_delegate.failed_painters.borrow().is_empty());
+
+    // When every frame fails, the group gives up and stops rendering.
+    rendering_group.simulate_renderer_failure(u32::MAX);
+    let spin_delegate = servo_delegate.clone();
+    let spin_rendering_group = rendering_group.clone();
+    servo_test.spin(move || {
+        spin_rendering_group.present();
+        spin_delegate.failed_painters.borrow().is_empty()
+    });
+    assert_eq!(*servo_delegate.failed_painters.borrow(), vec![painter_id]);
+}
+
+#[test]
+fn test_rendering_group_capture_to_shared_memory() {
+    let servo_test = ServoTest::new();
+    let rendering_group = servo_test.new_rendering_group();
+
+    let delegate = Rc::new(WebViewDelegateImpl::default());
+    let webview = WebViewBuilder::new_in_group(servo_test.servo(), &rendering_group)
+        .delegate(delegate.clone())
+        .url(Url::parse("data:text/html,<body style='background: green'>").unwrap())
+        .build();
+    show_webview_and_wait_for_rendering_to_be_ready(&servo_test, &webview, &delegate);
+
+    let capture = Rc::new(RefCell::new(None));
+    let callback_capture = capture.clone();
+    rendering_group.capture_to_shared_memory(move |result| {
+        *callback_capture.borrow_mut() = Some(result);
+    });
+    let spin_capture = capture.clone();
+    servo_test.spin(move || spin_capture.borrow().is_none());
+
+    let capture = capture
+        .borrow_mut()
+        .take()
+        .expect("Should have waited for capture")
+        .expect("Capture should succeed");
+    assert_eq!(capture.size, DeviceIntSize::new(300, 200));
+    assert_eq!(capture.pixels.len(), 300 * 200 * 4);
+    let offset = (100 * 300 + 150) * 4;
+    assert_eq!(&capture.pixels[offset..offset + 4], &[0, 128, 0, 255]);
+}
+
+#[test]
+fn test_rendering_group_overlay_drawing_callback() {
+    let servo_test = ServoTest::new();
+    let rendering_group = servo_test.new_rendering_group();
+
+    let delegate = Rc::new(WebViewDelegateImpl::default());
+    let webview = WebViewBuilder::new_in_group(servo_test.servo(), &rendering_group)
+        .delegate(delegate.clone())
+        .url(Url::parse("data:text/html,<body style='background: green'>").unwrap())
+        .build();
+    show_webview_and_wait_for_rendering_to_be_ready(&servo_test, &webview, &delegate);
+
+    // Draw a blue strip along the left edge of every frame.
+    let overlay_draws = Rc::new(Cell::new(0));
+    let callback_overlay_draws = overlay_draws.clone();
+    rendering_group.set_overlay_drawing_callback(move |rendering_context| {
+        assert_eq!(rendering_context.size(), PhysicalSize::new(300, 200));
+        let gl = rendering_context.gleam_gl_api();
+        gl.enable(gleam::gl::SCISSOR_TEST);
+        gl.scissor(0, 0, 10, 200);
+        gl.clear_color(0.0, 0.0, 1.0, 1.0);
+        gl.clear(gleam::gl::COLOR_BUFFER_BIT);
+        gl.disable(gleam::gl::SCISSOR_TEST);
+        callback_overlay_draws.set(callback_overlay_draws.get() + 1);
+    });
+    rendering_group.present();
+    assert!(overlay_draws.get() > 0);
+
+    let capture = Rc::new(RefCell::new(None));
+    let callback_capture = capture.clone();
+    rendering_group.capture_to_shared_memory(move |result| {
+        *callback_capture.borrow_mut() = Some(result);
+    });
+    let spin_capture = capture.clone();
+    servo_test.spin(move || spin_capture.borrow().is_none());
+    let capture = capture
+        .borrow_mut()
+        .take()
+        .expect("Should have waited for capture")
+        .expect("Capture should succeed");
+    let pixel = |x: usize, y: usize| {
+        let offset = (y * 300 + x) * 4;
+        capture.pixels[offset..offset + 4].to_vec()
+    };
+    assert_eq!(pixel(5, 100), vec![0, 0, 255, 255]);
+    assert_eq!(pixel(150, 100), vec![0, 128, 0, 255]);
+
+    // Once cleared, the overlay is no longer drawn.
+    rendering_group.clear_overlay_drawing_callback();
+    let draws_before_clearing = overlay_draws.get();
+    rendering_group.present();
+    assert_eq!(overlay_draws.get(), draws_before_clearing);
+}
+
+#[test]
+fn test_notify_when_next_frame_presented() {
+    let servo_test = ServoTest::new();
+    let delegate = Rc::new(WebViewDelegateImpl::default());
+    let webview = WebViewBuilder::new(servo_test.servo(), servo_test.rendering_context.clone())
+        .delegate(delegate.clone())
+        .url(Url::parse("data:text/html,<body style='background: green'>").unwrap())
+        .build();
+    show_webview_and_wait_for_rendering_to_be_ready(&servo_test, &webview, &delegate);
+
+    let presented = Rc::new(Cell::new(false));
+    let callback_presented = presented.clone();
+    webview.notify_when_next_frame_presented(move || callback_presented.set(true));
+
+    let _ = evaluate_javascript(
+        &servo_test,
+        webview.clone(),
+        "document.body.style.background = 'blue';",
+    );
+    servo_test.spin(move || !presented.get());
+}
+
+#[test]
+fn test_rendering_group_background() {
+    let servo_test = ServoTest::new();
+    let rendering_group = servo_test.new_rendering_group();
+    rendering_group.set_background(PaintBackground::Color(ColorF::new(1.0, 0.0, 0.0, 1.0)));
+
+    let delegate = Rc::new(WebViewDelegateImpl::default());
+    let webview = WebViewBuilder::new_in_group(servo_test.servo(), &rendering_group)
+        .delegate(delegate.clone())
+        .url(Url::parse("data:text/html,<body style='background: green'>").unwrap())
+        .build();
+    webview.set_rect(Some(DeviceIntRect::from_origin_and_size(
+        Point2D::new(0, 0),
+        Size2D::new(100, 100),
+    )));
+    show_webview_and_wait_for_rendering_to_be_ready(&servo_test, &webview, &delegate);
+
+    let image = capture_group(&servo_test, &rendering_group);
+    assert_eq!(image.get_pixel(250, 150).0, [255, 0, 0, 255]);
+}
+
+#[test]
+fn test_rendering_group_settings_outlive_painter() {
+    let servo_test = ServoTest::new();
+    let rendering_group = servo_test.new_rendering_group();
+
+    // Closing the only `WebView` removes the WebRender instance of the group, but the
+    // background should be applied to the one created for the next `WebView`.
+    let webview = WebViewBuilder::new_in_group(servo_test.servo(), &rendering_group).build();
+    rendering_group.set_background(PaintBackground::Color(ColorF::new(1.0, 0.0, 0.0, 1.0)));
+    drop(webview);
+    assert!(rendering_group.webviews().is_empty());
+
+    let delegate = Rc::new(WebViewDelegateImpl::default());
+    let webview = WebViewBuilder::new_in_group(servo_test.servo(), &rendering_group)
+        .delegate(delegate.clone())
+        .url(Url::parse("data:text/html,<body style='background: green'>").unwrap())
+        .build();
+    webview.set_rect(Some(DeviceIntRect::from_origin_and_size(
+        Point2D::new(0, 0),
+        Size2D::new(100, 100),
+    )));
+    show_webview_and_wait_for_rendering_to_be_ready(&servo_test, &webview, &delegate);
+
+    let image = capture_group(&servo_test, &rendering_group);
+    assert_eq!(image.get_pixel(250, 150).0, [255, 0, 0, 255]);
+}
+
+#[test]
+fn test_rendering_group_focus_and_hide_all_outlive_painter() {
+    let servo_test = ServoTest::new();
+    let rendering_group = servo_test.new_rendering_group();
+
+    // Both are remembered while the group has no WebRender instance and applied to the one
+    // created for the next `WebView`.
+    rendering_group.set_focused(true);
+    rendering_group.hide_all();
+    assert!(!rendering_group.focused());
+
+    let webview = WebViewBuilder::new_in_group(servo_test.servo(), &rendering_group).build();
+    assert!(rendering_group.focused());
+    let hidden_webviews = servo_test
+        .servo()
+        .paint_debug_state()
+        .into_iter()
+        .find(|state| state.painting_order.contains(&webview.id()))
+        .expect("Should find the Painter of the WebView")
+        .hidden_webviews;
+    assert_eq!(hidden_webviews, vec![webview.id()]);
+}
+
+#[test]
+fn test_webview_clip() {
+    let servo_test = ServoTest::new();
+    let rendering_group = servo_test.new_rendering_group();
+    rendering_group.set_background(PaintBackground::Color(ColorF::new(1.0, 0.0, 0.0, 1.0)));
+
+    let delegate = Rc::new(WebViewDelegateImpl::default());
+    let webview = WebViewBuilder::new_in_group(servo_test.servo(), &rendering_group)
+        .delegate(delegate.clone())
+        .url(Url::parse("data:text/html,<body style='background: green'>").unwrap())
+        .build();
+    webview.set_rect(Some(DeviceIntRect::from_origin_and_size(
+        Point2D::new(100, 100),
+        Size2D::new(100, 100),
+    )));
+    webview.set_clip(Some(WebViewClip {
+        rect: DeviceRect::from_origin_and_size(Point2D::new(0.0, 0.0), Size2D::new(50.0, 50.0)),
+        radii: BorderRadius::uniform(10.0),
+    }));
+    show_webview_and_wait_for_rendering_to_be_ready(&servo_test, &webview, &delegate);
+
+    let image = capture_group(&servo_test, &rendering_group);
+    assert_eq!(image.get_pixel(125, 125).0, [0, 128, 0, 255]);
+    assert_eq!(image.get_pixel(175, 175).0, [255, 0, 0, 255]);
+    // The rounded top-left corner of the clip is not painted.
+    assert_eq!(image.get_pixel(100, 100).0, [255, 0, 0, 255]);
+}
+
+#[test]
+fn test_rendering_group_draws_cursor() {
+    let servo_test = ServoTest::new();
+    let rendering_group = servo_test.new_rendering_group();
+    rendering_group.set_draws_cursor(true);
+
+    let delegate = Rc::new(WebViewDelegateImpl::default());
+    let webview = WebViewBuilder::new_in_group(servo_test.servo(), &rendering_group)
+        .delegate(delegate.clone())
+        .url(Url::parse("data:text/html,<body style='background: white'>").unwrap())
+        .build();
+    show_webview_and_wait_for_rendering_to_be_ready(&servo_test, &webview, &delegate);
+    webview.notify_input_event(InputEvent::MouseMove(MouseMoveEvent::new(
+        DevicePoint::new(50., 50.).into(),
+    )));
+
+    let image = capture_group(&servo_test, &rendering_group);
+    // The arrow is drawn in black below and to the right of the mouse position.
+    assert_eq!(image.get_pixel(51, 56).0, [0, 0, 0, 255]);
+    assert_eq!(image.get_pixel(150, 150).0, [255, 255, 255, 255]);
+}
+
+#[test]
+fn test_rendering_group_hidpi_scale_factor() {
+    let servo_test = ServoTest::new();
+    let create_webview_in_new_group = || {
+        let rendering_group = servo_test.new_rendering_group();
+        let webview = WebViewBuilder::new_in_group(servo_test.servo(), &rendering_group).build();
+        (rendering_group, webview)
+    };
+    let (first_group, first_webview) = create_webview_in_new_group();
+    let (_second_group, second_webview) = create_webview_in_new_group();
+
+    first_group.set_hidpi_scale_factor(Scale::new(2.0));
+    assert_eq!(first_webview.hidpi_scale_factor(), Scale::new(2.0));
+    assert_eq!(second_webview.hidpi_scale_factor(), Scale::new(1.0));
+
+    // New WebViews in the group start with the scale factor of the group.
+    let new_webview = WebViewBuilder::new_in_group(servo_test.servo(), &first_group).build();
+    assert_eq!(new_webview.hidpi_scale_factor(), Scale::new(2.0));
+}
+
+#[test]
+fn test_rendering_group_resize_synchronized() {
+    let servo_test = ServoTest::new();
+    let rendering_group = servo_test.new_rendering_group();
+
+    let delegate = Rc::new(WebViewDelegateImpl::default());
+    let webview = WebViewBuilder::new_in_group(servo_test.servo(), &rendering_group)
+        .delegate(delegate.clone())
+        .url(Url::parse("data:text/html,<body style='background: green'>").unwrap())
+        .build();
+    show_webview_and_wait_for_rendering_to_be_ready(&servo_test, &webview, &delegate);
+
+    let resized = Rc::new(Cell::new(false));
+    let callback_resized = resized.clone();
+    rendering_group.resize_synchronized(
+        PhysicalSize {
+            width: 400,
+            height: 250,
+        },
+        move || callback_resized.set(true),
+    );
+    let spin_resized = resized.clone();
+    servo_test.spin(move || !spin_resized.get());
+
+    let image = capture_group(&servo_test, &rendering_group);
+    assert_eq!(image.dimensions(), (400, 250));
+    assert_eq!(image.get_pixel(350, 200).0, [0, 128, 0, 255]);
+}
+
+#[test]
+fn test_rendering_group_resize_present_timeout() {
+    let servo_test = ServoTest::new();
+    let rendering_group = servo_test.new_rendering_group();
+
+    let delegate = Rc::new(WebViewDelegateImpl::default());
+    let webview = WebViewBuilder::new_in_group(servo_test.servo(), &rendering_group)
+        .delegate(delegate.clone())
+        .url(Url::parse("data:text/html,<body style='background: green'>").unwrap())
+        .build();
+    show_webview_and_wait_for_rendering_to_be_ready(&servo_test, &webview, &delegate);
+
+    rendering_group.set_resize_present_timeout(Some(Duration::from_secs(60)));
+    rendering_group.resize(PhysicalSize {
+        width: 400,
+        height: 250,
+    });
+
+    // The page has not been laid out at the new size yet, so nothing is presented.
+    assert!(!rendering_group.present());
+
+    // The next frame that is painted has the content laid out at the new size.
+    let painted = Rc::new(Cell::new(false));
+    let callback_painted = painted.clone();
+    webview.notify_when_next_frame_presented(move || callback_painted.set(true));
+    let spin_painted = painted.clone();
+    servo_test.spin(move || !spin_painted.get());
+
+    let image = capture_group(&servo_test, &rendering_group);
+    assert_eq!(image.dimensions(), (400, 250));
+    assert_eq!(image.get_pixel(350, 200).0, [0, 128, 0, 255]);
+}
+
+#[test]
+fn test_rendering_group_occlusion_notifier() {
+    let servo_test = ServoTest::new();
+    let create_webview_in_new_group = || {
+        let rendering_group = servo_test.new_rendering_group();
+        let webview = WebViewBuilder::new_in_group(servo_test.servo(), &rendering_group).build();
+        (rendering_group, webview)
+    };
+    let (first_group, first_webview) = create_webview_in_new_group();
+    let (_second_group, second_webview) = create_webview_in_new_group();
+
+    let throttled = |servo: &Servo, webview_id: WebViewId| {
+        servo
+            .paint_debug_state()
+            .into_iter()
+            .find(|state| state.painting_order.contains(&webview_id))
+            .expect("Should find the Painter of the WebView")
+            .throttled
+    };
+    assert!(!throttled(servo_test.servo(), first_webview.id()));
+
+    // Occlusion can be reported from another thread.
+    let occlusion_notifier = first_group
+        .occlusion_notifier()
+        .expect("Should have an OcclusionNotifier");
+    std::thread::spawn(move || occlusion_notifier.set_occluded(true))
+        .join()
+        .expect("Could not report occlusion");
+
+    let servo = servo_test.servo().clone();
+    let first_webview_id = first_webview.id();
+    servo_test.spin(move || !throttled(&servo, first_webview_id));
+    assert!(!throttled(servo_test.servo(), second_webview.id()));
+    assert!(!first_group.present());
+
+    first_group
+        .occlusion_notifier()
+        .expect("Should have an OcclusionNotifier")
+        .set_occluded(false);
+    let servo = servo_test.servo().clone();
+    servo_test.spin(move || throttled(&servo, first_webview_id));
+}
+
+#[test]
+fn test_rendering_group_capture_stream() {
+    let servo_test = ServoTest::new();
+    let rendering_group = servo_test.new_rendering_group();
+
+    let delegate = Rc::new(WebViewDelegateImpl::default());
+    let webview = WebViewBuilder::new_in_group(servo_test.servo(), &rendering_group)
+        .delegate(delegate.clone())
+        .url(Url::parse("data:text/html,<body style='background: green'>").unwrap())
+        .build();
+    show_webview_and_wait_for_rendering_to_be_ready(&servo_test, &webview, &delegate);
+
+    let receiver = rendering_group
+        .start_capture_stream(60.)
+        .expect("Should be able to start a capture stream");
+    webview.paint();
+
+    let frame = receiver
+        .try_recv()
+        .expect("Painting should have captured a frame");
+    assert_eq!(frame.image.dimensions(), (300, 200));
+    assert_eq!(frame.image.get_pixel(150, 100).0, [0, 128, 0, 255]);
+}
+
+#[test]
+fn test_webview_decorations() {
+    let servo_test = ServoTest::new();
+    let rendering_group = servo_test.new_rendering_group();
+    rendering_group.set_background(PaintBackground::Color(ColorF::WHITE));
+
+    let delegate = Rc::new(WebViewDelegateImpl::default());
+    let webview = WebViewBuilder::new_in_group(servo_test.servo(), &rendering_group)
+        .delegate(delegate.clone())
+        .url(Url::parse("data:text/html,<body style='background: green'>").unwrap())
+        .build();
+    webview.set_rect(Some(DeviceIntRect::from_origin_and_size(
+        Point2D::new(100, 50),
+        Size2D::new(100, 100),
+    )));
+    webview.set_decorations(WebViewDecorations {
+        border: Some(WebViewBorder {
+            width: 10.0,
+            color: ColorF::new(1.0, 0.0, 0.0, 1.0),
+        }),
+        focus_ring: Some(WebViewBorder {
+            width: 10.0,
+            color: ColorF::new(0.0, 0.0, 1.0, 1.0),
+        }),
+        shadow: None,
+    });
+    show_webview_and_wait_for_rendering_to_be_ready(&servo_test, &webview, &delegate);
+
+    let image = capture_group(&servo_test, &rendering_group);
+    assert_eq!(image.get_pixel(150, 100).0, [0, 128, 0, 255]);
+    assert_eq!(image.get_pixel(95, 100).0, [255, 0, 0, 255]);
+    assert_eq!(image.get_pixel(85, 100).0, [0, 0, 255, 255]);
+    assert_eq!(image.get_pixel(75, 100).0, [255, 255, 255, 255]);
+}
+
+#[test]
+fn test_decorations_of_covered_webview_are_painted() {
+    let servo_test = ServoTest::new();
+    let rendering_group = servo_test.new_rendering_group();
+    rendering_group.set_background(PaintBackground::Color(ColorF::WHITE));
+    let rect = DeviceIntRect::from_origin_and_size(Point2D::new(100, 50), Size2D::new(100, 100));
+
+    let bottom_delegate = Rc::new(WebViewDelegateImpl::default());
+    let bottom_webview = WebViewBuilder::new_in_group(servo_test.servo(), &rendering_group)
+        .delegate(bottom_delegate.clone())
+        .url(Url::parse("data:text/html,<body style='background: green'>").unwrap())
+        .build();
+    bottom_webview.set_rect(Some(rect));
+    bottom_webview.set_decorations(WebViewDecorations {
+        border: Some(WebViewBorder {
+            width: 10.0,
+            color: ColorF::new(1.0, 0.0, 0.0, 1.0),
+        }),
+        focus_ring: None,
+        shadow: None,
+    });
+    show_webview_and_wait_for_rendering_to_be_ready(&servo_test, &bottom_webview, &bottom_delegate);
+
+    // The opaque `WebView` on top covers the content of the bottom one, but not its border,
+    // so the bottom `WebView` must not be skipped when compositing.
+    let top_delegate = Rc::new(WebViewDelegateImpl::default());
+    let top_webview = WebViewBuilder::new_in_group(servo_test.servo(), &rendering_group)
+        .delegate(top_delegate.clone())
+        .url(Url::parse("data:text/html,<body style='background: blue'>").unwrap())
+        .build();
+    top_webview.set_rect(Some(rect));
+    top_webview.set_opaque(true);
+    top_webview.set_painting_index(usize::MAX);
+    show_webview_and_wait_for_rendering_to_be_ready(&servo_test, &top_webview, &top_delegate);
+
+    let image = capture_group(&servo_test, &rendering_group);
+    assert_eq!(image.get_pixel(150, 100).0, [0, 0, 255, 255]);
+    assert_eq!(image.get_pixel(95, 100).0, [255, 0, 0, 255]);
+}
+
+#[test]
+fn test_smooth_scroll_to() {
+    let servo_test = ServoTest::new();
+    let delegate = Rc::new(WebViewDelegateImpl::default());
+    let webview = WebViewBuilder::new(servo_test.servo(), servo_test.rendering_context.clone())
+        .delegate(delegate.clone())
+        .url(
+            Url::parse("data:text/html,<!DOCTYPE html><body style='height: 5000px'></body>")
+                .unwrap(),
+        )
+        .build();
+    show_webview_and_wait_for_rendering_to_be_ready(&servo_test, &webview, &delegate);
+
+    webview.smooth_scroll_to(LayoutVector2D::new(0.0, 300.0), Duration::from_millis(50));
+
+    let mut scroll_y = None;
+    for _ in 0..1000 {
+        scroll_y = Some(evaluate_javascript(
+            &servo_test,
+            webview.clone(),
+            "window.scrollY",
+        ));
+        if scroll_y == Some(Ok(JSValue::Number(300.0))) {
+            break;
+        }
+    }
+    assert_eq!(scroll_y, Some(Ok(JSValue::Number(300.0))));
+}
+
+#[test]
+fn test_overlay_scrollbars() {
+    let servo_test = ServoTest::new_with_builder(|builder| {
+        let mut preferences = Preferences::default();
+        preferences.overlay_scrollbars_enabled = true;
+        builder.preferences(preferences)
+    });
+    let rendering_group = servo_test.new_rendering_group();
+
+    let delegate = Rc::new(WebViewDelegateImpl::default());
+    let webview = WebViewBuilder::new_in_group(servo_test.servo(), &rendering_group)
+        .delegate(delegate.clone())
+        .url(
+            Url::parse(
+                "data:text/html,<!DOCTYPE html><body style='height: 5000px; background: green'>",
+            )
+            .unwrap(),
+        )
+        .build();
+    show_webview_and_wait_for_rendering_to_be_ready(&servo_test, &webview, &delegate);
+
+    webview.smooth_scroll_to(LayoutVector2D::new(0.0, 300.0), Duration::from_millis(10));
+    let mut scroll_y = None;
+    for _ in 0..1000 {
+        scroll_y = Some(evaluate_javascript(
+            &servo_test,
+            webview.clone(),
+            "window.scrollY",
+        ));
+        if scroll_y == Some(Ok(JSValue::Number(300.0))) {
+            break;
+        }
+    }
+    assert_eq!(scroll_y, Some(Ok(JSValue::Number(300.0))));
+
+    let image = capture_group(&servo_test, &rendering_group);
+
+    // The thumb of the vertical scrollbar is drawn near the top right corner, on top of the
+    // content, while the rest of the viewport is left untouched.
+    assert_ne!(image.get_pixel(295, 20).0, [0, 128, 0, 255]);
+    assert_eq!(image.get_pixel(150, 100).0, [0, 128, 0, 255]);
+}
+
+#[test]
+fn test_webview_paint_timing() {
+    let servo_test = ServoTest::new();
+    let delegate = Rc::new(WebViewDelegateImpl::default());
+    let webview = WebViewBuilder::new(servo_test.servo(), servo_test.rendering_context.clone())
+        .delegate(delegate.clone())
+        .url(Url::parse("data:text/html,<!DOCTYPE html><body>hello</body>").unwrap())
+        .build();
+    webview.hide();
+
+    let load_webview = webview.clone();
+    servo_test.spin(move || load_webview.load_status() != LoadStatus::Complete);
+    assert_eq!(webview.paint_timing().first_paint, None);
+
+    webview.show();
+    let paint_timing_webview = webview.clone();
+    servo_test.spin(move || {
+        paint_timing_webview
+            .paint_timing()
+            .first_contentful_paint
+            .is_none()
+    });
+    assert!(webview.paint_timing().first_paint.is_some());
+}
+
+#[test]
+fn test_webview_input_latency_histogram() {
+    let servo_test = ServoTest::new();
+    let delegate = Rc::new(WebViewDelegateImpl::default());
+    let webview = WebViewBuilder::new(servo_test.servo(), servo_test.rendering_context.clone())
+        .delegate(delegate.clone())
+        .url(
+            Url::parse(
+                "data:text/html,<!DOCTYPE html><body onmousemove=\"document.body.style.background = 'green'\">",
+            )
+            .unwrap(),
+        )
+        .build();
+
+    show_webview_and_wait_for_rendering_to_be_ready(&servo_test, &webview, &delegate);
+    assert_eq!(webview.input_latency_histogram().sample_count(), 0);
+
+    webview.notify_input_event(InputEvent::MouseMove(MouseMoveEvent::new(
+        DevicePoint::new(10., 10.).into(),
+    )));
+
+    let histogram_webview = webview.clone();
+    servo_test.spin(move || histogram_webview.input_latency_histogram().sample_count() == 0);
+
+    let histogram = webview.input_latency_histogram();
+    assert_eq!(histogram.sample_count(), 1);
+    assert_eq!(histogram.mean, histogram.max);
+}
+
+#[test]
+fn test_rendering_group_hit_test_overlapping_webviews() {
+    let servo_test = ServoTest::new();
+    let rendering_group = servo_test.new_rendering_group();
+    let create_webview = |color: &str| {
+        let delegate = Rc::new(WebViewDelegateImpl::default());
+        let webview = WebViewBuilder::new_in_group(servo_test.servo(), &rendering_group)
+            .delegate(delegate.clone())
+            .url(
+                Url::parse(&format!(
+                    "data:text/html,<body style='background: {color}'>"
+                ))
+                .unwrap(),
+            )
+            .build();
+        show_webview_and_wait_for_rendering_to_be_ready(&servo_test, &webview, &delegate);
+        webview
+    };
+    let bottom = create_webview("red");
+    let top = create_webview("green");
+    top.set_rect(Some(DeviceIntRect::from_origin_and_size(
+        Point2D::new(0, 0),
+        Size2D::new(100, 100),
+    )));
+
+    let hit_webview = |x: f32, y: f32| {
+        rendering_group
+            .hit_test(DevicePoint::new(x, y))
+            .map(|(webview_id, _)| webview_id)
+    };
+    assert_eq!(hit_webview(50., 50.), Some(top.id()));
+    assert_eq!(hit_webview(200., 150.), Some(bottom.id()));
+
+    top.hide();
+    assert_eq!(hit_webview(50., 50.), Some(bottom.id()));
+
+    bottom.hide();
+    assert_eq!(hit_webview(50., 50.), None);
+}
+
+#[test]
+fn test_paint_message_trace() {
+    let servo_test = ServoTest::new();
+    servo_test.servo().set_paint_message_tracing_enabled(true);
+
+    let delegate = Rc::new(WebViewDelegateImpl::default());
+    let webview = WebViewBuilder::new(servo_test.servo(), servo_test.rendering_context.clone())
+        .delegate(delegate.clone())
+        .url(Url::parse("data:text/html,<!DOCTYPE html><body>hello</body>").unwrap())
+        .build();
+    show_webview_and_wait_for_rendering_to_be_ready(&servo_test, &webview, &delegate);
+
+    let trace = servo_test.servo().paint_message_trace();
+    let display_list_entry = trace
+        .iter()
+        .find(|entry| entry.message == "SendDisplayList" && entry.webview_id == Some(webview.id()))
+        .expect("Should have traced a display list for the WebView");
+    assert!(display_list_entry.painter_id.is_some());
+    assert!(display_list_entry.document_id.is_some());
+    assert!(!display_list_entry.dropped);
+
+    servo_test.servo().set_paint_message_tracing_enabled(false);
+    assert!(servo_test.servo().paint_message_trace().is_empty());
+}
+
+#[test]
+fn test_webview_display_list_statistics() {
+    let servo_test = ServoTest::new();
+    let delegate = Rc::new(WebViewDelegateImpl::default());
+    let webview = WebViewBuilder::new(servo_test.servo(), servo_test.rendering_context.clone())
+        .delegate(delegate.clone())
+        .url(Url::parse("data:text/html,<!DOCTYPE html><body>hello</body>").unwrap())
+        .build();
+    show_webview_and_wait_for_rendering_to_be_ready(&servo_test, &webview, &delegate);
+
+    let statistics = webview.display_list_statistics();
+    assert!(statistics.size_in_bytes > 0);
+    assert!(statistics.item_count > 0);
+
+    // Painting again without any new display list leaves nothing for the last frame, while
+    // the installed display lists stay the same.
+    webview.paint();
+    let repainted_statistics = webview.display_list_statistics();
+    assert_eq!(repainted_statistics.last_frame_size_in_bytes, 0);
+    assert_eq!(repainted_statistics.last_frame_item_count, 0);
+    assert_eq!(repainted_statistics.size_in_bytes, statistics.size_in_bytes);
+}
+
+#[test]
+fn test_capture_hidden_webview() {
+    let servo_test = ServoTest::new();
+    let rendering_group = servo_test.new_rendering_group();
+
+    let green_delegate = Rc::new(WebViewDelegateImpl::default());
+    let green_webview = WebViewBuilder::new_in_group(servo_test.servo(), &rendering_group)
+        .delegate(green_delegate.clone())
+        .url(Url::parse("data:text/html,<body style='background: green'>").unwrap())
+        .build();
+    show_webview_and_wait_for_rendering_to_be_ready(&servo_test, &green_webview, &green_delegate);
+
+    let red_delegate = Rc::new(WebViewDelegateImpl::default());
+    let red_webview = WebViewBuilder::new_in_group(servo_test.servo(), &rendering_group)
+        .delegate(red_delegate.clone())
+        .url(Url::parse("data:text/html,<body style='background: red'>").unwrap())
+        .build();
+    show_webview_and_wait_for_rendering_to_be_ready(&servo_test, &red_webview, &red_delegate);
+    green_webview.hide();
+
+    let capture = Rc::new(RefCell::new(None));
+    let callback_capture = capture.clone();
+    green_webview.capture(
+        Some(DeviceRect::from_origin_and_size(
+            Point2D::new(10.0, 10.0),
+            Size2D::new(50.0, 40.0),
+        )),
+        move |result| {
+            *callback_capture.borrow_mut() = Some(result);
+        },
+    );
+    let spin_capture = capture.clone();
+    servo_test.spin(move || spin_capture.borrow().is_none());
+
+    let image = capture
+        .borrow_mut()
+        .take()
+        .expect("Should have waited for capture")
+        .expect("Capture should succeed");
+    assert_eq!(image.dimensions(), (50, 40));
+    assert_eq!(image.get_pixel(25, 20).0, [0, 128, 0, 255]);
+
+    // The rest of the group should still render the visible WebView afterwards.
+    let image = capture_group(&servo_test, &rendering_group);
+    assert_eq!(image.get_pixel(150, 100).0, [255, 0, 0, 255]);
+}
+
+#[test]
+fn test_floating_webview() {
+    let servo_test = ServoTest::new();
+    let rendering_group = servo_test.new_rendering_group();
+
+    let floating_delegate = Rc::new(WebViewDelegateImpl::default());
+    let floating_webview = WebViewBuilder::new_in_group(servo_test.servo(), &rendering_group)
+        .delegate(floating_delegate.clone())
+        .url(Url::parse("data:text/html,<body style='background: green'>").unwrap())
+        .build();
+    floating_webview.set_rect(Some(DeviceIntRect::from_origin_and_size(
+        Point2D::new(0, 0),
+        Size2D::new(100, 100),
+    )));
+    floating_webview.set_floating(true);
+    floating_webview.set_floating_offset(DeviceVector2D::new(50.0, 50.0));
+    show_webview_and_wait_for_rendering_to_be_ready(
+        &servo_test,
+        &floating_webview,
+        &floating_delegate,
+    );
+
+    // This `WebView` is created later, so it is above the floating `WebView` in the
+    // painting order.
+    let delegate = Rc::new(WebViewDelegateImpl::default());
+    let webview = WebViewBuilder::new_in_group(servo_test.servo(), &rendering_group)
+        .delegate(delegate.clone())
+        .url(Url::parse("data:text/html,<body style='background: red'>").unwrap())
+        .build();
+    show_webview_and_wait_for_rendering_to_be_ready(&servo_test, &webview, &delegate);
+
+    let image = capture_group(&servo_test, &rendering_group);
+    assert_eq!(image.get_pixel(25, 25).0, [255, 0, 0, 255]);
+    assert_eq!(image.get_pixel(125, 125).0, [0, 128, 0, 255]);
+}
+
+#[test]
+fn test_pinch_zoom_is_independent_per_rendering_group() {
+    let servo_test = ServoTest::new();
+    let create_webview = || {
+        let rendering_group = servo_test.new_rendering_group();
+        let delegate = Rc::new(WebViewDelegateImpl::default());
+        let webview = WebViewBuilder::new_in_group(servo_test.servo(), &rendering_group)
+            .delegate(delegate.clone())
+            .url(Url::parse("data:text/html,<body style='background: green'>").unwrap())
+            .build();
+        show_webview_and_wait_for_rendering_to_be_ready(&servo_test, &webview, &delegate);
+        (rendering_group, webview)
+    };
+    let (_first_group, first_webview) = create_webview();
+    let (_second_group, second_webview) = create_webview();
+
+    let initial_scale = second_webview.device_pixels_per_css_pixel();
+    first_webview.pinch_zoom(2.0, DevicePoint::new(10.0, 10.0));
+
+    let zoomed_webview = first_webview.clone();
+    servo_test.spin(move || zoomed_webview.device_pixels_per_css_pixel() == initial_scale);
+    assert_eq!(
+        first_webview.device_pixels_per_css_pixel().get(),
+        initial_scale.get() * 2.0
+    );
+    assert_eq!(second_webview.device_pixels_per_css_pixel(), initial_scale);
+}
+
+#[test]
+fn test_rendering_group_vsync_driven() {
+    let servo_test = ServoTest::new();
+    let rendering_group = servo_test.new_rendering_group();
+    let delegate = Rc::new(WebViewDelegateImpl::default());
+    let webview = WebViewBuilder::new_in_group(servo_test.servo(), &rendering_group)
+        .delegate(delegate.clone())
+        .url(Url::parse("data:text/html,<body style='background: green'>").unwrap())
+        .build();
+    rendering_group.set_vsync_driven(true);
+
+    let load_webview = webview.clone();
+    servo_test.spin(move || load_webview.load_status() != LoadStatus::Complete);
+    delegate.reset();
+
+    let _ = evaluate_javascript(
+        &servo_test,
+        webview.clone(),
+        "document.body.style.background = 'red';",
+    );
+
+    // Without vsync ticks, no frame is generated for the change, even once it has had plenty
+    // of time to reach WebRender.
+    let start = Instant::now();
+    servo_test.spin(move || start.elapsed() < Duration::from_millis(500));
+    assert!(!delegate.new_frame_ready.get());
+
+    // A single tick generates the frame.
+    rendering_group.notify_vsync();
+    let captured_delegate = delegate.clone();
+    servo_test.spin(move || !captured_delegate.new_frame_ready.get());
+}
+
+#[test]
+fn test_rendering_group_shader_warm_up() {
+    let servo_test = ServoTest::new();
+    let rendering_context = Rc::new(
+        SoftwareRenderingContext::new(PhysicalSize {
+            width: 300,
+            height: 200,
+        })
+        .expect("Could not create SoftwareRenderingContext"),
+    );
+    let rendering_group = RenderingGroupBuilder::new(servo_test.servo(), rendering_context)
+        .shader_precache_policy(ShaderPrecachePolicy::Async)
+        .build();
+
+    // Before its first `WebView`, the group has no WebRender instance with shaders to wait for.
+    assert!(!rendering_group.warm_up());
+
+    let delegate = Rc::new(WebViewDelegateImpl::default());
+    let webview = WebViewBuilder::new_in_group(servo_test.servo(), &rendering_group)
+        .delegate(delegate.clone())
+        .url(Url::parse("data:text/html,<body style='background: green'>").unwrap())
+        .build();
+
+    // The shaders compiled in the background are ready before the first frame is painted.
+    assert!(rendering_group.warm_up());
+    show_webview_and_wait_for_rendering_to_be_ready(&servo_test, &webview, &delegate);
+
+    let image = capture_group(&servo_test, &rendering_group);
+    assert_eq!(image.get_pixel(150, 100).0, [0, 128, 0, 255]);
+}
+
+#[test]
+fn test_rendering_group_frame_recording() {
+    let servo_test = ServoTest::new();
+    let rendering_group = servo_test.new_rendering_group();
+    let delegate = Rc::new(WebViewDelegateImpl::default());
+    let webview = WebViewBuilder::new_in_group(servo_test.servo(), &rendering_group)
+        .delegate(delegate.clone())
+        .url(Url::parse("data:text/html,<body style='background: green'>").unwrap())
+        .build();
+    show_webview_and_wait_for_rendering_to_be_ready(&servo_test, &webview, &delegate);
+
+    let directory =
+        std::env::temp_dir().join(format!("servo-frame-recording-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&directory);
+    rendering_group.start_frame_recording(directory.clone());
+    webview.paint();
+    rendering_group.stop_frame_recording();
+
+    let frames: Vec<_> = std::fs::read_dir(&directory)
+        .expect("Recording directory should exist")
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    assert!(!frames.is_empty());
+    let frame = image::open(&frames[0])
+        .expect("Recorded frame should be a valid image")
+        .to_rgba8();
+    assert_eq!(frame.dimensions(), (300, 200));
+    assert_eq!(frame.get_pixel(150, 100).0, [0, 128, 0, 255]);
+
+    let _ = std::fs::remove_dir_all(&directory);
+}
+
+#[test]
+fn test_rendering_group_webrender_debug() {
+    let servo_test = ServoTest::new();
+    let create_webview_in_new_group = || {
+        let rendering_group = servo_test.new_rendering_group();
+        let webview = WebViewBuilder::new_in_group(servo_test.servo(), &rendering_group).build();
+        (rendering_group, webview)
+    };
+    let (first_group, first_webview) = create_webview_in_new_group();
+    let (_second_group, second_webview) = create_webview_in_new_group();
+
+    let debug_options = |webview: &WebView| {
+        servo_test
+            .servo()
+            .paint_debug_state()
+            .into_iter()
+            .find(|state| state.painting_order.contains(&webview.id()))
+            .expect("Should find the Painter of the WebView")
+            .webrender_debug_options
+    };
+
+    first_group.set_webrender_debug(WebRenderDebugOption::GpuTimeQueries, true);
+    assert_eq!(
+        debug_options(&first_webview),
+        vec![WebRenderDebugOption::GpuTimeQueries]
+    );
+    assert!(debug_options(&second_webview).is_empty());
+
+    first_webview.toggle_webrender_debugging(WebRenderDebugOption::GpuTimeQueries);
+    assert!(debug_options(&first_webview).is_empty());
+    assert!(debug_options(&second_webview).is_empty());
+
+    // Toggling through a `WebView` changes the settings of its group, which are applied to
+    // the WebRender instance created after the last `WebView` of the group was closed.
+    first_webview.toggle_webrender_debugging(WebRenderDebugOption::TextureCacheDebug);
+    drop(first_webview);
+    let new_webview = WebViewBuilder::new_in_group(servo_test.servo(), &first_group).build();
+    assert_eq!(
+        debug_options(&new_webview),
+        vec![WebRenderDebugOption::TextureCacheDebug]
+    );
+}
+
+#[test]
+fn test_rendering_group_text_antialiasing() {
+    let servo_test = ServoTest::new();
+    let rendering_group = servo_test.new_rendering_group();
+    let delegate = Rc::new(WebViewDelegateImpl::default());
+    let webview = WebViewBuilder::new_in_group(servo_test.servo(), &rendering_group)
+        .delegate(delegate.clone())
+        .url(
+            Url::parse(
+                "data:text/html,<body style='background: white; color: black; font-size: 40px'>\
+                 Servo",
+            )
+            .unwrap(),
+        )
+        .build();
+    show_webview_and_wait_for_rendering_to_be_ready(&servo_test, &webview, &delegate);
+
+    rendering_group.set_text_antialiasing(TextAntialiasing::None);
+
+    // Without antialiasing, black text on a white background has no intermediate colors.
+    let image = capture_group(&servo_test, &rendering_group);
+    assert!(image.pixels().any(|pixel| pixel.0 == [0, 0, 0, 255]));
+    assert!(
+        image
+            .pixels()
+            .all(|pixel| pixel.0 == [0, 0, 0, 255] || pixel.0 == [255, 255, 255, 255])
+    );
+}
+
+#[test]
+fn test_low_priority_rendering_group_is_not_starved() {
+    let servo_test = ServoTest::new();
+
+    // A busy high priority group, which animates continuously.
+    let focused_group = servo_test.new_rendering_group();
+    let focused_delegate = Rc::new(WebViewDelegateImpl::default());
+    let _focused_webview = WebViewBuilder::new_in_group(servo_test.servo(), &focused_group)
+        .delegate(focused_delegate.clone())
+        .url(
+            Url::parse(
+                "data:text/html,<body><script>\
+                     function step(time) { \
+                       document.body.style.background = `rgb(${Math.floor(time) & 255}, 0, 0)`; \
+                       requestAnimationFrame(step); \
+                     } \
+                     requestAnimationFrame(step);\
+                     </script>",
+            )
+            .unwrap(),
+        )
+        .build();
+    focused_group.set_priority(RenderingPriority::High);
+
+    let background_group = servo_test.new_rendering_group();
+    let background_delegate = Rc::new(WebViewDelegateImpl::default());
+    let background_webview = WebViewBuilder::new_in_group(servo_test.servo(), &background_group)
+        .delegate(background_delegate.clone())
+        .url(Url::parse("data:text/html,<body style='background: green'>").unwrap())
+        .build();
+    background_group.set_priority(RenderingPriority::Low);
+
+    // The frames of the high priority group are scheduled first.
+    let debug_state = servo_test.servo().paint_debug_state();
+    let position_of = |group: &RenderingGroup| {
+        debug_state
+            .iter()
+            .position(|state| Some(state.painter_id) == group.painter_id())
+            .expect("Should find the Painter of the group")
+    };
+    assert!(position_of(&focused_group) < position_of(&background_group));
+    assert_eq!(
+        debug_state[position_of(&focused_group)].priority,
+        RenderingPriority::High
+    );
+    assert_eq!(
+        debug_state[position_of(&background_group)].priority,
+        RenderingPriority::Low
+    );
+
+    // The low priority group still gets to render its frames.
+    show_webview_and_wait_for_rendering_to_be_ready(
+        &servo_test,
+        &background_webview,
+        &background_delegate,
+    );
+}
+
+#[test]
+fn test_focused_rendering_group() {
+    let servo_test = ServoTest::new();
+    let create_webview_in_new_group = || {
+        let rendering_group = servo_test.new_rendering_group();
+        let delegate = Rc::new(WebViewDelegateImpl::default());
+        let webview = WebViewBuilder::new_in_group(servo_test.servo(), &rendering_group)
+            .delegate(delegate.clone())
+            .url(Url::parse("data:text/html,<body style='background: green'>").unwrap())
+            .build();
+        show_webview_and_wait_for_rendering_to_be_ready(&servo_test, &webview, &delegate);
+        (rendering_group, webview)
+    };
+    let (first_group, _first_webview) = create_webview_in_new_group();
+    let (second_group, _second_webview) = create_webview_in_new_group();
+
+    first_group.set_focused(true);
+    assert!(first_group.focused());
+    assert!(!second_group.focused());
+
+    // Focusing another group moves the focus away from the first one.
+    second_group.set_focused(true);
+    assert!(!first_group.focused());
+    assert!(second_group.focused());
+
+    // With an unlimited frame budget, no group is deferred and once the latest frames
+    // have been presented, there is nothing new to present.
+    assert!(!servo_test.servo().present_rendering_groups(Duration::MAX));
+    assert!(!first_group.present());
+    assert!(!second_group.present());
+
+    second_group.set_focused(false);
+    assert!(!second_group.focused());
+}
+
+#[test]
+fn test_paint_recording_and_input_replay() {
+    let servo_test = ServoTest::new();
+    let delegate = Rc::new(WebViewDelegateImpl::default());
+    let webview = WebViewBuilder::new(servo_test.servo(), servo_test.rendering_context.clone())
+        .delegate(delegate.clone())
+        .url(
+            Url::parse(
+                "data:text/html,<!DOCTYPE html><body onmousemove=\"window.moves = (window.moves || 0) + 1\">",
+            )
+            .unwrap(),
+        )
+        .build();
+    show_webview_and_wait_for_rendering_to_be_ready(&servo_test, &webview, &delegate);
+
+    // Nothing is recorded unless recording was started.
+    assert!(servo_test.servo().stop_paint_recording().is_none());
+
+    servo_test.servo().start_paint_recording();
+    webview.notify_input_event(InputEvent::MouseMove(MouseMoveEvent::new(
+        DevicePoint::new(50., 50.).into(),
+    )));
+    let wait_for_moves = |expected: f64| {
+        let mut moves = None;
+        for _ in 0..1000 {
+            moves = Some(evaluate_javascript(
+                &servo_test,
+                webview.clone(),
+                "window.moves || 0",
+            ));
+            if moves == Some(Ok(JSValue::Number(expected))) {
+                break;
+            }
+        }
+        assert_eq!(moves, Some(Ok(JSValue::Number(expected))));
+    };
+    wait_for_moves(1.0);
+
+    let recording = servo_test
+        .servo()
+        .stop_paint_recording()
+        .expect("Should have a recording");
+    assert_eq!(recording.input_events().count(), 1);
+    assert!(
+        recording
+            .events
+            .iter()
+            .any(|event| matches!(event.kind, RecordedPaintEventKind::Message(_)))
+    );
+    assert!(
+        recording
+            .events
+            .windows(2)
+            .all(|events| events[0].time <= events[1].time)
+    );
+
+    // Replaying the recording sends the same input event again, but only once the virtual
+    // clock reaches the time at which it was recorded.
+    let mut replayer = InputEventReplayer::new(&recording);
+    let (time, _, _) = recording.input_events().next().unwrap();
+    if !time.is_zero() {
+        assert!(replayer.advance(Duration::ZERO, |_| Some(webview.clone())));
+    }
+    assert!(!replayer.advance(time, |_| Some(webview.clone())));
+    wait_for_moves(2.0);
+}
+
+#[test]
+fn test_painting_order_all() {
+    let servo_test = ServoTest::new();
+    let create_webview = |rendering_group: &RenderingGroup| {
+        WebViewBuilder::new_in_group(servo_test.servo(), rendering_group)
+            .url(Url::parse("data:text/html,").unwrap())
+            .build()
+    };
+    let first_group = servo_test.new_rendering_group();
+    let second_group = servo_test.new_rendering_group();
+    let first_bottom = create_webview(&first_group);
+    let second_only = create_webview(&second_group);
+    let first_top = create_webview(&first_group);
+
+    let painting_order: Vec<_> = servo_test
+        .servo()
+        .painting_order_all()
+        .into_iter()
+        .map(|(rendering_context, webview)| (rendering_context, webview.id()))
+        .collect();
+    assert_eq!(painting_order.len(), 3);
+    assert!(Rc::ptr_eq(
+        &painting_order[0].0,
+        &first_group.rendering_context()
+    ));
+    assert_eq!(painting_order[0].1, first_bottom.id());
+    assert!(Rc::ptr_eq(
+        &painting_order[1].0,
+        &first_group.rendering_context()
+    ));
+    assert_eq!(painting_order[1].1, first_top.id());
+    assert!(Rc::ptr_eq(
+        &painting_order[2].0,
+        &second_group.rendering_context()
+    ));
+    assert_eq!(painting_order[2].1, second_only.id());
+}
+
+#[test]
+fn test_rendering_group_renderer_statistics() {
+    let servo_test = ServoTest::new();
+    let rendering_group = servo_test.new_rendering_group();
+    let idle_rendering_group = servo_test.new_rendering_group();
+    let idle_webview = WebViewBuilder::new_in_group(servo_test.servo(), &idle_rendering_group)
+        .url(Url::parse("data:text/html,").unwrap())
+        .build();
+
+    let delegate = Rc::new(WebViewDelegateImpl::default());
+    let webview = WebViewBuilder::new_in_group(servo_test.servo(), &rendering_group)
+        .delegate(delegate.clone())
+        .url(Url::parse("data:text/html,<body style='background: green'>").unwrap())
+        .build();
+    assert_eq!(rendering_group.renderer_statistics(), None);
+
+    show_webview_and_wait_for_rendering_to_be_ready(&servo_test, &webview, &delegate);
+
+    // Statistics are attributed only to the group that actually rendered.
+    assert!(rendering_group.renderer_statistics().is_some());
+    assert_eq!(idle_rendering_group.renderer_statistics(), None);
+    drop(idle_webview);
+}
+
+#[test]
+fn test_rendering_group_mirror_webview() {
+    let servo_test = ServoTest::new();
+    let source_group = servo_test.new_rendering_group();
+    let target_group = servo_test.new_rendering_group();
+    target_group.set_background(PaintBackground::Color(ColorF::new(1.0, 0.0, 0.0, 1.0)));
+
+    let delegate = Rc::new(WebViewDelegateImpl::default());
+    let webview = WebViewBuilder::new_in_group(servo_test.servo(), &source_group)
+        .delegate(delegate.clone())
+        .url(Url::parse("data:text/html,<body style='background: green'>").unwrap())
+        .build();
+    target_group.mirror_webview(
+        &webview,
+        DeviceIntRect::from_origin_and_size(Point2D::new(0, 0), Size2D::new(100, 100)),
+    );
+    show_webview_and_wait_for_rendering_to_be_ready(&servo_test, &webview, &delegate);
+
+    let capture = Rc::new(RefCell::new(None));
+    let callback_capture = capture.clone();
+    target_group.capture(move |result| {
+        *callback_capture.borrow_mut() = Some(result);
+    });
+    // The target group has no `WebView`s of its own, so it has to be painted explicitly.
+    let spin_target_group = target_group.clone();
+    let spin_capture = capture.clone();
+    servo_test.spin(move || {
+        spin_target_group.present();
+        spin_capture.borrow().is_none()
+    });
+
+    let image = capture
+        .borrow_mut()
+        .take()
+        .expect("Should have waited for capture")
+        .expect("Capture should succeed");
+    assert_eq!(image.get_pixel(50, 50).0, [0, 128, 0, 255]);
+    assert_eq!(image.get_pixel(250, 150).0, [255, 0, 0, 255]);
+}
+
+#[test]
+fn test_webview_lifecycle_events() {
+    #[derive(Default)]
+    struct ServoDelegateImpl {
+        events: RefCell<Vec<(WebViewId, WebViewLifecycleEvent)>>,
+    }
+    impl ServoDelegate for ServoDelegateImpl {
+        fn notify_webview_lifecycle_event(
+            &self,
+            webview_id: WebViewId,
+            event: WebViewLifecycleEvent,
+        ) {
+            self.events.borrow_mut().push((webview_id, event));
+        }
+    }
+
+    let servo_test = ServoTest::new();
+    let servo_delegate = Rc::new(ServoDelegateImpl::default());
+    servo_test.servo().set_delegate(servo_delegate.clone());
+
+    let webview = WebViewBuilder::new(servo_test.servo(), servo_test.rendering_context.clone())
+        .delegate(Rc::new(WebViewDelegateImpl::default()))
+        .build();
+    let webview_id = webview.id();
+    webview.hide();
+    webview.show();
+    drop(webview);
+
+    let spin_delegate = servo_delegate.clone();
+    servo_test.spin(move || spin_delegate.events.borrow().len() < 4);
+    assert_eq!(
+        *servo_delegate.events.borrow(),
+        vec![
+            (webview_id, WebViewLifecycleEvent::Added),
+            (webview_id, WebViewLifecycleEvent::Hidden),
+            (webview_id, WebViewLifecycleEvent::Shown),
+            (webview_id, WebViewLifecycleEvent::Removed),
+        ]
+    );
+}
+
+#[test]
+fn test_rendering_group_webview_limit() {
+    #[derive(Default)]
+    struct ServoDelegateImpl {
+        events: RefCell<Vec<(WebViewId, WebViewLifecycleEvent)>>,
+    }
+
+    impl ServoDelegate for ServoDelegateImpl {
+        fn notify_webview_lifecycle_event(
+            &self,
+            webview_id: WebViewId,
+            event: WebViewLifecycleEvent,
+        ) {
+            if matches!(
+                event,
+                WebViewLifecycleEvent::Suspended | WebViewLifecycleEvent::Resumed
+            ) {
+                self.events.borrow_mut().push((webview_id, event));
+            }
+        }
+    }
+
+    let servo_test = ServoTest::new();
+    let servo_delegate = Rc::new(ServoDelegateImpl::default());
+    servo_test.servo().set_delegate(servo_delegate.clone());
+
+    let rendering_group = servo_test.new_rendering_group();
+    let create_webview =
+        || WebViewBuilder::new_in_group(servo_test.servo(), &rendering_group).build();
+    let first_webview = create_webview();
+    let second_webview = create_webview();
+    rendering_group.set_webview_limit(Some(1));
+
+    // Visible `WebView`s are never suspended.
+    first_webview.hide();
+    second_webview.hide();
+    first_webview.show();
+
+    let spin_delegate = servo_delegate.clone();
+    servo_test.spin(move || spin_delegate.events.borrow().len() < 3);
+    assert_eq!(
+        *servo_delegate.events.borrow(),
+        vec![
+            (first_webview.id(), WebViewLifecycleEvent::Suspended),
+            (first_webview.id(), WebViewLifecycleEvent::Resumed),
+            (second_webview.id(), WebViewLifecycleEvent::Suspended),
+        ]
+    );
+}
+
+#[test]
+fn test_page_zoom() {
+    let servo_test = ServoTest::new();
+    let delegate = Rc::new(WebViewDelegateImpl::default());
+    let webview = WebViewBuilder::new(servo_test.servo(), servo_test.rendering_context.clone())
+        .delegate(delegate.clone())
+        .build();
+
+    // Default zoom should be 1.0
+    assert_eq!(webview.page_zoom(), 1.0);
+
+    webview.set_page_zoom(1.5);
+    assert_eq!(webview.page_zoom(), 1.5);
+
+    webview.set_page_zoom(0.5);
+    assert_eq!(webview.page_zoom(), 0.5);
+
+    // Should clamp to minimum
+    webview.set_page_zoom(-1.0);
+    assert_eq!(webview.page_zoom(), 0.1);
+
+    // Should clamp to maximum
+    webview.set_page_zoom(100.0);
+    assert_eq!(webview.page_zoom(), 10.0);
+}
+
+#[test]
+fn test_page_zoom_reflows_once_settled() {
+    let servo_test = ServoTest::new();
+    let delegate = Rc::new(WebViewDelegateImpl::default());
+    // The page records every device pixel ratio that it is laid out with.
+    let webview = WebViewBuilder::new(servo_test.servo(), servo_test.rendering_context.clone())
+        .delegate(delegate.clone())
+        .url(
+            Url::parse(
+                "data:text/html,<!DOCTYPE html><script>\
+                 window.seenRatios = new Set([devicePixelRatio]); \
+                 addEventListener('resize', () => seenRatios.add(devicePixelRatio)); \
+                 (function sample() { \
+                   seenRatios.add(devicePixelRatio); \
+                   requestAnimationFrame(sample); \
+                 })();\
+                 </script>",
+            )
+            .unwrap(),
+        )
+        .build();
+    show_webview_and_wait_for_rendering_to_be_ready(&servo_test, &webview, &delegate);
+    let initial_ratio = webview.device_pixels_per_css_pixel().get() as f64;
+
+    // Only the final page zoom of a quick series of changes is laid out.
+    webview.set_page_zoom(1.5);
+    webview.set_page_zoom(2.0);
+    assert_eq!(webview.page_zoom(), 2.0);
+
+    let settled_ratio = JSValue::Number(initial_ratio * 2.0);
+    let mut device_pixel_ratio = None;
+    for _ in 0..1000 {
+        device_pixel_ratio = Some(evaluate_javascript(
+            &servo_test,
+            webview.clone(),
+            "window.devicePixelRatio",
+        ));
+        if device_pixel_ratio == Some(Ok(settled_ratio.clone())) {
+            break;
+        }
+    }
+    assert_eq!(device_pixel_ratio, Some(Ok(settled_ratio.clone())));
+
+    let Ok(JSValue::Array(seen_ratios)) =
+        evaluate_javascript(&servo_test, webview.clone(), "Array.from(seenRatios)")
+    else {
+        panic!("Should have recorded the device pixel ratios");
+    };
+    assert!(seen_ratios.contains(&settled_ratio));
+    assert!(!seen_ratios.contains(&JSValue::Number(initial_ratio * 1.5)));
 }
 
 #[test]
@@ -5,12 +5,17 @@
 use std::cell::{Ref, RefCell, RefMut};
 use std::hash::Hash;
 use std::rc::{Rc, Weak};
+use std::sync::mpsc::Receiver;
 use std::time::Duration;
 
 use base::generic_channel::GenericSender;
 use base::id::WebViewId;
+use compositing::{
+    CapturedFrame, DisplayListStatistics, InputLatencyHistogram, PaintError, PainterOptions,
+    WebViewClip, WebViewDecorations, WebViewPaintTiming,
+};
 use compositing_traits::WebViewTrait;
-use compositing_traits::rendering_context::RenderingContext;
+use compositing_traits::rendering_context::{RenderingContext, SoftwareRenderingContext};
 use constellation_traits::{EmbedderToConstellationMessage, TraversalDirection};
 use dpi::PhysicalSize;
 use embedder_traits::{
@@ -19,20 +24,24 @@ use embedder_traits::{
     MediaSessionActionType, NewWebViewDetails, ScreenGeometry, ScreenshotCaptureError, Scroll,
     Theme, TraversalId, ViewportDetails, WebViewPoint, WebViewRect,
 };
-use euclid::{Scale, Size2D};
+use euclid::{Scale, Size2D, Transform2D};
 use image::RgbaImage;
+use log::warn;
 use servo_geometry::DeviceIndependentPixel;
 use servo_url::ServoUrl;
 use style_traits::CSSPixel;
 use url::Url;
-use webrender_api::units::{DeviceIntRect, DevicePixel, DevicePoint, DeviceSize};
+use webrender_api::ColorF;
+use webrender_api::units::{
+    DeviceIntRect, DevicePixel, DevicePoint, DeviceRect, DeviceSize, DeviceVector2D, LayoutVector2D,
+};
 
 use crate::clipboard_delegate::{ClipboardDelegate, DefaultClipboardDelegate};
 use crate::responders::IpcResponder;
 use crate::webview_delegate::{CreateNewWebViewRequest, DefaultWebViewDelegate, WebViewDelegate};
 use crate::{
-    ColorPicker, ContextMenu, EmbedderControl, InputMethodControl, SelectElement, Servo,
-    UserContentManager, WebRenderDebugOption,
+    ColorPicker, ContextMenu, EmbedderControl, InputMethodControl, RenderingGroup, SelectElement,
+    Servo, UserContentManager, WebRenderDebugOption,
 };
 
 pub(crate) const MINIMUM_WEBVIEW_SIZE: Size2D<i32, DevicePixel> = Size2D::new(1, 1);
@@ -86,6 +95,11 @@ pub(crate) struct WebViewInner {
     pub(crate) clipboard_delegate: Rc<dyn ClipboardDelegate>,
 
     rendering_context: Rc<dyn RenderingContext>,
+    /// The [`RenderingGroup`] that this [`WebView`] was placed into with
+    /// [`WebViewBuilder::new_in_group`], if any. Settings of the WebRender instance, such as
+    /// WebRender debugging options, are made through it, so that they are kept when the
+    /// WebRender instance of the group is recreated.
+    rendering_group: Option<RenderingGroup>,
     user_content_manager: Option<Rc<UserContentManager>>,
     hidpi_scale_factor: Scale<f32, DeviceIndependentPixel, DevicePixel>,
     load_status: LoadStatus,
@@ -115,15 +129,20 @@ impl Drop for WebViewInner {
 impl WebView {
     pub(crate) fn new(mut builder: WebViewBuilder) -> Self {
         let servo = builder.servo;
-        let painter_id = servo
-            .paint_mut()
-            .register_rendering_context(builder.rendering_context.clone());
-
+        let had_painter = servo
+            .paint()
+            .painter_id_for_rendering_context(&builder.rendering_context)
+            .is_some();
+        let painter_id = servo.paint_mut().register_rendering_context_with_options(
+            builder.rendering_context.clone(),
+            &builder.painter_options,
+        );
         let id = WebViewId::new(painter_id);
         let webview = Self(Rc::new(RefCell::new(WebViewInner {
             id,
             servo: servo.clone(),
             rendering_context: builder.rendering_context,
+            rendering_group: builder.rendering_group.clone(),
             delegate: builder.delegate,
             clipboard_delegate: Rc::new(DefaultClipboardDelegate),
             hidpi_scale_factor: builder.hidpi_scale_factor,
@@ -140,18 +159,29 @@ impl WebView {
         })));
 
         let viewport_details = webview.viewport_details();
-        servo.paint().add_webview(
-            Box::new(ServoRendererWebView {
-                weak_handle: webview.weak_handle(),
-                id,
-            }),
-            viewport_details,
+        warn_on_paint_error(
+            "add WebView",
+            servo.paint().add_webview(
+                Box::new(ServoRendererWebView {
+                    weak_handle: webview.weak_handle(),
+                    id,
+                }),
+                viewport_details,
+            ),
         );
 
         servo
             .webviews_mut()
             .insert(webview.id(), webview.weak_handle());
 
+        // Settings are applied once the `WebView` has been added, so that those affecting
+        // the `WebView`s of the group, such as `RenderingGroup::hide_all`, apply to it too.
+        if !had_painter {
+            if let Some(rendering_group) = builder.rendering_group.as_ref() {
+                rendering_group.apply_settings();
+            }
+        }
+
         let user_content_manager_id = builder
             .user_content_manager
             .as_ref()
@@ -323,6 +353,11 @@ impl WebView {
             return;
         }
         self.inner_mut().cursor = new_value;
+        let _ = self
+            .inner()
+            .servo
+            .paint()
+            .set_webview_cursor(self.id(), new_value);
         self.delegate().notify_cursor_changed(self, new_value);
     }
 
@@ -366,17 +401,157 @@ impl WebView {
     /// clamped by that value.
     ///
     /// This will also resize any other [`WebView`] using the same [`RenderingContext`]. A
-    /// [`WebView`] is always as big as its [`RenderingContext`].
+    /// [`WebView`] is always as big as its [`RenderingContext`], unless it has been positioned
+    /// with [`WebView::set_rect`].
     pub fn resize(&self, new_size: PhysicalSize<u32>) {
         let new_size = PhysicalSize {
             width: new_size.width.max(MINIMUM_WEBVIEW_SIZE.width as u32),
             height: new_size.height.max(MINIMUM_WEBVIEW_SIZE.height as u32),
         };
 
-        self.inner()
-            .servo
-            .paint()
-            .resize_rendering_context(self.id(), new_size);
+        warn_on_paint_error(
+            "resize WebView",
+            self.inner()
+                .servo
+                .paint()
+                .resize_rendering_context(self.id(), new_size),
+        );
+    }
+
+    /// Set the color used to clear this [`WebView`]'s [`RenderingContext`] before its contents
+    /// are painted. By default this is the value of the `shell_background_color_rgba`
+    /// preference.
+    ///
+    /// This will also change the clear color of any other [`WebView`] using the same
+    /// [`RenderingContext`].
+    pub fn set_clear_color(&self, clear_color: ColorF) {
+        warn_on_paint_error(
+            "set clear color",
+            self.inner()
+                .servo
+                .paint()
+                .set_clear_color(self.id(), clear_color),
+        );
+    }
+
+    /// Position this [`WebView`] at `rect` within its [`RenderingContext`], which allows
+    /// tiling several [`WebView`]s that share the same [`RenderingContext`], such as in a
+    /// split view. Passing `None` makes this [`WebView`] fill its entire [`RenderingContext`],
+    /// which is the default. Points passed to input events are always relative to the origin
+    /// of the [`WebView`].
+    pub fn set_rect(&self, rect: Option<DeviceIntRect>) {
+        warn_on_paint_error(
+            "set WebView rectangle",
+            self.inner()
+                .servo
+                .paint()
+                .set_webview_rect(self.id(), rect.map(|rect| rect.to_f32())),
+        );
+    }
+
+    /// Set the opacity used when compositing this [`WebView`] into its [`RenderingContext`],
+    /// from `0.0` (fully transparent) to `1.0` (fully opaque), which is the default. Values
+    /// outside of that range are clamped. This does not affect input event handling.
+    pub fn set_opacity(&self, opacity: f32) {
+        warn_on_paint_error(
+            "set WebView opacity",
+            self.inner()
+                .servo
+                .paint()
+                .set_webview_opacity(self.id(), opacity),
+        );
+    }
+
+    /// Cap the rate at which this [`WebView`] produces new frames to `max_frame_rate` frames
+    /// per second, for instance to save power for a dashboard in the background. Its
+    /// animations, including `requestAnimationFrame` callbacks, run at most at this rate, while
+    /// other [`WebView`]s of the same [`RenderingContext`] are not affected. Pass `None` to
+    /// remove the cap.
+    pub fn set_max_frame_rate(&self, max_frame_rate: Option<f32>) {
+        warn_on_paint_error(
+            "set WebView maximum frame rate",
+            self.inner()
+                .servo
+                .paint()
+                .set_webview_max_frame_rate(self.id(), max_frame_rate),
+        );
+    }
+
+    /// Declare whether or not the contents of this [`WebView`] are fully opaque, for instance
+    /// because the page always paints a solid background. Servo cannot determine this on its
+    /// own, so it defaults to `false`. [`WebView`]s that are completely covered by a visible,
+    /// opaque [`WebView`] above them in the painting order are skipped when compositing.
+    pub fn set_opaque(&self, opaque: bool) {
+        warn_on_paint_error(
+            "set WebView opaque",
+            self.inner()
+                .servo
+                .paint()
+                .set_webview_opaque(self.id(), opaque),
+        );
+    }
+
+    /// Make this [`WebView`] float above all non-floating [`WebView`]s of its
+    /// [`RenderingContext`], regardless of its painting index. This is useful for
+    /// picture-in-picture windows, which should not need their own native window.
+    pub fn set_floating(&self, floating: bool) {
+        warn_on_paint_error(
+            "set WebView floating",
+            self.inner()
+                .servo
+                .paint()
+                .set_webview_floating(self.id(), floating),
+        );
+    }
+
+    /// Move this [`WebView`] by `offset` device pixels while it is floating, for instance
+    /// while the user drags it. Unlike [`WebView::set_rect`], this does not cause the page to
+    /// relayout.
+    pub fn set_floating_offset(&self, offset: DeviceVector2D) {
+        warn_on_paint_error(
+            "set WebView floating offset",
+            self.inner()
+                .servo
+                .paint()
+                .set_webview_floating_offset(self.id(), offset),
+        );
+    }
+
+    /// Clip this [`WebView`] to a rectangle, optionally with rounded corners, when compositing
+    /// it into its [`RenderingContext`]. This allows for rounded tabs and sidebars without
+    /// changing the content of the page. Pass `None` to remove the clip.
+    pub fn set_clip(&self, clip: Option<WebViewClip>) {
+        warn_on_paint_error(
+            "set WebView clip",
+            self.inner().servo.paint().set_webview_clip(self.id(), clip),
+        );
+    }
+
+    /// Paint decorations, such as a border, a focus ring, or a drop shadow, around this
+    /// [`WebView`] when compositing it into its [`RenderingContext`]. This is useful for tab
+    /// previews and split views, since it does not require changing the content of the page.
+    pub fn set_decorations(&self, decorations: WebViewDecorations) {
+        warn_on_paint_error(
+            "set WebView decorations",
+            self.inner()
+                .servo
+                .paint()
+                .set_webview_decorations(self.id(), decorations),
+        );
+    }
+
+    /// Apply a 2D transformation, such as a scale, rotation, or translation, when compositing
+    /// this [`WebView`] into its [`RenderingContext`]. The transformation is relative to the
+    /// origin of this [`WebView`]'s rectangle and does not cause the page to relayout. This is
+    /// useful for showing live thumbnails of [`WebView`]s.
+    pub fn set_transform(&self, transform: Transform2D<f32, DevicePixel, DevicePixel>) {
+        warn_on_paint_error(
+            "set WebView transform",
+            self.inner()
+                .servo
+                .paint()
+                .set_webview_transform(self.id(), transform),
+        );
     }
 
     pub fn hidpi_scale_factor(&self) -> Scale<f32, DeviceIndependentPixel, DevicePixel> {
@@ -392,26 +567,65 @@ impl WebView {
         }
 
         self.inner_mut().hidpi_scale_factor = new_scale_factor;
-        self.inner()
-            .servo
-            .paint()
-            .set_hidpi_scale_factor(self.id(), new_scale_factor);
+        warn_on_paint_error(
+            "set HiDPI scale factor",
+            self.inner()
+                .servo
+                .paint()
+                .set_hidpi_scale_factor(self.id(), new_scale_factor),
+        );
     }
 
     pub fn show(&self) {
-        self.inner()
-            .servo
-            .paint()
-            .show_webview(self.id())
-            .expect("BUG: invalid WebView instance");
+        warn_on_paint_error(
+            "show WebView",
+            self.inner().servo.paint().show_webview(self.id()),
+        );
     }
 
     pub fn hide(&self) {
-        self.inner()
-            .servo
-            .paint()
-            .hide_webview(self.id())
-            .expect("BUG: invalid WebView instance");
+        warn_on_paint_error(
+            "hide WebView",
+            self.inner().servo.paint().hide_webview(self.id()),
+        );
+    }
+
+    /// Move this [`WebView`] to `index` in the painting order of its [`RenderingContext`].
+    /// [`WebView`]s are painted from index 0 upwards, so a [`WebView`] at a lower index
+    /// appears below those at higher indices. Indices past the end of the painting order
+    /// move this [`WebView`] to the top. Newly created [`WebView`]s are placed on top.
+    pub fn set_painting_index(&self, index: usize) {
+        warn_on_paint_error(
+            "set painting index",
+            self.inner()
+                .servo
+                .paint()
+                .move_webview_to_painting_index(self.id(), index),
+        );
+    }
+
+    /// Move this [`WebView`] so that it is painted directly above `other`. Both [`WebView`]s
+    /// must use the same [`RenderingContext`].
+    pub fn move_above(&self, other: &WebView) {
+        warn_on_paint_error(
+            "move WebView above another WebView",
+            self.inner()
+                .servo
+                .paint()
+                .move_webview_above(self.id(), other.id()),
+        );
+    }
+
+    /// Move this [`WebView`] so that it is painted directly below `other`. Both [`WebView`]s
+    /// must use the same [`RenderingContext`].
+    pub fn move_below(&self, other: &WebView) {
+        warn_on_paint_error(
+            "move WebView below another WebView",
+            self.inner()
+                .servo
+                .paint()
+                .move_webview_below(self.id(), other.id()),
+        );
     }
 
     pub fn notify_theme_change(&self, theme: Theme) {
@@ -478,22 +692,46 @@ impl WebView {
     /// Ask the [`WebView`] to scroll web content. Note that positive scroll offsets reveal more
     /// content on the bottom and right of the page.
     pub fn notify_scroll_event(&self, scroll: Scroll, point: WebViewPoint) {
-        self.inner()
-            .servo
-            .paint()
-            .notify_scroll_event(self.id(), scroll, point);
+        warn_on_paint_error(
+            "notify scroll event",
+            self.inner()
+                .servo
+                .paint()
+                .notify_scroll_event(self.id(), scroll, point),
+        );
+    }
+
+    /// Smoothly scroll the viewport of this [`WebView`] to `offset`, in CSS pixels from the
+    /// top-left of the page, over the given `duration`. The animation is driven by the
+    /// renderer, so it stays smooth even when the page is busy. Any later scroll event stops
+    /// the animation.
+    pub fn smooth_scroll_to(&self, offset: LayoutVector2D, duration: Duration) {
+        warn_on_paint_error(
+            "smooth scroll",
+            self.inner()
+                .servo
+                .paint()
+                .smooth_scroll_webview_to(self.id(), offset, duration),
+        );
     }
 
     pub fn notify_input_event(&self, event: InputEvent) -> InputEventId {
         let event: InputEventAndId = event.into();
         let event_id = event.id;
+        self.inner()
+            .servo
+            .paint()
+            .record_input_event(self.id(), &event.event);
 
         // Events with a `point` first go to `Paint` for hit testing.
         if event.event.point().is_some() {
-            self.inner()
-                .servo
-                .paint()
-                .notify_input_event(self.id(), event);
+            warn_on_paint_error(
+                "notify input event",
+                self.inner()
+                    .servo
+                    .paint()
+                    .notify_input_event(self.id(), event),
+            );
         } else {
             self.inner().servo.constellation_proxy().send(
                 EmbedderToConstellationMessage::ForwardInputEvent(
@@ -522,19 +760,91 @@ impl WebView {
     /// zoom, which will adjust the `devicePixelRatio` of the page and cause it to modify
     /// its layout.
     ///
+    /// The new page zoom is shown right away by scaling the current contents of the
+    /// [`WebView`], while the page is only laid out again once the page zoom stops changing.
+    /// This keeps changing the page zoom repeatedly, for instance with the mouse wheel,
+    /// responsive.
+    ///
     /// These values will be clamped internally. The values used for clamping can be
     /// adjusted by page content when `<meta viewport>` parsing is enabled via
     /// `Prefs::viewport_meta_enabled`.
     pub fn set_page_zoom(&self, new_zoom: f32) {
+        warn_on_paint_error(
+            "set page zoom",
+            self.inner()
+                .servo
+                .paint()
+                .set_page_zoom(self.id(), new_zoom),
+        );
+    }
+
+    /// Get the page zoom of the [`WebView`]. This is `1.0` if the zoom cannot be determined,
+    /// for instance because the `RenderingContext` of the [`WebView`] was unregistered.
+    pub fn page_zoom(&self) -> f32 {
+        self.inner()
+            .servo
+            .paint()
+            .page_zoom(self.id())
+            .unwrap_or_else(|error| {
+                warn!("Could not get page zoom: {error:?}");
+                1.0
+            })
+    }
+
+    /// The times at which the page currently displayed in this [`WebView`] was first painted
+    /// and first painted with content. These are only recorded once the page is painted to
+    /// the [`RenderingContext`] of this [`WebView`], so they stay empty while it is hidden.
+    /// The same times are reported to the page via the Paint Timing API.
+    pub fn paint_timing(&self) -> WebViewPaintTiming {
         self.inner()
             .servo
             .paint()
-            .set_page_zoom(self.id(), new_zoom);
+            .webview_paint_timing(self.id())
+            .unwrap_or_default()
+    }
+
+    /// Start a continuous capture of the area of this [`WebView`], sending at most
+    /// `frames_per_second` [`CapturedFrame`]s per second over the returned [`Receiver`], which
+    /// can be moved to another thread. Frames are only captured when this [`WebView`] is
+    /// painted, so nothing is sent while it is hidden or its contents do not change. The
+    /// capture stops once the [`Receiver`] is dropped or this [`WebView`] is closed. Frames
+    /// that are painted while a few earlier frames are still waiting to be received are
+    /// dropped.
+    pub fn start_capture_stream(&self, frames_per_second: f32) -> Option<Receiver<CapturedFrame>> {
+        match self
+            .inner()
+            .servo
+            .paint()
+            .start_webview_capture_stream(self.id(), frames_per_second)
+        {
+            Ok(receiver) => Some(receiver),
+            Err(error) => {
+                warn!("Could not start capture stream: {error:?}");
+                None
+            },
+        }
     }
 
-    /// Get the page zoom of the [`WebView`].
-    pub fn page_zoom(&self) -> f32 {
-        self.inner().servo.paint().page_zoom(self.id())
+    /// The size of the display lists of this [`WebView`], both in total and for its most
+    /// recently painted frame. This can be used to find out which [`WebView`] is responsible
+    /// for expensive WebRender scene building.
+    pub fn display_list_statistics(&self) -> DisplayListStatistics {
+        self.inner()
+            .servo
+            .paint()
+            .display_list_statistics(self.id())
+            .unwrap_or_default()
+    }
+
+    /// A histogram of the time between the most recent input events sent to this [`WebView`]
+    /// reaching the renderer and the presentation of the frames that reflect them. This
+    /// only covers input events that have been handled by the page and then painted.
+    pub fn input_latency_histogram(&self) -> InputLatencyHistogram {
+        self.inner()
+            .servo
+            .paint()
+            .input_latency_histogram(self.id())
+            .unwrap_or_default()
     }
 
     /// Adjust the pinch zoom on this [`WebView`] multiplying the current pinch zoom
@@ -546,18 +856,31 @@ impl WebView {
     ///
     /// The final pinch zoom values will be clamped to reasonable defaults (currently to
     /// the inclusive range [1.0, 10.0]).
+    ///
+    /// Pinch zoom is tracked separately for each [`WebView`], so this does not affect other
+    /// [`WebView`]s, whether they paint to the same [`RenderingContext`] or not.
     pub fn pinch_zoom(&self, pinch_zoom_delta: f32, center: DevicePoint) {
-        self.inner()
-            .servo
-            .paint()
-            .pinch_zoom(self.id(), pinch_zoom_delta, center);
+        warn_on_paint_error(
+            "pinch zoom",
+            self.inner()
+                .servo
+                .paint()
+                .pinch_zoom(self.id(), pinch_zoom_delta, center),
+        );
     }
 
+    /// The number of device pixels per CSS pixel of the [`WebView`], taking into account its
+    /// HiDPI scale factor, page zoom and pinch zoom. This is `1.0` if it cannot be determined,
+    /// for instance because the `RenderingContext` of the [`WebView`] was unregistered.
     pub fn device_pixels_per_css_pixel(&self) -> Scale<f32, CSSPixel, DevicePixel> {
         self.inner()
             .servo
             .paint()
             .device_pixels_per_page_pixel(self.id())
+            .unwrap_or_else(|error| {
+                warn!("Could not get device pixels per CSS pixel: {error:?}");
+                Scale::new(1.0)
+            })
     }
 
     pub fn exit_fullscreen(&self) {
@@ -568,17 +891,39 @@ impl WebView {
     }
 
     pub fn set_throttled(&self, throttled: bool) {
-        self.inner().servo.constellation_proxy().send(
-            EmbedderToConstellationMessage::SetWebViewThrottled(self.id(), throttled),
+        warn_on_paint_error(
+            "set WebView throttled",
+            self.inner()
+                .servo
+                .paint()
+                .set_webview_throttled(self.id(), throttled),
         );
     }
 
+    /// Toggle a WebRender debugging option for the [`RenderingContext`] of this [`WebView`].
+    /// Other `RenderingContext`s, such as those of other windows, are not affected. For a
+    /// [`WebView`] in a [`RenderingGroup`], this is the same as
+    /// [`RenderingGroup::toggle_webrender_debug`].
     pub fn toggle_webrender_debugging(&self, debugging: WebRenderDebugOption) {
-        self.inner().servo.paint().toggle_webrender_debug(debugging);
+        let rendering_group = self.inner().rendering_group.clone();
+        if let Some(rendering_group) = rendering_group {
+            rendering_group.toggle_webrender_debug(debugging);
+            return;
+        }
+        warn_on_paint_error(
+            "toggle WebRender debugging",
+            self.inner()
+                .servo
+                .paint()
+                .toggle_painter_webrender_debug(self.id().into(), debugging),
+        );
     }
 
     pub fn capture_webrender(&self) {
-        self.inner().servo.paint().capture_webrender(self.id());
+        warn_on_paint_error(
+            "capture WebRender",
+            self.inner().servo.paint().capture_webrender(self.id()),
+        );
     }
 
     pub fn toggle_sampling_profiler(&self, rate: Duration, max_duration: Duration) {
@@ -599,7 +944,10 @@ impl WebView {
 
     /// Paint the contents of this [`WebView`] into its `RenderingContext`.
     pub fn paint(&self) {
-        self.inner().servo.paint().render(self.id());
+        warn_on_paint_error(
+            "paint WebView",
+            self.inner().servo.paint().render(self.id()),
+        );
     }
 
     /// Get the [`UserContentManager`] associated with this [`WebView`].
@@ -649,6 +997,58 @@ impl WebView {
             .request_screenshot(self.id(), rect, Box::new(callback));
     }
 
+    /// Capture this [`WebView`] on its own, without any other [`WebView`]s sharing its
+    /// [`RenderingContext`] and even when it is hidden. The [`WebView`] is rendered offscreen
+    /// after the next frame for its [`RenderingContext`] is ready. `rect` is in device pixels
+    /// relative to the origin of the [`WebView`], and `None` captures the entire [`WebView`].
+    ///
+    /// Unlike [`WebView::take_screenshot`], this does not wait for the page to finish
+    /// loading.
+    pub fn capture(
+        &self,
+        rect: Option<DeviceRect>,
+        callback: impl FnOnce(Result<RgbaImage, ScreenshotCaptureError>) + 'static,
+    ) {
+        self.inner()
+            .servo
+            .paint()
+            .capture_webview(self.id(), rect, Box::new(callback));
+    }
+
+    /// Read back the current contents of this [`WebView`]'s entire [`RenderingContext`],
+    /// including any other [`WebView`]s that share it. This is mainly useful for
+    /// [`WebView`]s created with [`WebViewBuilder::new_headless`]. Unlike
+    /// [`WebView::take_screenshot`], this does not wait for the rendering to be up-to-date.
+    pub fn read_pixels(&self) -> Option<RgbaImage> {
+        match self.inner().servo.paint().read_pixels(self.id()) {
+            Ok(image) => image,
+            Err(error) => {
+                warn!("Could not read pixels: {error:?}");
+                None
+            },
+        }
+    }
+
+    /// Call `callback` once the next display list produced for this [`WebView`] has been
+    /// painted into its [`RenderingContext`], that is after the first call to
+    /// [`WebView::paint`] that includes it. The frame is on screen as soon as the embedder
+    /// presents the [`RenderingContext`]. This is useful to wait until changes to the page,
+    /// such as those made by [`WebView::evaluate_javascript`], are actually visible. If this
+    /// [`WebView`] is closed before that happens, `callback` is never called.
+    pub fn notify_when_next_frame_presented(&self, callback: impl FnOnce() + 'static) {
+        warn_on_paint_error(
+            "request frame presented notification",
+            self.inner()
+                .servo
+                .paint()
+                .request_frame_presented_notification(self.id(), Box::new(callback)),
+        );
+    }
+
+    pub(crate) fn set_rendering_context(&self, rendering_context: Rc<dyn RenderingContext>) {
+        self.inner_mut().rendering_context = rendering_context;
+    }
+
     pub(crate) fn set_history(self, new_back_forward_list: Vec<ServoUrl>, new_index: usize) {
         {
             let mut inner_mut = self.inner_mut();
@@ -744,6 +1144,15 @@ impl WebView {
     }
 }
 
+/// Log a [`PaintError`] that happened while trying to perform `action` on a [`WebView`]. This
+/// can happen, for instance, when the `RenderingContext` of a [`WebView`] has already been
+/// unregistered.
+fn warn_on_paint_error(action: &str, result: Result<(), PaintError>) {
+    if let Err(error) = result {
+        warn!("Could not {action}: {error:?}");
+    }
+}
+
 /// A structure used to expose a view of the [`WebView`] to the Servo
 /// renderer, without having the Servo renderer depend on the embedding layer.
 struct ServoRendererWebView {
@@ -776,6 +1185,8 @@ pub struct WebViewBuilder {
     hidpi_scale_factor: Scale<f32, DeviceIndependentPixel, DevicePixel>,
     create_new_webview_responder: Option<IpcResponder<Option<NewWebViewDetails>>>,
     user_content_manager: Option<Rc<UserContentManager>>,
+    painter_options: PainterOptions,
+    rendering_group: Option<RenderingGroup>,
 }
 
 impl WebViewBuilder {
@@ -788,9 +1199,36 @@ impl WebViewBuilder {
             delegate: Rc::new(DefaultWebViewDelegate),
             create_new_webview_responder: None,
             user_content_manager: None,
+            painter_options: Default::default(),
+            rendering_group: None,
         }
     }
 
+    /// Create a [`WebViewBuilder`] for a [`WebView`] in the given [`RenderingGroup`], which
+    /// paints to the [`RenderingContext`] of that group. The `WebView` also takes on the HiDPI
+    /// scale factor of the group, if one was set with
+    /// [`RenderingGroup::set_hidpi_scale_factor`]. If the group has no WebRender instance
+    /// because all of its `WebView`s were closed, a new one is created with the settings
+    /// previously made on the group.
+    pub fn new_in_group(servo: &Servo, rendering_group: &RenderingGroup) -> Self {
+        let mut builder = Self::new(servo, rendering_group.rendering_context());
+        builder.painter_options = rendering_group.painter_options().clone();
+        if let Some(hidpi_scale_factor) = rendering_group.hidpi_scale_factor() {
+            builder.hidpi_scale_factor = hidpi_scale_factor;
+        }
+        builder.rendering_group = Some(rendering_group.clone());
+        builder
+    }
+
+    /// Create a [`WebViewBuilder`] for a headless [`WebView`] that renders into a new
+    /// offscreen [`SoftwareRenderingContext`] of the given size, which does not require any
+    /// windowing system. The results of rendering can be read back using
+    /// [`WebView::read_pixels`].
+    pub fn new_headless(servo: &Servo, size: PhysicalSize<u32>) -> Result<Self, surfman::Error> {
+        let rendering_context = Rc::new(SoftwareRenderingContext::new(size)?);
+        Ok(Self::new(servo, rendering_context))
+    }
+
     pub(crate) fn new_for_create_request(
         servo: &Servo,
         rendering_context: Rc<dyn RenderingContext>,
@@ -435,6 +435,10 @@ pub struct ScrollTreeNodeId {
 
 static PAINTER_ID: AtomicU32 = AtomicU32::new(1);
 
+/// An identifier for a `Painter`, which paints to a single `RenderingContext`. A new
+/// [`PainterId`] is allocated for every `Painter` and they are never reused, even after the
+/// `Painter` is removed. This means that messages that arrive late for a removed `Painter` can
+/// never be routed to a different `Painter` and will instead fail to find a `Painter` at all.
 #[derive(
     Clone, Copy, Debug, PartialEq, PartialOrd, Ord, Hash, Eq, Serialize, Deserialize, MallocSizeOf,
 )]
@@ -448,7 +452,12 @@ impl fmt::Display for PainterId {
 
 impl PainterId {
     pub fn next() -> Self {
-        Self(PAINTER_ID.fetch_add(1, Ordering::Relaxed))
+        // Never wrap around, as that would lead to reusing the `PainterId` of an
+        // existing or removed `Painter`.
+        let id = PAINTER_ID
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |id| id.checked_add(1))
+            .expect("PainterId wrapped!");
+        Self(id)
     }
 }
 
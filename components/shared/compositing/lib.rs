@@ -90,10 +90,15 @@ pub enum PaintMessage {
     SetFrameTreeForWebView(WebViewId, SendableFrameTree),
     /// Set whether to use less resources by stopping animations.
     SetThrottled(WebViewId, PipelineId, bool),
-    /// WebRender has produced a new frame. This message informs `Paint` that
-    /// the frame is ready. It contains a bool to indicate if it needs to composite, the
-    /// `DocumentId` of the new frame and the `PainterId` of the associated painter.
-    NewWebRenderFrameReady(PainterId, DocumentId, bool),
+    /// WebRender has produced one or more new frames for the given `DocumentId` of the painter
+    /// with the given `PainterId`. At most one of these messages is in flight for each
+    /// document and the number of frames and whether any of them needs to be composited are
+    /// shared with the painter directly.
+    NewWebRenderFrameReady(PainterId, DocumentId),
+    /// WebRender has finished work that the renderer of the given painter must pick up, such
+    /// as low-priority resource updates, without producing a new frame. The bool indicates
+    /// whether a composite is needed.
+    WebRenderWakeUp(PainterId, bool),
     /// Script or the Constellation is notifying the renderer that a Pipeline has finished
     /// shutting down. The renderer will not discard the Pipeline until both report that
     /// they have fully shut it down, to avoid recreating it due to any subsequent
@@ -175,6 +180,10 @@ pub enum PaintMessage {
     /// Measure the current memory usage associated with `Paint`.
     /// The report must be sent on the provided channel once it's complete.
     CollectMemoryReport(ReportsChan),
+    /// Get the [`RendererStatistics`] of the most recent frame rendered by the renderer
+    /// with the given `PainterId`. The result will be returned via the provided channel
+    /// sender and is `None` if nothing has been rendered yet.
+    CollectRendererStatistics(PainterId, GenericSender<Option<RendererStatistics>>),
     /// A top-level frame has parsed a viewport metatag and is sending the new constraints.
     Viewport(WebViewId, ViewportDescription),
     /// Let `Paint` know that the given WebView is ready to have a screenshot taken
@@ -182,6 +191,11 @@ pub enum PaintMessage {
     ScreenshotReadinessReponse(WebViewId, FxHashMap<PipelineId, Epoch>),
     /// The candidate of largest-contentful-paint
     SendLCPCandidate(LCPCandidate, WebViewId, PipelineId, Epoch),
+    /// Set whether the `RenderingContext` of the painter with the given `PainterId` is
+    /// occluded, for instance because the operating system reported that its window was
+    /// minimized or covered. Unlike the rest of these messages, this is sent by the embedder,
+    /// possibly from the thread on which it receives window events.
+    SetOccluded(PainterId, bool),
 }
 
 impl Debug for PaintMessage {
@@ -191,6 +205,24 @@ impl Debug for PaintMessage {
     }
 }
 
+/// Statistics about the most recent frame rendered by WebRender for a single renderer. These
+/// allow attributing rendering cost to a specific group of `WebView`s.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct RendererStatistics {
+    /// The number of draw calls issued to render the frame.
+    pub draw_calls: usize,
+    /// The number of alpha render targets used to render the frame.
+    pub alpha_target_count: usize,
+    /// The number of color render targets used to render the frame.
+    pub color_target_count: usize,
+    /// The amount of texture data uploaded to the GPU for the frame, in megabytes.
+    pub texture_upload_mb: f64,
+    /// The time spent uploading resources to the GPU, in milliseconds.
+    pub resource_upload_time: f64,
+    /// The time spent applying GPU cache updates, in milliseconds.
+    pub gpu_cache_upload_time: f64,
+}
+
 #[derive(Deserialize, Serialize)]
 pub struct SendableFrameTree {
     pub pipeline: CompositionPipeline,
@@ -378,6 +410,19 @@ impl CrossProcessPaintApi {
         receiver.recv().ok()
     }
 
+    /// Get the [`RendererStatistics`] of the most recent frame rendered by the renderer with
+    /// the given [`PainterId`]. Blocks until `Paint` replies.
+    pub fn renderer_statistics_blocking(
+        &self,
+        painter_id: PainterId,
+    ) -> Option<RendererStatistics> {
+        let (sender, receiver) = generic_channel::channel().unwrap();
+        self.0
+            .send(PaintMessage::CollectRendererStatistics(painter_id, sender))
+            .ok()?;
+        receiver.recv().ok()?
+    }
+
     /// Sends a message to `Paint` for creating new image keys.
     /// `Paint` will then send a batch of keys over the constellation to the script_thread
     /// and the appropriate pipeline.
@@ -9,6 +9,7 @@ use std::num::NonZeroU32;
 use std::rc::Rc;
 use std::sync::Arc;
 
+use base::generic_channel::GenericSharedMemory;
 use dpi::PhysicalSize;
 use embedder_traits::RefreshDriver;
 use euclid::default::{Rect, Size2D as UntypedSize2D};
@@ -43,6 +44,18 @@ pub trait RenderingContext {
     /// buffer. That means that once Servo renders to the context, this should return those
     /// results, even before [`RenderingContext::present`] is called.
     fn read_to_image(&self, source_rectangle: DeviceIntRect) -> Option<RgbaImage>;
+    /// Read the contents of this [`RenderingContext`] into a new shared memory segment, like
+    /// [`RenderingContext::read_to_image`]. The pixels are in RGBA format with the origin at
+    /// the top-left, and can be sent to another process without being copied again. The
+    /// default implementation copies the result of [`RenderingContext::read_to_image`], while
+    /// OpenGL-based contexts read their pixels straight into the shared memory.
+    fn read_to_shared_memory(
+        &self,
+        source_rectangle: DeviceIntRect,
+    ) -> Option<GenericSharedMemory> {
+        let image = self.read_to_image(source_rectangle)?;
+        Some(GenericSharedMemory::from_bytes(image.as_raw()))
+    }
     /// Get the current size of this [`RenderingContext`].
     fn size(&self) -> PhysicalSize<u32>;
     /// Get the current size of this [`RenderingContext`] as [`Size2D`].
@@ -55,6 +68,22 @@ pub trait RenderingContext {
     /// Presents the rendered frame to the screen. In a double-buffered context, this would
     /// swap buffers.
     fn present(&self);
+    /// Presents the rendered frame to the screen, like [`RenderingContext::present`], but
+    /// only the areas in `damage` have changed since the previous frame. An empty `damage`
+    /// means that the entire surface may have changed. `damage` is in device pixels with the
+    /// origin at the top-left of the surface. Contexts that support partial updates, for
+    /// instance via `EGL_KHR_partial_update`, can use this to avoid swapping the whole
+    /// surface. The default implementation calls [`RenderingContext::present`].
+    fn present_with_damage(&self, _damage: &[DeviceIntRect]) {
+        self.present()
+    }
+    /// The age of the back buffer in frames, if this [`RenderingContext`] supports partial
+    /// presentation, with `0` meaning that its contents are undefined. When this returns
+    /// `Some`, Servo only repaints the areas of the surface that changed and passes them to
+    /// [`RenderingContext::present_with_damage`]. Defaults to `None`.
+    fn buffer_age(&self) -> Option<usize> {
+        None
+    }
     /// Makes the context the current OpenGL context for this thread.
     /// After calling this function, it is valid to use OpenGL rendering
     /// commands.
@@ -246,6 +275,20 @@ impl SurfmanRenderingContext {
         Framebuffer::read_framebuffer_to_image(&self.gleam_gl, framebuffer_id, source_rectangle)
     }
 
+    fn read_to_shared_memory(
+        &self,
+        source_rectangle: DeviceIntRect,
+    ) -> Option<GenericSharedMemory> {
+        let framebuffer_id = self
+            .framebuffer()
+            .map_or(0, |framebuffer| framebuffer.0.into());
+        Framebuffer::read_framebuffer_to_shared_memory(
+            &self.gleam_gl,
+            framebuffer_id,
+            source_rectangle,
+        )
+    }
+
     fn make_current(&self) -> Result<(), Error> {
         let device = &self.device.borrow();
         let context = &mut self.context.borrow();
@@ -339,6 +382,14 @@ impl RenderingContext for SoftwareRenderingContext {
         self.surfman_rendering_info.read_to_image(source_rectangle)
     }
 
+    fn read_to_shared_memory(
+        &self,
+        source_rectangle: DeviceIntRect,
+    ) -> Option<GenericSharedMemory> {
+        self.surfman_rendering_info
+            .read_to_shared_memory(source_rectangle)
+    }
+
     fn size(&self) -> PhysicalSize<u32> {
         self.size.get()
     }
@@ -463,6 +514,20 @@ impl WindowRenderingContext {
         OffscreenRenderingContext::new(self.clone(), size)
     }
 
+    /// Create an [`OffscreenRenderingContext`] that presents to the given `viewport` of this
+    /// window, in device pixels with the origin at the top-left. This allows several groups
+    /// of `WebView`s, each with their own WebRender instance, to share a single window
+    /// surface. See [`OffscreenRenderingContext::set_viewport`].
+    pub fn viewport_context(self: &Rc<Self>, viewport: DeviceIntRect) -> OffscreenRenderingContext {
+        let size = viewport.size().max(Size2D::zero()).to_u32();
+        let offscreen_context = OffscreenRenderingContext::new(
+            self.clone(),
+            PhysicalSize::new(size.width, size.height),
+        );
+        offscreen_context.set_viewport(Some(viewport));
+        offscreen_context
+    }
+
     /// Stop rendering to the window that was used to create this `WindowRenderingContext`
     /// or last set with [`Self::set_window`].
     ///
@@ -526,6 +591,13 @@ impl RenderingContext for WindowRenderingContext {
         self.surfman_context.read_to_image(source_rectangle)
     }
 
+    fn read_to_shared_memory(
+        &self,
+        source_rectangle: DeviceIntRect,
+    ) -> Option<GenericSharedMemory> {
+        self.surfman_context.read_to_shared_memory(source_rectangle)
+    }
+
     fn size(&self) -> PhysicalSize<u32> {
         self.size.get()
     }
@@ -575,7 +647,9 @@ impl RenderingContext for WindowRenderingContext {
     }
 }
 
-struct Framebuffer {
+/// An OpenGL framebuffer with a color texture and a depth buffer, which can be rendered to
+/// instead of the framebuffer of a [`RenderingContext`].
+pub struct Framebuffer {
     gl: Rc<dyn Gl>,
     framebuffer_id: gl::GLuint,
     renderbuffer_id: gl::GLuint,
@@ -583,7 +657,7 @@ struct Framebuffer {
 }
 
 impl Framebuffer {
-    fn bind(&self) {
+    pub fn bind(&self) {
         trace!("Binding FBO {}", self.framebuffer_id);
         self.gl
             .bind_framebuffer(gl::FRAMEBUFFER, self.framebuffer_id)
@@ -600,7 +674,7 @@ impl Drop for Framebuffer {
 }
 
 impl Framebuffer {
-    fn new(gl: Rc<dyn Gl>, size: PhysicalSize<u32>) -> Self {
+    pub fn new(gl: Rc<dyn Gl>, size: PhysicalSize<u32>) -> Self {
         let framebuffer_ids = gl.gen_framebuffers(1);
         gl.bind_framebuffer(gl::FRAMEBUFFER, framebuffer_ids[0]);
 
@@ -666,15 +740,55 @@ impl Framebuffer {
         }
     }
 
-    fn read_to_image(&self, source_rectangle: DeviceIntRect) -> Option<RgbaImage> {
+    /// Read the given rectangle of this framebuffer, with the origin at the bottom-left, into
+    /// an in-memory image.
+    pub fn read_to_image(&self, source_rectangle: DeviceIntRect) -> Option<RgbaImage> {
         Self::read_framebuffer_to_image(&self.gl, self.framebuffer_id, source_rectangle)
     }
 
+    /// Read the given rectangle of this framebuffer, with the origin at the bottom-left, into
+    /// a new shared memory segment. See [`RenderingContext::read_to_shared_memory`].
+    pub fn read_to_shared_memory(
+        &self,
+        source_rectangle: DeviceIntRect,
+    ) -> Option<GenericSharedMemory> {
+        Self::read_framebuffer_to_shared_memory(&self.gl, self.framebuffer_id, source_rectangle)
+    }
+
     fn read_framebuffer_to_image(
         gl: &Rc<dyn Gl>,
         framebuffer_id: u32,
         source_rectangle: DeviceIntRect,
     ) -> Option<RgbaImage> {
+        let size = source_rectangle.size().to_usize();
+        let mut pixels = vec![0; size.width * size.height * 4];
+        Self::read_framebuffer_into(gl, framebuffer_id, source_rectangle, &mut pixels);
+        RgbaImage::from_raw(size.width as u32, size.height as u32, pixels)
+    }
+
+    fn read_framebuffer_to_shared_memory(
+        gl: &Rc<dyn Gl>,
+        framebuffer_id: u32,
+        source_rectangle: DeviceIntRect,
+    ) -> Option<GenericSharedMemory> {
+        let size = source_rectangle.size().to_usize();
+        let mut shared_memory = GenericSharedMemory::from_byte(0, size.width * size.height * 4);
+        // SAFETY: The shared memory segment was just created, so nothing else can be reading
+        // from or writing to it.
+        #[expect(unsafe_code)]
+        let pixels = unsafe { shared_memory.deref_mut() };
+        Self::read_framebuffer_into(gl, framebuffer_id, source_rectangle, pixels);
+        Some(shared_memory)
+    }
+
+    /// Read the given rectangle of the given framebuffer into `pixels`, which must be large
+    /// enough to hold it in RGBA format, flipping it so that the origin is at the top-left.
+    fn read_framebuffer_into(
+        gl: &Rc<dyn Gl>,
+        framebuffer_id: u32,
+        source_rectangle: DeviceIntRect,
+        pixels: &mut [u8],
+    ) {
         gl.bind_framebuffer(gl::FRAMEBUFFER, framebuffer_id);
 
         // For some reason, OSMesa fails to render on the 3rd
@@ -685,13 +799,14 @@ impl Framebuffer {
         // See https://github.com/servo/servo/issues/18606.
         gl.bind_vertex_array(0);
 
-        let mut pixels = gl.read_pixels(
+        gl.read_pixels_into_buffer(
             source_rectangle.min.x,
             source_rectangle.min.y,
             source_rectangle.width(),
             source_rectangle.height(),
             gl::RGBA,
             gl::UNSIGNED_BYTE,
+            pixels,
         );
         let gl_error = gl.get_error();
         if gl_error != gl::NO_ERROR {
@@ -700,20 +815,12 @@ impl Framebuffer {
 
         // flip image vertically (texture is upside down)
         let source_rectangle = source_rectangle.to_usize();
-        let orig_pixels = pixels.clone();
         let stride = source_rectangle.width() * 4;
-        for y in 0..source_rectangle.height() {
-            let dst_start = y * stride;
-            let src_start = (source_rectangle.height() - y - 1) * stride;
-            let src_slice = &orig_pixels[src_start..src_start + stride];
-            pixels[dst_start..dst_start + stride].clone_from_slice(&src_slice[..stride]);
+        let height = source_rectangle.height();
+        for y in 0..height / 2 {
+            let (top, bottom) = pixels.split_at_mut((height - y - 1) * stride);
+            top[y * stride..(y + 1) * stride].swap_with_slice(&mut bottom[..stride]);
         }
-
-        RgbaImage::from_raw(
-            source_rectangle.width() as u32,
-            source_rectangle.height() as u32,
-            pixels,
-        )
     }
 }
 
@@ -721,6 +828,9 @@ pub struct OffscreenRenderingContext {
     parent_context: Rc<WindowRenderingContext>,
     size: Cell<PhysicalSize<u32>>,
     framebuffer: RefCell<Framebuffer>,
+    /// The area of the parent context that this context is copied to when it is presented,
+    /// if any. See [`OffscreenRenderingContext::set_viewport`].
+    viewport: Cell<Option<DeviceIntRect>>,
 }
 
 type RenderToParentCallback = Box<dyn Fn(&glow::Context, Rect<i32>) + Send + Sync>;
@@ -732,6 +842,7 @@ impl OffscreenRenderingContext {
             parent_context,
             size: Cell::new(size),
             framebuffer,
+            viewport: Cell::new(None),
         }
     }
 
@@ -739,6 +850,25 @@ impl OffscreenRenderingContext {
         &self.parent_context
     }
 
+    /// The area of the parent context that this context is copied to when it is presented.
+    pub fn viewport(&self) -> Option<DeviceIntRect> {
+        self.viewport.get()
+    }
+
+    /// Set the area of the parent context, in device pixels with the origin at the top-left,
+    /// that this context is copied to whenever it is presented. Nothing outside of the
+    /// viewport is touched, so several [`OffscreenRenderingContext`]s can present to
+    /// different parts of the same window. The embedder is responsible for presenting the
+    /// parent context once all of them have been presented. When this is `None`, which is the
+    /// default, presenting does nothing and the embedder should use
+    /// [`Self::render_to_parent_callback`] instead.
+    ///
+    /// The size of the viewport should match the size of this context, otherwise the contents
+    /// are scaled when copied.
+    pub fn set_viewport(&self, viewport: Option<DeviceIntRect>) {
+        self.viewport.set(viewport);
+    }
+
     pub fn render_to_parent_callback(&self) -> Option<RenderToParentCallback> {
         // Don't accept a `None` context for the source framebuffer.
         let front_framebuffer_id =
@@ -843,7 +973,34 @@ impl RenderingContext for OffscreenRenderingContext {
         self.framebuffer.borrow().bind();
     }
 
-    fn present(&self) {}
+    fn present(&self) {
+        let Some(viewport) = self.viewport.get() else {
+            return;
+        };
+        let Some(source_framebuffer_id) =
+            NonZeroU32::new(self.framebuffer.borrow().framebuffer_id).map(NativeFramebuffer)
+        else {
+            return;
+        };
+
+        // The viewport has a top-left origin, but OpenGL uses a bottom-left one.
+        let parent_height = self.parent_context.size().height as i32;
+        let target_rect = Rect::new(
+            Point2D::new(viewport.min.x, parent_height - viewport.max.y),
+            viewport.size().to_untyped(),
+        );
+        let size = self.size.get();
+        Self::blit_framebuffer(
+            &self.glow_gl_api(),
+            Rect::new(
+                Point2D::origin(),
+                Size2D::new(size.width as i32, size.height as i32),
+            ),
+            source_framebuffer_id,
+            target_rect,
+            self.parent_context.surfman_context.framebuffer(),
+        );
+    }
 
     fn make_current(&self) -> Result<(), surfman::Error> {
         self.parent_context.make_current()
@@ -876,6 +1033,15 @@ impl RenderingContext for OffscreenRenderingContext {
         self.framebuffer.borrow().read_to_image(source_rectangle)
     }
 
+    fn read_to_shared_memory(
+        &self,
+        source_rectangle: DeviceIntRect,
+    ) -> Option<GenericSharedMemory> {
+        self.framebuffer
+            .borrow()
+            .read_to_shared_memory(source_rectangle)
+    }
+
     fn refresh_driver(&self) -> Option<Rc<dyn RefreshDriver>> {
         self.parent_context().refresh_driver()
     }
@@ -1085,6 +1085,8 @@ pub enum ScreenshotCaptureError {
     CouldNotReadImage,
     /// The WebView that this screenshot request was made for no longer exists.
     WebViewDoesNotExist,
+    /// The `RenderingContext` that this capture request was made for is not used by Servo.
+    RenderingContextDoesNotExist,
 }
 
 #[derive(Clone, Copy, Debug, Deserialize, Serialize)]
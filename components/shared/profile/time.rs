@@ -67,6 +67,12 @@ pub enum ProfilerCategory {
     /// Not associated with a specific URL.
     Painting = 0x00,
 
+    /// The time between an input event reaching `Paint` and the presentation of the first
+    /// frame that reflects its handling.
+    ///
+    /// Not associated with a specific URL.
+    InputToPresentLatency = 0x01,
+
     /// The script thread is doing layout work.
     Layout = 0x10,
 
@@ -130,6 +136,7 @@ impl ProfilerCategory {
     pub const fn variant_name(&self) -> &'static str {
         match self {
             ProfilerCategory::Painting => "Painting",
+            ProfilerCategory::InputToPresentLatency => "InputToPresentLatency",
             ProfilerCategory::Layout => "Layout",
             ProfilerCategory::ImageSaving => "ImageSaving",
             ProfilerCategory::ScriptSpawnPipeline => "ScriptSpawnPipeline",
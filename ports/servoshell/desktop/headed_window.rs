@@ -733,6 +733,16 @@ impl PlatformWindow for HeadedWindow {
                             ));
                         }
                     },
+                    WindowEvent::MouseWheel { delta, .. }
+                        if self.modifiers_state.get().control_key() =>
+                    {
+                        // Zoom the page by 10% for every line scrolled with Ctrl held down.
+                        let lines = match delta {
+                            MouseScrollDelta::LineDelta(_, delta_y) => delta_y,
+                            MouseScrollDelta::PixelDelta(delta) => delta.y as f32 / LINE_HEIGHT,
+                        };
+                        webview.set_page_zoom(webview.page_zoom() * (1.0 + lines / 10.0).max(0.5));
+                    },
                     WindowEvent::MouseWheel { delta, .. } => {
                         let (delta_x, delta_y, mode) = match delta {
                             MouseScrollDelta::LineDelta(delta_x, delta_y) => (